@@ -1,6 +1,7 @@
 use std::{
     path::{Path, PathBuf},
     process::ExitCode,
+    sync::OnceLock,
 };
 
 use clap::Parser as _;
@@ -8,7 +9,7 @@ use omni_cli_core::{
     commands::{self, Cli, CliArgs, CliSubcommands},
     context::{self, Context, ContextError, get_root_dir},
 };
-use omni_tracing_subscriber::TracingConfig;
+use omni_tracing_subscriber::{OtlpConfig, OtlpExporter, TracingConfig};
 use scopeguard::defer;
 use system_traits::impls::RealSys;
 use trace::Level;
@@ -21,6 +22,9 @@ static ALLOC: dhat::Alloc = dhat::Alloc;
 #[global_allocator]
 static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
+/// Exports the spans of this process when `--otlp-endpoint` is set.
+static OTLP_EXPORTER: OnceLock<OtlpExporter> = OnceLock::new();
+
 #[cfg(feature = "enable-tracing")]
 fn init_tracing(
     config: &omni_tracing_subscriber::TracingConfig,
@@ -28,13 +32,27 @@ fn init_tracing(
     use omni_tracing_subscriber::TracingSubscriber;
     use tracing_subscriber::util::SubscriberInitExt;
 
-    TracingSubscriber::new(config, vec![])?.try_init()?;
+    let sub = TracingSubscriber::new(config, vec![])?;
+    if let Some(exporter) = sub.otlp_exporter() {
+        let _ = OTLP_EXPORTER.set(exporter);
+    }
+    sub.try_init()?;
 
     Ok(())
 }
 
+/// Export the spans that are still buffered, the process is about to exit.
+fn flush_traces() {
+    if let Some(exporter) = OTLP_EXPORTER.get()
+        && let Err(error) = exporter.shutdown()
+    {
+        trace::error!(%error, "flush_traces_failed");
+    }
+}
+
 #[inline(always)]
 fn exit(code: ExitCode) -> ! {
+    flush_traces();
     std::process::exit(if code == ExitCode::SUCCESS { 0 } else { 1 })
 }
 
@@ -107,6 +125,18 @@ pub async fn run(
             let context = create_ctx()?;
             commands::project::run(cmd, &context).await?;
         }
+        CliSubcommands::Graph(cmd) => {
+            let context = create_ctx()?;
+            commands::graph::run(cmd, &context).await?;
+        }
+        CliSubcommands::History(cmd) => {
+            let context = create_ctx()?;
+            commands::history::run(cmd, &context).await?;
+        }
+        CliSubcommands::Affected(cmd) => {
+            let context = create_ctx()?;
+            commands::affected::run(cmd, &context).await?;
+        }
         CliSubcommands::Tool(cmd) => {
             let context = create_ctx()?;
             commands::tool::run(cmd, &context).await?;
@@ -121,6 +151,18 @@ pub async fn run(
             };
             commands::mcp::run(mcp, &context).await?;
         }
+        CliSubcommands::Daemon(cmd) => {
+            let context = create_ctx()?;
+            commands::daemon::run(cmd, args, &context).await?;
+        }
+        CliSubcommands::Shell(shell) => {
+            let code = commands::shell::run(shell).await?;
+            std::process::exit(code);
+        }
+        CliSubcommands::AuditExec(audit) => {
+            let code = commands::audit_exec::run(audit).await?;
+            std::process::exit(code);
+        }
     }
 
     Ok(())
@@ -163,6 +205,10 @@ async fn run_main() -> eyre::Result<()> {
         stdout_show_traces: cli.args.stdout_show_traces,
         stderr_level: cli.args.stderr_log_level.value(),
         stderr_show_traces: cli.args.stderr_show_traces,
+        otlp: cli.args.otlp_endpoint.clone().map(|endpoint| OtlpConfig {
+            endpoint,
+            protocol: cli.args.otlp_protocol.value(),
+        }),
     };
 
     #[cfg(feature = "enable-tracing")]
@@ -196,6 +242,7 @@ pub fn main() -> eyre::Result<()> {
         .enable_all()
         .build()?;
     let result = rt.block_on(run_main());
+    flush_traces();
     // micro-optimization to force blocking background tasks to be abondoned
     // instead of waiting for them to finish
     rt.shutdown_background();
@@ -8,6 +8,7 @@ use omni_context::{
 };
 use omni_generator::GeneratorSys;
 use omni_messages::{OmniEventSubscriber, TracingSubscriber};
+use omni_run_history::RunRecord;
 use omni_task_executor::TaskExecutorSys;
 use omni_tracing_subscriber::TracingConfig;
 use system_traits::impls::RealSys as RealSysSync;
@@ -17,9 +18,10 @@ use crate::operations::generator::InspectViewKind;
 use crate::{
     OmniApiError,
     operations::{
+        affected::{AffectedRequest, AffectedResponse},
         cache::{
-            CachePruneRequest, CachePruneResponse, CacheRemoteSetupRequest,
-            CacheStatsRequest,
+            CacheExplainRequest, CacheExplainResponse, CachePruneRequest,
+            CachePruneResponse, CacheRemoteSetupRequest, CacheStatsRequest,
         },
         config_schema::{ConfigSchemaResponse, SchemaKind},
         env::{EnvRequest, EnvResponse},
@@ -29,7 +31,14 @@ use crate::{
             GeneratorRunRequest, GeneratorRunResponse,
             GeneratorValidateInputRequest, GeneratorValidateInputResponse,
         },
+        graph::{GraphRequest, GraphResponse},
         hash::HashResponse,
+        history::{
+            HistoryListRequest, HistoryListResponse, HistoryRegressionsRequest,
+            HistoryRegressionsResponse, HistoryTaskRequest,
+            HistoryTaskResponse,
+        },
+        project::ProjectListRequest,
         task::{TaskRunRequest, TaskRunResponse},
     },
     setup_guard::SetupGuard,
@@ -369,6 +378,19 @@ where
         .await
     }
 
+    /// Explain why a task misses the cache.
+    pub async fn cache_explain(
+        &self,
+        req: CacheExplainRequest,
+    ) -> eyre::Result<CacheExplainResponse> {
+        let mut ctx = self.ctx.lock().await;
+        crate::operations::cache::handle_cache_explain(
+            ctx.ensure_loaded().await?,
+            req,
+        )
+        .await
+    }
+
     /// Configure a remote cache server for this workspace.
     pub async fn cache_remote_setup(
         &self,
@@ -402,6 +424,59 @@ where
         ctx.as_context().cache_dir()
     }
 
+    /// List the recorded runs, newest first.
+    pub async fn history_list(
+        &self,
+        req: HistoryListRequest,
+    ) -> eyre::Result<HistoryListResponse> {
+        let ctx = self.ctx.lock().await;
+        crate::operations::history::handle_history_list(ctx.as_context(), req)
+            .await
+    }
+
+    /// Return a recorded run, or the latest one if `run_id` is `None`.
+    pub async fn history_show(
+        &self,
+        run_id: Option<&str>,
+    ) -> eyre::Result<Option<RunRecord>> {
+        let ctx = self.ctx.lock().await;
+        crate::operations::history::handle_history_show(
+            ctx.as_context(),
+            run_id,
+        )
+        .await
+    }
+
+    /// Return the durations of a task across the recorded runs.
+    pub async fn history_task(
+        &self,
+        req: HistoryTaskRequest,
+    ) -> eyre::Result<HistoryTaskResponse> {
+        let ctx = self.ctx.lock().await;
+        crate::operations::history::handle_history_task(ctx.as_context(), req)
+            .await
+    }
+
+    /// Find the tasks that got slower in the latest recorded runs.
+    pub async fn history_regressions(
+        &self,
+        req: HistoryRegressionsRequest,
+    ) -> eyre::Result<HistoryRegressionsResponse> {
+        let ctx = self.ctx.lock().await;
+        crate::operations::history::handle_history_regressions(
+            ctx.as_context(),
+            req,
+        )
+        .await
+    }
+
+    /// Load the workspace now instead of on the first operation that needs
+    /// it, e.g. to keep a long-lived instance warm.
+    pub async fn load(&self) -> eyre::Result<()> {
+        self.ctx.lock().await.load().await?;
+        Ok(())
+    }
+
     /// Return a JSON Schema for the requested configuration kind.
     ///
     /// This is a pure, synchronous operation — no workspace loading required.
@@ -424,7 +499,22 @@ where
     /// List the names of all projects in the workspace.
     pub async fn project_list(&self) -> eyre::Result<Vec<String>> {
         let ctx = self.ctx.lock().await;
-        crate::operations::project::handle_project_list(ctx.as_context()).await
+        crate::operations::project::handle_project_list(
+            ctx.as_context(),
+            &ProjectListRequest::default(),
+        )
+        .await
+    }
+
+    /// List the names of the projects matching the name and tag filters in
+    /// `req`.
+    pub async fn project_list_filtered(
+        &self,
+        req: ProjectListRequest,
+    ) -> eyre::Result<Vec<String>> {
+        let ctx = self.ctx.lock().await;
+        crate::operations::project::handle_project_list(ctx.as_context(), &req)
+            .await
     }
 
     /// Return the full configuration for the named project.
@@ -441,6 +531,31 @@ where
         )
         .await
     }
+
+    /// Export the project graph, or the resolved task graph for
+    /// `req.tasks` when it is not empty.
+    pub async fn graph(
+        &self,
+        req: GraphRequest,
+    ) -> eyre::Result<GraphResponse> {
+        let mut ctx = self.ctx.lock().await;
+        crate::operations::graph::handle_graph(ctx.ensure_loaded().await?, req)
+            .await
+    }
+
+    /// List the projects, and optionally the tasks, affected by the changes
+    /// between two refs without running anything.
+    pub async fn affected(
+        &self,
+        req: AffectedRequest,
+    ) -> eyre::Result<AffectedResponse> {
+        let mut ctx = self.ctx.lock().await;
+        crate::operations::affected::handle_affected(
+            ctx.ensure_loaded().await?,
+            req,
+        )
+        .await
+    }
 }
 
 // ── Generator operations ──────────────────────────────────────────────────────
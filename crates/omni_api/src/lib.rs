@@ -12,9 +12,14 @@ pub use error::OmniApiError;
 
 // Re-export commonly used types at the crate root.
 pub use operations::{
+    affected::{
+        AffectedFormat, AffectedProject, AffectedReason, AffectedRequest,
+        AffectedResponse,
+    },
     cache::{
-        CachePruneRequest, CachePruneResponse, CacheRemoteSetupRequest,
-        CacheStatsRequest,
+        CacheExplainRequest, CacheExplainResponse, CacheExplainStatus,
+        CacheInputChange, CacheInputChangeKind, CachePruneRequest,
+        CachePruneResponse, CacheRemoteSetupRequest, CacheStatsRequest,
     },
     config_schema::{ConfigSchemaResponse, SchemaKind},
     env::{EnvRequest, EnvResponse},
@@ -29,7 +34,16 @@ pub use operations::{
         StaticInputDefault, SubGeneratorRef, SubGeneratorValidationResult,
         WidgetView,
     },
+    graph::{
+        GraphEdge, GraphFormat, GraphKind, GraphNode, GraphRequest,
+        GraphResponse,
+    },
     hash::HashResponse,
+    history::{
+        HistoryListRequest, HistoryListResponse, HistoryRegressionsRequest,
+        HistoryRegressionsResponse, HistoryTaskRequest, HistoryTaskResponse,
+    },
+    project::ProjectListRequest,
     task::{TaskRunFilters, TaskRunRequest, TaskRunResponse},
     tool::{ToolInfo, ToolInspectResponse, ToolListResponse, ToolWorkingDir},
 };
@@ -0,0 +1,362 @@
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use omni_context::{ContextSys, LoadedContext};
+use omni_core::Project;
+use omni_execution_plan::{
+    Call, ExecutionPlanProvider as _, ScmAffectedFilter,
+};
+use omni_scm::{Scm as _, SelectScm, get_scm_implementation};
+use omni_task_executor::ContextExecutionPlanProvider;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sets::UnorderedSet;
+use strum::VariantArray;
+
+// ── Request ─────────────────────────────────────────────────────────────────
+
+/// Request to compute what is affected by the changes between two refs.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AffectedRequest {
+    /// Also resolve the tasks that would run for these task names. When
+    /// empty, only projects are reported.
+    pub tasks: Vec<String>,
+    /// SCM base commit, defaults to the SCM's default base.
+    pub base: Option<String>,
+    /// SCM target commit, defaults to the SCM's default target.
+    pub target: Option<String>,
+    /// SCM used to detect the changed files.
+    #[schemars(with = "String")]
+    pub scm: SelectScm,
+}
+
+impl Default for AffectedRequest {
+    fn default() -> Self {
+        Self {
+            tasks: vec![],
+            base: None,
+            target: None,
+            scm: SelectScm::Auto,
+        }
+    }
+}
+
+/// Output format of an [`AffectedResponse`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    strum::Display,
+    strum::EnumString,
+    VariantArray,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum AffectedFormat {
+    /// One project name per line, or one task per line when tasks were
+    /// requested.
+    #[default]
+    #[strum(serialize = "text")]
+    Text,
+    /// The [`AffectedResponse`] itself, serialized as JSON.
+    #[strum(serialize = "json")]
+    Json,
+}
+
+// ── Response ─────────────────────────────────────────────────────────────────
+
+/// Why a project is affected.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum AffectedReason {
+    /// A file inside the project changed.
+    Changed,
+    /// The project depends, directly or transitively, on a changed project.
+    Dependent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AffectedProject {
+    pub name: String,
+    pub reason: AffectedReason,
+}
+
+/// What is affected by the changes between two refs, sorted for stable
+/// output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct AffectedResponse {
+    /// Changed files, relative to the workspace root when they are inside it.
+    pub changed_files: Vec<PathBuf>,
+    pub projects: Vec<AffectedProject>,
+    /// Full names (`project#task`) of the tasks that would run, in execution
+    /// order. `None` when no task names were requested.
+    pub tasks: Option<Vec<String>>,
+}
+
+impl AffectedResponse {
+    /// Render the response in the requested format.
+    pub fn render(&self, format: AffectedFormat) -> eyre::Result<String> {
+        Ok(match format {
+            AffectedFormat::Text => self.to_text(),
+            AffectedFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = String::new();
+
+        // `write!` into a `String` never fails.
+        if let Some(tasks) = &self.tasks {
+            for task in tasks {
+                let _ = writeln!(out, "{task}");
+            }
+        } else {
+            for project in &self.projects {
+                let _ = writeln!(out, "{}", project.name);
+            }
+        }
+
+        out
+    }
+}
+
+// ── Handlers ─────────────────────────────────────────────────────────────────
+
+/// Resolve the projects, and optionally the tasks, affected by the files
+/// changed between `req.base` and `req.target`. Nothing is executed.
+pub async fn handle_affected<TSys: ContextSys>(
+    ctx: &LoadedContext<TSys>,
+    req: AffectedRequest,
+) -> eyre::Result<AffectedResponse> {
+    let root_dir = ctx.root_dir();
+    let changed_files = changed_files(
+        root_dir,
+        &ScmAffectedFilter {
+            scm: req.scm,
+            base: req.base.clone(),
+            target: req.target.clone(),
+        },
+    )?;
+
+    let graph = ctx.get_project_graph()?;
+    let changed = changed_projects(ctx.projects(), &changed_files);
+
+    let mut projects = vec![];
+    let mut seen = UnorderedSet::default();
+    for name in &changed {
+        seen.insert(name.clone());
+        projects.push(AffectedProject {
+            name: name.clone(),
+            reason: AffectedReason::Changed,
+        });
+    }
+    for name in &changed {
+        for (_, dependent) in graph.get_all_dependents_by_name(name)? {
+            if seen.insert(dependent.name.clone()) {
+                projects.push(AffectedProject {
+                    name: dependent.name,
+                    reason: AffectedReason::Dependent,
+                });
+            }
+        }
+    }
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let tasks = if req.tasks.is_empty() {
+        None
+    } else {
+        let scm_filter = ScmAffectedFilter {
+            scm: req.scm,
+            base: req.base.clone(),
+            target: req.target.clone(),
+        };
+
+        let plan = ContextExecutionPlanProvider::new(ctx).get_execution_plan(
+            &Call::new_tasks(&req.tasks[..]),
+            &[],
+            &[],
+            None,
+            None,
+            Some(&scm_filter),
+            false,
+            true,
+        )?;
+
+        Some(
+            plan.into_iter()
+                .flatten()
+                .map(|n| n.full_task_name().to_string())
+                .collect(),
+        )
+    };
+
+    let mut changed_files = changed_files
+        .into_iter()
+        .map(|path| match path.strip_prefix(root_dir) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path,
+        })
+        .collect::<Vec<_>>();
+    changed_files.sort();
+
+    Ok(AffectedResponse {
+        changed_files,
+        projects,
+        tasks,
+    })
+}
+
+/// The files changed between the refs of `filter`, as absolute paths.
+pub(crate) fn changed_files(
+    root_dir: &Path,
+    filter: &ScmAffectedFilter,
+) -> eyre::Result<Vec<PathBuf>> {
+    let root_dir_str = root_dir.to_string_lossy().replace('\\', "/");
+
+    let scm = get_scm_implementation(&root_dir_str, filter.scm)
+        .ok_or_else(|| eyre::eyre!("no supported scm repository found"))?;
+
+    Ok(scm
+        .changed_files(
+            filter.base.as_deref().unwrap_or(scm.default_base()),
+            filter.target.as_deref().unwrap_or(scm.default_target()),
+        )?
+        .into_iter()
+        .map(|path| {
+            if path.is_absolute() {
+                path
+            } else {
+                root_dir.join(path)
+            }
+        })
+        .collect())
+}
+
+/// Names of the projects owning at least one of `changed_files`. A file is
+/// owned by the project with the deepest directory containing it, so files
+/// of nested projects don't mark their parents as changed.
+pub(crate) fn changed_projects(
+    projects: &[Project],
+    changed_files: &[PathBuf],
+) -> Vec<String> {
+    let mut changed = vec![];
+
+    for file in changed_files {
+        let owner = projects
+            .iter()
+            .filter(|p| file.starts_with(&p.dir))
+            .max_by_key(|p| depth(&p.dir));
+
+        if let Some(owner) = owner
+            && !changed.contains(&owner.name)
+        {
+            changed.push(owner.name.clone());
+        }
+    }
+
+    changed.sort();
+    changed
+}
+
+fn depth(dir: &Path) -> usize {
+    dir.components().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, dir: &str) -> Project {
+        Project::new(name, PathBuf::from(dir), vec![], Default::default())
+    }
+
+    #[test]
+    fn changed_projects_uses_the_deepest_owning_project() {
+        let projects = vec![
+            project("root", "/ws"),
+            project("app", "/ws/apps/app"),
+            project("lib", "/ws/libs/lib"),
+        ];
+
+        let changed = changed_projects(
+            &projects,
+            &[
+                PathBuf::from("/ws/apps/app/src/main.rs"),
+                PathBuf::from("/ws/apps/app/Cargo.toml"),
+                PathBuf::from("/ws/README.md"),
+            ],
+        );
+
+        assert_eq!(changed, vec!["app".to_string(), "root".to_string()]);
+    }
+
+    #[test]
+    fn changed_projects_ignores_files_outside_projects() {
+        let projects = vec![project("app", "/ws/apps/app")];
+
+        let changed =
+            changed_projects(&projects, &[PathBuf::from("/ws/docs/index.md")]);
+
+        assert!(changed.is_empty());
+    }
+
+    fn response(tasks: Option<Vec<String>>) -> AffectedResponse {
+        AffectedResponse {
+            changed_files: vec![PathBuf::from("apps/app/src/main.rs")],
+            projects: vec![
+                AffectedProject {
+                    name: "app".to_string(),
+                    reason: AffectedReason::Changed,
+                },
+                AffectedProject {
+                    name: "web".to_string(),
+                    reason: AffectedReason::Dependent,
+                },
+            ],
+            tasks,
+        }
+    }
+
+    #[test]
+    fn text_lists_projects_when_no_tasks_were_requested() {
+        let rendered = response(None).render(AffectedFormat::Text).unwrap();
+
+        assert_eq!(rendered, "app\nweb\n");
+    }
+
+    #[test]
+    fn text_lists_tasks_when_tasks_were_requested() {
+        let rendered = response(Some(vec!["web#build".to_string()]))
+            .render(AffectedFormat::Text)
+            .unwrap();
+
+        assert_eq!(rendered, "web#build\n");
+    }
+
+    #[test]
+    fn json_includes_reasons() {
+        let rendered = response(None).render(AffectedFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+
+        assert_eq!(value["projects"][1]["reason"], "dependent");
+        assert!(value["tasks"].is_null());
+    }
+}
@@ -4,8 +4,8 @@ use bytesize::ByteSize;
 use derive_new::new;
 use maps::UnorderedMap;
 use omni_cache::{
-    CacheStats, CacheStatsArgs, Context as CacheContext, PruneCacheArgs,
-    PrunedCacheEntry, TaskExecutionCacheStore,
+    CacheStats, CacheStatsArgs, Context as CacheContext, InputChange,
+    InputChangeKind, PruneCacheArgs, PrunedCacheEntry, TaskExecutionCacheStore,
 };
 use omni_configurations::MetaConfiguration;
 use omni_context::{
@@ -55,10 +55,20 @@ pub struct CacheRemoteSetupRequest {
     pub org: String,
     pub ws: String,
     pub env: Option<String>,
+    /// Base64 encoded key to sign and verify the remote cache artifacts with.
+    #[serde(default)]
+    pub signing_key: Option<String>,
     /// Encrypt the remote cache configuration file.
     pub secure: bool,
 }
 
+/// Parameters for [`handle_cache_explain`].
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheExplainRequest {
+    /// The full task name, `project#task`.
+    pub task: String,
+}
+
 // ── Response types ────────────────────────────────────────────────────────────
 
 /// Result of a [`handle_cache_prune`] call.
@@ -72,6 +82,78 @@ pub struct CachePruneResponse {
     pub dry_run: bool,
 }
 
+/// How the current inputs of a task relate to its cached entries.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheExplainStatus {
+    /// An entry exists for the current digest.
+    Hit,
+    /// The task has never been cached.
+    NoEntry,
+    /// The last entry was cached without an input manifest, so it cannot be
+    /// compared.
+    NoManifest,
+    /// The current digest differs from the last cached entry.
+    Miss,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheInputChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl From<InputChangeKind> for CacheInputChangeKind {
+    fn from(kind: InputChangeKind) -> Self {
+        match kind {
+            InputChangeKind::Added => Self::Added,
+            InputChangeKind::Removed => Self::Removed,
+            InputChangeKind::Modified => Self::Modified,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CacheInputChange {
+    pub kind: CacheInputChangeKind,
+    pub name: String,
+}
+
+impl From<InputChange<String>> for CacheInputChange {
+    fn from(change: InputChange<String>) -> Self {
+        Self {
+            kind: change.kind.into(),
+            name: change.name,
+        }
+    }
+}
+
+/// Result of a [`handle_cache_explain`] call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CacheExplainResponse {
+    pub project: String,
+    pub task: String,
+    pub status: CacheExplainStatus,
+    /// bs58-encoded digest of the task's current inputs.
+    pub current_digest: String,
+    /// bs58-encoded digest of the cached entry the inputs were compared to.
+    pub cached_digest: Option<String>,
+    pub files: Vec<CacheInputChange>,
+    pub env_vars: Vec<CacheInputChange>,
+    pub args: Vec<CacheInputChange>,
+    /// Upstream tasks whose digest changed, by full task name.
+    pub dependencies: Vec<CacheInputChange>,
+    pub output_globs: Vec<CacheInputChange>,
+    pub command_changed: bool,
+    pub retry_command_changed: bool,
+}
+
 // ── Handlers ─────────────────────────────────────────────────────────────────
 
 /// Show per-project cache statistics.
@@ -209,6 +291,7 @@ where
         &req.org,
         &req.ws,
         req.env.as_deref(),
+        req.signing_key.as_deref(),
         req.secure,
         ctx.sys(),
     )
@@ -217,6 +300,76 @@ where
     Ok(())
 }
 
+/// Explain why a task misses the cache by comparing its current inputs with
+/// the inputs recorded for its last cached entry.
+pub async fn handle_cache_explain<TSys>(
+    ctx: &LoadedContext<TSys>,
+    req: CacheExplainRequest,
+) -> eyre::Result<CacheExplainResponse>
+where
+    TSys: TaskExecutorSys + Clone,
+{
+    let (project, task) = req.task.split_once('#').ok_or_else(|| {
+        eyre::eyre!("invalid task '{}', expected 'project#task'", req.task)
+    })?;
+
+    let inputs = ctx.get_task_inputs(project, task).await?;
+    let cache_store = ctx.as_context().create_cache_store();
+    let cached = cache_store
+        .get_input_manifest(project, task, &inputs.digest)
+        .await?;
+
+    let mut response = CacheExplainResponse {
+        project: project.to_string(),
+        task: task.to_string(),
+        status: CacheExplainStatus::NoEntry,
+        current_digest: bs58::encode(inputs.digest).into_string(),
+        cached_digest: None,
+        files: vec![],
+        env_vars: vec![],
+        args: vec![],
+        dependencies: vec![],
+        output_globs: vec![],
+        command_changed: false,
+        retry_command_changed: false,
+    };
+
+    let Some(cached) = cached else {
+        return Ok(response);
+    };
+
+    response.cached_digest =
+        Some(bs58::encode(cached.execution.digest).into_string());
+
+    if cached.execution.digest == inputs.digest {
+        response.status = CacheExplainStatus::Hit;
+        return Ok(response);
+    }
+
+    let Some(previous) = cached.manifest else {
+        response.status = CacheExplainStatus::NoManifest;
+        return Ok(response);
+    };
+
+    let diff = inputs.manifest.diff(&previous);
+
+    response.status = CacheExplainStatus::Miss;
+    response.files = diff.files.into_iter().map(Into::into).collect();
+    response.env_vars = diff.env_vars.into_iter().map(Into::into).collect();
+    response.args = diff.args.into_iter().map(Into::into).collect();
+    response.output_globs =
+        diff.output_globs.into_iter().map(Into::into).collect();
+    response.dependencies = diff
+        .dependency_digests
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    response.command_changed = diff.command_changed;
+    response.retry_command_changed = diff.retry_command_changed;
+
+    Ok(response)
+}
+
 // ── CacheContext wrapper ──────────────────────────────────────────────────────
 
 /// Adapts [`LoadedContext`] to the [`omni_cache::Context`] trait so that
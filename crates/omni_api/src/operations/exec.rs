@@ -64,7 +64,9 @@ where
 
     let mut builder = ExecutionConfigBuilder::default();
 
-    builder.call(Call::new_command(req.cmd[0].clone(), req.cmd[1..].to_vec()));
+    builder
+        .record_history(true)
+        .call(Call::new_command(req.cmd[0].clone(), req.cmd[1..].to_vec()));
 
     if let Some(output_logs) = req.output_logs {
         builder.output_logs(output_logs);
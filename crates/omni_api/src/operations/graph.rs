@@ -0,0 +1,485 @@
+use std::fmt::Write as _;
+
+use omni_context::{ContextSys, LoadedContext};
+use omni_core::EdgeType;
+use omni_execution_plan::{
+    Call, DefaultProjectFilter, ExecutionPlanProvider as _, ProjectFilter as _,
+    ScmAffectedFilter,
+};
+use omni_scm::SelectScm;
+use omni_task_executor::ContextExecutionPlanProvider;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sets::{UnorderedSet, unordered_set};
+use strum::{EnumIs, VariantArray};
+
+use super::affected::{changed_files, changed_projects};
+
+// ── Request ─────────────────────────────────────────────────────────────────
+
+/// Output format of an exported graph.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    strum::Display,
+    strum::EnumString,
+    VariantArray,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    #[default]
+    #[strum(serialize = "dot")]
+    Dot,
+    /// Mermaid flowchart.
+    #[strum(serialize = "mermaid")]
+    Mermaid,
+    /// The [`GraphResponse`] itself, serialized as JSON.
+    #[strum(serialize = "json")]
+    Json,
+}
+
+/// Request to export the project graph or the resolved task graph.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GraphRequest {
+    /// Tasks whose resolved execution graph is exported. When empty, the
+    /// project graph is exported instead.
+    pub tasks: Vec<String>,
+    /// Resolve only the requested tasks, without their dependencies.
+    pub ignore_dependencies: bool,
+    /// Also include tasks that depend on the matching tasks.
+    pub with_dependents: bool,
+    /// CEL expression to match against task meta configuration. For the
+    /// project graph, it is matched against project meta configuration.
+    pub meta: Option<String>,
+    /// Glob patterns to match project names. For the project graph, matching
+    /// projects are exported along with all of their dependencies.
+    pub project: Vec<String>,
+    /// Glob patterns to match project directories.
+    pub dir: Vec<String>,
+    /// SCM base commit for affected-files filtering.
+    pub scm_base: Option<String>,
+    /// SCM target commit for affected-files filtering.
+    pub scm_target: Option<String>,
+    /// SCM strategy for affected-files detection.
+    #[schemars(with = "String")]
+    pub scm_affected: SelectScm,
+}
+
+impl Default for GraphRequest {
+    fn default() -> Self {
+        Self {
+            tasks: vec![],
+            ignore_dependencies: false,
+            with_dependents: false,
+            meta: None,
+            project: vec![],
+            dir: vec![],
+            scm_base: None,
+            scm_target: None,
+            scm_affected: SelectScm::None,
+        }
+    }
+}
+
+// ── Response ─────────────────────────────────────────────────────────────────
+
+/// Which graph a [`GraphResponse`] describes.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    EnumIs,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum GraphKind {
+    Project,
+    Task,
+}
+
+/// A project (project graph) or a task (task graph).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GraphNode {
+    /// Project name, or the full task name (`project#task`).
+    pub id: String,
+    pub project: String,
+    /// `None` for project graph nodes.
+    pub task: Option<String>,
+}
+
+/// Directed edge; `from` depends on (or is co-scheduled with) `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    #[schemars(with = "String")]
+    pub edge_type: EdgeType,
+}
+
+/// An exported graph, with nodes and edges sorted for stable output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct GraphResponse {
+    pub kind: GraphKind,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl GraphResponse {
+    fn new(
+        kind: GraphKind,
+        mut nodes: Vec<GraphNode>,
+        mut edges: Vec<GraphEdge>,
+    ) -> Self {
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        edges.sort_by(|a, b| {
+            (&a.from, &a.to, a.edge_type).cmp(&(&b.from, &b.to, b.edge_type))
+        });
+        edges.dedup();
+
+        Self { kind, nodes, edges }
+    }
+
+    /// Render the graph in the requested format.
+    pub fn render(&self, format: GraphFormat) -> eyre::Result<String> {
+        Ok(match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+            GraphFormat::Json => serde_json::to_string_pretty(self)?,
+        })
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let name = if self.kind.is_project() {
+            "projects"
+        } else {
+            "tasks"
+        };
+
+        // `write!` into a `String` never fails.
+        let _ = writeln!(out, "digraph {name} {{");
+        let _ = writeln!(out, "  rankdir=LR;");
+        for node in &self.nodes {
+            let _ = writeln!(out, "  {};", dot_quote(&node.id));
+        }
+        for edge in &self.edges {
+            let attrs = match edge.edge_type {
+                EdgeType::Dependency => "",
+                EdgeType::Sibling => {
+                    " [style=dashed, arrowhead=none, label=\"sibling\"]"
+                }
+            };
+            let _ = writeln!(
+                out,
+                "  {} -> {}{attrs};",
+                dot_quote(&edge.from),
+                dot_quote(&edge.to)
+            );
+        }
+        out.push_str("}\n");
+
+        out
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+
+        // Mermaid ids can't contain `#` and friends, so nodes are referred to
+        // by position and labelled with their real id.
+        let index_of = |id: &str| {
+            self.nodes.binary_search_by(|n| n.id.as_str().cmp(id)).ok()
+        };
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let _ = writeln!(out, "  n{i}[\"{}\"]", mermaid_escape(&node.id));
+        }
+        for edge in &self.edges {
+            let (Some(from), Some(to)) =
+                (index_of(&edge.from), index_of(&edge.to))
+            else {
+                continue;
+            };
+            let arrow = match edge.edge_type {
+                EdgeType::Dependency => "-->",
+                EdgeType::Sibling => "-. sibling .-",
+            };
+            let _ = writeln!(out, "  n{from} {arrow} n{to}");
+        }
+
+        out
+    }
+}
+
+fn dot_quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn mermaid_escape(id: &str) -> String {
+    id.replace('"', "#quot;")
+}
+
+// ── Handlers ─────────────────────────────────────────────────────────────────
+
+/// Export the project graph, or the resolved task graph when `req.tasks` is
+/// not empty.
+pub async fn handle_graph<TSys: ContextSys>(
+    ctx: &LoadedContext<TSys>,
+    req: GraphRequest,
+) -> eyre::Result<GraphResponse> {
+    if req.tasks.is_empty() {
+        project_graph(ctx, &req)
+    } else {
+        task_graph(ctx, &req)
+    }
+}
+
+fn project_graph<TSys: ContextSys>(
+    ctx: &LoadedContext<TSys>,
+    req: &GraphRequest,
+) -> eyre::Result<GraphResponse> {
+    let graph = ctx.get_project_graph()?;
+    let projects = graph.get_projects()?;
+
+    let filters = req.project.iter().map(String::as_str).collect::<Vec<_>>();
+    let dir_filters = req.dir.iter().map(String::as_str).collect::<Vec<_>>();
+    let filter = DefaultProjectFilter::new(&filters, None)?
+        .with_dir_filters(&dir_filters, ctx.root_dir())?;
+    let meta_filter = req
+        .meta
+        .as_deref()
+        .map(omni_expressions::parse)
+        .transpose()?;
+    let matches_meta = |project: &str| {
+        let Some(meta_filter) = &meta_filter else {
+            return eyre::Ok(true);
+        };
+        let meta = match ctx.get_project_meta_config(project) {
+            Some(meta) => meta.clone().into_expression_context()?,
+            None => omni_expressions::Context::default(),
+        };
+
+        Ok(meta_filter.coerce_to_bool(&meta).unwrap_or(false))
+    };
+
+    // Like `omni affected`, a project is affected when it owns a changed file.
+    let affected = scm_filter(req)
+        .map(|scm_filter| {
+            let changed_files = changed_files(ctx.root_dir(), &scm_filter)?;
+            eyre::Ok(changed_projects(ctx.projects(), &changed_files))
+        })
+        .transpose()?;
+
+    let mut included: UnorderedSet<String> = unordered_set!();
+    for project in &projects {
+        if filter.should_include_project(project)?
+            && matches_meta(&project.name)?
+            && affected.as_ref().is_none_or(|a| a.contains(&project.name))
+        {
+            included.insert(project.name.clone());
+            included.extend(
+                graph
+                    .get_all_dependencies_by_name(&project.name)?
+                    .into_iter()
+                    .map(|(_, dep)| dep.name),
+            );
+        }
+    }
+
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    for project in projects
+        .iter()
+        .filter(|p| included.contains(p.name.as_str()))
+    {
+        nodes.push(GraphNode {
+            id: project.name.clone(),
+            project: project.name.clone(),
+            task: None,
+        });
+
+        for dep in &project.dependencies {
+            if included.contains(dep.as_str()) {
+                edges.push(GraphEdge {
+                    from: project.name.clone(),
+                    to: dep.clone(),
+                    edge_type: EdgeType::Dependency,
+                });
+            }
+        }
+    }
+
+    Ok(GraphResponse::new(GraphKind::Project, nodes, edges))
+}
+
+/// The affected-files filter of `req`. Giving a base or a target commit
+/// without a strategy detects the SCM automatically.
+fn scm_filter(req: &GraphRequest) -> Option<ScmAffectedFilter> {
+    let mut scm = req.scm_affected;
+    if scm.is_none() && (req.scm_base.is_some() || req.scm_target.is_some()) {
+        scm = SelectScm::Auto;
+    }
+
+    (!scm.is_none()).then(|| ScmAffectedFilter {
+        base: req.scm_base.clone(),
+        scm,
+        target: req.scm_target.clone(),
+    })
+}
+
+fn task_graph<TSys: ContextSys>(
+    ctx: &LoadedContext<TSys>,
+    req: &GraphRequest,
+) -> eyre::Result<GraphResponse> {
+    let project_filters =
+        req.project.iter().map(String::as_str).collect::<Vec<_>>();
+    let dir_filters = req.dir.iter().map(String::as_str).collect::<Vec<_>>();
+    let scm_filter = scm_filter(req);
+
+    let plan = ContextExecutionPlanProvider::new(ctx).get_execution_plan(
+        &Call::new_tasks(&req.tasks[..]),
+        &project_filters,
+        &dir_filters,
+        req.meta.as_deref(),
+        None,
+        scm_filter.as_ref(),
+        req.ignore_dependencies,
+        req.with_dependents,
+    )?;
+
+    let nodes = plan.iter().flatten().collect::<Vec<_>>();
+    let in_plan = nodes
+        .iter()
+        .map(|n| n.full_task_name())
+        .collect::<UnorderedSet<_>>();
+
+    let mut edges = vec![];
+    for node in &nodes {
+        let targets = node
+            .dependencies()
+            .iter()
+            .map(|d| (d, EdgeType::Dependency))
+            .chain(node.siblings().iter().map(|s| (s, EdgeType::Sibling)));
+
+        for (to, edge_type) in targets {
+            // With `ignore_dependencies` the plan may reference tasks that
+            // were not resolved; only keep edges between exported nodes.
+            if in_plan.contains(to.as_str()) {
+                edges.push(GraphEdge {
+                    from: node.full_task_name().to_string(),
+                    to: to.clone(),
+                    edge_type,
+                });
+            }
+        }
+    }
+
+    let nodes = nodes
+        .into_iter()
+        .map(|n| GraphNode {
+            id: n.full_task_name().to_string(),
+            project: n.project_name().to_string(),
+            task: Some(n.task_name().to_string()),
+        })
+        .collect();
+
+    Ok(GraphResponse::new(GraphKind::Task, nodes, edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_node(project: &str, task: &str) -> GraphNode {
+        GraphNode {
+            id: format!("{project}#{task}"),
+            project: project.to_string(),
+            task: Some(task.to_string()),
+        }
+    }
+
+    fn edge(from: &str, to: &str, edge_type: EdgeType) -> GraphEdge {
+        GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            edge_type,
+        }
+    }
+
+    fn sample() -> GraphResponse {
+        GraphResponse::new(
+            GraphKind::Task,
+            vec![
+                task_node("b", "build"),
+                task_node("a", "build"),
+                task_node("a", "dev"),
+            ],
+            vec![
+                edge("a#build", "b#build", EdgeType::Dependency),
+                edge("a#dev", "a#build", EdgeType::Sibling),
+                edge("a#build", "b#build", EdgeType::Dependency),
+            ],
+        )
+    }
+
+    #[test]
+    fn new_sorts_nodes_and_dedups_edges() {
+        let graph = sample();
+
+        let ids = graph
+            .nodes
+            .iter()
+            .map(|n| n.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["a#build", "a#dev", "b#build"]);
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn renders_dot_with_edge_types() {
+        let dot = sample().render(GraphFormat::Dot).unwrap();
+
+        assert!(dot.starts_with("digraph tasks {\n"));
+        assert!(dot.contains("  \"a#build\";\n"));
+        assert!(dot.contains("  \"a#build\" -> \"b#build\";\n"));
+        assert!(dot.contains(
+            "  \"a#dev\" -> \"a#build\" [style=dashed, arrowhead=none, label=\"sibling\"];\n"
+        ));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn renders_mermaid_with_positional_ids() {
+        let mermaid = sample().render(GraphFormat::Mermaid).unwrap();
+
+        assert_eq!(
+            mermaid,
+            "flowchart LR\n  n0[\"a#build\"]\n  n1[\"a#dev\"]\n  n2[\"b#build\"]\n  n0 --> n2\n  n1 -. sibling .- n0\n"
+        );
+    }
+
+    #[test]
+    fn renders_json_round_trip() {
+        let graph = sample();
+        let json = graph.render(GraphFormat::Json).unwrap();
+
+        let parsed: GraphResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, graph);
+    }
+
+    #[test]
+    fn dot_quotes_special_characters() {
+        assert_eq!(dot_quote(r#"a"b\c"#), r#""a\"b\\c""#);
+    }
+}
@@ -0,0 +1,119 @@
+use omni_context::{Context, ContextSys};
+use omni_run_history::{
+    Regression, RegressionOptions, RunHistory, RunRecord, TaskDuration,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ── Request types ─────────────────────────────────────────────────────────────
+
+/// Parameters for [`handle_history_list`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryListRequest {
+    /// Only return the latest runs. If `None`, return all recorded runs.
+    pub limit: Option<usize>,
+}
+
+/// Parameters for [`handle_history_task`].
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryTaskRequest {
+    /// The full task name, `project#task`.
+    pub task: String,
+    /// Only look at the latest runs. If `None`, look at all recorded runs.
+    pub limit: Option<usize>,
+}
+
+/// Parameters for [`handle_history_regressions`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryRegressionsRequest {
+    /// How many times slower than its baseline a task has to be, `1.5` by
+    /// default.
+    pub threshold: Option<f64>,
+    /// How many previous executions make up the baseline, `10` by default.
+    pub window: Option<usize>,
+}
+
+// ── Response types ────────────────────────────────────────────────────────────
+
+/// Result of a [`handle_history_list`] call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryListResponse {
+    /// The runs, newest first.
+    pub runs: Vec<RunRecord>,
+}
+
+/// Result of a [`handle_history_task`] call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryTaskResponse {
+    pub task: String,
+    /// The runs that included the task, newest first.
+    pub durations: Vec<TaskDuration>,
+}
+
+/// Result of a [`handle_history_regressions`] call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryRegressionsResponse {
+    /// How many runs were looked at.
+    pub runs: usize,
+    /// The regressed tasks, most regressed first.
+    pub regressions: Vec<Regression>,
+}
+
+// ── Handlers ─────────────────────────────────────────────────────────────────
+
+/// List the recorded runs of the workspace.
+pub async fn handle_history_list<TSys: ContextSys>(
+    ctx: &Context<TSys>,
+    req: HistoryListRequest,
+) -> eyre::Result<HistoryListResponse> {
+    let runs = RunHistory::new(ctx.cache_dir())
+        .latest(req.limit.unwrap_or(usize::MAX))
+        .await?;
+
+    Ok(HistoryListResponse { runs })
+}
+
+/// Return the run with `run_id`, or the latest run if `run_id` is `None`.
+pub async fn handle_history_show<TSys: ContextSys>(
+    ctx: &Context<TSys>,
+    run_id: Option<&str>,
+) -> eyre::Result<Option<RunRecord>> {
+    Ok(RunHistory::new(ctx.cache_dir()).get(run_id).await?)
+}
+
+/// Return the durations of a task across the recorded runs.
+pub async fn handle_history_task<TSys: ContextSys>(
+    ctx: &Context<TSys>,
+    req: HistoryTaskRequest,
+) -> eyre::Result<HistoryTaskResponse> {
+    let runs = RunHistory::new(ctx.cache_dir())
+        .latest(req.limit.unwrap_or(usize::MAX))
+        .await?;
+
+    Ok(HistoryTaskResponse {
+        durations: omni_run_history::task_durations(&runs, &req.task),
+        task: req.task,
+    })
+}
+
+/// Find the tasks whose latest execution was slower than their previous
+/// ones.
+pub async fn handle_history_regressions<TSys: ContextSys>(
+    ctx: &Context<TSys>,
+    req: HistoryRegressionsRequest,
+) -> eyre::Result<HistoryRegressionsResponse> {
+    let mut options = RegressionOptions::default();
+    if let Some(threshold) = req.threshold {
+        options.threshold = threshold;
+    }
+    if let Some(window) = req.window {
+        options.window = window;
+    }
+
+    let runs = RunHistory::new(ctx.cache_dir()).runs().await?;
+
+    Ok(HistoryRegressionsResponse {
+        regressions: omni_run_history::regressions(&runs, &options),
+        runs: runs.len(),
+    })
+}
@@ -1,9 +1,12 @@
+pub mod affected;
 pub mod cache;
 pub mod config_schema;
 pub mod env;
 pub mod exec;
 pub mod generator;
+pub mod graph;
 pub mod hash;
+pub mod history;
 pub mod project;
 pub mod task;
 pub mod tool;
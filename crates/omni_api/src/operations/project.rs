@@ -1,14 +1,56 @@
 use omni_configurations::ProjectConfiguration;
 use omni_context::{Context, ContextSys};
+use omni_execution_plan::{TagFilter, TagMatcher};
+use omni_utils::glob::NegatableGlobSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// ── Request ─────────────────────────────────────────────────────────────────
+
+/// Parameters for [`handle_project_list`]. Every filter is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectListRequest {
+    /// Glob patterns to match project names, `!pattern` excludes.
+    #[serde(default)]
+    pub project: Vec<String>,
+    /// Glob patterns; only projects with a matching tag are listed.
+    #[serde(default)]
+    pub tag: Vec<String>,
+    /// Glob patterns; projects with a matching tag are not listed.
+    #[serde(default)]
+    pub exclude_tag: Vec<String>,
+}
 
 // ── Handlers ─────────────────────────────────────────────────────────────────
 
-/// List the names of all projects in the workspace.
+/// List the names of the projects in the workspace that match `req`.
 pub async fn handle_project_list<TSys: ContextSys>(
     ctx: &Context<TSys>,
+    req: &ProjectListRequest,
 ) -> eyre::Result<Vec<String>> {
     let loaded = ctx.load_project_configurations().await?;
-    Ok(loaded.into_iter().map(|p| p.name).collect())
+    let name_matcher = if req.project.is_empty() {
+        None
+    } else {
+        Some(NegatableGlobSet::new(&req.project)?)
+    };
+    let tag_filter = TagFilter::new(req.tag.clone(), req.exclude_tag.clone());
+    let tag_matcher = if tag_filter.is_empty() {
+        None
+    } else {
+        Some(TagMatcher::new(&tag_filter)?)
+    };
+
+    Ok(loaded
+        .into_iter()
+        .filter(|p| name_matcher.as_ref().is_none_or(|m| m.is_match(&p.name)))
+        .filter(|p| {
+            tag_matcher
+                .as_ref()
+                .is_none_or(|m| m.is_match_tags(&p.tags.to_vec_inner()))
+        })
+        .map(|p| p.name)
+        .collect())
 }
 
 /// Return the full configuration for the named project.
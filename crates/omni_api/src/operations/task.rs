@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use maps::UnorderedMap;
 use omni_context::LoadedContext;
-use omni_execution_plan::{Call, ScmAffectedFilter};
+use omni_execution_plan::{Call, ScmAffectedFilter, TagFilter};
 use omni_messages::ExecutionEventSubscriber;
 use omni_scm::SelectScm;
 use omni_task_executor::{
@@ -24,6 +24,10 @@ pub struct TaskRunFilters {
     pub project: Vec<String>,
     /// Glob patterns to match project directories.
     pub dir: Vec<String>,
+    /// Glob patterns; only projects with a matching tag are included.
+    pub tag: Vec<String>,
+    /// Glob patterns; projects with a matching tag are excluded.
+    pub exclude_tag: Vec<String>,
     /// Maximum number of tasks to run concurrently.
     pub max_concurrency: Option<usize>,
     /// Print commands instead of executing them.
@@ -39,6 +43,8 @@ pub struct TaskRunFilters {
     pub retry: Option<u8>,
     /// Override retry interval for all tasks.
     pub retry_interval: Option<Duration>,
+    /// Override the execution timeout for all tasks.
+    pub task_timeout: Option<Duration>,
     /// Extra key=value arguments forwarded to tasks.
     pub args: Vec<(String, String)>,
 }
@@ -49,6 +55,8 @@ impl Default for TaskRunFilters {
             meta: None,
             project: vec![],
             dir: vec![],
+            tag: vec![],
+            exclude_tag: vec![],
             max_concurrency: None,
             dry_run: false,
             scm_base: None,
@@ -56,6 +64,7 @@ impl Default for TaskRunFilters {
             scm_affected: SelectScm::None,
             retry: None,
             retry_interval: None,
+            task_timeout: None,
             args: vec![],
         }
     }
@@ -145,6 +154,7 @@ where
         .on_failure(req.on_failure)
         .no_cache(req.no_cache)
         .force(req.force)
+        .record_history(true)
         .call(Call::new_tasks(&req.tasks[..]));
 
     if let Some(output_logs) = req.output_logs {
@@ -177,6 +187,13 @@ pub(crate) fn apply_filters(
     builder.project_filters(filters.project.clone());
     builder.dir_filters(filters.dir.clone());
 
+    if !filters.tag.is_empty() || !filters.exclude_tag.is_empty() {
+        builder.tag_filter(TagFilter::new(
+            filters.tag.clone(),
+            filters.exclude_tag.clone(),
+        ));
+    }
+
     if let Some(max_conc) = filters.max_concurrency {
         builder.max_concurrency(max_conc);
     }
@@ -191,6 +208,10 @@ pub(crate) fn apply_filters(
         builder.retry_interval(retry_interval);
     }
 
+    if let Some(task_timeout) = filters.task_timeout {
+        builder.timeout(task_timeout);
+    }
+
     let mut scm = filters.scm_affected;
     if scm.is_none()
         && (filters.scm_base.is_some() || filters.scm_target.is_some())
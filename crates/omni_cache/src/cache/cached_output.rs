@@ -1,7 +1,10 @@
 use std::path::PathBuf;
 
 use derive_new::new;
-use omni_hasher::impls::DefaultHash;
+use omni_hasher::{
+    Hasher as _,
+    impls::{DefaultHash, DefaultHasher},
+};
 use omni_types::OmniPath;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
@@ -61,6 +64,36 @@ pub struct CachedTaskExecution {
     #[new(into)]
     #[serde(default = "default_tries")]
     pub tries: u8,
+
+    /// Digest of the content hashes of `files`, see [`outputs_digest`].
+    /// `None` for entries cached before output hashes were recorded.
+    #[new(default)]
+    #[serde(default)]
+    pub outputs_digest: Option<DefaultHash>,
+}
+
+impl CachedTaskExecution {
+    /// Whether the files on disk, whose content hashes are `current` (`None`
+    /// when missing) in the same order as `files`, are exactly the cached
+    /// outputs, in which case nothing needs to be restored.
+    pub fn outputs_up_to_date(&self, current: &[Option<DefaultHash>]) -> bool {
+        let Some(expected) = self.outputs_digest else {
+            return false;
+        };
+
+        current.len() == self.files.len()
+            && current
+                .iter()
+                .copied()
+                .collect::<Option<Vec<_>>>()
+                .is_some_and(|current| outputs_digest(&current) == expected)
+    }
+}
+
+/// Digest of the concatenated content hashes of a task's output files, in the
+/// order they are stored in the cache entry.
+pub fn outputs_digest(hashes: &[DefaultHash]) -> DefaultHash {
+    DefaultHasher::hash(&hashes.concat())
 }
 
 #[inline(always)]
@@ -112,4 +145,103 @@ pub struct CachedFileOutput {
     /// Canonical path to the original file
     #[new(into)]
     pub original_path: OmniPath,
+
+    /// Hash of the file content when it was cached
+    #[new(default)]
+    #[serde(default)]
+    pub hash: Option<DefaultHash>,
+}
+
+impl CachedFileOutput {
+    /// Whether the file on disk, whose content hash is `current` (`None` when
+    /// missing), has to be (re)written from the cache.
+    pub fn needs_restore(&self, current: Option<&DefaultHash>) -> bool {
+        match (current, &self.hash) {
+            (None, _) => true,
+            (Some(current), Some(cached)) => current != cached,
+            // Entries cached before hashes were recorded can't tell, keep
+            // the existing file.
+            (Some(_), None) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(hash: Option<DefaultHash>) -> CachedFileOutput {
+        CachedFileOutput {
+            cached_path: PathBuf::from("a.cache"),
+            original_path: OmniPath::new("dist/a.txt"),
+            hash,
+        }
+    }
+
+    fn execution(files: Vec<CachedFileOutput>) -> CachedTaskExecution {
+        let hashes = files.iter().filter_map(|f| f.hash).collect::<Vec<_>>();
+        CachedTaskExecution {
+            project_name: "project".to_string(),
+            task_name: "build".to_string(),
+            task_exec: None,
+            task_retry_exec: None,
+            digest: [0; 32],
+            dependency_digests: vec![],
+            logs_path: None,
+            files,
+            exit_code: 0,
+            execution_duration: std::time::Duration::ZERO,
+            execution_time: OffsetDateTime::UNIX_EPOCH,
+            tries: 1,
+            outputs_digest: Some(outputs_digest(&hashes)),
+        }
+    }
+
+    #[test]
+    fn test_outputs_up_to_date() {
+        let execution =
+            execution(vec![file(Some([1; 32])), file(Some([2; 32]))]);
+
+        assert!(execution.outputs_up_to_date(&[Some([1; 32]), Some([2; 32])]));
+        assert!(!execution.outputs_up_to_date(&[Some([1; 32]), Some([3; 32])]));
+        assert!(!execution.outputs_up_to_date(&[Some([1; 32]), None]));
+        assert!(!execution.outputs_up_to_date(&[Some([1; 32])]));
+    }
+
+    #[test]
+    fn test_outputs_up_to_date_without_digest() {
+        let mut execution = execution(vec![file(Some([1; 32]))]);
+        execution.outputs_digest = None;
+
+        assert!(!execution.outputs_up_to_date(&[Some([1; 32])]));
+    }
+
+    #[test]
+    fn test_needs_restore() {
+        assert!(file(Some([1; 32])).needs_restore(None));
+        assert!(file(Some([1; 32])).needs_restore(Some(&[2; 32])));
+        assert!(!file(Some([1; 32])).needs_restore(Some(&[1; 32])));
+        assert!(file(None).needs_restore(None));
+        assert!(!file(None).needs_restore(Some(&[2; 32])));
+    }
+
+    #[test]
+    fn test_decode_entry_without_hashes() {
+        #[derive(Serialize)]
+        struct LegacyFileOutput {
+            cached_path: PathBuf,
+            original_path: OmniPath,
+        }
+
+        let bytes = rmp_serde::encode::to_vec(&LegacyFileOutput {
+            cached_path: PathBuf::from("a.cache"),
+            original_path: OmniPath::new("dist/a.txt"),
+        })
+        .expect("should encode");
+
+        let decoded: CachedFileOutput =
+            rmp_serde::decode::from_slice(&bytes).expect("should decode");
+
+        assert_eq!(decoded, file(None));
+    }
 }
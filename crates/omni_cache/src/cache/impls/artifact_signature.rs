@@ -0,0 +1,245 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use base64::Engine as _;
+use ring::{digest, hmac};
+
+use crate::ArtifactRejection;
+
+/// Marks a signed artifact. It is followed by the SHA-256 digest of the
+/// archive and the HMAC-SHA256 signature binding that digest to the cache
+/// entry, then by the archive itself.
+const SIGNATURE_MAGIC: &[u8; 8] = b"OMNISIG1";
+const TAG_LEN: usize = 32;
+
+/// Size of the header in front of the archive of a signed artifact.
+pub const SIGNATURE_HEADER_LEN: u64 =
+    (SIGNATURE_MAGIC.len() + digest::SHA256_OUTPUT_LEN + TAG_LEN) as u64;
+
+/// Keys shorter than this are rejected, HMAC-SHA256 is only as strong as its
+/// key.
+const MIN_KEY_LEN: usize = 32;
+
+/// Signs and verifies remote cache artifacts with a key shared by everyone
+/// using the cache.
+#[derive(Clone, Debug)]
+pub struct ArtifactSigner {
+    key: hmac::Key,
+}
+
+impl ArtifactSigner {
+    /// Create a signer from a base64 encoded key of at least 32 bytes.
+    pub fn from_base64(key: &str) -> Result<Self, String> {
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(key.trim())
+            .map_err(|e| format!("key is not valid base64: {e}"))?;
+
+        if key.len() < MIN_KEY_LEN {
+            return Err(format!(
+                "key is {} bytes long, expected at least {MIN_KEY_LEN}",
+                key.len()
+            ));
+        }
+
+        Ok(Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, &key),
+        })
+    }
+
+    /// Sign the artifact of the cache entry `cache_digest` in `file`, whose
+    /// archive was written after [`SIGNATURE_HEADER_LEN`] reserved bytes.
+    pub fn sign<F: Read + Write + Seek>(
+        &self,
+        mut file: F,
+        cache_digest: &str,
+    ) -> io::Result<()> {
+        file.seek(SeekFrom::Start(SIGNATURE_HEADER_LEN))?;
+        let content_digest = sha256(&mut file)?;
+        let tag = hmac::sign(
+            &self.key,
+            &signed_message(cache_digest, &content_digest),
+        );
+
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(SIGNATURE_MAGIC)?;
+        file.write_all(&content_digest)?;
+        file.write_all(tag.as_ref())?;
+        file.flush()
+    }
+
+    /// Verify the artifact of the cache entry `cache_digest` in `file`. On
+    /// success its archive starts at [`SIGNATURE_HEADER_LEN`].
+    pub fn verify<F: Read + Seek>(
+        &self,
+        mut file: F,
+        cache_digest: &str,
+    ) -> io::Result<Result<(), ArtifactRejection>> {
+        let Some(header) = read_header(&mut file)? else {
+            return Ok(Err(ArtifactRejection::Unsigned));
+        };
+
+        let (content_digest, tag) = header.split_at(digest::SHA256_OUTPUT_LEN);
+
+        if sha256(&mut file)? != content_digest {
+            return Ok(Err(ArtifactRejection::DigestMismatch));
+        }
+
+        Ok(hmac::verify(
+            &self.key,
+            &signed_message(cache_digest, content_digest),
+            tag,
+        )
+        .map_err(|_| ArtifactRejection::InvalidSignature))
+    }
+}
+
+/// Where the archive of the artifact in `file` starts, for reading artifacts
+/// without verifying them. Unsigned artifacts are archives as-is.
+pub fn archive_offset<F: Read + Seek>(mut file: F) -> io::Result<u64> {
+    Ok(if read_header(&mut file)?.is_some() {
+        SIGNATURE_HEADER_LEN
+    } else {
+        0
+    })
+}
+
+/// Read the signature header, returning the digest and tag following the
+/// magic bytes, or `None` if the artifact isn't signed.
+fn read_header<F: Read + Seek>(file: &mut F) -> io::Result<Option<Vec<u8>>> {
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut header = Vec::with_capacity(SIGNATURE_HEADER_LEN as usize);
+    file.by_ref()
+        .take(SIGNATURE_HEADER_LEN)
+        .read_to_end(&mut header)?;
+
+    if header.len() as u64 != SIGNATURE_HEADER_LEN
+        || !header.starts_with(SIGNATURE_MAGIC)
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(header.split_off(SIGNATURE_MAGIC.len())))
+}
+
+/// The cache digest is signed along with the content, so a validly signed
+/// artifact can't be served for another entry.
+fn signed_message(cache_digest: &str, content_digest: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(
+        SIGNATURE_MAGIC.len() + cache_digest.len() + 1 + content_digest.len(),
+    );
+    message.extend_from_slice(SIGNATURE_MAGIC);
+    message.extend_from_slice(cache_digest.as_bytes());
+    message.push(0);
+    message.extend_from_slice(content_digest);
+    message
+}
+
+fn sha256<R: Read>(mut reader: R) -> io::Result<Vec<u8>> {
+    let mut context = digest::Context::new(&digest::SHA256);
+    let mut buf = vec![0u8; 64 * 1024];
+
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.update(&buf[..n]);
+    }
+
+    Ok(context.finish().as_ref().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+
+    fn signed(
+        signer: &ArtifactSigner,
+        digest: &str,
+        content: &[u8],
+    ) -> Vec<u8> {
+        let mut file = Cursor::new(vec![0u8; SIGNATURE_HEADER_LEN as usize]);
+        file.seek(SeekFrom::End(0)).expect("should seek");
+        file.write_all(content).expect("should write");
+        signer.sign(&mut file, digest).expect("should sign");
+        file.into_inner()
+    }
+
+    #[test]
+    fn test_verify_signed_artifact() {
+        let signer = ArtifactSigner::from_base64(KEY).expect("valid key");
+        let artifact = signed(&signer, "digest", b"archive");
+
+        let result = signer
+            .verify(Cursor::new(&artifact), "digest")
+            .expect("should read");
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            &artifact[SIGNATURE_HEADER_LEN as usize..],
+            b"archive".as_slice()
+        );
+        assert_eq!(
+            archive_offset(Cursor::new(&artifact)).expect("should read"),
+            SIGNATURE_HEADER_LEN
+        );
+    }
+
+    #[test]
+    fn test_reject_unsigned_artifact() {
+        let signer = ArtifactSigner::from_base64(KEY).expect("valid key");
+
+        let result = signer
+            .verify(Cursor::new(b"archive"), "digest")
+            .expect("should read");
+
+        assert_eq!(result, Err(ArtifactRejection::Unsigned));
+        assert_eq!(
+            archive_offset(Cursor::new(b"archive")).expect("should read"),
+            0
+        );
+    }
+
+    #[test]
+    fn test_reject_tampered_artifact() {
+        let signer = ArtifactSigner::from_base64(KEY).expect("valid key");
+        let mut artifact = signed(&signer, "digest", b"archive");
+        *artifact.last_mut().unwrap() ^= 1;
+
+        let result = signer
+            .verify(Cursor::new(&artifact), "digest")
+            .expect("should read");
+
+        assert_eq!(result, Err(ArtifactRejection::DigestMismatch));
+    }
+
+    #[test]
+    fn test_reject_artifact_of_other_entry_or_key() {
+        let signer = ArtifactSigner::from_base64(KEY).expect("valid key");
+        let artifact = signed(&signer, "digest", b"archive");
+
+        let result = signer
+            .verify(Cursor::new(&artifact), "other-digest")
+            .expect("should read");
+        assert_eq!(result, Err(ArtifactRejection::InvalidSignature));
+
+        let other = ArtifactSigner::from_base64(
+            "ZmVkY2JhOTg3NjU0MzIxMGZlZGNiYTk4NzY1NDMyMTA=",
+        )
+        .expect("valid key");
+        let result = other
+            .verify(Cursor::new(&artifact), "digest")
+            .expect("should read");
+        assert_eq!(result, Err(ArtifactRejection::InvalidSignature));
+    }
+
+    #[test]
+    fn test_reject_short_key() {
+        assert!(ArtifactSigner::from_base64("c2hvcnQ=").is_err());
+        assert!(ArtifactSigner::from_base64("not base64!").is_err());
+    }
+}
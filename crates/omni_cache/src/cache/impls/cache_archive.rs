@@ -1,38 +1,233 @@
-use std::io;
-use std::path::Path;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Seek as _, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 
 use flate2::Compression;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
-use tar::{Archive, Builder};
+use omni_configurations::ArchiveCompression;
+use rayon::prelude::*;
+use tar::{Archive, Builder, EntryType};
 
-pub fn archive<W: io::Write>(src_dir: &Path, dst: W) -> io::Result<()> {
-    // Wrap with gzip encoder
-    let enc = GzEncoder::new(dst, Compression::default());
-    // Create tar builder on top of gzip
+/// Header in front of archives recording the codec they are compressed with.
+/// Gzip archives are written without one, so that omni versions predating the
+/// header can still read them, and an archive without a header is gzip.
+const CODEC_HEADER_MAGIC: &[u8; 8] = b"OMNIARC1";
+const CODEC_HEADER_LEN: u64 = CODEC_HEADER_MAGIC.len() as u64 + 1;
+const ZSTD_CODEC: u8 = 1;
+
+/// Archives at least this large (compressed) have their files written in
+/// parallel by [`unarchive_file`].
+pub const PARALLEL_UNARCHIVE_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// Upper bound of file contents buffered in memory before they are flushed to
+/// disk during a parallel extraction.
+const PARALLEL_UNARCHIVE_BATCH_SIZE: usize = 64 * 1024 * 1024;
+
+pub fn archive<W: io::Write>(
+    src_dir: &Path,
+    mut dst: W,
+    compression: ArchiveCompression,
+) -> io::Result<()> {
+    match compression {
+        ArchiveCompression::Gzip { level } => {
+            let level = level.map(Compression::new).unwrap_or_default();
+            append_dir(src_dir, GzEncoder::new(dst, level))?.finish()?;
+        }
+        ArchiveCompression::Zstd { level } => {
+            dst.write_all(CODEC_HEADER_MAGIC)?;
+            dst.write_all(&[ZSTD_CODEC])?;
+            append_dir(src_dir, zstd::Encoder::new(dst, level)?)?.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn append_dir<W: io::Write>(src_dir: &Path, enc: W) -> io::Result<W> {
     let mut tar = Builder::new(enc);
 
     // Append directory *contents* into the archive with root prefix "." so extraction will restore contents into target dir.
     // If you want the directory itself to be included as the top-level entry, change the prefix.
     tar.append_dir_all(".", src_dir)?;
 
-    // Finish writing and ensure gzip is flushed
-    let enc = tar.into_inner()?;
-    enc.finish()?;
-    Ok(())
+    tar.into_inner()
 }
 
-pub fn unarchive<R: io::Read>(dst_dir: &Path, src: R) -> io::Result<()> {
-    // Wrap with gzip decoder
-    let decoder = GzDecoder::new(src);
-    // Create tar archive reader
-    let mut archive = Archive::new(decoder);
+/// Unpack an archive created by [`archive`] into `dst_dir`. The codec is read
+/// from the archive's header, so entries written with any codec restore.
+pub fn unarchive<R: BufRead>(dst_dir: &Path, src: R) -> io::Result<()> {
+    let mut archive = Archive::new(decoder(src)?);
 
     // Unpack into dst_dir (will create directories as needed)
     archive.unpack(dst_dir)?;
     Ok(())
 }
 
+/// Same as [`unarchive`], but the archive is read from `src_file` starting
+/// `offset` bytes in, and for archives of at least
+/// [`PARALLEL_UNARCHIVE_THRESHOLD`] bytes the regular files are written to
+/// disk in parallel. Decompression itself is sequential.
+pub fn unarchive_file(
+    dst_dir: &Path,
+    src_file: &Path,
+    offset: u64,
+) -> io::Result<()> {
+    let mut file = fs::File::open(src_file)?;
+    let size = file.metadata()?.len().saturating_sub(offset);
+    file.seek(SeekFrom::Start(offset))?;
+    let src = BufReader::new(file);
+
+    if size < PARALLEL_UNARCHIVE_THRESHOLD as u64 {
+        return unarchive(dst_dir, src);
+    }
+
+    unarchive_entries_parallel(dst_dir, src)
+}
+
+fn unarchive_entries_parallel<R: BufRead>(
+    dst_dir: &Path,
+    src: R,
+) -> io::Result<()> {
+    let mut archive = Archive::new(decoder(src)?);
+    let mut pending = vec![];
+    let mut pending_size = 0;
+
+    fs::create_dir_all(dst_dir)?;
+    let dst_dir = &dst_dir.canonicalize()?;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+
+        match entry.header().entry_type() {
+            EntryType::Regular | EntryType::Continuous => {
+                // Entries with a path escaping `dst_dir` are skipped, symlinks
+                // redirecting them are rejected by `write_files`.
+                let Some(path) = sanitize_entry_path(&entry.path()?) else {
+                    continue;
+                };
+                let mode = entry.header().mode().ok();
+                let mut contents = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut contents)?;
+
+                pending_size += contents.len();
+                pending.push((dst_dir.join(path), contents, mode));
+
+                if pending_size >= PARALLEL_UNARCHIVE_BATCH_SIZE {
+                    write_files(dst_dir, &mut pending)?;
+                    pending_size = 0;
+                }
+            }
+            EntryType::Directory => {
+                entry.unpack_in(dst_dir)?;
+            }
+            _ => {
+                // Links may point at files that are still pending.
+                write_files(dst_dir, &mut pending)?;
+                pending_size = 0;
+                entry.unpack_in(dst_dir)?;
+            }
+        }
+    }
+
+    write_files(dst_dir, &mut pending)
+}
+
+fn decoder<'a, R: BufRead + 'a>(mut src: R) -> io::Result<Box<dyn Read + 'a>> {
+    let mut header = Vec::with_capacity(CODEC_HEADER_LEN as usize);
+    src.by_ref()
+        .take(CODEC_HEADER_LEN)
+        .read_to_end(&mut header)?;
+
+    match header.strip_prefix(CODEC_HEADER_MAGIC) {
+        Some([ZSTD_CODEC]) => Ok(Box::new(zstd::Decoder::with_buffer(src)?)),
+        Some(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unknown cache archive codec",
+        )),
+        // Not a header but the start of a gzip archive.
+        None => {
+            Ok(Box::new(GzDecoder::new(io::Cursor::new(header).chain(src))))
+        }
+    }
+}
+
+fn sanitize_entry_path(path: &Path) -> Option<PathBuf> {
+    let mut sanitized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => sanitized.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    (!sanitized.as_os_str().is_empty()).then_some(sanitized)
+}
+
+/// Write the pending files, all of which are below the canonical `dst_dir`.
+fn write_files(
+    dst_dir: &Path,
+    files: &mut Vec<(PathBuf, Vec<u8>, Option<u32>)>,
+) -> io::Result<()> {
+    files.par_drain(..).try_for_each(|(path, contents, mode)| {
+        if let Some(parent) = path.parent() {
+            ensure_inside(dst_dir, parent)?;
+            fs::create_dir_all(parent)?;
+        }
+
+        // A symlink unpacked earlier at the same path is replaced rather than
+        // written through.
+        if fs::symlink_metadata(&path).is_ok_and(|m| m.is_symlink()) {
+            fs::remove_file(&path)?;
+        }
+
+        fs::write(&path, contents)?;
+        set_mode(&path, mode)
+    })
+}
+
+/// Fail if `dir` resolves to a directory outside of the canonical `dst_dir`,
+/// e.g. because one of its parents is a symlink unpacked from the archive.
+/// Only the part of `dir` that already exists is resolved, so nothing is
+/// created outside of `dst_dir` before the check.
+fn ensure_inside(dst_dir: &Path, dir: &Path) -> io::Result<()> {
+    let existing = dir
+        .ancestors()
+        .find(|a| fs::symlink_metadata(a).is_ok())
+        .unwrap_or(dir);
+
+    if existing.canonicalize()?.starts_with(dst_dir) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "archive entry in {} is outside of {}",
+                dir.display(),
+                dst_dir.display()
+            ),
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: Option<u32>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    if let Some(mode) = mode {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: Option<u32>) -> io::Result<()> {
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
@@ -63,7 +258,8 @@ mod tests {
         let path = dir.path();
 
         let mut buff = Cursor::new(Vec::new());
-        archive(path, &mut buff).expect("failed to archive");
+        archive(path, &mut buff, ArchiveCompression::default())
+            .expect("failed to archive");
 
         let buff = buff.into_inner();
         assert!(!buff.is_empty(), "should have data");
@@ -76,10 +272,11 @@ mod tests {
         let path = dir.path();
 
         let mut buff = Cursor::new(Vec::new());
-        archive(path, &mut buff).expect("failed to archive");
+        archive(path, &mut buff, ArchiveCompression::default())
+            .expect("failed to archive");
 
-        let mut buff2 = Cursor::new(buff.into_inner());
-        unarchive(path, &mut buff2).expect("failed to unarchive");
+        let buff2 = Cursor::new(buff.into_inner());
+        unarchive(path, buff2).expect("failed to unarchive");
 
         assert!(
             std::fs::read_to_string(path.join("a/a.txt"))
@@ -100,4 +297,227 @@ mod tests {
             "c.txt should exist"
         );
     }
+
+    fn assert_fixture_restored(path: &Path) {
+        for name in ["a", "b", "c"] {
+            assert_eq!(
+                std::fs::read_to_string(
+                    path.join(format!("{name}/{name}.txt"))
+                )
+                .expect("failed to read file"),
+                name,
+                "{name}.txt should be restored"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unarchive_zstd() {
+        let src = tempdir().expect("failed to create tempdir");
+        prepare_fixture(&src);
+        let dst = tempdir().expect("failed to create tempdir");
+
+        let mut buff = Vec::new();
+        archive(src.path(), &mut buff, ArchiveCompression::Zstd { level: 3 })
+            .expect("failed to archive");
+
+        assert!(
+            buff.starts_with(CODEC_HEADER_MAGIC),
+            "should have a codec header"
+        );
+        assert_eq!(buff[CODEC_HEADER_LEN as usize - 1], ZSTD_CODEC);
+
+        unarchive(dst.path(), &buff[..]).expect("failed to unarchive");
+
+        assert_fixture_restored(dst.path());
+    }
+
+    #[test]
+    fn test_unarchive_gzip_without_header() {
+        let src = tempdir().expect("failed to create tempdir");
+        prepare_fixture(&src);
+        let dst = tempdir().expect("failed to create tempdir");
+
+        let mut buff = Vec::new();
+        archive(
+            src.path(),
+            &mut buff,
+            ArchiveCompression::Gzip { level: Some(9) },
+        )
+        .expect("failed to archive");
+
+        assert!(
+            buff.starts_with(&[0x1f, 0x8b]),
+            "should be a gzip archive without a codec header"
+        );
+
+        unarchive(dst.path(), &buff[..]).expect("failed to unarchive");
+
+        assert_fixture_restored(dst.path());
+    }
+
+    #[test]
+    fn test_unarchive_rejects_unknown_codec() {
+        let dst = tempdir().expect("failed to create tempdir");
+
+        let mut buff = CODEC_HEADER_MAGIC.to_vec();
+        buff.push(0xff);
+        buff.extend_from_slice(b"not an archive");
+
+        let err = unarchive(dst.path(), &buff[..]).expect_err("should fail");
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_unarchive_file_large_archive() {
+        let src = tempdir().expect("failed to create tempdir");
+        prepare_fixture(&src);
+        let dst = tempdir().expect("failed to create tempdir");
+
+        // Incompressible contents so the archive crosses the threshold.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let big = (0..PARALLEL_UNARCHIVE_THRESHOLD + 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect::<Vec<_>>();
+        std::fs::create_dir_all(src.path().join("d/e"))
+            .expect("failed to create dir");
+        std::fs::write(src.path().join("d/e/big.bin"), &big)
+            .expect("failed to write file");
+
+        let file =
+            tempfile::NamedTempFile::new().expect("failed to create temp file");
+        archive(
+            src.path(),
+            file.as_file(),
+            ArchiveCompression::Zstd { level: 1 },
+        )
+        .expect("failed to archive");
+        assert!(
+            file.as_file().metadata().expect("failed to stat").len()
+                >= PARALLEL_UNARCHIVE_THRESHOLD as u64
+        );
+
+        unarchive_file(dst.path(), file.path(), 0)
+            .expect("failed to unarchive");
+
+        assert_fixture_restored(dst.path());
+        assert_eq!(
+            std::fs::read(dst.path().join("d/e/big.bin"))
+                .expect("failed to read file"),
+            big
+        );
+    }
+
+    #[test]
+    fn test_unarchive_file() {
+        let src = tempdir().expect("failed to create tempdir");
+        prepare_fixture(&src);
+        let dst = tempdir().expect("failed to create tempdir");
+        let file =
+            tempfile::NamedTempFile::new().expect("failed to create temp file");
+
+        archive(src.path(), file.as_file(), ArchiveCompression::default())
+            .expect("failed to archive");
+
+        unarchive_file(dst.path(), file.path(), 0)
+            .expect("failed to unarchive");
+
+        assert_fixture_restored(dst.path());
+    }
+
+    #[cfg(unix)]
+    fn malicious_archive(
+        build: impl FnOnce(&mut Builder<&mut Vec<u8>>),
+    ) -> Vec<u8> {
+        let mut tar = Vec::new();
+        build(&mut Builder::new(&mut tar));
+
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        io::Write::write_all(&mut enc, &tar).expect("failed to compress");
+        enc.finish().expect("failed to compress")
+    }
+
+    #[cfg(unix)]
+    fn append_symlink(
+        tar: &mut Builder<&mut Vec<u8>>,
+        path: &str,
+        target: &Path,
+    ) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Symlink);
+        header.set_size(0);
+        tar.append_link(&mut header, path, target)
+            .expect("failed to append symlink");
+    }
+
+    #[cfg(unix)]
+    fn append_file(tar: &mut Builder<&mut Vec<u8>>, path: &str) {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(EntryType::Regular);
+        header.set_size(4);
+        header.set_mode(0o644);
+        tar.append_data(&mut header, path, &b"evil"[..])
+            .expect("failed to append file");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unarchive_parallel_rejects_symlinked_parent() {
+        let outside = tempdir().expect("failed to create tempdir");
+        let dst = tempdir().expect("failed to create tempdir");
+
+        let buff = malicious_archive(|tar| {
+            append_symlink(tar, "link", outside.path());
+            append_file(tar, "link/nested/evil.txt");
+        });
+
+        let err = unarchive_entries_parallel(dst.path(), &buff[..])
+            .expect_err("should reject the entry");
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(!outside.path().join("nested").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unarchive_parallel_replaces_symlinked_file() {
+        let outside = tempdir().expect("failed to create tempdir");
+        let dst = tempdir().expect("failed to create tempdir");
+        let target = outside.path().join("target.txt");
+        std::fs::write(&target, "safe").expect("failed to write file");
+
+        let buff = malicious_archive(|tar| {
+            append_symlink(tar, "file.txt", &target);
+            append_file(tar, "file.txt");
+        });
+
+        unarchive_entries_parallel(dst.path(), &buff[..])
+            .expect("failed to unarchive");
+
+        assert_eq!(
+            std::fs::read_to_string(&target).expect("failed to read file"),
+            "safe"
+        );
+        assert_eq!(
+            std::fs::read_to_string(dst.path().join("file.txt"))
+                .expect("failed to read file"),
+            "evil"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_entry_path() {
+        assert_eq!(
+            sanitize_entry_path(Path::new("./a/b.txt")),
+            Some(PathBuf::from("a/b.txt"))
+        );
+        assert_eq!(sanitize_entry_path(Path::new("../a.txt")), None);
+        assert_eq!(sanitize_entry_path(Path::new("/etc/passwd")), None);
+    }
 }
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use maps::UnorderedMap;
+use serde::{Deserialize, Serialize};
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct LocalExecutionDurationsData {
+    durations_map: UnorderedMap<String, UnorderedMap<String, Duration>>,
+}
+
+/// The duration of the latest successful execution of every task, used to
+/// estimate how long a task will take before it runs.
+pub struct LocalExecutionDurationsDb<'a> {
+    path: &'a std::path::Path,
+    data: LocalExecutionDurationsData,
+}
+
+impl<'a> LocalExecutionDurationsDb<'a> {
+    pub async fn load(
+        path: &'a std::path::Path,
+    ) -> Result<Self, LocalExecutionDurationsDbError> {
+        if tokio::fs::try_exists(path).await? {
+            let bytes = tokio::fs::read(path).await?;
+            // The durations are only scheduling hints, so a database that
+            // can't be decoded (e.g. written by an incompatible version) is
+            // dropped and rebuilt rather than failing every run.
+            let data = rmp_serde::decode::from_slice(&bytes).unwrap_or_else(
+                |e: rmp_serde::decode::Error| {
+                    log::warn!(
+                        "resetting unreadable execution durations database \
+                         at {}: {e}",
+                        path.display()
+                    );
+                    LocalExecutionDurationsData::default()
+                },
+            );
+
+            Ok(Self { path, data })
+        } else {
+            Ok(Self {
+                path,
+                data: Default::default(),
+            })
+        }
+    }
+
+    pub fn update_execution_duration(
+        &mut self,
+        project_name: &str,
+        task_name: &str,
+        duration: Duration,
+    ) {
+        self.data
+            .durations_map
+            .entry(project_name.to_string())
+            .or_default()
+            .insert(task_name.to_string(), duration);
+    }
+
+    /// Every recorded duration, by full task name.
+    pub fn execution_durations(&self) -> UnorderedMap<String, Duration> {
+        self.data
+            .durations_map
+            .iter()
+            .flat_map(|(project_name, tasks)| {
+                tasks.iter().map(move |(task_name, duration)| {
+                    (format!("{project_name}#{task_name}"), *duration)
+                })
+            })
+            .collect()
+    }
+
+    pub async fn save(&self) -> Result<(), LocalExecutionDurationsDbError> {
+        let bytes = rmp_serde::encode::to_vec(&self.data)?;
+
+        // Same as the last-used db: write a sibling temp file and rename it
+        // into place so readers never observe a torn database.
+        let tmp_path = self.path.with_extension(format!(
+            "tmp-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+
+        tokio::fs::write(&tmp_path, &bytes).await?;
+        tokio::fs::rename(&tmp_path, self.path).await?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct LocalExecutionDurationsDbError(
+    pub(crate) LocalExecutionDurationsDbErrorInner,
+);
+
+impl LocalExecutionDurationsDbError {
+    #[allow(unused)]
+    pub fn kind(&self) -> LocalExecutionDurationsDbErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<LocalExecutionDurationsDbErrorInner>> From<T>
+    for LocalExecutionDurationsDbError
+{
+    fn from(value: T) -> Self {
+        let inner = value.into();
+        Self(inner)
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants)]
+#[strum_discriminants(
+    name(LocalExecutionDurationsDbErrorKind),
+    vis(pub),
+    repr(u8)
+)]
+pub(crate) enum LocalExecutionDurationsDbErrorInner {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    RmpSerdeEncode(#[from] rmp_serde::encode::Error),
+
+    #[error(transparent)]
+    RmpSerdeDecode(#[from] rmp_serde::decode::Error),
+}
@@ -1,19 +1,20 @@
 use std::{
     collections::HashSet,
-    io::Cursor,
+    io::Write as _,
     path::{Path, PathBuf},
     sync::{
-        Arc, OnceLock,
+        Arc, Mutex, OnceLock,
         atomic::{AtomicU64, Ordering},
     },
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use bytes::Bytes;
 use bytesize::ByteSize;
 use derive_new::new;
+use futures::StreamExt as _;
 use maps::{Map, UnorderedMap, unordered_map};
 use omni_collector::{CollectConfig, CollectResult, Collector};
+use omni_configurations::ArchiveCompression;
 use omni_execution_plan::{
     Call, DefaultExecutionPlanProvider, ExecutionPlanProvider,
 };
@@ -38,22 +39,30 @@ use system_traits::{
     FsWriteAsync as _, impls::RealSys,
 };
 use time::OffsetDateTime;
-use tokio::task::JoinSet;
+use tokio::{io::AsyncWriteExt as _, task::JoinSet};
 use trace::Level;
 
 use crate::{
-    CacheStats, CacheStatsArgs, CachedFileOutput, CachedTaskExecution,
-    CachedTaskExecutionHash, Context, FileCacheStats, ProjectCacheStats,
-    PruneCacheArgs, PrunedCacheEntry, StaleStatus, TaskCacheStats,
-    TaskExecutionCacheStore, TaskExecutionInfo, TaskExecutionInfoExt,
+    ArtifactRejection, CacheStats, CacheStatsArgs, CachedFileOutput,
+    CachedInputManifest, CachedTaskExecution, CachedTaskExecutionHash, Context,
+    FileCacheStats, ProjectCacheStats, PruneCacheArgs, PrunedCacheEntry,
+    RejectedArtifact, StaleStatus, TaskCacheStats, TaskExecutionCacheStore,
+    TaskExecutionInfo, TaskExecutionInfoExt,
     impls::{
-        cache_archive::archive,
+        artifact_signature::{
+            ArtifactSigner, SIGNATURE_HEADER_LEN, archive_offset,
+        },
+        cache_archive::{archive, unarchive_file},
+        durations_db::{
+            LocalExecutionDurationsDb, LocalExecutionDurationsDbError,
+        },
         last_used_db::{LocalLastUsedDb, LocalLastUsedDbError},
         lock::{
-            CacheLockGuard, LAST_USED_LOCK_FILE, PRUNE_LOCK_FILE,
-            REPLACE_LOCK_FILE, lock_file_path,
+            CacheLockGuard, DURATIONS_LOCK_FILE, LAST_USED_LOCK_FILE,
+            PRUNE_LOCK_FILE, REPLACE_LOCK_FILE, lock_file_path,
         },
     },
+    outputs_digest,
 };
 
 pub use omni_utils::path::{
@@ -62,9 +71,10 @@ pub use omni_utils::path::{
 
 use omni_utils::glob::build_glob_set;
 
-use omni_hasher::project_dir_hasher::impls::RealDirHasherError;
-
-use super::cache_archive::unarchive;
+use omni_hasher::{
+    HasherError, default::hash_file_streaming,
+    project_dir_hasher::impls::RealDirHasherError,
+};
 
 #[derive(Clone, Debug)]
 pub struct HybridTaskExecutionCacheStore {
@@ -77,6 +87,19 @@ pub struct HybridTaskExecutionCacheStore {
     /// when remote caching is disabled or every entry is served locally. It is
     /// only built on first actual remote access via [`Self::client`].
     client: OnceLock<Arc<DefaultRemoteCacheClient>>,
+    /// Remote artifacts that failed integrity verification, shared between
+    /// clones so they are reported once no matter which clone rejected them.
+    rejected_artifacts: Arc<Mutex<RejectedArtifacts>>,
+}
+
+#[derive(Debug, Default)]
+struct RejectedArtifacts {
+    /// Digests of every rejected artifact, so that they are overwritten when
+    /// the task's fresh result is uploaded instead of being kept because an
+    /// artifact already exists.
+    digests: HashSet<String>,
+    /// Rejections not yet returned by `take_rejected_artifacts`.
+    unreported: Vec<RejectedArtifact>,
 }
 
 #[derive(
@@ -107,6 +130,29 @@ pub struct EnabledRemoteConfig {
 
     #[new(into)]
     pub environment_code: Option<String>,
+
+    pub compression: ArchiveCompression,
+
+    /// Base64 encoded key the uploaded artifacts are signed with and the
+    /// downloaded ones verified against. When set, unsigned or tampered
+    /// artifacts are treated as cache misses.
+    #[new(into)]
+    pub signing_key: Option<String>,
+}
+
+impl EnabledRemoteConfig {
+    fn signer(
+        &self,
+    ) -> Result<Option<ArtifactSigner>, LocalTaskExecutionCacheStoreError> {
+        self.signing_key
+            .as_deref()
+            .map(ArtifactSigner::from_base64)
+            .transpose()
+            .map_err(|e| {
+                LocalTaskExecutionCacheStoreErrorInner::InvalidSigningKey(e)
+                    .into()
+            })
+    }
 }
 
 impl HybridTaskExecutionCacheStore {
@@ -123,6 +169,7 @@ impl HybridTaskExecutionCacheStore {
             ws_root_dir,
             remote_config: remote_config.into(),
             client: OnceLock::new(),
+            rejected_artifacts: Default::default(),
         }
     }
 
@@ -133,6 +180,18 @@ impl HybridTaskExecutionCacheStore {
         self.client
             .get_or_init(|| Arc::new(DefaultRemoteCacheClient::default()))
     }
+
+    fn rejected_artifacts(
+        &self,
+    ) -> std::sync::MutexGuard<'_, RejectedArtifacts> {
+        self.rejected_artifacts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn was_rejected(&self, digest: &str) -> bool {
+        self.rejected_artifacts().digests.contains(digest)
+    }
 }
 
 fn hashtext(text: &str) -> String {
@@ -167,6 +226,7 @@ impl HybridTaskExecutionCacheStore {
                     dir_globs,
                     meta_filter,
                     None,
+                    None,
                     false,
                     false,
                 )?;
@@ -260,6 +320,37 @@ impl HybridTaskExecutionCacheStore {
         Ok(())
     }
 
+    /// Records the duration of every successful execution in `cache_infos` so
+    /// that later runs can estimate how long the tasks take.
+    async fn update_execution_durations(
+        &self,
+        cache_infos: &[crate::NewCacheInfo<'_>],
+    ) -> Result<(), LocalTaskExecutionCacheStoreError> {
+        if cache_infos.iter().all(|i| i.exit_code != 0) {
+            return Ok(());
+        }
+
+        let _guard = CacheLockGuard::acquire_exclusive(lock_file_path(
+            &self.cache_dir,
+            DURATIONS_LOCK_FILE,
+        ))
+        .await?;
+
+        let path = self.cache_dir.join(EXECUTION_DURATIONS_DB_FILE);
+        let mut durations_db = LocalExecutionDurationsDb::load(&path).await?;
+
+        for info in cache_infos.iter().filter(|i| i.exit_code == 0) {
+            durations_db.update_execution_duration(
+                info.task.project_name,
+                info.task.task_name,
+                info.execution_duration,
+            );
+        }
+
+        durations_db.save().await?;
+        Ok(())
+    }
+
     /// Reads the recorded exit code of a published cache entry.
     ///
     /// Returns `None` when no complete entry is present at `metadata_path`.
@@ -283,6 +374,26 @@ impl HybridTaskExecutionCacheStore {
         Ok(Some(metadata.exit_code))
     }
 
+    /// Reads the metadata of the published cache entry in `entry_dir`, if it
+    /// belongs to `task_name`.
+    async fn read_task_entry(
+        &self,
+        entry_dir: &Path,
+        task_name: &str,
+    ) -> Result<Option<CachedTaskExecution>, LocalTaskExecutionCacheStoreError>
+    {
+        let metadata_path = entry_dir.join(CACHE_OUTPUT_METADATA_FILE);
+        if !self.sys.fs_exists_async(&metadata_path).await? {
+            return Ok(None);
+        }
+
+        let bytes = self.sys.fs_read_async(&metadata_path).await?;
+        let metadata: CachedTaskExecution =
+            rmp_serde::decode::from_slice(&bytes)?;
+
+        Ok((metadata.task_name == task_name).then_some(metadata))
+    }
+
     /// Atomically publishes a fully-populated staging directory to its final
     /// content-addressed location.
     ///
@@ -419,7 +530,9 @@ impl HybridTaskExecutionCacheStore {
 
 const LOGS_CACHE_FILE: &str = "logs.cache";
 const CACHE_OUTPUT_METADATA_FILE: &str = "cache.meta.bin";
+const INPUT_MANIFEST_FILE: &str = "inputs.manifest.bin";
 const LAST_USED_TIMESTAMPS_DB_FILE: &str = "last-used-timestamps.db";
+const EXECUTION_DURATIONS_DB_FILE: &str = "execution-durations.db";
 
 /// Prefix for in-progress staging directories created while publishing a
 /// cache entry. A staging directory is populated in full and then atomically
@@ -437,6 +550,32 @@ const STALE_STAGING_AGE: std::time::Duration =
 /// a single process publishes the same digest from multiple tasks at once.
 static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
 
+/// Unpack the downloaded `artifact` of the cache entry `digest` into
+/// `output_dir`. When artifacts are signed, it is verified first and not
+/// unpacked at all if that fails.
+fn unpack_artifact(
+    artifact: &Path,
+    output_dir: &Path,
+    digest: &str,
+    signer: Option<&ArtifactSigner>,
+) -> std::io::Result<Result<(), ArtifactRejection>> {
+    let file = std::fs::File::open(artifact)?;
+
+    let offset = match signer {
+        Some(signer) => {
+            if let Err(reason) = signer.verify(&file, digest)? {
+                return Ok(Err(reason));
+            }
+            SIGNATURE_HEADER_LEN
+        }
+        None => archive_offset(&file)?,
+    };
+
+    unarchive_file(output_dir, artifact, offset)?;
+
+    Ok(Ok(()))
+}
+
 /// Builds a unique staging directory path that is a sibling of `output_dir`
 /// (and therefore on the same filesystem, so the publishing rename is atomic).
 fn staging_dir_for(output_dir: &Path) -> PathBuf {
@@ -489,6 +628,7 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                     cache_output_dirs: true,
                     input_files: true,
                     output_files: true,
+                    manifests: true,
                 },
             )
             .await?;
@@ -627,12 +767,34 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                             "cache file hard link {original_abs_path:?} to {cache_abs_file_path:?}"
                         );
 
+                        let hashed_path = cache_abs_file_path.clone();
+                        let hash = tokio::task::spawn_blocking(move || {
+                            hash_file_streaming(&hashed_path)
+                        })
+                        .await
+                        .map_err(std::io::Error::other)??;
+
                         cache_output_files.push(CachedFileOutput {
                             cached_path: PathBuf::from(cache_file_name),
                             original_path: path.clone(),
+                            hash: Some(hash),
                         });
                     }
 
+                    let output_hashes = cache_output_files
+                        .iter()
+                        .filter_map(|f| f.hash)
+                        .collect::<Vec<_>>();
+
+                    if let Some(manifest) = &result.manifest {
+                        self.sys
+                            .fs_write_async(
+                                staging_dir.join(INPUT_MANIFEST_FILE),
+                                rmp_serde::encode::to_vec(manifest)?,
+                            )
+                            .await?;
+                    }
+
                     // All cached paths are stored relative to the entry dir.
                     let metadata = CachedTaskExecution {
                         project_name: result.task.project_name.to_string(),
@@ -654,8 +816,11 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                         dependency_digests: new_cache_info
                             .task
                             .dependency_digests
-                            .to_vec(),
+                            .values()
+                            .copied()
+                            .collect(),
                         tries: new_cache_info.tries,
+                        outputs_digest: Some(outputs_digest(&output_hashes)),
                     };
                     let bytes = rmp_serde::encode::to_vec(&metadata)?;
 
@@ -704,15 +869,27 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
             }
         }
 
+        // The durations only guide scheduling, failing to record them must
+        // not fail a run whose outputs were cached fine.
+        if let Err(e) = self.update_execution_durations(cache_infos).await {
+            log::warn!("failed to record the execution durations: {e}");
+        }
+
         if !cached_results.is_empty()
             && let RemoteConfig::Enabled(conf) = &self.remote_config
         {
             log::debug!("Uploading remote cache artifacts...");
+            let signer = conf.signer()?;
             let mut tasks = JoinSet::new();
             for (hash, output_dir, replacing) in cached_results {
                 let client = self.client().clone();
                 let conf = conf.clone();
+                let signer = signer.clone();
                 let digest = bs58::encode(hash.digest).into_string();
+                let task = format!("{}#{}", hash.project_name, hash.task_name);
+                // The existing artifact of a rejected entry must be replaced
+                // by the one of the task that just ran in its place.
+                let replacing = replacing || self.was_rejected(&digest);
 
                 tasks.spawn(async move {
                     let config = RemoteAccessArgs {
@@ -725,6 +902,7 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                         org: &conf.organization_code,
                         tenant: &conf.tenant_code,
                         ws: &conf.workspace_code,
+                        task: Some(&task),
                     };
 
                     // A remote artifact is keyed by digest, so an existing one
@@ -737,11 +915,32 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                         return Ok::<_, LocalTaskExecutionCacheStoreError>(());
                     }
 
-                    let mut artifact = Vec::new();
-                    archive(&output_dir, Cursor::new(&mut artifact))?;
+                    // Archive to disk and stream it from there, so the
+                    // artifact is never held in memory as a whole. Signed
+                    // artifacts reserve room for the signature in front of
+                    // the archive, filled in once it is written.
+                    let compression = conf.compression;
+                    let signed_digest = digest.clone();
+                    let artifact = tokio::task::spawn_blocking(move || {
+                        let artifact = tempfile::NamedTempFile::new()?;
+                        let mut file = artifact.as_file();
+                        if signer.is_some() {
+                            file.write_all(
+                                &[0; SIGNATURE_HEADER_LEN as usize],
+                            )?;
+                        }
+                        archive(&output_dir, file, compression)?;
+                        if let Some(signer) = &signer {
+                            signer.sign(file, &signed_digest)?;
+                        }
+                        Ok::<_, std::io::Error>(artifact)
+                    })
+                    .await
+                    .map_err(std::io::Error::other)??;
 
+                    let reader = tokio::fs::File::open(artifact.path()).await?;
                     client
-                        .put_artifact(&config, &digest, Bytes::from(artifact))
+                        .put_artifact_stream(&config, &digest, Box::pin(reader))
                         .await?;
 
                     log::debug!("Uploaded cache for {}", digest);
@@ -778,6 +977,7 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
         let collected = self.collect(projects, &config).await?;
 
         if let RemoteConfig::Enabled(conf) = &self.remote_config {
+            let signer = conf.signer()?;
             let mut tasks = JoinSet::new();
             for project in &collected {
                 let output_dir = project
@@ -795,11 +995,14 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
 
                 let client = self.client().clone();
                 let conf = conf.clone();
+                let signer = signer.clone();
                 let output_dir = output_dir.to_path_buf();
+                let project_name = project.task.project_name.to_string();
+                let task_name = project.task.task_name.to_string();
 
                 tasks.spawn(async move {
                     let response = client
-                        .get_artifact(
+                        .get_artifact_stream(
                             &RemoteAccessArgs {
                                 api_key: &conf.api_key,
                                 api_base_url: &conf.api_base_url,
@@ -810,24 +1013,75 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                                 org: &conf.organization_code,
                                 tenant: &conf.tenant_code,
                                 ws: &conf.workspace_code,
+                                task: None,
                             },
                             &digest,
                         )
                         .await?;
 
-                    if let Some(bytes) = response {
+                    if let Some(mut stream) = response {
+                        let artifact = tempfile::NamedTempFile::new()?;
+                        let mut writer =
+                            tokio::fs::File::create(artifact.path()).await?;
+                        while let Some(chunk) = stream.next().await {
+                            writer.write_all(&chunk?).await?;
+                        }
+                        writer.flush().await?;
+                        drop(writer);
+
                         log::debug!("fetched remote cache for {}", digest);
-                        unarchive(&output_dir, Cursor::new(bytes))?;
+                        let rejection =
+                            tokio::task::spawn_blocking(move || {
+                                let result = unpack_artifact(
+                                    artifact.path(),
+                                    &output_dir,
+                                    &digest,
+                                    signer.as_ref(),
+                                )?;
+
+                                Ok::<_, std::io::Error>(result.err().map(
+                                    |reason| {
+                                        RejectedArtifact::new(
+                                            project_name,
+                                            task_name,
+                                            digest,
+                                            reason,
+                                        )
+                                    },
+                                ))
+                            })
+                            .await
+                            .map_err(std::io::Error::other)??;
+
+                        return Ok(rejection);
                     }
 
-                    Ok::<_, LocalTaskExecutionCacheStoreError>(())
+                    Ok::<_, LocalTaskExecutionCacheStoreError>(None)
                 });
             }
 
             let results = tasks.join_all().await;
 
+            let mut rejected = vec![];
             for result in results {
-                result?;
+                if let Some(rejection) = result? {
+                    log::warn!(
+                        "rejected remote cache artifact {} of {}#{}: {}",
+                        rejection.digest,
+                        rejection.project_name,
+                        rejection.task_name,
+                        rejection.reason
+                    );
+                    rejected.push(rejection);
+                }
+            }
+
+            if !rejected.is_empty() {
+                let mut rejected_artifacts = self.rejected_artifacts();
+                for rejection in rejected {
+                    rejected_artifacts.digests.insert(rejection.digest.clone());
+                    rejected_artifacts.unreported.push(rejection);
+                }
             }
         }
 
@@ -1140,7 +1394,9 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                 ..Default::default()
             })
             .await?;
-        let time_upper_limit = args.older_than.map(|older_than| OffsetDateTime::now_utc() - older_than);
+        let time_upper_limit = args
+            .older_than
+            .map(|older_than| OffsetDateTime::now_utc() - older_than);
 
         let mut entries = vec![];
 
@@ -1181,6 +1437,7 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
                         args.dir_globs,
                         args.meta_filter,
                         None,
+                        None,
                         false,
                         false,
                     )?;
@@ -1331,6 +1588,88 @@ impl TaskExecutionCacheStore for HybridTaskExecutionCacheStore {
 
         self.force_prune_inner(entries).await
     }
+
+    async fn get_input_manifest(
+        &self,
+        project_name: &str,
+        task_name: &str,
+        digest: &DefaultHash,
+    ) -> Result<Option<CachedInputManifest>, Self::Error> {
+        let project_output_dir =
+            self.cache_dir.join(path_safe(project_name)).join("output");
+
+        if !tokio::fs::try_exists(&project_output_dir).await? {
+            return Ok(None);
+        }
+
+        let digest_dir =
+            project_output_dir.join(bs58::encode(digest).into_string());
+        let mut found = self
+            .read_task_entry(&digest_dir, task_name)
+            .await?
+            .map(|exec| (exec, digest_dir));
+
+        if found.is_none() {
+            let mut entries = tokio::fs::read_dir(&project_output_dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                if is_internal_cache_entry(&entry.file_name().to_string_lossy())
+                {
+                    continue;
+                }
+
+                let entry_dir = entry.path();
+                let Some(exec) =
+                    self.read_task_entry(&entry_dir, task_name).await?
+                else {
+                    continue;
+                };
+
+                if found.as_ref().is_none_or(|(latest, _)| {
+                    exec.execution_time > latest.execution_time
+                }) {
+                    found = Some((exec, entry_dir));
+                }
+            }
+        }
+
+        let Some((execution, entry_dir)) = found else {
+            return Ok(None);
+        };
+
+        let manifest_path = entry_dir.join(INPUT_MANIFEST_FILE);
+        let manifest = if self.sys.fs_exists_async(&manifest_path).await? {
+            let bytes = self.sys.fs_read_async(&manifest_path).await?;
+            // a manifest recorded in an older format can't be compared, which
+            // is reported the same as a missing one
+            rmp_serde::decode::from_slice(&bytes)
+                .inspect_err(|e| {
+                    log::debug!(
+                        "ignoring unreadable input manifest at {}: {e}",
+                        manifest_path.display()
+                    )
+                })
+                .ok()
+        } else {
+            None
+        };
+
+        Ok(Some(CachedInputManifest::new(execution, manifest)))
+    }
+
+    async fn get_execution_durations(
+        &self,
+    ) -> Result<UnorderedMap<String, std::time::Duration>, Self::Error> {
+        let path = self.cache_dir.join(EXECUTION_DURATIONS_DB_FILE);
+
+        Ok(LocalExecutionDurationsDb::load(&path)
+            .await?
+            .execution_durations())
+    }
+
+    fn take_rejected_artifacts(&self) -> Vec<RejectedArtifact> {
+        std::mem::take(&mut self.rejected_artifacts().unreported)
+    }
 }
 
 #[derive(new, Clone, Copy)]
@@ -1489,6 +1828,9 @@ pub(crate) enum LocalTaskExecutionCacheStoreErrorInner {
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
+    #[error(transparent)]
+    Hasher(#[from] HasherError),
+
     #[error(transparent)]
     DirHasher(#[from] RealDirHasherError),
 
@@ -1516,6 +1858,9 @@ pub(crate) enum LocalTaskExecutionCacheStoreErrorInner {
     #[error(transparent)]
     LastUsedDb(#[from] LocalLastUsedDbError),
 
+    #[error(transparent)]
+    ExecutionDurationsDb(#[from] LocalExecutionDurationsDbError),
+
     #[error(transparent)]
     ExecutionPlan(#[from] omni_execution_plan::ExecutionPlanProviderError),
 
@@ -1529,12 +1874,18 @@ pub(crate) enum LocalTaskExecutionCacheStoreErrorInner {
         "a loaded workspace context is required to apply `--stale-only`, `--dir` or `--meta` filters while pruning the cache"
     )]
     MissingContext,
+
+    #[error("invalid remote cache signing key: {0}")]
+    InvalidSigningKey(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{NewCacheInfo, cache::impls::HybridTaskExecutionCacheStore};
+    use crate::{
+        InputChange, InputChangeKind, NewCacheInfo,
+        cache::impls::HybridTaskExecutionCacheStore,
+    };
     use bytes::Bytes;
     use derive_new::new;
     use omni_command_config::CommandConfig;
@@ -1640,7 +1991,7 @@ mod tests {
         output_files: Vec<OmniPath>,
         env_vars: maps::Map<String, String>,
         input_env_cache_keys: Vec<String>,
-        pub dependency_digests: Vec<DefaultHash>,
+        pub dependency_digests: Map<String, DefaultHash>,
         pub args: Map<String, serde_json::Value>,
     }
 
@@ -1678,7 +2029,7 @@ mod tests {
             project_dir,
             env_vars: env_vars(),
             input_env_cache_keys: env_cache_keys(),
-            dependency_digests: vec![],
+            dependency_digests: Map::default(),
             args: Map::default(),
         };
         f(&mut owned);
@@ -1814,6 +2165,58 @@ mod tests {
         assert!(cached_output2.is_some(), "cached output should exist");
     }
 
+    #[tokio::test]
+    async fn test_input_manifest_explains_changed_files() {
+        let temp = fixture(&["project1"]).await;
+        let dir = temp.path();
+        let cache = cache_store(dir);
+        let task = task("task", "project1", dir);
+
+        let cache_hash = cache
+            .cache(&new_cache_info(Some(&LOGS_CONTENT), *task.get()))
+            .await
+            .expect("failed to cache");
+
+        sys()
+            .fs_write_async(
+                task.get().project_dir.join("src/a-test.txt"),
+                "new content",
+            )
+            .await
+            .expect("failed to write file");
+
+        let cached = cache
+            .get_input_manifest("project1", "task", &[0; 32])
+            .await
+            .expect("failed to get input manifest")
+            .expect("entry should exist");
+
+        assert_eq!(cached.execution.digest, cache_hash.digest);
+
+        let previous = cached.manifest.expect("manifest should be recorded");
+        let mut current = previous.clone();
+        current
+            .files
+            .insert("@project/src/a-test.txt".to_string(), [1; 32]);
+
+        let diff = current.diff(&previous);
+        assert_eq!(
+            diff.files,
+            vec![InputChange {
+                kind: InputChangeKind::Modified,
+                name: "@project/src/a-test.txt".to_string(),
+            }]
+        );
+        assert!(
+            cache
+                .get_input_manifest("project1", "other", &[0; 32])
+                .await
+                .expect("failed to get input manifest")
+                .is_none(),
+            "other tasks should not match"
+        );
+    }
+
     #[tokio::test]
     async fn test_success_replaces_cached_failure() {
         let temp = fixture(&["project1"]).await;
@@ -1863,6 +2266,93 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_execution_durations_record_successful_executions() {
+        let temp = fixture(&["project1", "project2"]).await;
+        let dir = temp.path();
+        let cache = cache_store(dir);
+        let task1 = task("task", "project1", dir);
+        let task2 = task("task", "project2", dir);
+
+        assert!(
+            cache
+                .get_execution_durations()
+                .await
+                .expect("failed to get durations")
+                .is_empty()
+        );
+
+        cache
+            .cache_many(&[
+                NewCacheInfo {
+                    execution_duration: std::time::Duration::from_secs(3),
+                    ..new_cache_info(Some(&LOGS_CONTENT), *task1.get())
+                },
+                new_cache_info_with_exit_code(
+                    Some(&LOGS_CONTENT),
+                    *task2.get(),
+                    1,
+                ),
+            ])
+            .await
+            .expect("failed to cache");
+
+        let durations = cache
+            .get_execution_durations()
+            .await
+            .expect("failed to get durations");
+
+        assert_eq!(
+            durations.get("project1#task"),
+            Some(&std::time::Duration::from_secs(3))
+        );
+        assert!(
+            !durations.contains_key("project2#task"),
+            "failed executions should not be recorded"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execution_durations_reset_corrupted_db() {
+        let temp = fixture(&["project1"]).await;
+        let dir = temp.path();
+        let cache = cache_store(dir);
+        let task = task("task", "project1", dir);
+
+        let db_path = dir.join(".omni/cache").join(EXECUTION_DURATIONS_DB_FILE);
+        tokio::fs::create_dir_all(db_path.parent().unwrap())
+            .await
+            .expect("failed to create cache dir");
+        tokio::fs::write(&db_path, b"not a durations db")
+            .await
+            .expect("failed to write db");
+
+        assert!(
+            cache
+                .get_execution_durations()
+                .await
+                .expect("corrupted db should read as empty")
+                .is_empty()
+        );
+
+        cache
+            .cache_many(&[NewCacheInfo {
+                execution_duration: std::time::Duration::from_secs(2),
+                ..new_cache_info(Some(&LOGS_CONTENT), *task.get())
+            }])
+            .await
+            .expect("corrupted durations db should not fail caching");
+
+        let durations = cache
+            .get_execution_durations()
+            .await
+            .expect("failed to get durations");
+        assert_eq!(
+            durations.get("project1#task"),
+            Some(&std::time::Duration::from_secs(2))
+        );
+    }
+
     #[tokio::test]
     async fn test_failure_does_not_replace_cached_success() {
         let temp = fixture(&["project1"]).await;
@@ -2621,10 +3111,7 @@ mod tests {
             handles.push(tokio::spawn(async move {
                 let task = task("task", "project1", &root);
                 cache
-                    .cache(&new_cache_info(
-                        Some(&LOGS_CONTENT),
-                        *task.get(),
-                    ))
+                    .cache(&new_cache_info(Some(&LOGS_CONTENT), *task.get()))
                     .await
                     .map(|_| ())
                     .map_err(|e| e.to_string())
@@ -2790,10 +3277,7 @@ mod tests {
 
         let seed_task = task("task", "project1", &root);
         cache
-            .cache(&new_cache_info(
-                Some(&LOGS_CONTENT),
-                *seed_task.get(),
-            ))
+            .cache(&new_cache_info(Some(&LOGS_CONTENT), *seed_task.get()))
             .await
             .expect("failed to cache");
 
@@ -2839,4 +3323,98 @@ mod tests {
             "a last-used timestamp should have been recorded"
         );
     }
+
+    fn signed_artifact(
+        src: &Path,
+        digest: &str,
+        signer: &ArtifactSigner,
+    ) -> tempfile::NamedTempFile {
+        let artifact =
+            tempfile::NamedTempFile::new().expect("can't create temp file");
+        let mut file = artifact.as_file();
+        file.write_all(&[0; SIGNATURE_HEADER_LEN as usize])
+            .expect("can't reserve signature");
+        archive(src, file, ArchiveCompression::default())
+            .expect("can't archive");
+        signer.sign(file, digest).expect("can't sign");
+        artifact
+    }
+
+    #[test]
+    fn test_unpack_artifact_verifies_signature() {
+        let src = tempfile::tempdir().expect("can't create temp dir");
+        std::fs::write(src.path().join("out.txt"), TXT_CONTENT)
+            .expect("can't write file");
+        let signer = ArtifactSigner::from_base64(
+            "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=",
+        )
+        .expect("valid key");
+        let artifact = signed_artifact(src.path(), "digest", &signer);
+
+        let dst = tempfile::tempdir().expect("can't create temp dir");
+        let result = unpack_artifact(
+            artifact.path(),
+            dst.path(),
+            "digest",
+            Some(&signer),
+        )
+        .expect("can't unpack");
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            std::fs::read_to_string(dst.path().join("out.txt"))
+                .expect("can't read file"),
+            TXT_CONTENT
+        );
+
+        // A client without a key still reads signed artifacts.
+        let dst = tempfile::tempdir().expect("can't create temp dir");
+        unpack_artifact(artifact.path(), dst.path(), "digest", None)
+            .expect("can't unpack")
+            .expect("should not be rejected");
+        assert!(dst.path().join("out.txt").exists());
+
+        // A signed artifact served for another entry is rejected and nothing
+        // is unpacked.
+        let dst = tempfile::tempdir().expect("can't create temp dir");
+        let result = unpack_artifact(
+            artifact.path(),
+            dst.path(),
+            "other-digest",
+            Some(&signer),
+        )
+        .expect("can't unpack");
+        assert_eq!(result, Err(ArtifactRejection::InvalidSignature));
+        assert!(!dst.path().join("out.txt").exists());
+    }
+
+    #[test]
+    fn test_unpack_artifact_rejects_unsigned_when_signing() {
+        let src = tempfile::tempdir().expect("can't create temp dir");
+        std::fs::write(src.path().join("out.txt"), TXT_CONTENT)
+            .expect("can't write file");
+        let artifact =
+            tempfile::NamedTempFile::new().expect("can't create temp file");
+        archive(
+            src.path(),
+            artifact.as_file(),
+            ArchiveCompression::default(),
+        )
+        .expect("can't archive");
+        let signer = ArtifactSigner::from_base64(
+            "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=",
+        )
+        .expect("valid key");
+
+        let dst = tempfile::tempdir().expect("can't create temp dir");
+        let result = unpack_artifact(
+            artifact.path(),
+            dst.path(),
+            "digest",
+            Some(&signer),
+        )
+        .expect("can't unpack");
+
+        assert_eq!(result, Err(ArtifactRejection::Unsigned));
+        assert!(!dst.path().join("out.txt").exists());
+    }
 }
@@ -4,7 +4,8 @@
 //! process. A handful of operations cannot be made safe by content-addressed
 //! atomic publishing alone (see [`super::hybrid`]):
 //!
-//! * read-modify-write of the shared last-used-timestamps database, and
+//! * read-modify-write of the shared last-used-timestamps and execution
+//!   durations databases, and
 //! * pruning, which deletes cache entries that another process may be
 //!   publishing into at the same moment.
 //!
@@ -22,6 +23,8 @@ pub(crate) const LOCKS_DIR: &str = ".locks";
 pub(crate) const PRUNE_LOCK_FILE: &str = "prune.lock";
 /// Lock file guarding the last-used-timestamps database.
 pub(crate) const LAST_USED_LOCK_FILE: &str = "last-used.lock";
+/// Lock file guarding the execution durations database.
+pub(crate) const DURATIONS_LOCK_FILE: &str = "durations.lock";
 /// Lock file serializing in-place replacement of an already-published cache
 /// entry (e.g. upgrading a cached failure to a later successful re-execution
 /// of the same digest).
@@ -1,4 +1,6 @@
+mod artifact_signature;
 mod cache_archive;
+mod durations_db;
 mod hybrid;
 mod last_used_db;
 mod lock;
@@ -1,8 +1,12 @@
-use std::error::Error;
+use std::{error::Error, time::Duration};
+
+use maps::{UnorderedMap, unordered_map};
+use omni_hasher::impls::DefaultHash;
 
 use crate::{
-    CacheStats, CacheStatsArgs, CachedTaskExecution, CachedTaskExecutionHash,
-    Context, NewCacheInfo, PruneCacheArgs, PrunedCacheEntry, TaskExecutionInfo,
+    CacheStats, CacheStatsArgs, CachedInputManifest, CachedTaskExecution,
+    CachedTaskExecutionHash, Context, NewCacheInfo, PruneCacheArgs,
+    PrunedCacheEntry, RejectedArtifact, TaskExecutionInfo,
 };
 
 #[async_trait::async_trait]
@@ -53,4 +57,28 @@ pub trait TaskExecutionCacheStore: Send + Sync {
         &self,
         args: &CacheStatsArgs<'_, TContext>,
     ) -> Result<CacheStats, Self::Error>;
+
+    /// Returns the cached entry of a task whose digest is `digest` if there is
+    /// one, otherwise the task's most recently cached entry.
+    async fn get_input_manifest(
+        &self,
+        project_name: &str,
+        task_name: &str,
+        digest: &DefaultHash,
+    ) -> Result<Option<CachedInputManifest>, Self::Error>;
+
+    /// Returns the duration of the latest successful execution of every task
+    /// that was cached, by full task name. Stores that don't record durations
+    /// return none.
+    async fn get_execution_durations(
+        &self,
+    ) -> Result<UnorderedMap<String, Duration>, Self::Error> {
+        Ok(unordered_map!())
+    }
+
+    /// Returns the remote artifacts rejected by integrity verification since
+    /// the last call. Stores without remote artifacts never reject any.
+    fn take_rejected_artifacts(&self) -> Vec<RejectedArtifact> {
+        Vec::new()
+    }
 }
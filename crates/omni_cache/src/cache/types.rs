@@ -11,6 +11,12 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use yoke::Yokeable;
 
+use crate::CachedTaskExecution;
+
+pub use omni_collector::{
+    InputChange, InputChangeKind, InputManifest, InputManifestDiff,
+};
+
 #[allow(clippy::too_many_arguments)]
 #[derive(Clone, Copy, PartialEq, Eq, Debug, new, Yokeable, Serialize)]
 pub struct TaskExecutionInfo<'a> {
@@ -25,7 +31,7 @@ pub struct TaskExecutionInfo<'a> {
     pub input_files: &'a [OmniPath],
     pub input_env_keys: &'a [String],
     pub env_vars: &'a Map<String, String>,
-    pub dependency_digests: &'a [DefaultHash],
+    pub dependency_digests: &'a Map<String, DefaultHash>,
     pub args: &'a Map<String, serde_json::Value>,
 }
 
@@ -60,3 +66,46 @@ pub enum StaleStatus {
     Stale,
     Fresh,
 }
+
+/// A cached execution of a task along with the [`InputManifest`] that was
+/// recorded for it. Entries cached before manifests were recorded have none.
+#[derive(Clone, PartialEq, Debug, new)]
+pub struct CachedInputManifest {
+    pub execution: CachedTaskExecution,
+    pub manifest: Option<InputManifest>,
+}
+
+/// Why a remote cache artifact failed integrity verification.
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    strum::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactRejection {
+    #[strum(to_string = "it is not signed")]
+    Unsigned,
+    #[strum(to_string = "its content doesn't match its signed digest")]
+    DigestMismatch,
+    #[strum(to_string = "its signature is invalid")]
+    InvalidSignature,
+}
+
+/// A remote cache artifact that failed integrity verification. It was not
+/// unpacked and the task was treated as a cache miss.
+#[derive(
+    Clone, PartialEq, Eq, Debug, new, Serialize, Deserialize, JsonSchema,
+)]
+pub struct RejectedArtifact {
+    pub project_name: String,
+    pub task_name: String,
+    /// The base58 encoded digest of the cache entry.
+    pub digest: String,
+    pub reason: ArtifactRejection,
+}
@@ -0,0 +1,542 @@
+//! Observe which files a process tree actually reads and writes.
+//!
+//! This is the *audit* counterpart to the OS sandbox: instead of denying
+//! access it records it, so a caller can compare what a process touched against
+//! what it declared. On Linux (x86_64 and aarch64) the process is run under
+//! `ptrace(2)` with syscall tracing; every descendant it forks, clones or execs
+//! is followed, and the path-taking syscalls that read or modify file contents
+//! are decoded on entry and recorded once they succeed:
+//!
+//! * `open`/`openat`/`openat2`/`creat` — a read, a write, or both, from the
+//!   access mode and `O_CREAT`/`O_TRUNC` (directory and `O_PATH` opens are not
+//!   content access and are skipped);
+//! * `execve`/`execveat` — a read of the executed file;
+//! * `rename*`, `unlink*`, `truncate` — a write of every path involved.
+//!
+//! Relative paths are resolved against the tracee's `cwd` or the `dirfd` it
+//! passed, then normalized lexically; symlinks are not followed. Anything that
+//! bypasses these syscalls (e.g. `mmap` of an fd inherited from the parent) is
+//! not seen, so the log is a faithful lower bound rather than a proof.
+//!
+//! The tracer must be the process that spawns the traced command, so callers
+//! run [`trace_command`] in a dedicated wrapper process and exchange the
+//! resulting [`AccessLog`] through a file.
+
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// How a file was accessed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccessKind {
+    Read,
+    Write,
+}
+
+/// A single successful file access by a traced process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileAccess {
+    pub kind: AccessKind,
+    pub path: PathBuf,
+}
+
+/// The deduplicated set of files a traced process tree read and wrote.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessLog {
+    pub reads: BTreeSet<PathBuf>,
+    pub writes: BTreeSet<PathBuf>,
+}
+
+impl AccessLog {
+    pub fn record(&mut self, access: FileAccess) {
+        match access.kind {
+            AccessKind::Read => self.reads.insert(access.path),
+            AccessKind::Write => self.writes.insert(access.path),
+        };
+    }
+}
+
+/// Whether [`trace_command`] can trace processes on this target.
+pub fn is_supported() -> bool {
+    cfg!(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))
+}
+
+/// Run `command` to completion while reporting every file access of it and
+/// its descendants to `on_access`, returning its exit status, which tells a
+/// process killed by a signal apart from one that exited.
+///
+/// Blocks the calling thread, which becomes the tracer: no other code in the
+/// process may `wait` for children while this runs.
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub fn trace_command(
+    command: std::process::Command,
+    on_access: impl FnMut(FileAccess),
+) -> std::io::Result<std::process::ExitStatus> {
+    linux::trace(command, on_access)
+}
+
+/// Tracing is unavailable on this target.
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+pub fn trace_command(
+    _command: std::process::Command,
+    _on_access: impl FnMut(FileAccess),
+) -> std::io::Result<std::process::ExitStatus> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "file access tracing is only supported on Linux (x86_64, aarch64)",
+    ))
+}
+
+/// Lexically normalize an absolute path: drop `.` and resolve `..` without
+/// touching the filesystem (the file may no longer exist).
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod linux {
+    use std::{
+        collections::{HashMap, HashSet},
+        ffi::OsStr,
+        fs::File,
+        io,
+        os::unix::{
+            ffi::OsStrExt as _,
+            fs::FileExt as _,
+            process::{CommandExt, ExitStatusExt as _},
+        },
+        path::PathBuf,
+        process::{Command, ExitStatus},
+    };
+
+    use libc::{c_long, c_void, pid_t};
+
+    use super::{AccessKind, FileAccess, normalize};
+
+    #[cfg(target_env = "musl")]
+    type Request = libc::c_int;
+    #[cfg(not(target_env = "musl"))]
+    type Request = libc::c_uint;
+
+    /// Longest path read out of the tracee, matching `PATH_MAX`.
+    const PATH_MAX: usize = 4096;
+    const PAGE_SIZE: u64 = 4096;
+
+    unsafe fn ptrace(
+        request: Request,
+        pid: pid_t,
+        addr: usize,
+        data: usize,
+    ) -> io::Result<c_long> {
+        // SAFETY: upheld by the callers; `addr`/`data` are either plain
+        // integers or pointers to live, correctly sized buffers.
+        let ret = unsafe {
+            libc::ptrace(request, pid, addr as *mut c_void, data as *mut c_void)
+        };
+        if ret == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(ret)
+        }
+    }
+
+    /// Resume a stopped tracee until its next syscall boundary, delivering
+    /// `signal` (0 for none). A tracee that died in the meantime is ignored.
+    fn resume(pid: pid_t, signal: i32) {
+        // SAFETY: PTRACE_SYSCALL takes no pointers.
+        let _ =
+            unsafe { ptrace(libc::PTRACE_SYSCALL, pid, 0, signal as usize) };
+    }
+
+    struct Regs {
+        nr: c_long,
+        args: [u64; 6],
+        ret: i64,
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn regs(pid: pid_t) -> io::Result<Regs> {
+        let mut r: libc::user_regs_struct = unsafe { std::mem::zeroed() };
+        // SAFETY: `r` is a live `user_regs_struct` the kernel fills in.
+        unsafe {
+            ptrace(
+                libc::PTRACE_GETREGS,
+                pid,
+                0,
+                &mut r as *mut libc::user_regs_struct as usize,
+            )?
+        };
+        Ok(Regs {
+            nr: r.orig_rax as c_long,
+            args: [r.rdi, r.rsi, r.rdx, r.r10, r.r8, r.r9],
+            ret: r.rax as i64,
+        })
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn regs(pid: pid_t) -> io::Result<Regs> {
+        const NT_PRSTATUS: usize = 1;
+        let mut r: libc::user_regs_struct = unsafe { std::mem::zeroed() };
+        let mut iov = libc::iovec {
+            iov_base: &mut r as *mut libc::user_regs_struct as *mut c_void,
+            iov_len: std::mem::size_of::<libc::user_regs_struct>(),
+        };
+        // SAFETY: `iov` points at a live `user_regs_struct` of the size it
+        // advertises.
+        unsafe {
+            ptrace(
+                libc::PTRACE_GETREGSET,
+                pid,
+                NT_PRSTATUS,
+                &mut iov as *mut libc::iovec as usize,
+            )?
+        };
+        Ok(Regs {
+            nr: r.regs[8] as c_long,
+            args: [
+                r.regs[0], r.regs[1], r.regs[2], r.regs[3], r.regs[4],
+                r.regs[5],
+            ],
+            ret: r.regs[0] as i64,
+        })
+    }
+
+    /// Read a NUL-terminated string out of the tracee's memory, one page at a
+    /// time so a string ending just before an unmapped page is still read.
+    fn read_c_string(mem: &File, mut addr: u64) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+        while out.len() < PATH_MAX {
+            let chunk = (PAGE_SIZE - addr % PAGE_SIZE) as usize;
+            let mut buf = vec![0u8; chunk];
+            let n = mem.read_at(&mut buf, addr).ok()?;
+            if n == 0 {
+                return None;
+            }
+            if let Some(end) = buf[..n].iter().position(|b| *b == 0) {
+                out.extend_from_slice(&buf[..end]);
+                return Some(out);
+            }
+            out.extend_from_slice(&buf[..n]);
+            addr += n as u64;
+        }
+        None
+    }
+
+    fn read_u64(mem: &File, addr: u64) -> Option<u64> {
+        let mut buf = [0u8; 8];
+        mem.read_exact_at(&mut buf, addr).ok()?;
+        Some(u64::from_ne_bytes(buf))
+    }
+
+    /// Decodes the path-taking syscalls of one stopped tracee.
+    struct Decoder {
+        pid: pid_t,
+        mem: File,
+    }
+
+    impl Decoder {
+        fn new(pid: pid_t) -> Option<Self> {
+            let mem = File::open(format!("/proc/{pid}/mem")).ok()?;
+            Some(Self { pid, mem })
+        }
+
+        /// The absolute path of the `addr` argument, relative to `dirfd`.
+        fn path(&self, dirfd: u64, addr: u64) -> Option<PathBuf> {
+            let raw = read_c_string(&self.mem, addr)?;
+            if raw.is_empty() {
+                return None;
+            }
+            let path = PathBuf::from(OsStr::from_bytes(&raw));
+            if path.is_absolute() {
+                return Some(normalize(&path));
+            }
+            let base = if dirfd as i32 == libc::AT_FDCWD {
+                std::fs::read_link(format!("/proc/{}/cwd", self.pid)).ok()?
+            } else {
+                std::fs::read_link(format!(
+                    "/proc/{}/fd/{}",
+                    self.pid, dirfd as i32
+                ))
+                .ok()?
+            };
+            Some(normalize(&base.join(path)))
+        }
+
+        fn open(
+            &self,
+            dirfd: u64,
+            addr: u64,
+            flags: u64,
+            out: &mut Vec<FileAccess>,
+        ) {
+            let flags = flags as i32;
+            if flags & (libc::O_DIRECTORY | libc::O_PATH) != 0 {
+                return;
+            }
+            let Some(path) = self.path(dirfd, addr) else {
+                return;
+            };
+            let mode = flags & libc::O_ACCMODE;
+            if mode != libc::O_WRONLY {
+                out.push(FileAccess {
+                    kind: AccessKind::Read,
+                    path: path.clone(),
+                });
+            }
+            if mode != libc::O_RDONLY
+                || flags & (libc::O_CREAT | libc::O_TRUNC) != 0
+            {
+                out.push(FileAccess {
+                    kind: AccessKind::Write,
+                    path,
+                });
+            }
+        }
+
+        fn access(
+            &self,
+            kind: AccessKind,
+            dirfd: u64,
+            addr: u64,
+            out: &mut Vec<FileAccess>,
+        ) {
+            if let Some(path) = self.path(dirfd, addr) {
+                out.push(FileAccess { kind, path });
+            }
+        }
+
+        /// The accesses `nr` would perform if it succeeds.
+        fn decode(&self, nr: c_long, a: [u64; 6]) -> Vec<FileAccess> {
+            const CWD: u64 = libc::AT_FDCWD as u64;
+            let mut out = Vec::new();
+            match nr {
+                libc::SYS_openat => self.open(a[0], a[1], a[2], &mut out),
+                libc::SYS_openat2 => {
+                    // `struct open_how` starts with the `u64` flags.
+                    if let Some(flags) = read_u64(&self.mem, a[2]) {
+                        self.open(a[0], a[1], flags, &mut out);
+                    }
+                }
+                libc::SYS_execve => {
+                    self.access(AccessKind::Read, CWD, a[0], &mut out)
+                }
+                libc::SYS_execveat => {
+                    self.access(AccessKind::Read, a[0], a[1], &mut out)
+                }
+                libc::SYS_renameat2 => {
+                    self.access(AccessKind::Write, a[0], a[1], &mut out);
+                    self.access(AccessKind::Write, a[2], a[3], &mut out);
+                }
+                libc::SYS_unlinkat => {
+                    // `AT_REMOVEDIR` removes a directory, not file contents.
+                    if a[2] as i32 & libc::AT_REMOVEDIR == 0 {
+                        self.access(AccessKind::Write, a[0], a[1], &mut out);
+                    }
+                }
+                libc::SYS_truncate => {
+                    self.access(AccessKind::Write, CWD, a[0], &mut out)
+                }
+                #[cfg(target_arch = "x86_64")]
+                libc::SYS_open => self.open(CWD, a[0], a[1], &mut out),
+                #[cfg(target_arch = "x86_64")]
+                libc::SYS_creat => self.open(
+                    CWD,
+                    a[0],
+                    (libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC) as u64,
+                    &mut out,
+                ),
+                #[cfg(target_arch = "x86_64")]
+                libc::SYS_renameat => {
+                    self.access(AccessKind::Write, a[0], a[1], &mut out);
+                    self.access(AccessKind::Write, a[2], a[3], &mut out);
+                }
+                #[cfg(target_arch = "x86_64")]
+                libc::SYS_rename => {
+                    self.access(AccessKind::Write, CWD, a[0], &mut out);
+                    self.access(AccessKind::Write, CWD, a[1], &mut out);
+                }
+                #[cfg(target_arch = "x86_64")]
+                libc::SYS_unlink => {
+                    self.access(AccessKind::Write, CWD, a[0], &mut out)
+                }
+                _ => {}
+            }
+            out
+        }
+    }
+
+    pub(super) fn trace(
+        mut command: Command,
+        mut on_access: impl FnMut(FileAccess),
+    ) -> io::Result<ExitStatus> {
+        // SAFETY: the hook runs in the forked child before `execve` and only
+        // issues PTRACE_TRACEME, which takes no pointers.
+        unsafe {
+            command.pre_exec(|| unsafe {
+                ptrace(libc::PTRACE_TRACEME, 0, 0, 0).map(|_| ())
+            });
+        }
+        // The std `Child` is never waited on: the tracee is reaped by the
+        // `waitpid` loop below.
+        let child = command.spawn()?;
+        let root = child.id() as pid_t;
+
+        // The child stops with SIGTRAP once its `execve` succeeds.
+        let mut status = 0;
+        // SAFETY: `status` is a live `c_int`.
+        if unsafe { libc::waitpid(root, &mut status, libc::__WALL) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        if !libc::WIFSTOPPED(status) || libc::WSTOPSIG(status) != libc::SIGTRAP
+        {
+            // SAFETY: `root` is our own, not yet reaped, child.
+            unsafe { libc::kill(root, libc::SIGKILL) };
+            return Err(io::Error::other(format!(
+                "the traced process did not stop after exec (status {status:#x})"
+            )));
+        }
+
+        let options = libc::PTRACE_O_TRACESYSGOOD
+            | libc::PTRACE_O_TRACEFORK
+            | libc::PTRACE_O_TRACEVFORK
+            | libc::PTRACE_O_TRACECLONE
+            | libc::PTRACE_O_TRACEEXEC
+            | libc::PTRACE_O_EXITKILL;
+        // SAFETY: PTRACE_SETOPTIONS takes the options as a plain integer.
+        unsafe { ptrace(libc::PTRACE_SETOPTIONS, root, 0, options as usize)? };
+        resume(root, 0);
+
+        let mut known: HashSet<pid_t> = HashSet::from([root]);
+        // Tracees stopped at a syscall entry, with the accesses that syscall
+        // performs if it succeeds; the next syscall stop is its exit.
+        let mut in_syscall: HashMap<pid_t, Vec<FileAccess>> = HashMap::new();
+        let mut exit_status = None;
+
+        loop {
+            let mut status = 0;
+            // SAFETY: `status` is a live `c_int`.
+            let pid = unsafe { libc::waitpid(-1, &mut status, libc::__WALL) };
+            if pid == -1 {
+                let err = io::Error::last_os_error();
+                match err.raw_os_error() {
+                    Some(libc::ECHILD) => break,
+                    Some(libc::EINTR) => continue,
+                    _ => return Err(err),
+                }
+            }
+
+            if libc::WIFEXITED(status) || libc::WIFSIGNALED(status) {
+                known.remove(&pid);
+                in_syscall.remove(&pid);
+                if pid == root {
+                    exit_status = Some(ExitStatus::from_raw(status));
+                }
+                continue;
+            }
+
+            if !libc::WIFSTOPPED(status) {
+                continue;
+            }
+
+            let signal = libc::WSTOPSIG(status);
+            let is_new = known.insert(pid);
+
+            if signal == libc::SIGTRAP | 0x80 {
+                if let Some(pending) = in_syscall.remove(&pid) {
+                    if !pending.is_empty()
+                        && regs(pid).is_ok_and(|r| r.ret >= 0)
+                    {
+                        pending.into_iter().for_each(&mut on_access);
+                    }
+                } else {
+                    let pending = regs(pid)
+                        .ok()
+                        .zip(Decoder::new(pid))
+                        .map(|(r, d)| d.decode(r.nr, r.args))
+                        .unwrap_or_default();
+                    in_syscall.insert(pid, pending);
+                }
+                resume(pid, 0);
+            } else if signal == libc::SIGTRAP && status >> 16 != 0 {
+                // A fork/clone/exec event stop; the new tracee reports itself.
+                resume(pid, 0);
+            } else if signal == libc::SIGSTOP && is_new {
+                // A freshly attached tracee starts with a SIGSTOP that must
+                // not be delivered.
+                resume(pid, 0);
+            } else {
+                resume(pid, signal);
+            }
+        }
+
+        drop(child);
+        exit_status.ok_or_else(|| {
+            io::Error::other("the traced process exited without a status")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_resolves_dot_segments_lexically() {
+        assert_eq!(
+            normalize(Path::new("/repo/app/./src/../dist/out.js")),
+            PathBuf::from("/repo/app/dist/out.js")
+        );
+        assert_eq!(normalize(Path::new("/../etc")), PathBuf::from("/etc"));
+    }
+
+    #[test]
+    fn access_log_deduplicates_by_kind() {
+        let mut log = AccessLog::default();
+        for kind in [AccessKind::Read, AccessKind::Read, AccessKind::Write] {
+            log.record(FileAccess {
+                kind,
+                path: PathBuf::from("/repo/a"),
+            });
+        }
+
+        assert_eq!(log.reads.len(), 1);
+        assert_eq!(log.writes.len(), 1);
+    }
+}
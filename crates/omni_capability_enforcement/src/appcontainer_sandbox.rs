@@ -1305,6 +1305,7 @@ mod tests {
             exec_programs: Vec::new(),
             connect_ports: Vec::new(),
             confine: false,
+            confine_connect: false,
         };
         let mut command = Command::new(&cmd);
         command
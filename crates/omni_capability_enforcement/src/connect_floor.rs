@@ -0,0 +1,225 @@
+//! [`ConnectFloor`]: a [`Tier::OsSandbox`] backend that claims `net` for
+//! processes with **no** in-process shim.
+//!
+//! [`NativeOsSandbox`](crate::NativeOsSandbox) lowers concrete `net` ports into
+//! the Landlock `connect` floor but never claims `net`: for bridge scripts the
+//! host half of a `host:port` rule is enforced by the script shim, so the OS
+//! floor is only a backstop. A native process (e.g. an `omni run` task) has no
+//! shim, so the kernel floor is the *only* net mechanism. This backend claims
+//! `net` exactly when that floor can stand on its own:
+//!
+//! * an any-host rule with a concrete port (`*:443`) lowers to that port;
+//! * an any-host, any-port rule (`*`, `*:*`) leaves connects unconfined;
+//! * a host-specific rule (`registry.npmjs.org:443`) cannot be matched by the
+//!   kernel and is reported as a [`Gap`], so it fails closed unless the rule
+//!   opts into `on_unenforceable: warn`;
+//! * a `deny` is representable only when the port allow-list already excludes
+//!   it; otherwise it is a [`Gap`].
+//!
+//! When nothing is allowed the spec sets
+//! [`confine_connect`](crate::OsSandboxSpec::confine_connect), so every
+//! outbound connect is denied. Landlock governs TCP only; UDP (and therefore
+//! plain DNS lookups) is not confined by this floor.
+//!
+//! Coverage is claimed only on Linux with a Landlock ABI of at least 4, and
+//! never when the OS sandbox is disabled via `OMNI_DISABLE_OS_SANDBOX`, so a
+//! host without the floor fails closed at [`require_full_coverage`].
+//!
+//! [`require_full_coverage`]: crate::require_full_coverage
+
+use omni_capabilities::{
+    CapabilityAtom, CapabilityDomain, RequiredCapabilities,
+};
+
+use crate::{
+    BackendPlan, Coverage, EnforcementBackend, EnforcementError, Gap,
+    OsSandboxSpec, PatternResolver, Tier, lower::split_host_port,
+};
+
+const NAME: &str = "landlock-connect";
+
+/// The kernel TCP-connect floor, claiming `net` on hosts that provide it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConnectFloor {
+    disabled: bool,
+}
+
+impl ConnectFloor {
+    /// A floor whose coverage reflects the resolved launch posture: `disabled`
+    /// is the single `OMNI_DISABLE_OS_SANDBOX` read threaded from the spawner.
+    pub fn resolved(disabled: bool) -> Self {
+        Self { disabled }
+    }
+
+    /// Whether the running kernel can confine TCP connects by port.
+    pub fn is_supported() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            crate::landlock_sandbox::abi_version() >= 4
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+}
+
+impl EnforcementBackend for ConnectFloor {
+    fn name(&self) -> &'static str {
+        NAME
+    }
+
+    fn tier(&self) -> Tier {
+        Tier::OsSandbox
+    }
+
+    fn coverage(&self) -> Coverage {
+        if self.disabled || !Self::is_supported() {
+            return Coverage::none();
+        }
+        Coverage::of([CapabilityDomain::Net])
+    }
+
+    fn plan(
+        &self,
+        req: &RequiredCapabilities,
+        _roots: &dyn PatternResolver,
+    ) -> Result<BackendPlan, EnforcementError> {
+        // Keep the lowered spec in lock-step with the claimed coverage.
+        if self.disabled || !Self::is_supported() {
+            return Ok(BackendPlan::new());
+        }
+        Ok(lower(req))
+    }
+}
+
+/// Lower the `net` policy into a connect-port allow-list. Factored out of
+/// [`ConnectFloor::plan`] so it is testable on hosts without Landlock V4.
+fn lower(req: &RequiredCapabilities) -> BackendPlan {
+    let mut plan = BackendPlan::new();
+    let mut ports: Vec<u16> = Vec::new();
+    let mut any_port = false;
+
+    let Some(rules) = req.domains().get(&CapabilityDomain::Net) else {
+        plan.spawn.os_sandbox = Some(OsSandboxSpec {
+            confine_connect: true,
+            ..Default::default()
+        });
+        return plan;
+    };
+
+    for atom in &rules.allow {
+        let (host, port) = split_host_port(&atom.pattern);
+        if !is_any_host(host) {
+            plan.gaps.push(gap(
+                atom,
+                "the kernel connect floor matches ports, not hosts; allow \
+                 `*:<port>` instead or set `on_unenforceable: warn`",
+            ));
+            continue;
+        }
+        match port {
+            None | Some("*") => any_port = true,
+            Some(p) => match p.parse::<u16>() {
+                Ok(p) if !ports.contains(&p) => ports.push(p),
+                Ok(_) => {}
+                Err(_) => plan.gaps.push(gap(atom, "not a TCP port")),
+            },
+        }
+    }
+
+    for atom in &rules.deny {
+        let (_host, port) = split_host_port(&atom.pattern);
+        let implied = !any_port
+            && match port {
+                None | Some("*") => ports.is_empty(),
+                Some(p) => p.parse::<u16>().is_ok_and(|p| !ports.contains(&p)),
+            };
+        if !implied {
+            plan.gaps.push(gap(
+                atom,
+                "a port allow-list cannot express a `deny` that an allowed \
+                 port would re-open",
+            ));
+        }
+    }
+
+    if !any_port {
+        plan.spawn.os_sandbox = Some(OsSandboxSpec {
+            connect_ports: ports,
+            confine_connect: true,
+            ..Default::default()
+        });
+    }
+
+    plan
+}
+
+fn is_any_host(host: &str) -> bool {
+    host == "*" || host == "**"
+}
+
+fn gap(atom: &CapabilityAtom, reason: &str) -> Gap {
+    Gap {
+        backend: NAME.to_string(),
+        domain: CapabilityDomain::Net,
+        id: atom.id,
+        pattern: atom.pattern.clone(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use omni_capabilities::{CapabilityRules, project};
+
+    use super::*;
+
+    fn lower_json(json: &str) -> BackendPlan {
+        let cfg: CapabilityRules = serde_json::from_str(json).unwrap();
+        lower(&project(&cfg, &()))
+    }
+
+    #[test]
+    fn no_net_rules_denies_every_connect() {
+        let plan = lower_json(
+            r#"[{ "access": "allow", "domain": "fs.read", "patterns": ["/repo/**"] }]"#,
+        );
+        let spec = plan.spawn.os_sandbox.expect("connects are confined");
+        assert!(spec.confine_connect);
+        assert!(spec.connect_ports.is_empty());
+        assert!(plan.gaps.is_empty());
+    }
+
+    #[test]
+    fn any_host_ports_lower_and_host_rules_are_gaps() {
+        let plan = lower_json(
+            r#"[{ "access": "allow", "domain": "net", "patterns": ["*:443", "*:443", "example.com:80"] }]"#,
+        );
+        let spec = plan.spawn.os_sandbox.expect("connects are confined");
+        assert_eq!(spec.connect_ports, vec![443]);
+        assert_eq!(plan.gaps.len(), 1);
+        assert_eq!(plan.gaps[0].pattern, "example.com:80");
+    }
+
+    #[test]
+    fn any_host_any_port_leaves_connects_unconfined() {
+        let plan = lower_json(
+            r#"[{ "access": "allow", "domain": "net", "patterns": ["*:*"] }]"#,
+        );
+        assert!(plan.spawn.os_sandbox.is_none());
+        assert!(plan.gaps.is_empty());
+    }
+
+    #[test]
+    fn deny_is_a_gap_only_when_an_allowed_port_reopens_it() {
+        let plan = lower_json(
+            r#"[
+                { "access": "allow", "domain": "net", "patterns": ["*:443"] },
+                { "access": "deny",  "domain": "net", "patterns": ["*:22", "evil.com:443"] }
+            ]"#,
+        );
+        assert_eq!(plan.gaps.len(), 1);
+        assert_eq!(plan.gaps[0].pattern, "evil.com:443");
+    }
+}
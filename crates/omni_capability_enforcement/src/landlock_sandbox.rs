@@ -68,6 +68,11 @@
 //! * `host:*` (all-ports) and `deny` rules cannot be a port allow-list and are
 //!   never lowered (see [`platform`](crate::platform)).
 //!
+//! A spec with [`OsSandboxSpec::confine_connect`] set handles `ConnectTcp` even
+//! when it grants no port, so every outbound connect is denied. That is what
+//! [`ConnectFloor`](crate::ConnectFloor) relies on to claim `net` for native
+//! processes that have no shim.
+//!
 //! [best-effort compatibility]: https://docs.rs/landlock/latest/landlock/enum.CompatLevel.html
 //!
 //! ## Fork-safety note
@@ -157,11 +162,12 @@ pub fn restrict(spec: &OsSandboxSpec) -> io::Result<()> {
 
     // The filesystem floor is always installed (best-effort down to whatever the
     // kernel supports). The TCP `connect` floor is added only when the kernel is
-    // V4+ and the policy named concrete ports, so an older kernel still enforces
-    // fs. See the module docs for why this net floor is port-only and
-    // connect-only.
+    // V4+ and the policy named concrete ports (or asked for connects to be
+    // confined outright), so an older kernel still enforces fs. See the module
+    // docs for why this net floor is port-only and connect-only.
     let net_abi = abi_version();
-    let confine_net = net_abi >= 4 && !spec.connect_ports.is_empty();
+    let confine_net = net_abi >= 4
+        && (spec.confine_connect || !spec.connect_ports.is_empty());
 
     let mut ruleset = Ruleset::default()
         .handle_access(read_write)
@@ -313,6 +319,7 @@ pub(crate) fn restrict_paths(
         exec_programs: Vec::new(),
         connect_ports: Vec::new(),
         confine: false,
+        confine_connect: false,
     })
 }
 
@@ -21,7 +21,9 @@
 //!   errors rather than silent holes.
 //! * [`Tier::OsSandbox`] — the kernel access-control sandbox for the target OS
 //!   ([`NativeOsSandbox`]: Landlock / Seatbelt / AppContainer). Declared per
-//!   platform; the integrations themselves are deferred.
+//!   platform; the integrations themselves are deferred. [`ConnectFloor`]
+//!   additionally claims `net` through the Landlock connect floor, for native
+//!   processes that have no script shim.
 //! * [`Tier::InProcessBroker`] — a per-operation broker at omni's I/O boundary
 //!   (the generator's `TransactionSys` / the bridge services). [`BridgeBroker`]
 //!   is the descriptor: it enforces every mediated domain exactly at runtime,
@@ -98,10 +100,12 @@
     feature(windows_process_extensions_raw_attribute)
 )]
 
+pub mod access_trace;
 #[cfg(target_os = "windows")]
 pub mod appcontainer_sandbox;
 pub mod backend;
 pub mod broker;
+pub mod connect_floor;
 pub mod deno;
 pub mod error;
 #[cfg(target_os = "linux")]
@@ -120,10 +124,12 @@ mod unix_sandbox;
 
 // @anchor:mods
 
+pub use access_trace::{AccessKind, AccessLog, FileAccess, trace_command};
 pub use backend::{
     BackendPlan, Coverage, EnforcementBackend, Gap, PatternResolver, Tier,
 };
 pub use broker::BridgeBroker;
+pub use connect_floor::ConnectFloor;
 pub use deno::DenoFlags;
 pub use error::{EnforcementError, EnforcementErrorKind};
 pub use node::NodePermissions;
@@ -48,6 +48,16 @@ pub struct OsSandboxSpec {
     /// port allow-list and are never lowered here.
     pub connect_ports: Vec<u16>,
 
+    /// Whether outbound TCP *connects* are confined even when
+    /// [`connect_ports`](Self::connect_ports) is empty, so a process whose
+    /// policy allows no network at all cannot connect anywhere.
+    ///
+    /// Without it an empty port list means "no net floor" (the historical
+    /// behaviour, where the in-process shim is the net authority). A backend
+    /// that *claims* `net` coverage sets it, because for a native process with
+    /// no shim an empty allow-list has to mean "deny every connect".
+    pub confine_connect: bool,
+
     /// Whether the OS sandbox tier should still *establish* the container even
     /// when it grants no policy paths of its own.
     ///
@@ -67,8 +77,8 @@ impl OsSandboxSpec {
         Self::default()
     }
 
-    /// Whether the spec grants no policy paths, programs, or ports. This is
-    /// deliberately paths-only and ignores [`confine`](Self::confine): a spec
+    /// Whether the spec grants no policy paths, programs, or ports and does not
+    /// confine connects. This deliberately ignores [`confine`](Self::confine): a spec
     /// that only establishes the container (no lowered policy) is still
     /// `is_empty`, so callers that ask "did any policy lower into an OS grant?"
     /// get an honest answer. The plan uses `!is_empty() || confine` to decide
@@ -78,16 +88,19 @@ impl OsSandboxSpec {
             && self.write_paths.is_empty()
             && self.exec_programs.is_empty()
             && self.connect_ports.is_empty()
+            && !self.confine_connect
     }
 
-    /// Fold another spec's paths into this one (order preserved). `confine` is
-    /// OR-folded: the merged spec confines if either side did.
+    /// Fold another spec's paths into this one (order preserved). `confine` and
+    /// `confine_connect` are OR-folded: the merged spec confines if either side
+    /// did.
     pub fn extend(&mut self, other: OsSandboxSpec) {
         self.read_paths.extend(other.read_paths);
         self.write_paths.extend(other.write_paths);
         self.exec_programs.extend(other.exec_programs);
         self.connect_ports.extend(other.connect_ports);
         self.confine |= other.confine;
+        self.confine_connect |= other.confine_connect;
     }
 }
 
@@ -198,4 +211,20 @@ mod tests {
         base.extend(OsSandboxSpec::default());
         assert!(base.confine, "a non-confining fold cannot clear confine");
     }
+
+    #[test]
+    fn confine_connect_alone_is_not_empty() {
+        let spec = OsSandboxSpec {
+            confine_connect: true,
+            ..Default::default()
+        };
+        assert!(
+            !spec.is_empty(),
+            "a spec that denies every connect still has to be installed"
+        );
+
+        let mut base = OsSandboxSpec::default();
+        base.extend(spec);
+        assert!(base.confine_connect, "confine_connect ORs in on extend");
+    }
 }
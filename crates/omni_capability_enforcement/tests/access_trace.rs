@@ -0,0 +1,96 @@
+//! Live test of [`trace_command`]: it traces a **real process tree** (a shell
+//! that forks `cat`) and checks the reads and writes of the grandchild are
+//! attributed to the right files.
+//!
+//! Linux-only; it **skips** when `ptrace` is unavailable (e.g. a container
+//! with a seccomp profile that forbids it), so it is safe in any CI.
+
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use omni_capability_enforcement::{AccessLog, access_trace, trace_command};
+
+/// First existing candidate path, or `None` (test then skips).
+fn first_existing(candidates: &[&str]) -> Option<PathBuf> {
+    candidates.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+fn sh() -> Option<PathBuf> {
+    first_existing(&["/bin/sh", "/usr/bin/sh"])
+}
+
+#[test]
+fn traces_reads_and_writes_of_descendants() {
+    if !access_trace::is_supported() {
+        eprintln!("skipping: access tracing unsupported on this target");
+        return;
+    }
+    let Some(sh) = sh() else {
+        eprintln!("skipping: no sh");
+        return;
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = fs::canonicalize(dir.path()).unwrap();
+    fs::write(root.join("in.txt"), "hello").unwrap();
+
+    let mut cmd = Command::new(sh);
+    cmd.args(["-c", "cat in.txt > out.txt"])
+        .current_dir(&root)
+        .stdin(Stdio::null());
+
+    let mut log = AccessLog::default();
+    let status = match trace_command(cmd, |access| log.record(access)) {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("skipping: ptrace unavailable: {e}");
+            return;
+        }
+    };
+
+    assert!(status.success());
+    assert_eq!(fs::read_to_string(root.join("out.txt")).unwrap(), "hello");
+    assert!(log.reads.contains(&root.join("in.txt")), "{log:?}");
+    assert!(log.writes.contains(&root.join("out.txt")), "{log:?}");
+    assert!(!log.writes.contains(&root.join("in.txt")), "{log:?}");
+}
+
+#[test]
+fn propagates_the_exit_code() {
+    if !access_trace::is_supported() {
+        return;
+    }
+    let Some(sh) = sh() else {
+        return;
+    };
+
+    let mut cmd = Command::new(sh);
+    cmd.args(["-c", "exit 3"]).stdin(Stdio::null());
+
+    if let Ok(status) = trace_command(cmd, |_| {}) {
+        assert_eq!(status.code(), Some(3));
+    }
+}
+
+#[test]
+fn reports_the_killing_signal() {
+    use std::os::unix::process::ExitStatusExt as _;
+
+    if !access_trace::is_supported() {
+        return;
+    }
+    let Some(sh) = sh() else {
+        return;
+    };
+
+    let mut cmd = Command::new(sh);
+    cmd.args(["-c", "kill -TERM $$"]).stdin(Stdio::null());
+
+    if let Ok(status) = trace_command(cmd, |_| {}) {
+        assert_eq!(status.code(), None);
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+    }
+}
@@ -119,6 +119,7 @@ fn landlock_confines_reads_to_the_granted_subtree() {
         exec_programs: vec![],
         connect_ports: vec![],
         confine: false,
+        confine_connect: false,
     };
 
     // (1) A read inside the granted subtree succeeds.
@@ -167,6 +168,7 @@ fn landlock_confines_writes_to_the_granted_subtree() {
         exec_programs: vec![],
         connect_ports: vec![],
         confine: false,
+        confine_connect: false,
     };
 
     let write_cmd = |target: &Path| {
@@ -236,6 +238,7 @@ fn landlock_permits_cross_directory_rename_within_a_granted_writable_subtree() {
         exec_programs: vec![],
         connect_ports: vec![],
         confine: false,
+        confine_connect: false,
     };
 
     // (1) A cross-directory rename *within* the granted subtree (a `refer`
@@ -317,6 +320,7 @@ fn landlock_gates_execution_by_the_binary_directory_grant() {
         exec_programs: vec![],
         connect_ports: vec![],
         confine: false,
+        confine_connect: false,
     };
 
     // (1) A program whose directory is granted can be executed. `cat /dev/null`
@@ -396,6 +400,7 @@ fn landlock_confines_tcp_connect_to_the_granted_port() {
         exec_programs: vec![],
         connect_ports: vec![port],
         confine: false,
+        confine_connect: false,
     };
     let out = run(connect_cmd(&bash, port), &allow_spec);
     assert!(
@@ -413,6 +418,7 @@ fn landlock_confines_tcp_connect_to_the_granted_port() {
         exec_programs: vec![],
         connect_ports: vec![other],
         confine: false,
+        confine_connect: false,
     };
     let out = run(connect_cmd(&bash, port), &deny_spec);
     assert!(
@@ -464,6 +470,7 @@ fn landlock_confinement_is_inherited_by_a_grandchild() {
         exec_programs: vec![],
         connect_ports: vec![],
         confine: false,
+        confine_connect: false,
     };
 
     // `sh -c '<cat> <path>'`: `cat` is the grandchild. An absolute `cat` path
@@ -522,6 +529,7 @@ fn landlock_sets_no_new_privs_on_child_and_grandchild() {
         exec_programs: vec![],
         connect_ports: vec![],
         confine: false,
+        confine_connect: false,
     };
 
     // Child: the confined `cat` reads its own status.
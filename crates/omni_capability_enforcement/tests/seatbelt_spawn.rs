@@ -93,6 +93,7 @@ fn spec_with(read: Vec<PathBuf>, write: Vec<PathBuf>) -> OsSandboxSpec {
         exec_programs: Vec::new(),
         connect_ports: Vec::new(),
         confine: false,
+        confine_connect: false,
     }
 }
 
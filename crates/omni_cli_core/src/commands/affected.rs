@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use clap_utils::EnumValueAdapter;
+use omni_api::{AffectedFormat, AffectedRequest, OmniApi};
+use omni_context::Context;
+use omni_daemon::daemon_or_local;
+use omni_messages::NoopSubscriber;
+use omni_scm::SelectScm;
+use omni_tracing_subscriber::noop_subscriber;
+use tracing_futures::WithSubscriber as _;
+
+#[derive(Args)]
+pub struct AffectedCommand {
+    #[arg(
+        help = "Also list the tasks that would run for these tasks, including the tasks of dependent projects. If not specified, only the affected projects are listed"
+    )]
+    pub task: Vec<String>,
+
+    #[arg(
+        long,
+        short,
+        help = "Output format",
+        default_value_t = EnumValueAdapter::new(AffectedFormat::Text),
+        value_enum
+    )]
+    pub format: EnumValueAdapter<AffectedFormat>,
+
+    #[arg(
+        long,
+        short,
+        help = "Write the output to the specified file instead of stdout"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        alias = "base",
+        short = 'b',
+        help = "The base commit to compare against"
+    )]
+    pub scm_base: Option<String>,
+
+    #[arg(
+        long,
+        alias = "target",
+        short = 't',
+        help = "The target commit to compare against"
+    )]
+    pub scm_target: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = EnumValueAdapter::new(SelectScm::Auto),
+        value_enum,
+        help = "The scm to use for detecting changed files"
+    )]
+    pub scm: EnumValueAdapter<SelectScm>,
+}
+
+pub async fn run(command: &AffectedCommand, ctx: &Context) -> eyre::Result<()> {
+    let req = AffectedRequest {
+        tasks: command.task.clone(),
+        base: command.scm_base.clone(),
+        target: command.scm_target.clone(),
+        scm: command.scm.value(),
+    };
+
+    // The output is meant to be consumed by CI scripts, keep traces out of it.
+    let affected = daemon_or_local(
+        ctx,
+        async |daemon| daemon.affected(req.clone()).await,
+        async || {
+            OmniApi::new_with_sys(ctx.clone(), NoopSubscriber)
+                .affected(req.clone())
+                .with_subscriber(noop_subscriber())
+                .await
+        },
+    )
+    .await?;
+    let rendered = affected.render(command.format.value())?;
+
+    if let Some(output) = &command.output {
+        std::fs::write(output, rendered)?;
+    } else {
+        print!("{rendered}");
+    }
+
+    Ok(())
+}
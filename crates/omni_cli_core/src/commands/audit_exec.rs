@@ -0,0 +1,72 @@
+use std::{
+    path::PathBuf,
+    process::{Command, ExitStatus},
+};
+
+use omni_capability_enforcement::{AccessLog, trace_command};
+
+#[derive(clap::Args)]
+pub struct AuditExecCommand {
+    #[command(flatten)]
+    pub args: AuditExecArgs,
+}
+
+#[derive(clap::Args)]
+pub struct AuditExecArgs {
+    #[arg(
+        long = "log",
+        help = "Where to write the JSON log of the files the command accessed"
+    )]
+    pub log: PathBuf,
+
+    #[arg(
+        num_args(1..),
+        required = true,
+        help = "The command to trace",
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    pub cmd: Vec<String>,
+}
+
+/// Run the command under the file access tracer, write the access log and
+/// return the command's exit code. A command killed by a signal is reported
+/// on stderr and exits with `128 + signal`, like in a shell. Used by
+/// `omni run --audit-io` as the wrapper around each task process.
+pub async fn run(audit: &AuditExecCommand) -> eyre::Result<i32> {
+    let (program, args) = audit
+        .args
+        .cmd
+        .split_first()
+        .ok_or_else(|| eyre::eyre!("no command provided to audit-exec"))?;
+
+    let mut command = Command::new(program);
+    command.args(args);
+
+    // The thread that spawns the tracee must be the one that traces it.
+    let (status, log) = tokio::task::spawn_blocking(move || {
+        let mut log = AccessLog::default();
+        let status = trace_command(command, |access| log.record(access))?;
+        Ok::<_, std::io::Error>((status, log))
+    })
+    .await??;
+
+    tokio::fs::write(&audit.args.log, serde_json::to_vec(&log)?).await?;
+
+    exit_code(status)
+}
+
+fn exit_code(status: ExitStatus) -> eyre::Result<i32> {
+    if let Some(code) = status.code() {
+        return Ok(code);
+    }
+
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status)
+    {
+        eprintln!("audit-exec: the command was killed by signal {signal}");
+        return Ok(128 + signal);
+    }
+
+    eyre::bail!("the command exited without an exit code ({status})")
+}
@@ -5,13 +5,17 @@ use bytesize::ByteSize;
 use clap::Subcommand;
 use itertools::Itertools;
 use omni_api::{
-    CachePruneRequest, CacheRemoteSetupRequest, CacheStatsRequest, OmniApi,
+    CacheExplainRequest, CacheExplainResponse, CacheExplainStatus,
+    CacheInputChange, CacheInputChangeKind, CachePruneRequest,
+    CacheRemoteSetupRequest, CacheStatsRequest, OmniApi,
 };
 use omni_cache::PrunedCacheEntry;
 use omni_context::Context;
 use omni_messages::NoopSubscriber;
 use owo_colors::OwoColorize;
 
+use crate::commands::cache_remote_admin::{self, AdminArgs};
+
 #[derive(clap::Args)]
 #[command(author, version, about = "Cache management commands")]
 pub struct CacheCommand {
@@ -38,6 +42,13 @@ pub enum CacheSubcommands {
         #[command(flatten)]
         args: RemoteArgs,
     },
+    #[command(
+        about = "Explain why a task misses the cache by comparing its inputs with the last cached entry"
+    )]
+    Explain {
+        #[arg(help = "The task to explain, in the form of project#task")]
+        task: String,
+    },
 }
 
 #[derive(clap::Args)]
@@ -132,6 +143,13 @@ pub enum RemoteSubcommands {
         #[command(flatten)]
         args: SetupArgs,
     },
+    #[command(
+        about = "Manage the tenants, organizations, workspaces, environments and API keys of a remote cache server through its admin API"
+    )]
+    Admin {
+        #[command(flatten)]
+        args: AdminArgs,
+    },
 }
 
 #[derive(clap::Args, Debug)]
@@ -166,6 +184,20 @@ pub struct SetupArgs {
     )]
     pub env: Option<String>,
 
+    #[arg(
+        long,
+        help = "Base64 encoded key of at least 32 bytes to sign and verify the remote cache artifacts with. Everyone sharing the remote cache needs the same key",
+        conflicts_with = "generate_signing_key"
+    )]
+    pub signing_key: Option<String>,
+
+    #[arg(
+        long,
+        help = "Generate a key to sign and verify the remote cache artifacts with and print it, so it can be shared with everyone using the remote cache",
+        default_value_t = false
+    )]
+    pub generate_signing_key: bool,
+
     #[arg(
         long,
         short,
@@ -243,8 +275,21 @@ pub async fn run(command: &CacheCommand, ctx: &Context) -> eyre::Result<()> {
             prune(&api, args).await?;
         }
 
+        CacheSubcommands::Explain { task } => {
+            let explanation = api
+                .cache_explain(CacheExplainRequest { task: task.clone() })
+                .await?;
+            display_explanation(&explanation);
+        }
+
         CacheSubcommands::Remote { args } => match args.subcommand {
             RemoteSubcommands::Setup { ref args } => {
+                let signing_key = if args.generate_signing_key {
+                    Some(omni_setup::generate_signing_key())
+                } else {
+                    args.signing_key.clone()
+                };
+
                 api.cache_remote_setup(CacheRemoteSetupRequest {
                     api_base_url: args.api_base_url.clone(),
                     api_key: args.api_key.clone(),
@@ -252,6 +297,7 @@ pub async fn run(command: &CacheCommand, ctx: &Context) -> eyre::Result<()> {
                     org: args.org.clone(),
                     ws: args.ws.clone(),
                     env: args.env.clone(),
+                    signing_key: signing_key.clone(),
                     secure: args.secure,
                 })
                 .await
@@ -260,6 +306,18 @@ pub async fn run(command: &CacheCommand, ctx: &Context) -> eyre::Result<()> {
                         "Failed to setup remote caching. Please check your credentials and try again."
                     );
                 })?;
+
+                if args.generate_signing_key
+                    && let Some(signing_key) = signing_key
+                {
+                    println!(
+                        "Remote cache artifacts will be signed with the key below. Set it up with `--signing-key` everywhere else the remote cache is used:"
+                    );
+                    println!("{signing_key}");
+                }
+            }
+            RemoteSubcommands::Admin { ref args } => {
+                cache_remote_admin::run(args).await?;
             }
         },
     }
@@ -381,3 +439,67 @@ fn display_pruned_entries(pruned: &[PrunedCacheEntry]) {
         println!();
     }
 }
+
+fn display_explanation(explanation: &CacheExplainResponse) {
+    let task = format!("{}#{}", explanation.project, explanation.task);
+
+    match explanation.status {
+        CacheExplainStatus::Hit => {
+            println!("{} is cached ({})", task, explanation.current_digest);
+            return;
+        }
+        CacheExplainStatus::NoEntry => {
+            println!("{} has never been cached", task);
+            return;
+        }
+        CacheExplainStatus::NoManifest => {
+            println!(
+                "{} was last cached without an input manifest, re-run it to record one",
+                task
+            );
+            return;
+        }
+        CacheExplainStatus::Miss => {}
+    }
+
+    println!(
+        "{} misses the cache: {} (current) != {} (cached)",
+        task,
+        explanation.current_digest,
+        explanation.cached_digest.as_deref().unwrap_or("N/A")
+    );
+
+    display_changes("Files", &explanation.files);
+    display_changes("Env Vars", &explanation.env_vars);
+    display_changes("Args", &explanation.args);
+    display_changes("Upstream Tasks", &explanation.dependencies);
+    display_changes("Output Globs", &explanation.output_globs);
+
+    if explanation.command_changed {
+        println!("  Command: changed");
+    }
+    if explanation.retry_command_changed {
+        println!("  Retry Command: changed");
+    }
+}
+
+fn display_changes(label: &str, changes: &[CacheInputChange]) {
+    if changes.is_empty() {
+        return;
+    }
+
+    println!("  {}:", label);
+    for change in changes {
+        match change.kind {
+            CacheInputChangeKind::Added => {
+                println!("    {} {}", "+".green(), change.name)
+            }
+            CacheInputChangeKind::Removed => {
+                println!("    {} {}", "-".red(), change.name)
+            }
+            CacheInputChangeKind::Modified => {
+                println!("    {} {}", "~".yellow(), change.name)
+            }
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use std::time::Duration;
+
+use clap::Subcommand;
+use comfy_table::{TableStyle, presets::UTF8_FULL};
+use omni_remote_cache_client::{
+    AdminApiKey, AdminEntity, NewAdminApiKey, NewAdminEntity, RemoteAdminArgs,
+    RemoteCacheAdminClient,
+};
+use owo_colors::OwoColorize as _;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+#[derive(clap::Args, Debug)]
+pub struct AdminArgs {
+    #[arg(
+        long,
+        short = 'b',
+        help = "The endpoint base URL of the remote cache server",
+        env = "OMNI_REMOTE_CACHE_API_BASE_URL"
+    )]
+    pub api_base_url: String,
+
+    #[arg(
+        long,
+        short,
+        help = "The admin API key of the remote cache server",
+        env = "OMNI_REMOTE_CACHE_ADMIN_API_KEY",
+        hide_env_values = true
+    )]
+    pub admin_api_key: String,
+
+    #[clap(subcommand)]
+    pub subcommand: AdminSubcommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AdminSubcommands {
+    #[command(about = "Manage tenants")]
+    Tenant {
+        #[clap(subcommand)]
+        subcommand: EntitySubcommands,
+    },
+    #[command(about = "Manage the organizations of a tenant")]
+    Org {
+        #[arg(long, short, help = "The tenant code")]
+        tenant: String,
+
+        #[clap(subcommand)]
+        subcommand: EntitySubcommands,
+    },
+    #[command(about = "Manage the workspaces of an organization")]
+    Ws {
+        #[arg(long, short, help = "The tenant code")]
+        tenant: String,
+
+        #[arg(long, short, help = "The organization code")]
+        org: String,
+
+        #[clap(subcommand)]
+        subcommand: EntitySubcommands,
+    },
+    #[command(about = "Manage the environments of a workspace")]
+    Env {
+        #[arg(long, short, help = "The tenant code")]
+        tenant: String,
+
+        #[arg(long, short, help = "The organization code")]
+        org: String,
+
+        #[arg(long, short, help = "The workspace code")]
+        ws: String,
+
+        #[clap(subcommand)]
+        subcommand: EntitySubcommands,
+    },
+    #[command(about = "Manage API keys")]
+    Key {
+        #[clap(subcommand)]
+        subcommand: KeySubcommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EntitySubcommands {
+    #[command(about = "List them")]
+    List,
+    #[command(about = "Create a new one")]
+    Create {
+        #[command(flatten)]
+        args: CreateEntityArgs,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CreateEntityArgs {
+    #[arg(help = "The code used to refer to it")]
+    pub code: String,
+
+    #[arg(long, help = "The display name, defaults to the code")]
+    pub display_name: Option<String>,
+
+    #[arg(long, help = "The description")]
+    pub description: Option<String>,
+}
+
+impl From<&CreateEntityArgs> for NewAdminEntity {
+    fn from(args: &CreateEntityArgs) -> Self {
+        NewAdminEntity::new(
+            args.code.clone(),
+            args.display_name.clone(),
+            args.description.clone(),
+        )
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KeySubcommands {
+    #[command(about = "List the API keys, without the keys themselves")]
+    List,
+    #[command(
+        about = "Mint a new API key, it is printed once and can't be retrieved afterwards"
+    )]
+    Create {
+        #[arg(long, help = "The description")]
+        description: Option<String>,
+
+        #[arg(
+            long = "scope",
+            help = "Allow only the given scope, e.g. read:artifacts. Can be repeated, allows all scopes if omitted"
+        )]
+        scopes: Vec<String>,
+
+        #[arg(
+            long = "tenant",
+            help = "Allow only the given tenant. Can be repeated, allows all tenants if omitted"
+        )]
+        tenants: Vec<String>,
+
+        #[arg(
+            long = "org",
+            help = "Allow only the given organization. Can be repeated, allows all organizations if omitted"
+        )]
+        orgs: Vec<String>,
+
+        #[arg(
+            long = "ws",
+            help = "Allow only the given workspace. Can be repeated, allows all workspaces if omitted"
+        )]
+        workspaces: Vec<String>,
+
+        #[arg(
+            long = "env",
+            help = "Allow only the given environment. Can be repeated, allows all environments if omitted"
+        )]
+        environments: Vec<String>,
+
+        #[arg(
+            long,
+            help = "Expire the key after the given duration, e.g. 30d. Never expires if omitted",
+            value_parser = humantime::parse_duration
+        )]
+        expires_in: Option<Duration>,
+    },
+    #[command(about = "Revoke an API key, requests using it are rejected")]
+    Revoke {
+        #[arg(help = "The id of the API key, as shown by `list`")]
+        id: String,
+    },
+}
+
+pub async fn run(args: &AdminArgs) -> eyre::Result<()> {
+    let client = RemoteCacheAdminClient::default();
+    let admin = RemoteAdminArgs::new(&args.api_base_url, &args.admin_api_key);
+
+    match &args.subcommand {
+        AdminSubcommands::Tenant { subcommand } => match subcommand {
+            EntitySubcommands::List => {
+                display_entities(&client.list_tenants(&admin).await?);
+            }
+            EntitySubcommands::Create { args: entity } => {
+                let tenant =
+                    client.create_tenant(&admin, &entity.into()).await?;
+                log::info!("Created tenant {}", tenant.code);
+            }
+        },
+        AdminSubcommands::Org { tenant, subcommand } => match subcommand {
+            EntitySubcommands::List => {
+                display_entities(
+                    &client.list_organizations(&admin, tenant).await?,
+                );
+            }
+            EntitySubcommands::Create { args: entity } => {
+                let org = client
+                    .create_organization(&admin, tenant, &entity.into())
+                    .await?;
+                log::info!("Created organization {}", org.code);
+            }
+        },
+        AdminSubcommands::Ws {
+            tenant,
+            org,
+            subcommand,
+        } => match subcommand {
+            EntitySubcommands::List => {
+                display_entities(
+                    &client.list_workspaces(&admin, tenant, org).await?,
+                );
+            }
+            EntitySubcommands::Create { args: entity } => {
+                let ws = client
+                    .create_workspace(&admin, tenant, org, &entity.into())
+                    .await?;
+                log::info!("Created workspace {}", ws.code);
+            }
+        },
+        AdminSubcommands::Env {
+            tenant,
+            org,
+            ws,
+            subcommand,
+        } => match subcommand {
+            EntitySubcommands::List => {
+                display_entities(
+                    &client.list_environments(&admin, tenant, org, ws).await?,
+                );
+            }
+            EntitySubcommands::Create { args: entity } => {
+                let env = client
+                    .create_environment(&admin, tenant, org, ws, &entity.into())
+                    .await?;
+                log::info!("Created environment {}", env.code);
+            }
+        },
+        AdminSubcommands::Key { subcommand } => match subcommand {
+            KeySubcommands::List => {
+                display_api_keys(&client.list_api_keys(&admin).await?);
+            }
+            KeySubcommands::Create {
+                description,
+                scopes,
+                tenants,
+                orgs,
+                workspaces,
+                environments,
+                expires_in,
+            } => {
+                let minted = client
+                    .create_api_key(
+                        &admin,
+                        &NewAdminApiKey {
+                            description: description.clone(),
+                            scopes: scopes.clone().into(),
+                            tenants: tenants.clone().into(),
+                            organizations: orgs.clone().into(),
+                            workspaces: workspaces.clone().into(),
+                            environments: environments.clone().into(),
+                            expires_at: expires_in
+                                .map(|d| OffsetDateTime::now_utc() + d),
+                        },
+                    )
+                    .await?;
+                println!("Id:  {}", minted.id);
+                println!("Key: {}", minted.key.green());
+                log::warn!("Store the key now, it can't be retrieved again");
+            }
+            KeySubcommands::Revoke { id } => {
+                client.revoke_api_key(&admin, id).await?;
+                log::info!("Revoked API key {}", id);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+const STYLE: TableStyle = UTF8_FULL.with_rounded_corners();
+
+fn display_entities(entities: &[AdminEntity]) {
+    let mut table = comfy_table::Table::new();
+    table.load_style(STYLE).set_header(vec![
+        "Code",
+        "Display Name",
+        "Description",
+    ]);
+
+    for entity in entities {
+        table.add_row(vec![
+            entity.code.clone(),
+            entity.display_name.clone(),
+            entity.description.clone().unwrap_or_default(),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+fn display_api_keys(api_keys: &[AdminApiKey]) {
+    let mut table = comfy_table::Table::new();
+    table.load_style(STYLE).set_header(vec![
+        "Id",
+        "Description",
+        "Enabled",
+        "Scopes",
+        "Tenants",
+        "Organizations",
+        "Workspaces",
+        "Environments",
+        "Expires At",
+    ]);
+
+    for api_key in api_keys {
+        table.add_row(vec![
+            api_key.id.clone(),
+            api_key.description.clone().unwrap_or_default(),
+            api_key.enabled.to_string(),
+            api_key.scopes.to_string(),
+            api_key.tenants.to_string(),
+            api_key.organizations.to_string(),
+            api_key.workspaces.to_string(),
+            api_key.environments.to_string(),
+            api_key
+                .expires_at
+                .and_then(|t| t.format(&Rfc3339).ok())
+                .unwrap_or_else(|| "never".to_string()),
+        ]);
+    }
+
+    println!("{table}");
+}
@@ -4,13 +4,17 @@ use clap::Args;
 use clap_utils::EnumValueAdapter;
 use maps::UnorderedMap;
 use omni_configurations::{Ui, WorkspaceConfiguration};
-use omni_execution_plan::ScmAffectedFilter;
+use omni_execution_plan::{ScmAffectedFilter, TagFilter};
 use omni_scm::SelectScm;
 use omni_task_executor::ExecutionConfigBuilder;
 use omni_task_output_logs::LogsDisplay;
 
-use crate::commands::{
-    common_types::SerializationFormat, parser::parse_key_value,
+use crate::{
+    commands::{
+        common_types::SerializationFormat,
+        parser::{parse_key_value, redact_command_line},
+    },
+    reporter::Reporter,
 };
 
 #[derive(Args, Debug)]
@@ -25,16 +29,28 @@ pub struct RunArgs {
     #[arg(
         long,
         short,
-        help = "Filter based on the project name matching the passed argument, accepts glob patterns"
+        help = "Filter based on the project name matching the passed argument, accepts glob patterns, prefix with `!` to exclude"
     )]
     pub project: Vec<String>,
 
     #[arg(
         long,
-        help = "Filter based on projects residing in the specified directories, accepts glob patterns"
+        help = "Filter based on projects residing in the specified directories, accepts glob patterns, prefix with `!` to exclude"
     )]
     pub dir: Vec<String>,
 
+    #[arg(
+        long,
+        help = "Filter based on projects having a tag matching the passed argument, accepts glob patterns"
+    )]
+    pub tag: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Exclude projects having a tag matching the passed argument, accepts glob patterns"
+    )]
+    pub exclude_tag: Vec<String>,
+
     #[arg(long, short = 'c', help = "How many concurrent tasks to run")]
     pub max_concurrency: Option<usize>,
 
@@ -60,6 +76,20 @@ pub struct RunArgs {
     )]
     pub result_format: Option<SerializationFormat>,
 
+    #[arg(
+        long,
+        help = "Report the results of the execution for CI systems: junit writes a JUnit XML report, github emits GitHub Actions annotations and a step summary. Can be specified multiple times",
+        value_enum
+    )]
+    pub reporter: Vec<Reporter>,
+
+    #[arg(
+        long,
+        help = "The file the junit reporter writes to",
+        default_value = "omni-junit.xml"
+    )]
+    pub junit_output: PathBuf,
+
     #[arg(
         long,
         alias = "base",
@@ -90,6 +120,13 @@ pub struct RunArgs {
     )]
     pub retry_interval: Option<Duration>,
 
+    #[arg(
+        long,
+        help = "How long a task may run before it is terminated, takes precedence over `timeout` task configuration if specified",
+        value_parser = humantime::parse_duration
+    )]
+    pub task_timeout: Option<Duration>,
+
     #[arg(
         long,
         alias = "affected",
@@ -145,6 +182,13 @@ impl RunArgs {
         builder.project_filters(self.project.clone());
         builder.dir_filters(self.dir.clone());
 
+        if !self.tag.is_empty() || !self.exclude_tag.is_empty() {
+            builder.tag_filter(TagFilter::new(
+                self.tag.clone(),
+                self.exclude_tag.clone(),
+            ));
+        }
+
         if let Some(max_concurrency) = self.max_concurrency {
             builder.max_concurrency(max_concurrency);
         }
@@ -159,6 +203,10 @@ impl RunArgs {
             builder.retry_interval(retry_interval);
         }
 
+        if let Some(task_timeout) = self.task_timeout {
+            builder.timeout(task_timeout);
+        }
+
         let mut scm = self.scm_affected.value();
         // `--scm-base`/`--scm-target` implicitly enable scm filtering (using the
         // auto-detected scm) even when `--scm-affected` was not passed.
@@ -193,5 +241,9 @@ impl RunArgs {
         if let Some(output_cached_logs) = &self.output_cached_logs {
             builder.output_cached_logs(output_cached_logs.value());
         }
+
+        builder
+            .record_history(true)
+            .command_line(redact_command_line(std::env::args()));
     }
 }
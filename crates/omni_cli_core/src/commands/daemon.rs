@@ -0,0 +1,219 @@
+use std::{
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use clap::{Args, Subcommand};
+use omni_context::Context;
+use omni_daemon::{DaemonClient, DaemonErrorKind, DaemonServer};
+
+use super::{CliArgs, common_types::SerializationFormat};
+
+/// How long `start` waits for the daemon to serve its first request.
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+const START_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Args)]
+pub struct DaemonCommand {
+    #[command(subcommand)]
+    subcommand: DaemonSubcommands,
+}
+
+#[derive(Subcommand)]
+pub enum DaemonSubcommands {
+    #[command(about = "Start the daemon for the workspace in the background")]
+    Start,
+
+    #[command(about = "Stop the daemon of the workspace")]
+    Stop,
+
+    #[command(about = "Show the status of the daemon of the workspace")]
+    Status {
+        #[arg(
+            value_enum,
+            long,
+            short,
+            help = "If provided, the status will be serialized in the format specified"
+        )]
+        format: Option<SerializationFormat>,
+    },
+
+    #[command(about = "Run the daemon in the foreground")]
+    Run,
+}
+
+pub async fn run(
+    command: &DaemonCommand,
+    args: &CliArgs,
+    ctx: &Context,
+) -> eyre::Result<()> {
+    if let DaemonSubcommands::Run = command.subcommand {
+        DaemonServer::new(ctx.clone()).run().await?;
+        return Ok(());
+    }
+
+    let Some(client) = DaemonClient::for_context(ctx) else {
+        eyre::bail!(
+            "the daemon can't be used when inheriting environment variables"
+        );
+    };
+
+    match &command.subcommand {
+        DaemonSubcommands::Start => start(&client, args, ctx).await,
+        DaemonSubcommands::Stop => match client.shutdown().await {
+            Ok(()) => {
+                log::info!("Daemon stopped");
+                Ok(())
+            }
+            Err(e) if e.kind() == DaemonErrorKind::Io => {
+                log::info!("No daemon is running for this workspace");
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        },
+        DaemonSubcommands::Status { format } => match client.status().await {
+            Ok(status) => {
+                if let Some(format) = format {
+                    omni_file_data_serde::to_writer(
+                        &mut std::io::stdout(),
+                        &status,
+                        format.to_serde_format(),
+                    )?;
+                } else {
+                    println!(
+                        "Daemon running for {}",
+                        status.root_dir.display()
+                    );
+                    println!("  PID: {}", status.pid);
+                    println!("  Env: {}", status.env);
+                    println!(
+                        "  Uptime: {}",
+                        humantime::format_duration(Duration::from_secs(
+                            status.uptime_secs
+                        ))
+                    );
+                    println!("  Requests: {}", status.requests);
+                    println!("  Reloads: {}", status.reloads);
+                    println!("  Cached Responses: {}", status.cached_responses);
+                }
+                Ok(())
+            }
+            Err(e) if e.kind() == DaemonErrorKind::Io => {
+                println!("No daemon is running for this workspace");
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        },
+        DaemonSubcommands::Run => unreachable!("handled above"),
+    }
+}
+
+async fn start(
+    client: &DaemonClient,
+    args: &CliArgs,
+    ctx: &Context,
+) -> eyre::Result<()> {
+    if let Ok(status) = client.status().await {
+        log::info!("Daemon is already running with PID {}", status.pid);
+        return Ok(());
+    }
+
+    let mut daemon = Command::new(std::env::current_exe()?);
+    daemon
+        .args(forwarded_args(args))
+        .args(["daemon", "run"])
+        .current_dir(ctx.root_dir())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    detach(&mut daemon);
+
+    let mut child = daemon.spawn()?;
+    let started = std::time::Instant::now();
+
+    while started.elapsed() < START_TIMEOUT {
+        if let Some(status) = child.try_wait()? {
+            eyre::bail!("daemon exited during startup with {status}");
+        }
+
+        match client.status().await {
+            Ok(status) => {
+                log::info!("Daemon started with PID {}", status.pid);
+                return Ok(());
+            }
+            Err(e) if e.is_unavailable() => {
+                tokio::time::sleep(START_POLL_INTERVAL).await;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    eyre::bail!(
+        "daemon did not respond within {}",
+        humantime::format_duration(START_TIMEOUT)
+    )
+}
+
+/// The arguments the context of the daemon must be created with to serve
+/// this process.
+fn forwarded_args(args: &CliArgs) -> Vec<String> {
+    let mut forwarded = vec![];
+
+    if let Some(env) = &args.env {
+        forwarded.extend(["--env".to_string(), env.clone()]);
+    }
+
+    for env_file in args.env_file.iter().flatten() {
+        forwarded.extend(["--env-file".to_string(), env_file.clone()]);
+    }
+
+    if let Some(marker) = &args.env_root_dir_marker {
+        forwarded.extend(["--env-root-dir-marker".to_string(), marker.clone()]);
+    }
+
+    forwarded
+}
+
+/// Keep the daemon running when the terminal that started it goes away.
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt as _;
+
+    command.process_group(0);
+}
+
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt as _;
+
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+
+    command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forwarded_args() {
+        let args = CliArgs {
+            env: Some("production".to_string()),
+            env_file: Some(vec![".env".to_string(), ".env.{ENV}".to_string()]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            forwarded_args(&args),
+            [
+                "--env",
+                "production",
+                "--env-file",
+                ".env",
+                "--env-file",
+                ".env.{ENV}",
+            ]
+        );
+    }
+}
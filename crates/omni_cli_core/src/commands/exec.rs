@@ -6,10 +6,11 @@ use omni_task_executor::ExecutionConfigBuilder;
 use crate::{
     commands::{
         common_args::RunArgs,
-        utils::{exit_code, get_results_settings},
+        utils::{exit_code, get_results_settings, write_reports},
     },
     context::Context,
     executor::{Call, TaskExecutor},
+    reporter::ReportingSubscriber,
 };
 
 use super::utils::resolve_subscriber;
@@ -58,12 +59,16 @@ pub async fn run(
 
     let ctx = ctx.clone().into_loaded().await?;
     let sub = resolve_subscriber(command.run.ui, ctx.scratch_dir());
-    let executor = TaskExecutor::new(config, &ctx, &sub);
+    let reporting = ReportingSubscriber::new(&sub)
+        .enabled(!command.run.reporter.is_empty());
+    let executor = TaskExecutor::new(config, &ctx, &reporting);
 
     let results = executor.run().await?;
 
     sub.wait().await;
 
+    write_reports(&command.run, &reporting.report())?;
+
     // report_execution_results is now handled by CliSubscriber::on_execution_complete
 
     if let Some((fmt, results_file_path)) = output_settings {
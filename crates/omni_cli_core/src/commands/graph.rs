@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use clap_utils::EnumValueAdapter;
+use omni_api::{GraphFormat, GraphRequest, OmniApi};
+use omni_context::Context;
+use omni_daemon::daemon_or_local;
+use omni_messages::NoopSubscriber;
+use omni_scm::SelectScm;
+use omni_tracing_subscriber::noop_subscriber;
+use tracing_futures::WithSubscriber as _;
+
+#[derive(Args)]
+pub struct GraphCommand {
+    #[arg(
+        help = "Export the resolved execution graph of these tasks. If not specified, the project graph is exported"
+    )]
+    pub task: Vec<String>,
+
+    #[arg(
+        long,
+        short,
+        help = "Output format of the graph",
+        default_value_t = EnumValueAdapter::new(GraphFormat::Dot),
+        value_enum
+    )]
+    pub format: EnumValueAdapter<GraphFormat>,
+
+    #[arg(
+        long,
+        short,
+        help = "Write the graph to the specified file instead of stdout"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        alias = "ignore-deps",
+        short,
+        help = "Resolve the tasks without dependencies. Not compatible with --with-dependents",
+        conflicts_with = "with_dependents",
+        default_value_t = false
+    )]
+    pub ignore_dependencies: bool,
+
+    #[arg(
+        long,
+        short,
+        help = "Include the dependents of the matching tasks. Not compatible with --ignore-deps",
+        default_value_t = false
+    )]
+    pub with_dependents: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Filter the tasks based on the meta configuration, accepts CEL syntax"
+    )]
+    pub meta: Option<String>,
+
+    #[arg(
+        long,
+        short,
+        help = "Filter based on the project name matching the passed argument, accepts glob patterns. For the project graph, the dependencies of matching projects are included"
+    )]
+    pub project: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Filter based on projects residing in the specified directories, accepts glob patterns"
+    )]
+    pub dir: Vec<String>,
+
+    #[arg(
+        long,
+        alias = "base",
+        short = 'b',
+        help = "The base commit to compare against. This will implicitly enable --scm-affected"
+    )]
+    pub scm_base: Option<String>,
+
+    #[arg(
+        long,
+        alias = "target",
+        short = 't',
+        help = "The target commit to compare against. This will implicitly enable --scm-affected"
+    )]
+    pub scm_target: Option<String>,
+
+    #[arg(
+        long,
+        alias = "affected",
+        default_value_t = EnumValueAdapter::new(SelectScm::None),
+        default_missing_value = "auto",
+        value_enum,
+        num_args = 0..=1,
+        help = "Enable scm-based filtering of tasks. Optionally specify the scm to use for detecting affected tasks",
+    )]
+    pub scm_affected: EnumValueAdapter<SelectScm>,
+}
+
+pub async fn run(command: &GraphCommand, ctx: &Context) -> eyre::Result<()> {
+    let req = GraphRequest {
+        tasks: command.task.clone(),
+        ignore_dependencies: command.ignore_dependencies,
+        with_dependents: command.with_dependents,
+        meta: command.meta.clone(),
+        project: command.project.clone(),
+        dir: command.dir.clone(),
+        scm_base: command.scm_base.clone(),
+        scm_target: command.scm_target.clone(),
+        scm_affected: command.scm_affected.value(),
+    };
+
+    // The graph is meant to be piped into other tools, keep traces out of it.
+    let graph = daemon_or_local(
+        ctx,
+        async |daemon| daemon.graph(req.clone()).await,
+        async || {
+            OmniApi::new_with_sys(ctx.clone(), NoopSubscriber)
+                .graph(req.clone())
+                .with_subscriber(noop_subscriber())
+                .await
+        },
+    )
+    .await?;
+    let rendered = graph.render(command.format.value())?;
+
+    if let Some(output) = &command.output {
+        std::fs::write(output, rendered)?;
+    } else {
+        print!("{rendered}");
+    }
+
+    Ok(())
+}
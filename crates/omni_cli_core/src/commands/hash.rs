@@ -1,6 +1,7 @@
 use clap::{Args, Subcommand};
-use omni_api::OmniApi;
+use omni_api::{HashResponse, OmniApi};
 use omni_context::Context;
+use omni_daemon::daemon_or_local;
 use omni_messages::NoopSubscriber;
 use omni_tracing_subscriber::noop_subscriber;
 use tracing_futures::WithSubscriber as _;
@@ -49,6 +50,31 @@ pub struct HashProjectCommand {
 }
 
 pub async fn run(command: &HashCommand, ctx: &Context) -> eyre::Result<()> {
+    let response = daemon_or_local(
+        ctx,
+        async |daemon| match &command.subcommand {
+            HashSubcommands::Workspace => daemon.hash_workspace().await,
+            HashSubcommands::Project { command } => {
+                daemon.hash_project(&command.project, &command.task).await
+            }
+        },
+        async || hash_locally(command, ctx).await,
+    )
+    .await?;
+
+    if command.args.raw {
+        print!("{}", response.hash);
+    } else {
+        println!("{}", response.hash);
+    }
+
+    Ok(())
+}
+
+async fn hash_locally(
+    command: &HashCommand,
+    ctx: &Context,
+) -> eyre::Result<HashResponse> {
     let api = OmniApi::new_with_sys(ctx.clone(), NoopSubscriber);
 
     let response = if command.args.raw {
@@ -73,11 +99,5 @@ pub async fn run(command: &HashCommand, ctx: &Context) -> eyre::Result<()> {
         }
     };
 
-    if command.args.raw {
-        print!("{}", response.hash);
-    } else {
-        println!("{}", response.hash);
-    }
-
-    Ok(())
+    Ok(response)
 }
@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use clap::{Args, Subcommand};
+use omni_api::{
+    HistoryListRequest, HistoryRegressionsRequest, HistoryTaskRequest, OmniApi,
+};
+use omni_context::Context;
+use omni_messages::NoopSubscriber;
+use omni_run_history::{RunRecord, TaskRunStatus};
+use owo_colors::OwoColorize;
+
+use super::common_types::SerializationFormat;
+
+#[derive(Args)]
+pub struct HistoryCommand {
+    #[command(subcommand)]
+    subcommand: HistorySubcommands,
+}
+
+#[derive(Subcommand)]
+pub enum HistorySubcommands {
+    #[command(about = "List the recorded runs, newest first")]
+    List {
+        #[arg(
+            long,
+            short,
+            help = "Only list this many runs",
+            default_value_t = 20
+        )]
+        limit: usize,
+
+        #[arg(
+            value_enum,
+            long,
+            short,
+            help = "If provided, the runs will be serialized in the format specified"
+        )]
+        format: Option<SerializationFormat>,
+    },
+
+    #[command(about = "Show the tasks of a recorded run")]
+    Show {
+        #[arg(help = "The id of the run to show, defaults to the latest run")]
+        id: Option<String>,
+
+        #[arg(
+            value_enum,
+            long,
+            short,
+            help = "If provided, the run will be serialized in the format specified"
+        )]
+        format: Option<SerializationFormat>,
+    },
+
+    #[command(about = "Show how the duration of a task evolved across runs")]
+    Task {
+        #[arg(help = "The task to show, in the form of project#task")]
+        task: String,
+
+        #[arg(
+            long,
+            short,
+            help = "Only look at this many runs",
+            default_value_t = 20
+        )]
+        limit: usize,
+
+        #[arg(
+            value_enum,
+            long,
+            short,
+            help = "If provided, the durations will be serialized in the format specified"
+        )]
+        format: Option<SerializationFormat>,
+    },
+
+    #[command(
+        about = "List the tasks whose latest execution was slower than their previous ones"
+    )]
+    Regressions {
+        #[arg(
+            long,
+            short,
+            help = "How many times slower than the median of its previous executions a task has to be [default: 1.5]"
+        )]
+        threshold: Option<f64>,
+
+        #[arg(
+            long,
+            short,
+            help = "How many previous executions the median is taken over [default: 10]"
+        )]
+        window: Option<usize>,
+
+        #[arg(
+            value_enum,
+            long,
+            short,
+            help = "If provided, the regressions will be serialized in the format specified"
+        )]
+        format: Option<SerializationFormat>,
+    },
+}
+
+pub async fn run(command: &HistoryCommand, ctx: &Context) -> eyre::Result<()> {
+    let api = OmniApi::new_with_sys(ctx.clone(), NoopSubscriber);
+
+    match &command.subcommand {
+        HistorySubcommands::List { limit, format } => {
+            let response = api
+                .history_list(HistoryListRequest {
+                    limit: Some(*limit),
+                })
+                .await?;
+
+            if let Some(format) = format {
+                write(&response, *format)?;
+            } else if response.runs.is_empty() {
+                println!("No runs recorded yet");
+            } else {
+                for run in &response.runs {
+                    display_run_summary(run);
+                }
+            }
+        }
+
+        HistorySubcommands::Show { id, format } => {
+            let Some(run) = api.history_show(id.as_deref()).await? else {
+                match id {
+                    Some(id) => eyre::bail!("no run with id '{id}'"),
+                    None => {
+                        println!("No runs recorded yet");
+                        return Ok(());
+                    }
+                }
+            };
+
+            if let Some(format) = format {
+                write(&run, *format)?;
+            } else {
+                display_run(&run);
+            }
+        }
+
+        HistorySubcommands::Task {
+            task,
+            limit,
+            format,
+        } => {
+            let response = api
+                .history_task(HistoryTaskRequest {
+                    task: task.clone(),
+                    limit: Some(*limit),
+                })
+                .await?;
+
+            if let Some(format) = format {
+                write(&response, *format)?;
+            } else if response.durations.is_empty() {
+                println!("{task} wasn't run in the recorded runs");
+            } else {
+                println!("{task}:");
+                for duration in &response.durations {
+                    println!(
+                        "  {}  {}  {:>10}  {}",
+                        duration.run_id,
+                        format_timestamp(duration.started_at),
+                        duration
+                            .elapsed
+                            .map(format_duration)
+                            .unwrap_or_default(),
+                        display_status(duration.status, duration.cache_hit),
+                    );
+                }
+            }
+        }
+
+        HistorySubcommands::Regressions {
+            threshold,
+            window,
+            format,
+        } => {
+            let response = api
+                .history_regressions(HistoryRegressionsRequest {
+                    threshold: *threshold,
+                    window: *window,
+                })
+                .await?;
+
+            if let Some(format) = format {
+                write(&response, *format)?;
+            } else if response.regressions.is_empty() {
+                println!(
+                    "No regressions found in the last {} runs",
+                    response.runs
+                );
+            } else {
+                for regression in &response.regressions {
+                    println!(
+                        "{}: {} (was {}), {} in run {}",
+                        regression.task,
+                        format_duration(regression.elapsed).red(),
+                        format_duration(regression.baseline),
+                        format!("{:.2}x", regression.ratio).red(),
+                        regression.run_id,
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write<T: serde::Serialize>(
+    value: &T,
+    format: SerializationFormat,
+) -> eyre::Result<()> {
+    omni_file_data_serde::to_writer(
+        &mut std::io::stdout(),
+        value,
+        format.to_serde_format(),
+    )?;
+
+    Ok(())
+}
+
+fn display_run_summary(run: &RunRecord) {
+    let result = if run.is_failure() {
+        "failed".red().to_string()
+    } else {
+        "passed".green().to_string()
+    };
+
+    println!(
+        "{}  {}  {:>10}  {}  {} tasks, {} cached  {}",
+        run.id,
+        format_timestamp(run.started_at),
+        format_duration(run.elapsed),
+        result,
+        run.tasks.len(),
+        run.cache_hits(),
+        run.command_line.as_deref().unwrap_or(&run.call),
+    );
+}
+
+fn display_run(run: &RunRecord) {
+    println!("Run: {}", run.id);
+    println!("  Started: {}", format_timestamp(run.started_at));
+    println!("  Duration: {}", format_duration(run.elapsed));
+    if let Some(command_line) = &run.command_line {
+        println!("  Command: {command_line}");
+    }
+    println!("  Call: {}", run.call);
+    if !run.filters.is_empty() {
+        println!(
+            "  Filters: {}",
+            serde_json::to_string(&run.filters).unwrap()
+        );
+    }
+    println!(
+        "  Tasks: {} succeeded, {} failed, {} errored, {} skipped, {} cached",
+        run.count(TaskRunStatus::Succeeded),
+        run.count(TaskRunStatus::Failed),
+        run.count(TaskRunStatus::Errored),
+        run.count(TaskRunStatus::Skipped),
+        run.cache_hits(),
+    );
+
+    for task in &run.tasks {
+        let mut line = format!(
+            "    - {}: {}",
+            task.task,
+            display_status(task.status, task.cache_hit)
+        );
+        if let Some(elapsed) = task.elapsed {
+            line.push_str(&format!(" in {}", format_duration(elapsed)));
+        }
+        if let Some(exit_code) = task.exit_code.filter(|c| *c != 0) {
+            line.push_str(&format!(", exit code {exit_code}"));
+        }
+        if task.tries > 1 {
+            line.push_str(&format!(", {} tries", task.tries));
+        }
+        if let Some(skip_reason) = &task.skip_reason {
+            line.push_str(&format!(", {skip_reason}"));
+        }
+        println!("{line}");
+    }
+}
+
+fn display_status(status: TaskRunStatus, cache_hit: bool) -> String {
+    match status {
+        TaskRunStatus::Succeeded if cache_hit => "cached".green().to_string(),
+        TaskRunStatus::Succeeded => status.green().to_string(),
+        TaskRunStatus::Failed | TaskRunStatus::Errored => {
+            status.red().to_string()
+        }
+        TaskRunStatus::Skipped => status.yellow().to_string(),
+    }
+}
+
+fn format_timestamp(timestamp: time::OffsetDateTime) -> String {
+    timestamp
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default()
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
+}
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use audit_exec::AuditExecCommand;
 use clap::{Args, Parser, Subcommand};
 use clap_utils::EnumValueAdapter;
 use completion::CompletionCommand;
@@ -7,37 +8,47 @@ use config::ConfigCommand;
 use env::EnvCommand;
 use exec::ExecCommand;
 use mcp::McpCommand;
-use omni_tracing_subscriber::Level;
+use omni_tracing_subscriber::{Level, OtlpProtocol};
 use run::RunCommand;
+use shell::ShellCommand;
 use strum::{EnumDiscriminants, EnumIs};
 mod common_args;
 mod common_types;
 mod generator_common_args;
 mod parser;
+mod run_watch;
 mod utils;
 
 use crate::{
     build,
     commands::{
-        cache::CacheCommand, declspec::DeclspecCommand,
-        generator::GeneratorCommand, hash::HashCommand, init::InitCommand,
-        project::ProjectCommand, tool::ToolCommand,
+        affected::AffectedCommand, cache::CacheCommand, daemon::DaemonCommand,
+        declspec::DeclspecCommand, generator::GeneratorCommand,
+        graph::GraphCommand, hash::HashCommand, history::HistoryCommand,
+        init::InitCommand, project::ProjectCommand, tool::ToolCommand,
     },
 };
 
+pub mod affected;
+pub mod audit_exec;
 pub mod cache;
+mod cache_remote_admin;
 pub mod completion;
 pub mod config;
+pub mod daemon;
 pub mod declspec;
 pub mod env;
 pub mod exec;
 pub mod generator;
 mod generator_utils;
+pub mod graph;
 pub mod hash;
+pub mod history;
 pub mod init;
 pub mod mcp;
 pub mod project;
 pub mod run;
+pub mod shell;
 pub mod tool;
 
 const ABOUT: &str = "omni is development workflow orchestration tool";
@@ -146,6 +157,22 @@ pub struct CliArgs {
         group = "inherit-env-vars",
     )]
     pub inherit_env_vars: bool,
+
+    #[arg(
+        long,
+        help = "Export traces of the run to the OpenTelemetry collector at the specified base URL, e.g. http://localhost:4317",
+        env = "OMNI_OTLP_ENDPOINT"
+    )]
+    pub otlp_endpoint: Option<String>,
+
+    #[arg(
+        long,
+        help = "The protocol to export traces to the OpenTelemetry collector with",
+        value_enum,
+        default_value = "grpc",
+        env = "OMNI_OTLP_PROTOCOL"
+    )]
+    pub otlp_protocol: EnumValueAdapter<OtlpProtocol>,
 }
 
 impl Default for CliArgs {
@@ -161,6 +188,8 @@ impl Default for CliArgs {
             inherit_env_vars: false,
             stdout_show_traces: false,
             stderr_show_traces: false,
+            otlp_endpoint: None,
+            otlp_protocol: EnumValueAdapter::new(OtlpProtocol::Grpc),
         }
     }
 }
@@ -201,9 +230,34 @@ pub enum CliSubcommands {
     #[command(about = "Project related commands")]
     Project(ProjectCommand),
 
+    #[command(about = "Export the project graph or the task graph")]
+    Graph(GraphCommand),
+
+    #[command(about = "Inspect the history of task runs")]
+    History(HistoryCommand),
+
+    #[command(
+        about = "List the projects and tasks affected by the changes between two commits"
+    )]
+    Affected(AffectedCommand),
+
     #[command(about = "Tool related subcommands")]
     Tool(ToolCommand),
 
     #[command(about = "Start an MCP server for AI agent integration")]
     Mcp(McpCommand),
+
+    #[command(
+        about = "Manage the background daemon that keeps the workspace loaded"
+    )]
+    Daemon(DaemonCommand),
+
+    #[command(about = "Run a script with omni's built-in cross-platform shell")]
+    Shell(ShellCommand),
+
+    #[command(
+        hide = true,
+        about = "Trace the files a command reads and writes"
+    )]
+    AuditExec(AuditExecCommand),
 }
@@ -24,6 +24,62 @@ where
     Ok((key.parse()?, value.parse()?))
 }
 
+const REDACTED: &str = "***";
+
+/// Join the command line `args` for display, masking the values of `--arg`
+/// and of environment variable like `KEY=value` arguments, which may carry
+/// secrets.
+pub fn redact_command_line<S: AsRef<str>>(
+    args: impl IntoIterator<Item = S>,
+) -> String {
+    let mut redacted = vec![];
+    let mut is_arg_value = false;
+
+    for arg in args {
+        let arg = arg.as_ref();
+
+        redacted.push(if is_arg_value {
+            redact_key_value("", arg)
+        } else {
+            redact_arg(arg)
+        });
+        is_arg_value = matches!(arg, "--arg" | "-a");
+    }
+
+    redacted.join(" ")
+}
+
+fn redact_arg(arg: &str) -> String {
+    let key_value = if let Some(key_value) = arg.strip_prefix("--arg=") {
+        key_value
+    } else if let Some(rest) = arg.strip_prefix("-a")
+        && rest.contains('=')
+    {
+        rest.strip_prefix('=').unwrap_or(rest)
+    } else if is_env_like(arg) {
+        arg
+    } else {
+        return arg.to_string();
+    };
+
+    let (flag, key_value) = arg.split_at(arg.len() - key_value.len());
+    redact_key_value(flag, key_value)
+}
+
+fn redact_key_value(flag: &str, key_value: &str) -> String {
+    match key_value.split_once('=') {
+        Some((key, _)) => format!("{flag}{key}={REDACTED}"),
+        None => format!("{flag}{REDACTED}"),
+    }
+}
+
+fn is_env_like(arg: &str) -> bool {
+    arg.split_once('=').is_some_and(|(key, _)| {
+        key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +95,30 @@ mod tests {
             ("key".to_string(), "123".parse().unwrap())
         );
     }
+
+    #[test]
+    fn test_redact_command_line() {
+        assert_eq!(
+            redact_command_line([
+                "omni",
+                "run",
+                "build",
+                "--arg",
+                "token=secret",
+                "--arg=key=secret",
+                "-a",
+                "other=secret",
+                "-aname=secret",
+                "-a=last=secret",
+                "--scm-base=main",
+                "--",
+                "API_KEY=secret",
+                "--meta",
+                "lang == 'rust'",
+            ]),
+            "omni run build --arg token=*** --arg=key=*** -a other=*** \
+             -aname=*** -a=last=*** --scm-base=main -- API_KEY=*** --meta \
+             lang == 'rust'"
+        );
+    }
 }
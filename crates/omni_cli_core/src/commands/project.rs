@@ -1,7 +1,8 @@
 use clap::{ArgAction, Args, Subcommand};
 use derive_new::new;
-use omni_api::OmniApi;
+use omni_api::{OmniApi, ProjectListRequest};
 use omni_context::Context;
+use omni_daemon::daemon_or_local;
 use omni_messages::NoopSubscriber;
 use omni_tracing_subscriber::noop_subscriber;
 use serde::Serialize;
@@ -113,19 +114,50 @@ pub struct ListArgs {
         help = "If provided, the list will be serialized in the format specified"
     )]
     format: Option<SerializationFormat>,
+
+    #[arg(
+        long,
+        short,
+        help = "Only list projects whose name matches the passed argument, accepts glob patterns, prefix with `!` to exclude"
+    )]
+    project: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Only list projects having a tag matching the passed argument, accepts glob patterns"
+    )]
+    tag: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Exclude projects having a tag matching the passed argument, accepts glob patterns"
+    )]
+    exclude_tag: Vec<String>,
 }
 
 async fn run_list(command: &ListCommand, ctx: &Context) -> eyre::Result<()> {
-    let api = OmniApi::new_with_sys(ctx.clone(), NoopSubscriber);
-
-    let names = if command.args.raw {
-        api.project_list()
-            .with_subscriber(noop_subscriber())
-            .await?
-    } else {
-        api.project_list().await?
+    let req = ProjectListRequest {
+        project: command.args.project.clone(),
+        tag: command.args.tag.clone(),
+        exclude_tag: command.args.exclude_tag.clone(),
     };
 
+    let names = daemon_or_local(
+        ctx,
+        async |daemon| daemon.project_list(req.clone()).await,
+        async || {
+            let api = OmniApi::new_with_sys(ctx.clone(), NoopSubscriber);
+            if command.args.raw {
+                api.project_list_filtered(req.clone())
+                    .with_subscriber(noop_subscriber())
+                    .await
+            } else {
+                api.project_list_filtered(req.clone()).await
+            }
+        },
+    )
+    .await?;
+
     if let Some(format) = command.args.format {
         if format == SerializationFormat::Toml {
             omni_file_data_serde::to_writer(
@@ -1,16 +1,26 @@
-use std::process::ExitCode;
+use std::{
+    path::PathBuf,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
 use clap::Args;
 use clap_utils::EnumValueAdapter;
+use omni_messages::{
+    ExecutionEventSubscriber as _, ExecutionPhase, PhaseCompletedEvent,
+};
 use omni_task_executor::{ExecutionConfigBuilder, Force};
 
 use crate::{
     commands::{
         common_args::RunArgs,
-        utils::{exit_code, get_results_settings},
+        run_watch,
+        utils::{exit_code, get_results_settings, write_reports},
     },
     context::Context,
     executor::{Call, OnFailure, TaskExecutor},
+    profile::ProfileSubscriber,
+    reporter::ReportingSubscriber,
 };
 
 use super::utils::resolve_subscriber;
@@ -65,6 +75,36 @@ pub struct RunCommand {
     )]
     pub force: EnumValueAdapter<Force>,
 
+    #[arg(
+        long,
+        help = "Keep watching the inputs of the tasks and re-run the affected tasks when they change. Persistent tasks are only restarted when their own inputs change",
+        default_value_t = false
+    )]
+    pub watch: bool,
+
+    #[arg(
+        long,
+        help = "How long to wait for file changes to settle before re-running tasks in watch mode",
+        default_value = "200ms",
+        value_parser = humantime::parse_duration,
+        requires = "watch"
+    )]
+    pub watch_debounce: Duration,
+
+    #[arg(
+        long,
+        help = "Trace the files each task reads and writes and report undeclared inputs and outputs. Tasks restored from the cache are not audited; combine with --force",
+        default_value_t = false
+    )]
+    pub audit_io: bool,
+
+    #[arg(
+        long,
+        help = "Record a profile of the run and write it to the specified file in the Chrome trace event format, which can be opened in Perfetto",
+        conflicts_with = "watch"
+    )]
+    pub profile: Option<PathBuf>,
+
     #[command(flatten)]
     pub run: RunArgs,
 }
@@ -87,22 +127,68 @@ pub async fn run(
         .on_failure(command.on_failure.value())
         .no_cache(command.no_cache)
         .force(command.force.value())
+        .audit_io(command.audit_io)
         .call(Call::new_tasks(&command.task[..]));
 
     command
         .run
         .apply_to(&mut builder, ctx.workspace_configuration());
 
+    if command.watch {
+        if !command.run.reporter.is_empty() {
+            eyre::bail!("--reporter can't be used with --watch");
+        }
+
+        return run_watch::run(
+            builder,
+            ctx,
+            command.watch_debounce,
+            output_settings,
+        )
+        .await;
+    }
+
     let config = builder.build()?;
 
+    let started = Instant::now();
     let ctx = ctx.clone().into_loaded().await?;
     let sub = resolve_subscriber(command.run.ui, ctx.scratch_dir());
-    let executor = TaskExecutor::new(config, &ctx, &sub);
+    let reporting = ReportingSubscriber::new(&sub)
+        .enabled(!command.run.reporter.is_empty());
+
+    let results = if let Some(profile_path) = &command.profile {
+        let profiler = ProfileSubscriber::new(&reporting, started);
+        profiler
+            .on_phase_completed(PhaseCompletedEvent {
+                phase: ExecutionPhase::Discovery,
+                task_id: None,
+                elapsed: started.elapsed(),
+            })
+            .await;
+
+        let results = TaskExecutor::new(config, &ctx, &profiler).run().await;
+
+        // write the profile of failed runs too, they are the ones worth
+        // looking into; a failed run is reported before a failed write
+        let written = omni_file_data_serde::write_with_format_async(
+            omni_file_data_serde::Format::Json,
+            profile_path,
+            &profiler.trace(),
+            ctx.sys(),
+        )
+        .await;
 
-    let results = executor.run().await?;
+        let results = results?;
+        written?;
+        results
+    } else {
+        TaskExecutor::new(config, &ctx, &reporting).run().await?
+    };
 
     sub.wait().await;
 
+    write_reports(&command.run, &reporting.report())?;
+
     // report_execution_results is now handled by CliSubscriber::on_execution_complete
 
     if let Some((fmt, results_file_path)) = output_settings {
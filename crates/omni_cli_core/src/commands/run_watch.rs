@@ -0,0 +1,756 @@
+//! Watch mode for `omni run`.
+//!
+//! The tasks resolved for the call are indexed by the input files declared in
+//! their cache key, tasks without any are indexed by their whole project. File
+//! system events are debounced, mapped to the projects whose task inputs they
+//! touch, and the inputs of the tasks of those projects are re-hashed through
+//! a [`ProjectDirHasher`] so that events which leave the content as it was
+//! (e.g. saving an unmodified file) do not trigger anything. The changed tasks
+//! and their dependents are then executed again, with the hashes of the tasks
+//! that don't run again taken from their last run so the cache keys stay the
+//! same as in a full run.
+//!
+//! Persistent tasks (dev servers) are started once and keep running across
+//! re-runs. They are only restarted when their own inputs change.
+
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    process::ExitCode,
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
+
+use globset::GlobSet;
+use maps::{Map, UnorderedMap, unordered_map};
+use notify::{Event, EventKind, RecursiveMode, Watcher as _};
+use omni_collector::{CollectConfig, Collector, ProjectTaskInfo};
+use omni_core::TaskExecutionNode;
+use omni_execution_plan::ExecutionPlanProvider as _;
+use omni_hasher::{
+    Hasher as _,
+    impls::{DefaultHash, DefaultHasher},
+    project_dir_hasher::{ProjectDirHasher as _, impls::RealDirHasher},
+};
+use omni_types::{OmniPath, Root, enum_map};
+use omni_utils::glob::build_glob_set;
+use sets::{UnorderedSet, unordered_set};
+use tokio::sync::mpsc;
+
+use crate::{
+    commands::{common_types::SerializationFormat, utils::exit_code},
+    constants,
+    context::{Context, LoadedContext},
+    executor::{
+        ContextExecutionPlanProvider, ExecutionConfig, ExecutionConfigBuilder,
+        TaskExecutionResult, TaskExecutor,
+    },
+    subscriber::CliSubscriber,
+};
+
+type TaskRun =
+    Pin<Box<dyn Future<Output = eyre::Result<Vec<TaskExecutionResult>>>>>;
+
+/// Persistent task runs, by full task name.
+type PersistentRuns = UnorderedMap<String, TaskRun>;
+
+/// Dir in the scratch dir holding the index of the watcher's hasher.
+const WATCH_INDEX_DIR: &str = "watch-index";
+
+pub async fn run(
+    builder: ExecutionConfigBuilder,
+    ctx: &Context,
+    debounce: Duration,
+    output_settings: Option<(SerializationFormat, PathBuf)>,
+) -> eyre::Result<ExitCode> {
+    let config = builder.build()?;
+    let mut loaded = ctx.clone().into_loaded().await?;
+
+    // The index of the cache's hasher only holds the files of the latest
+    // digests, sharing it would make both hash everything again.
+    let hasher = RealDirHasher::builder()
+        .workspace_root_dir(loaded.root_dir())
+        .index_dir(loaded.scratch_dir().join(WATCH_INDEX_DIR))
+        .build()?;
+
+    let mut index = WatchIndex::new(&loaded, &config)?;
+    index.rehash(&loaded, &hasher, &index.projects()).await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let _ = tx.send(event);
+        })?;
+    watcher.watch(loaded.root_dir(), RecursiveMode::Recursive)?;
+
+    let mut persistent = unordered_map!();
+    let mut results = vec![];
+    let mut hashes = unordered_map!();
+
+    let initial = index
+        .tasks
+        .iter()
+        .filter(|t| !t.node.persistent())
+        .map(|t| t.node.full_task_name().to_string())
+        .collect::<UnorderedSet<_>>();
+
+    if !initial.is_empty() {
+        let run = start_run(&loaded, &builder, initial, &hashes)?;
+        let Some(initial_results) = run_alongside(run, &mut persistent).await
+        else {
+            return Ok(exit_code(&results));
+        };
+        results = initial_results?;
+        record_hashes(&mut hashes, &results);
+        write_results(&loaded, output_settings.as_ref(), &results).await?;
+    }
+
+    for task in index.tasks.iter().filter(|t| t.node.persistent()) {
+        let name = task.node.full_task_name().to_string();
+        let run = start_run(
+            &loaded,
+            &builder,
+            unordered_set!(name.clone()),
+            &hashes,
+        )?;
+        persistent.insert(name, run);
+    }
+
+    log::info!("Watching for changes, press Ctrl+C to stop");
+
+    loop {
+        let paths = tokio::select! {
+            paths = next_changes(&mut rx, debounce) => match paths {
+                Some(paths) => paths,
+                None => break,
+            },
+            (name, result) = next_exited(&mut persistent) => {
+                persistent.remove(&name);
+                report_persistent_exit(&name, result);
+                continue;
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        };
+
+        let paths = paths
+            .into_iter()
+            .filter(|p| !p.starts_with(ctx.omni_dir()) && !index.is_output(p))
+            .collect::<Vec<_>>();
+
+        if paths.is_empty() {
+            continue;
+        }
+
+        let mut forced = unordered_set!();
+        let mut reconfigured = unordered_set!();
+        if paths.iter().any(|p| is_project_config_file(p)) {
+            reconfigured = loaded
+                .projects()
+                .iter()
+                .filter(|project| {
+                    paths.iter().any(|p| {
+                        is_project_config_file(p)
+                            && p.parent() == Some(project.dir.as_path())
+                    })
+                })
+                .map(|project| project.name.clone())
+                .collect::<UnorderedSet<_>>();
+
+            match reload(ctx, &config).await {
+                Ok((new_loaded, mut new_index)) => {
+                    new_index.hashes = std::mem::take(&mut index.hashes);
+                    loaded = new_loaded;
+                    index = new_index;
+                }
+                Err(e) => {
+                    log::error!("Failed to reload the workspace: {e}");
+                    continue;
+                }
+            }
+
+            forced.extend(
+                index
+                    .tasks
+                    .iter()
+                    .filter(|t| reconfigured.contains(t.node.project_name()))
+                    .map(|t| t.node.full_task_name().to_string()),
+            );
+        }
+
+        let mut touched = index.touched(&paths);
+        touched.extend(reconfigured);
+        let mut changed = match index.rehash(&loaded, &hasher, &touched).await {
+            Ok(changed) => changed,
+            Err(e) => {
+                log::error!("Failed to hash the changed projects: {e}");
+                continue;
+            }
+        };
+        changed.extend(forced);
+
+        if changed.is_empty() {
+            continue;
+        }
+
+        let affected = collect_dependents(&changed, &index.dependents);
+        let (restart, rerun): (Vec<_>, Vec<_>) = index
+            .tasks
+            .iter()
+            .filter(|t| affected.contains(t.node.full_task_name()))
+            .partition(|t| t.node.persistent());
+
+        // Persistent tasks are left running unless their own inputs changed.
+        let restart = restart
+            .into_iter()
+            .map(|t| t.node.full_task_name().to_string())
+            .filter(|name| changed.contains(name))
+            .collect::<Vec<_>>();
+        let rerun = rerun
+            .into_iter()
+            .map(|t| t.node.full_task_name().to_string())
+            .collect::<UnorderedSet<_>>();
+
+        log::info!(
+            "Detected changes in {} file(s), running {} task(s)",
+            paths.len(),
+            rerun.len() + restart.len()
+        );
+
+        // Dropping a run terminates the process trees of its tasks.
+        for name in &restart {
+            persistent.remove(name);
+        }
+
+        if !rerun.is_empty() {
+            let run = match start_run(&loaded, &builder, rerun, &hashes) {
+                Ok(run) => run_alongside(run, &mut persistent).await,
+                Err(e) => Some(Err(e)),
+            };
+            match run {
+                Some(Ok(rerun_results)) => {
+                    results = rerun_results;
+                    record_hashes(&mut hashes, &results);
+                    if let Err(e) = write_results(
+                        &loaded,
+                        output_settings.as_ref(),
+                        &results,
+                    )
+                    .await
+                    {
+                        log::error!("Failed to write the results: {e}");
+                    }
+                }
+                Some(Err(e)) => log::error!("Failed to run tasks: {e}"),
+                None => break,
+            }
+        }
+
+        for name in restart {
+            match start_run(
+                &loaded,
+                &builder,
+                unordered_set!(name.clone()),
+                &hashes,
+            ) {
+                Ok(run) => {
+                    persistent.insert(name, run);
+                }
+                Err(e) => {
+                    log::error!(
+                        "Failed to restart persistent task '{name}': {e}"
+                    )
+                }
+            }
+        }
+    }
+
+    Ok(exit_code(&results))
+}
+
+struct WatchedTask {
+    node: TaskExecutionNode,
+    input_files: Vec<OmniPath>,
+    inputs: Arc<GlobSet>,
+}
+
+/// The tasks of the resolved plan along with what is needed to map file
+/// changes to them.
+struct WatchIndex {
+    tasks: Vec<WatchedTask>,
+    /// Cached outputs of every task, changes to them never trigger a re-run.
+    outputs: Arc<GlobSet>,
+    /// Full names of the tasks depending on a task, by full task name.
+    dependents: UnorderedMap<String, Vec<String>>,
+    /// Last known hash of the inputs of every task, by full task name.
+    hashes: UnorderedMap<String, DefaultHash>,
+}
+
+impl WatchIndex {
+    fn new(
+        ctx: &LoadedContext,
+        config: &ExecutionConfig,
+    ) -> eyre::Result<Self> {
+        let project_filters = config
+            .project_filters()
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>();
+        let dir_filters = config
+            .dir_filters()
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>();
+
+        let plan = ContextExecutionPlanProvider::new(ctx).get_execution_plan(
+            config.call(),
+            &project_filters,
+            &dir_filters,
+            config.meta_filter().as_deref(),
+            config.tag_filter().as_ref(),
+            config.scm_affected_filter().as_ref(),
+            config.ignore_dependencies(),
+            config.with_dependents(),
+        )?;
+
+        let root_dir = ctx.root_dir();
+        let mut tasks = vec![];
+        let mut outputs = vec![];
+        let mut dependents: UnorderedMap<String, Vec<String>> =
+            unordered_map!();
+
+        for node in plan.into_iter().flatten() {
+            for dep in node.dependencies() {
+                dependents
+                    .entry(dep.clone())
+                    .or_default()
+                    .push(node.full_task_name().to_string());
+            }
+
+            let (mut input_files, output_files) = ctx
+                .get_cache_info(node.project_name(), node.task_name())
+                .map(|ci| {
+                    (ci.key_input_files.clone(), ci.cache_output_files.clone())
+                })
+                .unwrap_or_default();
+
+            // Without declared inputs, any file of the project may affect
+            // the task.
+            if input_files
+                .iter()
+                .all(|f| is_config_file(f.unresolved_path()))
+            {
+                input_files.push(OmniPath::new("**/*"));
+            }
+
+            outputs.extend(resolve_patterns(root_dir, &node, &output_files));
+            let inputs = build_glob_set(&resolve_patterns(
+                root_dir,
+                &node,
+                &input_files,
+            ))?;
+
+            tasks.push(WatchedTask {
+                node,
+                input_files,
+                inputs,
+            });
+        }
+
+        Ok(Self {
+            tasks,
+            outputs: build_glob_set(&outputs)?,
+            dependents,
+            hashes: unordered_map!(),
+        })
+    }
+
+    fn is_output(&self, path: &Path) -> bool {
+        self.outputs.is_match(normalize(path).as_str())
+    }
+
+    /// Names of the projects of the tasks.
+    fn projects(&self) -> UnorderedSet<String> {
+        self.tasks
+            .iter()
+            .map(|t| t.node.project_name().to_string())
+            .collect()
+    }
+
+    /// Names of the projects with a task input matching any of `paths`.
+    fn touched(&self, paths: &[PathBuf]) -> UnorderedSet<String> {
+        let paths = paths.iter().map(|p| normalize(p)).collect::<Vec<_>>();
+
+        self.tasks
+            .iter()
+            .filter(|t| paths.iter().any(|p| t.inputs.is_match(p.as_str())))
+            .map(|t| t.node.project_name().to_string())
+            .collect()
+    }
+
+    /// Re-hash the inputs of every task of `projects`, returning the full
+    /// names of the tasks whose hash changed. The input files of the tasks of
+    /// a project are hashed together, so the hasher keeps a single index of
+    /// them.
+    async fn rehash(
+        &mut self,
+        ctx: &LoadedContext,
+        hasher: &RealDirHasher,
+        projects: &UnorderedSet<String>,
+    ) -> eyre::Result<UnorderedSet<String>> {
+        let no_env_vars = Map::default();
+        let no_digests = Map::default();
+        let no_args = Map::default();
+        let infos = self
+            .tasks
+            .iter()
+            .filter(|t| projects.contains(t.node.project_name()))
+            .map(|t| ProjectTaskInfo {
+                project_name: t.node.project_name(),
+                project_dir: t.node.project_dir(),
+                task_name: t.node.task_name(),
+                task_exec: None,
+                task_retry_exec: None,
+                output_files: &[],
+                input_files: &t.input_files,
+                input_env_keys: &[],
+                env_vars: &no_env_vars,
+                dependency_digests: &no_digests,
+                args: &no_args,
+            })
+            .collect::<Vec<_>>();
+
+        let cache_dir = ctx.cache_dir();
+        let collected =
+            Collector::new(ctx.root_dir(), &cache_dir, ctx.sys().clone())
+                .collect(
+                    &infos,
+                    &CollectConfig {
+                        input_files: true,
+                        ..Default::default()
+                    },
+                )
+                .await?;
+
+        // Outputs are written by the runs, they would change the hashes of
+        // tasks with the whole project as input.
+        let mut task_files = vec![];
+        for result in collected {
+            let mut files = result
+                .input_files
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|f| !self.is_output(&f.resolve(&result.roots)))
+                .collect::<Vec<_>>();
+            files.sort();
+            task_files.push((result.task, files));
+        }
+
+        let mut file_hashes = unordered_map!();
+        for project in projects {
+            let Some((task, _)) =
+                task_files.iter().find(|(t, _)| t.project_name == project)
+            else {
+                continue;
+            };
+
+            let mut files = task_files
+                .iter()
+                .filter(|(t, _)| t.project_name == project)
+                .flat_map(|(_, files)| files.iter().cloned())
+                .collect::<Vec<_>>();
+            files.sort();
+            files.dedup();
+
+            let hashes = hasher
+                .hash_files::<DefaultHasher>(project, task.project_dir, &files)
+                .await?;
+            file_hashes.extend(
+                hashes
+                    .into_iter()
+                    .map(|(file, hash)| ((project.as_str(), file), hash)),
+            );
+        }
+
+        let mut changed = unordered_set!();
+        for (task, files) in &task_files {
+            let hashes = files
+                .iter()
+                .filter_map(|f| {
+                    file_hashes.get(&(task.project_name, f.clone())).copied()
+                })
+                .collect::<Vec<_>>();
+            let hash = DefaultHasher::hash(&hashes.concat());
+
+            let name = format!("{}#{}", task.project_name, task.task_name);
+            if self.hashes.get(&name) != Some(&hash) {
+                changed.insert(name.clone());
+            }
+            self.hashes.insert(name, hash);
+        }
+
+        Ok(changed)
+    }
+}
+
+async fn reload(
+    ctx: &Context,
+    config: &ExecutionConfig,
+) -> eyre::Result<(LoadedContext, WatchIndex)> {
+    let loaded = ctx.clone().into_loaded().await?;
+    let index = WatchIndex::new(&loaded, config)?;
+
+    Ok((loaded, index))
+}
+
+/// Keep the hashes of the tasks of the latest run. A task without a hash,
+/// e.g. one that errored, no longer has a valid one from an earlier run.
+fn record_hashes(
+    hashes: &mut UnorderedMap<String, DefaultHash>,
+    results: &[TaskExecutionResult],
+) {
+    for result in results {
+        let name = result.task().full_task_name().to_string();
+        match result.hash() {
+            Some(hash) => hashes.insert(name, hash),
+            None => hashes.remove(&name),
+        };
+    }
+}
+
+fn start_run(
+    ctx: &LoadedContext,
+    builder: &ExecutionConfigBuilder,
+    tasks: UnorderedSet<String>,
+    hashes: &UnorderedMap<String, DefaultHash>,
+) -> eyre::Result<TaskRun> {
+    let mut builder = builder.clone();
+    let config = builder
+        .only_tasks(tasks)
+        .dependency_hashes(hashes.clone())
+        .build()?;
+    let ctx = ctx.clone();
+    // Runs overlap with each other, a single terminal UI can't be shared.
+    let sub = CliSubscriber::new_stream(ctx.scratch_dir());
+
+    Ok(Box::pin(async move {
+        let results = TaskExecutor::new(config, &ctx, &sub).run().await?;
+        sub.wait().await;
+
+        Ok(results)
+    }))
+}
+
+/// Drive `run` to completion while keeping the persistent tasks alive.
+/// Returns `None` when interrupted with Ctrl+C.
+async fn run_alongside(
+    mut run: TaskRun,
+    persistent: &mut PersistentRuns,
+) -> Option<eyre::Result<Vec<TaskExecutionResult>>> {
+    loop {
+        tokio::select! {
+            result = &mut run => return Some(result),
+            (name, result) = next_exited(persistent) => {
+                persistent.remove(&name);
+                report_persistent_exit(&name, result);
+            }
+            _ = tokio::signal::ctrl_c() => return None,
+        }
+    }
+}
+
+/// Wait for the first persistent run to finish. Never resolves when there is
+/// none.
+async fn next_exited(
+    persistent: &mut PersistentRuns,
+) -> (String, eyre::Result<Vec<TaskExecutionResult>>) {
+    std::future::poll_fn(|cx| {
+        for (name, run) in persistent.iter_mut() {
+            if let Poll::Ready(result) = run.as_mut().poll(cx) {
+                return Poll::Ready((name.clone(), result));
+            }
+        }
+
+        Poll::Pending
+    })
+    .await
+}
+
+fn report_persistent_exit(
+    name: &str,
+    result: eyre::Result<Vec<TaskExecutionResult>>,
+) {
+    if let Err(e) = result {
+        log::error!("Persistent task '{name}' failed: {e}");
+    }
+}
+
+/// Wait for the next batch of changes. Events are collected until none
+/// arrived for `debounce`.
+async fn next_changes(
+    rx: &mut mpsc::UnboundedReceiver<notify::Result<Event>>,
+    debounce: Duration,
+) -> Option<Vec<PathBuf>> {
+    let mut paths = vec![];
+    collect_paths(rx.recv().await?, &mut paths);
+
+    while let Ok(event) = tokio::time::timeout(debounce, rx.recv()).await {
+        let Some(event) = event else {
+            break;
+        };
+        collect_paths(event, &mut paths);
+    }
+
+    paths.sort();
+    paths.dedup();
+
+    Some(paths)
+}
+
+fn collect_paths(event: notify::Result<Event>, paths: &mut Vec<PathBuf>) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            paths.extend(event.paths);
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("File watcher error: {e}"),
+    }
+}
+
+async fn write_results(
+    ctx: &LoadedContext,
+    output_settings: Option<&(SerializationFormat, PathBuf)>,
+    results: &[TaskExecutionResult],
+) -> eyre::Result<()> {
+    if let Some((fmt, results_file_path)) = output_settings {
+        omni_file_data_serde::write_with_format_async(
+            fmt.to_serde_format(),
+            results_file_path,
+            &results,
+            ctx.sys(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Whether `path` is an omni configuration file, e.g. a project's one or one
+/// it extends.
+fn is_config_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name() else {
+        return false;
+    };
+
+    let file_name = file_name.to_string_lossy();
+    constants::SUPPORTED_EXTENSIONS
+        .iter()
+        .any(|ext| file_name.ends_with(&format!(".omni.{ext}")))
+}
+
+fn is_project_config_file(path: &Path) -> bool {
+    let Some(file_name) = path.file_name() else {
+        return false;
+    };
+
+    constants::SUPPORTED_EXTENSIONS.iter().any(|ext| {
+        file_name.to_string_lossy()
+            == constants::PROJECT_OMNI.replace("{ext}", ext)
+    })
+}
+
+/// `tasks` along with everything transitively depending on them.
+fn collect_dependents(
+    tasks: &UnorderedSet<String>,
+    dependents: &UnorderedMap<String, Vec<String>>,
+) -> UnorderedSet<String> {
+    let mut affected = tasks.clone();
+    let mut queue = tasks.iter().cloned().collect::<Vec<_>>();
+
+    while let Some(task) = queue.pop() {
+        for dependent in dependents.get(&task).into_iter().flatten() {
+            if affected.insert(dependent.clone()) {
+                queue.push(dependent.clone());
+            }
+        }
+    }
+
+    affected
+}
+
+fn resolve_patterns(
+    root_dir: &Path,
+    node: &TaskExecutionNode,
+    files: &[OmniPath],
+) -> Vec<String> {
+    let root_map = enum_map! {
+        Root::Project => node.project_dir(),
+        Root::Workspace => root_dir,
+    };
+
+    files
+        .iter()
+        .map(|file| {
+            let resolved = file.resolve(&root_map);
+            if resolved.is_absolute() {
+                normalize(&resolved)
+            } else {
+                normalize(&node.project_dir().join(path_clean::clean(resolved)))
+            }
+        })
+        .collect()
+}
+
+fn normalize(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    if cfg!(windows) {
+        path.replace("\\", "/")
+    } else {
+        path.into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_project_config_file() {
+        assert!(is_project_config_file(Path::new("a/project.omni.yaml")));
+        assert!(is_project_config_file(Path::new("a/project.omni.toml")));
+        assert!(!is_project_config_file(Path::new("a/workspace.omni.yaml")));
+        assert!(!is_project_config_file(Path::new("a/project.omni.txt")));
+    }
+
+    #[test]
+    fn test_is_config_file() {
+        assert!(is_config_file(Path::new("a/project.omni.yaml")));
+        assert!(is_config_file(Path::new("base.omni.json")));
+        assert!(!is_config_file(Path::new("a/src/main.rs")));
+        assert!(!is_config_file(Path::new("a/omni.yaml")));
+    }
+
+    #[test]
+    fn test_collect_dependents_is_transitive() {
+        let dependents = unordered_map!(
+            "lib#build".to_string() => vec!["app#build".to_string()],
+            "app#build".to_string() => vec!["app#test".to_string()],
+            "other#build".to_string() => vec!["other#test".to_string()],
+        );
+
+        let affected = collect_dependents(
+            &unordered_set!("lib#build".to_string()),
+            &dependents,
+        );
+
+        assert_eq!(
+            affected,
+            unordered_set!(
+                "lib#build".to_string(),
+                "app#build".to_string(),
+                "app#test".to_string(),
+            )
+        );
+    }
+}
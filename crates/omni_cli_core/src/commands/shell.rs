@@ -0,0 +1,25 @@
+use omni_shell::Shell;
+
+#[derive(clap::Args)]
+pub struct ShellCommand {
+    #[command(flatten)]
+    pub args: ShellArgs,
+}
+
+#[derive(clap::Args)]
+pub struct ShellArgs {
+    #[arg(
+        short = 'c',
+        long = "command",
+        help = "The script to run, e.g. 'cargo build && cargo test'"
+    )]
+    pub command: String,
+}
+
+/// Run the script with the built-in shell in the current directory and
+/// return its exit code.
+pub async fn run(shell: &ShellCommand) -> eyre::Result<i32> {
+    let cwd = std::env::current_dir()?;
+
+    Ok(Shell::new(cwd).run_str(&shell.args.command)?)
+}
@@ -7,6 +7,7 @@ use omni_configurations::Ui;
 use crate::{
     commands::{common_args::RunArgs, common_types::SerializationFormat},
     executor::TaskExecutionResult,
+    reporter::RunReport,
     subscriber::CliSubscriber,
 };
 
@@ -49,6 +50,15 @@ pub fn get_results_settings(
     }
 }
 
+/// Write `report` with every reporter selected in `args`.
+pub fn write_reports(args: &RunArgs, report: &RunReport) -> eyre::Result<()> {
+    for reporter in &args.reporter {
+        reporter.write(report, &args.junit_output)?;
+    }
+
+    Ok(())
+}
+
 pub fn exit_code(results: &[TaskExecutionResult]) -> ExitCode {
     let has_error = results.iter().any(|r| r.is_failure());
 
@@ -7,6 +7,8 @@ pub mod constants;
 pub mod context;
 pub mod core;
 pub mod executor;
+pub mod profile;
+pub mod reporter;
 pub mod subscriber;
 pub mod task_output_capture;
 pub mod utils;
@@ -0,0 +1,572 @@
+//! Profiles of task runs in the Chrome trace event format, which opens in
+//! Perfetto and `chrome://tracing`.
+//!
+//! Every span is placed on a lane (a "thread" of the trace) that is free for
+//! its whole duration, so the number of lanes in use at any time shows how
+//! many tasks and phases ran concurrently.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use omni_messages::execution::events::{
+    SchedulingCompletedEvent, SchedulingStartEvent,
+};
+use omni_messages::{
+    CacheHitEvent, DiagnosticEvent, DiagnosticSubscriber,
+    ExecutionCompleteEvent, ExecutionEventSubscriber, ExecutionPhase,
+    ExecutionPlanReadyEvent, PhaseCompletedEvent, TaskCompletedEvent,
+    TaskFailedEvent, TaskOutputStreamEvent, TaskRetryingEvent,
+    TaskSkippedEvent, TaskStartedEvent,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::json;
+
+/// The thread of the trace holding the spans that cover the whole run.
+const RUN_TID: usize = 0;
+
+/// Records the events of a run, forwarding them to `inner`, and turns them
+/// into a [`ChromeTrace`].
+pub struct ProfileSubscriber<S> {
+    inner: S,
+    /// Timestamps of the trace are relative to this instant.
+    origin: Instant,
+    recorder: Mutex<Recorder>,
+}
+
+impl<S> ProfileSubscriber<S> {
+    /// Create a profiler whose trace starts at `origin`, usually the start of
+    /// the command, so that work done before the executor ran is included.
+    pub fn new(inner: S, origin: Instant) -> Self {
+        Self {
+            inner,
+            origin,
+            recorder: Mutex::new(Recorder::default()),
+        }
+    }
+
+    /// The trace of everything recorded so far.
+    pub fn trace(&self) -> ChromeTrace {
+        let recorder = self.recorder.lock();
+
+        let mut events = vec![
+            TraceEvent::metadata("process_name", RUN_TID, "omni run"),
+            TraceEvent::metadata("thread_name", RUN_TID, "run"),
+        ];
+        events.extend((0..recorder.lanes.len()).map(|lane| {
+            TraceEvent::metadata(
+                "thread_name",
+                lane_tid(lane),
+                &format!("lane {}", lane + 1),
+            )
+        }));
+        events.extend(recorder.events.iter().cloned());
+
+        ChromeTrace {
+            trace_events: events,
+            display_time_unit: "ms",
+        }
+    }
+
+    fn now(&self) -> u64 {
+        micros(self.origin.elapsed())
+    }
+
+    fn record(&self, f: impl FnOnce(&mut Recorder, u64)) {
+        let now = self.now();
+        f(&mut self.recorder.lock(), now);
+    }
+}
+
+/// A trace in the JSON object format of the Chrome trace event format.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChromeTrace {
+    trace_events: Vec<TraceEvent>,
+    display_time_unit: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TraceEvent {
+    name: String,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    cat: &'static str,
+    ph: &'static str,
+    ts: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<u64>,
+    pid: u32,
+    tid: usize,
+    /// Scope of instant events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    s: Option<&'static str>,
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    args: serde_json::Value,
+}
+
+impl TraceEvent {
+    fn span(
+        name: impl Into<String>,
+        cat: &'static str,
+        tid: usize,
+        start: u64,
+        end: u64,
+        args: serde_json::Value,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            cat,
+            ph: "X",
+            ts: start,
+            dur: Some(end.saturating_sub(start)),
+            pid: std::process::id(),
+            tid,
+            s: None,
+            args,
+        }
+    }
+
+    fn instant(
+        name: impl Into<String>,
+        cat: &'static str,
+        tid: usize,
+        ts: u64,
+        args: serde_json::Value,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            cat,
+            ph: "i",
+            ts,
+            dur: None,
+            pid: std::process::id(),
+            tid,
+            s: Some("t"),
+            args,
+        }
+    }
+
+    fn metadata(name: &str, tid: usize, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            cat: "",
+            ph: "M",
+            ts: 0,
+            dur: None,
+            pid: std::process::id(),
+            tid,
+            s: None,
+            args: json!({ "name": value }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    events: Vec<TraceEvent>,
+    /// The end of the last span on each lane, `None` while a task runs on it.
+    lanes: Vec<Option<u64>>,
+    /// The start and lane of every running task.
+    running: HashMap<String, (u64, usize)>,
+    execution_started: Option<u64>,
+}
+
+impl Recorder {
+    /// Take the first lane that is free from `start` on, and keep it until
+    /// `end`, or until released if `end` is `None`.
+    fn take_lane(&mut self, start: u64, end: Option<u64>) -> usize {
+        let free = self
+            .lanes
+            .iter()
+            .position(|l| l.is_some_and(|free_from| free_from <= start));
+
+        match free {
+            Some(lane) => {
+                self.lanes[lane] = end;
+                lane
+            }
+            None => {
+                self.lanes.push(end);
+                self.lanes.len() - 1
+            }
+        }
+    }
+
+    fn task_started(&mut self, task_id: &str, now: u64) {
+        let lane = self.take_lane(now, None);
+        self.running.insert(task_id.to_string(), (now, lane));
+    }
+
+    fn task_finished(
+        &mut self,
+        task_id: &str,
+        now: u64,
+        args: serde_json::Value,
+    ) {
+        let Some((start, lane)) = self.running.remove(task_id) else {
+            return;
+        };
+
+        self.lanes[lane] = Some(now);
+        self.events.push(TraceEvent::span(
+            task_id,
+            "task",
+            lane_tid(lane),
+            start,
+            now,
+            args,
+        ));
+    }
+
+    /// The thread for an instant event of `task_id`, the lane of the task
+    /// while it runs.
+    fn task_tid(&self, task_id: &str) -> usize {
+        self.running
+            .get(task_id)
+            .map_or(RUN_TID, |(_, lane)| lane_tid(*lane))
+    }
+
+    fn instant(
+        &mut self,
+        name: impl Into<String>,
+        cat: &'static str,
+        tid: usize,
+        now: u64,
+        args: serde_json::Value,
+    ) {
+        self.events
+            .push(TraceEvent::instant(name, cat, tid, now, args));
+    }
+
+    fn phase_completed(&mut self, e: PhaseCompletedEvent, now: u64) {
+        let start = now.saturating_sub(micros(e.elapsed));
+        let phase = e.phase.to_string();
+        let args = json!({ "task_id": e.task_id });
+
+        let (name, tid) = match (&e.phase, e.task_id.as_deref()) {
+            (ExecutionPhase::Discovery | ExecutionPhase::Planning, _) => {
+                (phase, RUN_TID)
+            }
+            // nested in the span of the running task
+            (_, Some(task_id)) if self.running.contains_key(task_id) => {
+                (phase, self.task_tid(task_id))
+            }
+            (_, Some(task_id)) => (
+                format!("{phase} {task_id}"),
+                lane_tid(self.take_lane(start, Some(now))),
+            ),
+            (_, None) => (phase, lane_tid(self.take_lane(start, Some(now)))),
+        };
+
+        self.events
+            .push(TraceEvent::span(name, "phase", tid, start, now, args));
+    }
+}
+
+fn lane_tid(lane: usize) -> usize {
+    lane + 1
+}
+
+fn micros(duration: Duration) -> u64 {
+    duration.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+impl<S: DiagnosticSubscriber> DiagnosticSubscriber for ProfileSubscriber<S> {
+    fn wants_diagnostics(&self) -> bool {
+        self.inner.wants_diagnostics()
+    }
+
+    fn on_diagnostic(&self, event: DiagnosticEvent) {
+        self.inner.on_diagnostic(event)
+    }
+
+    fn on_diagnostics_batched(
+        &self,
+        events: impl IntoIterator<Item = DiagnosticEvent>,
+    ) {
+        self.inner.on_diagnostics_batched(events)
+    }
+}
+
+impl<S: ExecutionEventSubscriber> ExecutionEventSubscriber
+    for ProfileSubscriber<S>
+{
+    fn wants_task_output_stream(&self) -> bool {
+        self.inner.wants_task_output_stream()
+    }
+
+    fn wants_task_input_stream(&self) -> bool {
+        self.inner.wants_task_input_stream()
+    }
+
+    async fn on_task_started(&self, e: TaskStartedEvent) {
+        self.record(|r, now| r.task_started(&e.task_id, now));
+        self.inner.on_task_started(e).await
+    }
+
+    async fn on_execution_plan_ready(&self, e: ExecutionPlanReadyEvent) {
+        self.record(|r, now| {
+            r.instant(
+                "execution plan ready",
+                "execution",
+                RUN_TID,
+                now,
+                json!({
+                    "total": e.total,
+                    "has_interactive_or_persistent_tasks":
+                        e.has_interactive_or_persistent_tasks,
+                }),
+            )
+        });
+        self.inner.on_execution_plan_ready(e).await
+    }
+
+    async fn on_task_output_stream(&self, e: TaskOutputStreamEvent) {
+        self.record(|r, now| {
+            r.instant(
+                "output stream",
+                "task",
+                r.task_tid(&e.task_id),
+                now,
+                json!({
+                    "task_id": e.task_id,
+                    "is_replay": e.is_replay,
+                    "is_interactive": e.is_interactive,
+                }),
+            )
+        });
+        self.inner.on_task_output_stream(e).await
+    }
+
+    async fn on_task_completed(&self, e: TaskCompletedEvent) {
+        self.record(|r, now| {
+            r.task_finished(
+                &e.task_id,
+                now,
+                json!({
+                    "project": e.project,
+                    "task": e.task,
+                    "exit_code": e.exit_code,
+                    "cache_hit": e.cache_hit,
+                    "tries": e.tries,
+                }),
+            )
+        });
+        self.inner.on_task_completed(e).await
+    }
+
+    async fn on_task_failed(&self, e: TaskFailedEvent) {
+        self.record(|r, now| {
+            r.task_finished(
+                &e.task_id,
+                now,
+                json!({
+                    "project": e.project,
+                    "task": e.task,
+                    "reason": e.reason.to_string(),
+                    "error": e.error,
+                    "tries": e.tries,
+                }),
+            )
+        });
+        self.inner.on_task_failed(e).await
+    }
+
+    async fn on_task_skipped(&self, e: TaskSkippedEvent) {
+        self.record(|r, now| {
+            r.instant(
+                format!("skipped {}", e.task_id),
+                "task",
+                RUN_TID,
+                now,
+                json!({
+                    "reason": e.reason.to_string(),
+                    "dependency": e.dependency,
+                }),
+            )
+        });
+        self.inner.on_task_skipped(e).await
+    }
+
+    async fn on_task_retrying(&self, e: TaskRetryingEvent) {
+        self.record(|r, now| {
+            r.instant(
+                "retrying",
+                "task",
+                r.task_tid(&e.task_id),
+                now,
+                json!({
+                    "task_id": e.task_id,
+                    "attempt": e.attempt,
+                    "max_retries": e.max_retries,
+                }),
+            )
+        });
+        self.inner.on_task_retrying(e).await
+    }
+
+    async fn on_cache_hit(&self, e: CacheHitEvent) {
+        self.record(|r, now| {
+            r.instant(
+                format!("cache hit {}", e.task_id),
+                "cache",
+                RUN_TID,
+                now,
+                json!({
+                    "digest": bs58::encode(&e.digest).into_string(),
+                    "replay_logs": e.replay_logs,
+                }),
+            )
+        });
+        self.inner.on_cache_hit(e).await
+    }
+
+    async fn on_execution_complete(&self, e: ExecutionCompleteEvent) {
+        self.record(|r, now| {
+            r.instant(
+                "execution complete",
+                "execution",
+                RUN_TID,
+                now,
+                json!({
+                    "total": e.total,
+                    "succeeded": e.succeeded,
+                    "failed": e.failed,
+                    "skipped": e.skipped,
+                    "cache_hits": e.cache_hits,
+                    "time_saved_us": micros(e.total_time_saved),
+                }),
+            )
+        });
+        self.inner.on_execution_complete(e).await
+    }
+
+    async fn on_scheduling_start(&self, e: SchedulingStartEvent) {
+        self.record(|r, now| r.execution_started = Some(now));
+        self.inner.on_scheduling_start(e).await
+    }
+
+    async fn on_scheduling_completed(&self, e: SchedulingCompletedEvent) {
+        self.record(|r, now| {
+            if let Some(start) = r.execution_started.take() {
+                r.events.push(TraceEvent::span(
+                    "execution",
+                    "execution",
+                    RUN_TID,
+                    start,
+                    now,
+                    serde_json::Value::Null,
+                ));
+            }
+        });
+        self.inner.on_scheduling_completed(e).await
+    }
+
+    async fn on_phase_completed(&self, e: PhaseCompletedEvent) {
+        self.record(|r, now| r.phase_completed(e.clone(), now));
+        self.inner.on_phase_completed(e).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use omni_messages::NoopSubscriber;
+
+    use super::*;
+
+    fn started(task_id: &str) -> TaskStartedEvent {
+        TaskStartedEvent {
+            task_id: task_id.to_string(),
+            project: "p".to_string(),
+            task: task_id.to_string(),
+        }
+    }
+
+    fn completed(task_id: &str) -> TaskCompletedEvent {
+        TaskCompletedEvent {
+            task_id: task_id.to_string(),
+            project: "p".to_string(),
+            task: task_id.to_string(),
+            exit_code: 0,
+            elapsed: Duration::ZERO,
+            cache_hit: false,
+            tries: 1,
+        }
+    }
+
+    fn spans(trace: &ChromeTrace) -> Vec<(&str, usize)> {
+        trace
+            .trace_events
+            .iter()
+            .filter(|e| e.ph == "X")
+            .map(|e| (e.name.as_str(), e.tid))
+            .collect()
+    }
+
+    #[test]
+    fn test_take_lane_reuses_free_lanes() {
+        let mut recorder = Recorder::default();
+
+        assert_eq!(recorder.take_lane(0, None), 0);
+        assert_eq!(recorder.take_lane(5, Some(10)), 1);
+        assert_eq!(recorder.take_lane(8, Some(12)), 2);
+        // lane 1 is free again from 10 on
+        assert_eq!(recorder.take_lane(10, Some(20)), 1);
+        // spans that started before the end of the last span of a lane never
+        // go on that lane
+        assert_eq!(recorder.take_lane(9, Some(11)), 3);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_tasks_get_their_own_lanes() {
+        let profiler = ProfileSubscriber::new(NoopSubscriber, Instant::now());
+
+        profiler.on_task_started(started("a")).await;
+        profiler.on_task_started(started("b")).await;
+        profiler
+            .on_phase_completed(PhaseCompletedEvent {
+                phase: ExecutionPhase::Process,
+                task_id: Some("b".to_string()),
+                elapsed: Duration::ZERO,
+            })
+            .await;
+        profiler.on_task_completed(completed("b")).await;
+        profiler.on_task_completed(completed("a")).await;
+        profiler.on_task_started(started("c")).await;
+        profiler.on_task_completed(completed("c")).await;
+
+        let trace = profiler.trace();
+
+        assert_eq!(
+            spans(&trace),
+            [("process", 2), ("b", 2), ("a", 1), ("c", 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trace_serializes_to_the_chrome_format() {
+        let profiler = ProfileSubscriber::new(NoopSubscriber, Instant::now());
+
+        profiler
+            .on_phase_completed(PhaseCompletedEvent {
+                phase: ExecutionPhase::Discovery,
+                task_id: None,
+                elapsed: Duration::ZERO,
+            })
+            .await;
+
+        let value = serde_json::to_value(profiler.trace()).unwrap();
+        let events = value["traceEvents"].as_array().unwrap();
+
+        assert_eq!(value["displayTimeUnit"], "ms");
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[0]["name"], "process_name");
+
+        let discovery = events.last().unwrap();
+        assert_eq!(discovery["name"], "discovery");
+        assert_eq!(discovery["ph"], "X");
+        assert_eq!(discovery["tid"], RUN_TID);
+        assert_eq!(discovery["dur"], 0);
+        assert!(discovery.get("s").is_none());
+    }
+}
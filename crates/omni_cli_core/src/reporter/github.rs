@@ -0,0 +1,164 @@
+use std::fmt::Write as _;
+
+use super::{RunReport, TaskOutcome, seconds};
+
+/// Workflow commands annotating every failed task, with the tail of its
+/// output as the message.
+pub fn github_annotations(report: &RunReport) -> String {
+    let mut commands = String::new();
+
+    for task in &report.tasks {
+        let TaskOutcome::Failed { error, .. } = &task.outcome else {
+            continue;
+        };
+
+        let message = match &task.log_tail {
+            Some(log_tail) => format!("{error}\n\n{log_tail}"),
+            None => error.clone(),
+        };
+
+        writeln!(
+            commands,
+            "::error title={}::{}",
+            escape_property(&format!("Task '{}' failed", task.task_id)),
+            escape_data(&message),
+        )
+        .unwrap();
+    }
+
+    commands
+}
+
+/// Markdown for the job summary: the totals of the run, a table of all
+/// tasks, and the output of the failed ones.
+pub fn github_step_summary(report: &RunReport) -> String {
+    let mut md = String::from("## omni\n\n");
+
+    writeln!(
+        md,
+        "{} tasks in {}s: {} passed ({} cached), {} failed, {} skipped\n",
+        report.tasks.len(),
+        seconds(report.elapsed),
+        report.tasks.len() - report.failed() - report.skipped(),
+        report.cache_hits(),
+        report.failed(),
+        report.skipped(),
+    )
+    .unwrap();
+
+    md.push_str("| Task | Result | Duration | Tries |\n");
+    md.push_str("| --- | --- | --- | --- |\n");
+    for task in &report.tasks {
+        let result = match &task.outcome {
+            TaskOutcome::Passed { cache_hit: true } => {
+                "✅ passed (cached)".to_string()
+            }
+            TaskOutcome::Passed { cache_hit: false } => "✅ passed".to_string(),
+            TaskOutcome::Failed { reason, .. } => format!("❌ {reason}"),
+            TaskOutcome::Skipped {
+                reason,
+                dependency: Some(dependency),
+            } => format!("⏭️ skipped, {reason}: `{dependency}`"),
+            TaskOutcome::Skipped { reason, .. } => {
+                format!("⏭️ skipped, {reason}")
+            }
+        };
+
+        writeln!(
+            md,
+            "| `{}` | {} | {}s | {} |",
+            task.task_id,
+            result.replace('|', "\\|"),
+            seconds(task.elapsed),
+            task.tries,
+        )
+        .unwrap();
+    }
+
+    for task in &report.tasks {
+        if let (TaskOutcome::Failed { .. }, Some(log_tail)) =
+            (&task.outcome, &task.log_tail)
+        {
+            write!(
+                md,
+                "\n<details><summary><code>{}</code></summary>\n\n```text\n{log_tail}\n```\n\n</details>\n",
+                task.task_id,
+            )
+            .unwrap();
+        }
+    }
+
+    md.push('\n');
+    md
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use omni_messages::{TaskFailureReason, TaskSkipReason};
+
+    use super::*;
+    use crate::reporter::tests::task;
+
+    fn report() -> RunReport {
+        RunReport {
+            tasks: vec![
+                task("a#build", TaskOutcome::Passed { cache_hit: true }, None),
+                task(
+                    "a#test",
+                    TaskOutcome::Failed {
+                        reason: TaskFailureReason::ExitCode,
+                        error: "exit code '1'".to_string(),
+                    },
+                    Some("100% broken\nat a.rs:1"),
+                ),
+                task(
+                    "b#test",
+                    TaskOutcome::Skipped {
+                        reason: TaskSkipReason::DependeeTaskFailure,
+                        dependency: Some("a#test".to_string()),
+                    },
+                    None,
+                ),
+            ],
+            elapsed: Duration::from_secs(3),
+        }
+    }
+
+    #[test]
+    fn test_github_annotations() {
+        assert_eq!(
+            github_annotations(&report()),
+            "::error title=Task 'a#test' failed::exit code '1'%0A%0A100%25 broken%0Aat a.rs:1\n"
+        );
+    }
+
+    #[test]
+    fn test_github_step_summary() {
+        let summary = github_step_summary(&report());
+
+        assert!(summary.contains(
+            "3 tasks in 3.000s: 1 passed (1 cached), 1 failed, 1 skipped"
+        ));
+        assert!(
+            summary.contains("| `a#build` | ✅ passed (cached) | 1.500s | 1 |")
+        );
+        assert!(summary.contains(
+            "| `b#test` | ⏭️ skipped, dependee task failed: `a#test` | 1.500s | 1 |"
+        ));
+        assert!(summary.contains("```text\n100% broken\nat a.rs:1\n```"));
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt::Write as _;
+
+use omni_messages::TaskFailureReason;
+
+use super::{RunReport, TaskOutcome, TaskReport, seconds};
+
+/// Render `report` as JUnit XML. Every project is a testsuite and every task
+/// a testcase of it; tasks that couldn't run are errors rather than
+/// failures.
+pub fn junit_xml(report: &RunReport) -> String {
+    let mut projects: Vec<(&str, Vec<&TaskReport>)> = vec![];
+    for task in &report.tasks {
+        match projects.iter_mut().find(|(p, _)| *p == task.project) {
+            Some((_, tasks)) => tasks.push(task),
+            None => projects.push((&task.project, vec![task])),
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let (failures, errors) = count_failures(report.tasks.iter());
+    writeln!(
+        xml,
+        "<testsuites name=\"omni\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{}\" time=\"{}\">",
+        report.tasks.len(),
+        report.skipped(),
+        seconds(report.elapsed),
+    )
+    .unwrap();
+
+    for (project, tasks) in projects {
+        let (failures, errors) = count_failures(tasks.iter().copied());
+        let skipped = tasks.iter().filter(|t| t.outcome.is_skipped()).count();
+        writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{}\">",
+            escape(project),
+            tasks.len(),
+            seconds(tasks.iter().map(|t| t.elapsed).sum()),
+        )
+        .unwrap();
+
+        for task in tasks {
+            write_testcase(&mut xml, task);
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn write_testcase(xml: &mut String, task: &TaskReport) {
+    write!(
+        xml,
+        "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+        escape(&task.task),
+        escape(&task.project),
+        seconds(task.elapsed),
+    )
+    .unwrap();
+
+    match &task.outcome {
+        TaskOutcome::Passed { .. } => {
+            xml.push_str(" />\n");
+            return;
+        }
+        TaskOutcome::Failed { reason, error } => {
+            let element = if *reason == TaskFailureReason::Error {
+                "error"
+            } else {
+                "failure"
+            };
+            write!(
+                xml,
+                ">\n      <{element} message=\"{}\" type=\"{}\">",
+                escape(error),
+                escape(&reason.to_string()),
+            )
+            .unwrap();
+            if let Some(log_tail) = &task.log_tail {
+                xml.push_str(&escape(log_tail));
+            }
+            writeln!(xml, "</{element}>").unwrap();
+        }
+        TaskOutcome::Skipped { reason, dependency } => {
+            let message = match dependency {
+                Some(dependency) => format!("{reason}: {dependency}"),
+                None => reason.to_string(),
+            };
+            writeln!(
+                xml,
+                ">\n      <skipped message=\"{}\" />",
+                escape(&message)
+            )
+            .unwrap();
+        }
+    }
+
+    xml.push_str("    </testcase>\n");
+}
+
+/// The number of failed tasks, and of those that failed to run.
+fn count_failures<'a>(
+    tasks: impl Iterator<Item = &'a TaskReport>,
+) -> (usize, usize) {
+    tasks.fold((0, 0), |(failures, errors), task| match &task.outcome {
+        TaskOutcome::Failed {
+            reason: TaskFailureReason::Error,
+            ..
+        } => (failures, errors + 1),
+        TaskOutcome::Failed { .. } => (failures + 1, errors),
+        _ => (failures, errors),
+    })
+}
+
+/// Escape `text` for attributes and text nodes, dropping the characters XML
+/// can't represent.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use omni_messages::TaskSkipReason;
+
+    use super::*;
+    use crate::reporter::tests::task;
+
+    #[test]
+    fn test_junit_xml() {
+        let report = RunReport {
+            tasks: vec![
+                task("a#build", TaskOutcome::Passed { cache_hit: true }, None),
+                task(
+                    "a#test",
+                    TaskOutcome::Failed {
+                        reason: TaskFailureReason::ExitCode,
+                        error: "exit code '1'".to_string(),
+                    },
+                    Some("expected <1> & got \"2\"\u{7}"),
+                ),
+                task(
+                    "b#test",
+                    TaskOutcome::Skipped {
+                        reason: TaskSkipReason::DependeeTaskFailure,
+                        dependency: Some("a#test".to_string()),
+                    },
+                    None,
+                ),
+            ],
+            elapsed: Duration::from_secs(3),
+        };
+
+        assert_eq!(
+            junit_xml(&report),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="omni" tests="3" failures="1" errors="0" skipped="1" time="3.000">
+  <testsuite name="a" tests="2" failures="1" errors="0" skipped="0" time="3.000">
+    <testcase name="build" classname="a" time="1.500" />
+    <testcase name="test" classname="a" time="1.500">
+      <failure message="exit code &apos;1&apos;" type="exited with a non-zero exit code">expected &lt;1&gt; &amp; got &quot;2&quot;</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="b" tests="1" failures="0" errors="0" skipped="1" time="1.500">
+    <testcase name="test" classname="b" time="1.500">
+      <skipped message="dependee task failed: a#test" />
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn test_tasks_that_failed_to_run_are_errors() {
+        let report = RunReport {
+            tasks: vec![task(
+                "a#build",
+                TaskOutcome::Failed {
+                    reason: TaskFailureReason::Error,
+                    error: "program not found".to_string(),
+                },
+                None,
+            )],
+            elapsed: Duration::ZERO,
+        };
+
+        let xml = junit_xml(&report);
+
+        assert!(xml.contains("failures=\"0\" errors=\"1\""));
+        assert!(xml.contains(
+            "<error message=\"program not found\" type=\"failed to run\"></error>"
+        ));
+    }
+}
@@ -0,0 +1,595 @@
+//! Reports of task runs for CI systems.
+//!
+//! [`ReportingSubscriber`] records the outcome of every task from the events
+//! of the run, along with the tail of its output, and turns them into a
+//! [`RunReport`] that the [`Reporter`]s render.
+
+mod github;
+mod junit;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Write as _},
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use lazy_regex::regex;
+use omni_messages::execution::events::{
+    SchedulingCompletedEvent, SchedulingStartEvent,
+};
+use omni_messages::{
+    CacheHitEvent, DiagnosticEvent, DiagnosticSubscriber,
+    ExecutionCompleteEvent, ExecutionEventSubscriber, ExecutionPlanReadyEvent,
+    PhaseCompletedEvent, TaskCompletedEvent, TaskFailedEvent,
+    TaskFailureReason, TaskOutputStream, TaskOutputStreamEvent,
+    TaskRetryingEvent, TaskSkipReason, TaskSkippedEvent, TaskStartedEvent,
+};
+use parking_lot::Mutex;
+use tokio::io::{AsyncRead, ReadBuf};
+
+pub use github::{github_annotations, github_step_summary};
+pub use junit::junit_xml;
+
+/// How many bytes of the output of a task are kept for its report.
+const LOG_TAIL_BYTES: usize = 16 * 1024;
+/// How many lines of the output of a failed task are reported.
+const LOG_TAIL_LINES: usize = 50;
+
+#[derive(
+    ValueEnum, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub enum Reporter {
+    /// A JUnit XML report with one testcase per task.
+    Junit,
+    /// GitHub Actions annotations for failed tasks and a step summary.
+    Github,
+}
+
+impl Reporter {
+    /// Write `report` where this reporter puts it. The junit reporter writes
+    /// to `junit_output`; the github reporter prints its workflow commands
+    /// and appends to the step summary when running in GitHub Actions.
+    pub fn write(
+        self,
+        report: &RunReport,
+        junit_output: &Path,
+    ) -> eyre::Result<()> {
+        match self {
+            Reporter::Junit => {
+                if let Some(parent) = junit_output.parent()
+                    && !parent.as_os_str().is_empty()
+                {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(junit_output, junit_xml(report))?;
+            }
+            Reporter::Github => {
+                print!("{}", github_annotations(report));
+
+                if let Some(summary) = std::env::var_os("GITHUB_STEP_SUMMARY") {
+                    let mut file = std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(summary)?;
+                    file.write_all(github_step_summary(report).as_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The outcome of every task of a run, in the order the tasks started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunReport {
+    pub tasks: Vec<TaskReport>,
+    pub elapsed: Duration,
+}
+
+impl RunReport {
+    pub fn failed(&self) -> usize {
+        self.tasks.iter().filter(|t| t.outcome.is_failed()).count()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.tasks.iter().filter(|t| t.outcome.is_skipped()).count()
+    }
+
+    pub fn cache_hits(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|t| {
+                matches!(t.outcome, TaskOutcome::Passed { cache_hit: true })
+            })
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskReport {
+    pub task_id: String,
+    pub project: String,
+    pub task: String,
+    pub elapsed: Duration,
+    pub tries: u8,
+    pub outcome: TaskOutcome,
+    /// The last lines of the output of the task, if it produced any.
+    pub log_tail: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, strum::EnumIs)]
+pub enum TaskOutcome {
+    Passed {
+        cache_hit: bool,
+    },
+    Failed {
+        reason: TaskFailureReason,
+        error: String,
+    },
+    Skipped {
+        reason: TaskSkipReason,
+        /// The failed dependency the task was skipped for.
+        dependency: Option<String>,
+    },
+}
+
+/// Records the events of a run for a [`RunReport`], forwarding them to
+/// `inner`.
+pub struct ReportingSubscriber<S> {
+    inner: S,
+    enabled: bool,
+    recorder: Mutex<Recorder>,
+}
+
+impl<S> ReportingSubscriber<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            enabled: true,
+            recorder: Mutex::new(Recorder::default()),
+        }
+    }
+
+    /// Whether events are recorded. Disabled subscribers only forward events,
+    /// so that runs without reporters don't pay for keeping output around.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// The report of every task that finished so far. Output is read by the
+    /// inner subscriber, so the report is only complete once it consumed the
+    /// output of all tasks.
+    pub fn report(&self) -> RunReport {
+        let recorder = self.recorder.lock();
+
+        RunReport {
+            tasks: recorder
+                .tasks
+                .iter()
+                .filter_map(|t| {
+                    Some(TaskReport {
+                        task_id: t.task_id.clone(),
+                        project: t.project.clone(),
+                        task: t.task.clone(),
+                        elapsed: t.elapsed,
+                        tries: t.tries,
+                        outcome: t.outcome.clone()?,
+                        log_tail: t
+                            .output
+                            .as_ref()
+                            .and_then(|o| o.lock().text()),
+                    })
+                })
+                .collect(),
+            elapsed: recorder.elapsed,
+        }
+    }
+
+    fn record(&self, f: impl FnOnce(&mut Recorder)) {
+        if self.enabled {
+            f(&mut self.recorder.lock());
+        }
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    tasks: Vec<TaskRecord>,
+    /// Index of every task in `tasks`.
+    index: HashMap<String, usize>,
+    elapsed: Duration,
+}
+
+impl Recorder {
+    fn task(
+        &mut self,
+        task_id: &str,
+        project: &str,
+        task: &str,
+    ) -> &mut TaskRecord {
+        let index = match self.index.get(task_id) {
+            Some(index) => *index,
+            None => {
+                self.tasks.push(TaskRecord {
+                    task_id: task_id.to_string(),
+                    project: project.to_string(),
+                    task: task.to_string(),
+                    started: None,
+                    elapsed: Duration::ZERO,
+                    tries: 0,
+                    outcome: None,
+                    output: None,
+                });
+                self.index.insert(task_id.to_string(), self.tasks.len() - 1);
+                self.tasks.len() - 1
+            }
+        };
+
+        &mut self.tasks[index]
+    }
+}
+
+struct TaskRecord {
+    task_id: String,
+    project: String,
+    task: String,
+    started: Option<Instant>,
+    elapsed: Duration,
+    tries: u8,
+    /// `None` until the task finished.
+    outcome: Option<TaskOutcome>,
+    output: Option<Arc<Mutex<LogTail>>>,
+}
+
+impl TaskRecord {
+    fn elapsed_since_start(&self) -> Duration {
+        self.started.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+}
+
+/// The last bytes of the output of a task.
+#[derive(Default)]
+struct LogTail {
+    bytes: VecDeque<u8>,
+    /// Whether bytes were dropped from the front, so that the first line may
+    /// be incomplete.
+    truncated: bool,
+}
+
+impl LogTail {
+    fn push(&mut self, bytes: &[u8]) {
+        self.bytes.extend(bytes);
+
+        if self.bytes.len() > LOG_TAIL_BYTES {
+            self.bytes.drain(..self.bytes.len() - LOG_TAIL_BYTES);
+            self.truncated = true;
+        }
+    }
+
+    /// The last lines of the output without terminal escape sequences, or
+    /// `None` if there was no output.
+    fn text(&self) -> Option<String> {
+        let bytes = self.bytes.iter().copied().collect::<Vec<_>>();
+        let text = String::from_utf8_lossy(&bytes);
+        let text = regex!(r"\x1b\[[0-9;?]*[ -/]*[@-~]").replace_all(&text, "");
+
+        let mut lines = text.lines().collect::<Vec<_>>();
+        if self.truncated && !lines.is_empty() {
+            lines.remove(0);
+        }
+        let start = lines.len().saturating_sub(LOG_TAIL_LINES);
+        let tail = lines[start..].join("\n");
+        let tail = tail.trim_matches('\n');
+
+        (!tail.trim().is_empty()).then(|| tail.to_string())
+    }
+}
+
+/// Keeps the tail of everything read from `inner`.
+struct TailReader<R> {
+    inner: R,
+    tail: Arc<Mutex<LogTail>>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for TailReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+
+        if let Poll::Ready(Ok(())) = &poll {
+            self.tail.lock().push(&buf.filled()[filled..]);
+        }
+
+        poll
+    }
+}
+
+impl<S: DiagnosticSubscriber> DiagnosticSubscriber for ReportingSubscriber<S> {
+    fn wants_diagnostics(&self) -> bool {
+        self.inner.wants_diagnostics()
+    }
+
+    fn on_diagnostic(&self, event: DiagnosticEvent) {
+        self.inner.on_diagnostic(event)
+    }
+
+    fn on_diagnostics_batched(
+        &self,
+        events: impl IntoIterator<Item = DiagnosticEvent>,
+    ) {
+        self.inner.on_diagnostics_batched(events)
+    }
+}
+
+impl<S: ExecutionEventSubscriber> ExecutionEventSubscriber
+    for ReportingSubscriber<S>
+{
+    fn wants_task_output_stream(&self) -> bool {
+        self.inner.wants_task_output_stream()
+    }
+
+    fn wants_task_input_stream(&self) -> bool {
+        self.inner.wants_task_input_stream()
+    }
+
+    async fn on_task_started(&self, e: TaskStartedEvent) {
+        self.record(|r| {
+            r.task(&e.task_id, &e.project, &e.task).started =
+                Some(Instant::now());
+        });
+        self.inner.on_task_started(e).await
+    }
+
+    async fn on_execution_plan_ready(&self, e: ExecutionPlanReadyEvent) {
+        self.inner.on_execution_plan_ready(e).await
+    }
+
+    async fn on_task_output_stream(&self, mut e: TaskOutputStreamEvent) {
+        if self.enabled {
+            // every attempt streams its own output, only the last one is kept
+            let tail = Arc::new(Mutex::new(LogTail::default()));
+            self.record(|r| {
+                r.task(&e.task_id, &e.project, &e.task).output =
+                    Some(tail.clone());
+            });
+
+            e.stream = TaskOutputStream {
+                reader: Box::new(TailReader {
+                    inner: e.stream.reader,
+                    tail,
+                }),
+                writer: e.stream.writer,
+            };
+        }
+        self.inner.on_task_output_stream(e).await
+    }
+
+    async fn on_task_completed(&self, e: TaskCompletedEvent) {
+        self.record(|r| {
+            let task = r.task(&e.task_id, &e.project, &e.task);
+            task.elapsed = e.elapsed;
+            task.tries = e.tries;
+            task.outcome = Some(if e.exit_code == 0 {
+                TaskOutcome::Passed {
+                    cache_hit: e.cache_hit,
+                }
+            } else {
+                TaskOutcome::Failed {
+                    reason: TaskFailureReason::ExitCode,
+                    error: format!("exit code '{}'", e.exit_code),
+                }
+            });
+        });
+        self.inner.on_task_completed(e).await
+    }
+
+    async fn on_task_failed(&self, e: TaskFailedEvent) {
+        self.record(|r| {
+            let task = r.task(&e.task_id, &e.project, &e.task);
+            task.elapsed = task.elapsed_since_start();
+            task.tries = e.tries;
+            task.outcome = Some(TaskOutcome::Failed {
+                reason: e.reason,
+                error: e.error.clone(),
+            });
+        });
+        self.inner.on_task_failed(e).await
+    }
+
+    async fn on_task_skipped(&self, e: TaskSkippedEvent) {
+        self.record(|r| {
+            r.task(&e.task_id, &e.project, &e.task).outcome =
+                Some(TaskOutcome::Skipped {
+                    reason: e.reason.clone(),
+                    dependency: e.dependency.clone(),
+                });
+        });
+        self.inner.on_task_skipped(e).await
+    }
+
+    async fn on_task_retrying(&self, e: TaskRetryingEvent) {
+        self.inner.on_task_retrying(e).await
+    }
+
+    async fn on_cache_hit(&self, e: CacheHitEvent) {
+        self.inner.on_cache_hit(e).await
+    }
+
+    async fn on_execution_complete(&self, e: ExecutionCompleteEvent) {
+        self.record(|r| r.elapsed = e.elapsed);
+        self.inner.on_execution_complete(e).await
+    }
+
+    async fn on_scheduling_start(&self, e: SchedulingStartEvent) {
+        self.inner.on_scheduling_start(e).await
+    }
+
+    async fn on_scheduling_completed(&self, e: SchedulingCompletedEvent) {
+        self.inner.on_scheduling_completed(e).await
+    }
+
+    async fn on_phase_completed(&self, e: PhaseCompletedEvent) {
+        self.inner.on_phase_completed(e).await
+    }
+}
+
+/// Render a duration in seconds, as CI systems expect.
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use omni_messages::NoopSubscriber;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    use super::*;
+
+    pub(super) fn task(
+        task_id: &str,
+        outcome: TaskOutcome,
+        log_tail: Option<&str>,
+    ) -> TaskReport {
+        let (project, task) = task_id.split_once('#').unwrap();
+
+        TaskReport {
+            task_id: task_id.to_string(),
+            project: project.to_string(),
+            task: task.to_string(),
+            elapsed: Duration::from_millis(1500),
+            tries: 1,
+            outcome,
+            log_tail: log_tail.map(str::to_string),
+        }
+    }
+
+    /// Reads the output of tasks to the end, like the CLI subscriber.
+    struct ReadingSubscriber;
+
+    impl DiagnosticSubscriber for ReadingSubscriber {}
+
+    impl ExecutionEventSubscriber for ReadingSubscriber {
+        async fn on_task_output_stream(&self, mut e: TaskOutputStreamEvent) {
+            let mut output = vec![];
+            e.stream.reader.read_to_end(&mut output).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_log_tail_keeps_the_last_lines_without_escape_sequences() {
+        let mut tail = LogTail::default();
+        tail.push(b"\x1b[31merror\x1b[0m: something broke\n");
+        tail.push(b"second line\n");
+
+        assert_eq!(
+            tail.text().as_deref(),
+            Some("error: something broke\nsecond line")
+        );
+
+        let mut tail = LogTail::default();
+        for i in 0..LOG_TAIL_BYTES {
+            tail.push(format!("line {i}\n").as_bytes());
+        }
+        let text = tail.text().unwrap();
+
+        assert_eq!(text.lines().count(), LOG_TAIL_LINES);
+        assert!(text.ends_with(&format!("line {}", LOG_TAIL_BYTES - 1)));
+    }
+
+    #[tokio::test]
+    async fn test_report_records_outcomes_and_output() {
+        let reporting = ReportingSubscriber::new(ReadingSubscriber);
+
+        reporting
+            .on_task_started(TaskStartedEvent {
+                task_id: "a#test".to_string(),
+                project: "a".to_string(),
+                task: "test".to_string(),
+            })
+            .await;
+
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        writer.write_all(b"assertion failed\n").await.unwrap();
+        drop(writer);
+
+        reporting
+            .on_task_output_stream(TaskOutputStreamEvent {
+                task_id: "a#test".to_string(),
+                project: "a".to_string(),
+                task: "test".to_string(),
+                is_replay: false,
+                is_interactive: false,
+                output_logs: Default::default(),
+                stream: TaskOutputStream {
+                    reader: Box::new(reader),
+                    writer: None,
+                },
+            })
+            .await;
+        reporting
+            .on_task_failed(TaskFailedEvent {
+                task_id: "a#test".to_string(),
+                project: "a".to_string(),
+                task: "test".to_string(),
+                reason: TaskFailureReason::ExitCode,
+                error: "exit code '1'".to_string(),
+                tries: 2,
+            })
+            .await;
+        reporting
+            .on_task_skipped(TaskSkippedEvent {
+                task_id: "b#test".to_string(),
+                project: "b".to_string(),
+                task: "test".to_string(),
+                reason: TaskSkipReason::DependeeTaskFailure,
+                dependency: Some("a#test".to_string()),
+            })
+            .await;
+
+        let report = reporting.report();
+
+        assert_eq!(report.tasks.len(), 2);
+        assert_eq!(report.failed(), 1);
+        assert_eq!(report.skipped(), 1);
+        assert_eq!(report.tasks[0].tries, 2);
+        assert_eq!(
+            report.tasks[0].log_tail.as_deref(),
+            Some("assertion failed")
+        );
+        assert_eq!(
+            report.tasks[1].outcome,
+            TaskOutcome::Skipped {
+                reason: TaskSkipReason::DependeeTaskFailure,
+                dependency: Some("a#test".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_subscriber_records_nothing() {
+        let reporting = ReportingSubscriber::new(NoopSubscriber).enabled(false);
+
+        reporting
+            .on_task_skipped(TaskSkippedEvent {
+                task_id: "b#test".to_string(),
+                project: "b".to_string(),
+                task: "test".to_string(),
+                reason: TaskSkipReason::Disabled,
+                dependency: None,
+            })
+            .await;
+
+        assert!(reporting.report().tasks.is_empty());
+    }
+}
@@ -9,7 +9,7 @@ use comfy_table::{Cell, Color, Table, presets::UTF8_FULL};
 use indicatif::{
     MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle,
 };
-use omni_messages::execution::events::BatchCompletedEvent;
+use omni_messages::execution::events::SchedulingCompletedEvent;
 use omni_messages::generator::events::{
     GeneratorActionFailedEvent, GeneratorActionInProgressEvent,
     GeneratorActionSkippedEvent, GeneratorActionSuccessEvent,
@@ -18,8 +18,9 @@ use omni_messages::{
     CacheHitEvent, DiagnosticEvent, DiagnosticLevel, DiagnosticSubscriber,
     ExecutionCompleteEvent, ExecutionEventSubscriber, ExecutionPlanReadyEvent,
     GeneratorCompletedEvent, GeneratorEventSubscriber, GeneratorStartEvent,
-    TaskCompletedEvent, TaskFailedEvent, TaskOutputStreamEvent,
-    TaskRetryingEvent, TaskSkipReason, TaskSkippedEvent, TaskStartedEvent,
+    TaskCompletedEvent, TaskFailedEvent, TaskFailureReason,
+    TaskOutputStreamEvent, TaskRetryingEvent, TaskSkipReason, TaskSkippedEvent,
+    TaskStartedEvent,
 };
 use omni_task_output_logs::LogsDisplay;
 use omni_term_ui::mux_output_presenter::{
@@ -783,12 +784,15 @@ impl ExecutionEventSubscriber for CliSubscriber {
 
     async fn on_task_failed(&self, e: TaskFailedEvent) {
         self.finish_capture(&e.task_id, true).await;
-        self.emit(
-            format!("Task '{}' error: {}", e.task_id, e.error)
-                .red()
-                .to_string(),
-            EmitLevel::Error,
-        );
+        let msg = match e.reason {
+            TaskFailureReason::TimedOut => {
+                format!("Task '{}' {}", e.task_id, e.error)
+            }
+            TaskFailureReason::ExitCode | TaskFailureReason::Error => {
+                format!("Task '{}' error: {}", e.task_id, e.error)
+            }
+        };
+        self.emit(msg.red().to_string(), EmitLevel::Error);
     }
 
     async fn on_task_skipped(&self, e: TaskSkippedEvent) {
@@ -942,7 +946,7 @@ impl ExecutionEventSubscriber for CliSubscriber {
         self.with_task_progress(|tp| tp.finish());
     }
 
-    async fn on_batch_completed(&self, _event: BatchCompletedEvent) {
+    async fn on_scheduling_completed(&self, _event: SchedulingCompletedEvent) {
         self.wait().await;
     }
 }
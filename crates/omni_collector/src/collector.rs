@@ -14,7 +14,9 @@ use omni_command_config::CommandConfig;
 use omni_hasher::{
     Hasher,
     impls::{DefaultHash, DefaultHasher},
-    project_dir_hasher::{ProjectDirHasher, impls::RealDirHasher},
+    project_dir_hasher::{
+        Compat, HashTree, ProjectDirHasher, impls::RealDirHasher,
+    },
 };
 use omni_types::{OmniPath, Root, RootMap};
 use omni_utils::glob::build_glob_set;
@@ -24,7 +26,15 @@ use omni_utils::path::{
 use system_traits::{FsMetadata, FsMetadataAsync, auto_impl, impls::RealSys};
 use trace::Level;
 
-use crate::error::{Error, ErrorInner};
+use crate::{
+    InputManifest,
+    error::{Error, ErrorInner},
+};
+
+/// File in the cache dir holding the salt of the env var and arg hashes in
+/// manifests.
+const MANIFEST_SALT_FILE: &str = "manifest.salt";
+const MANIFEST_SALT_LEN: usize = 16;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CollectConfig {
@@ -32,6 +42,8 @@ pub struct CollectConfig {
     pub input_files: bool,
     pub digests: bool,
     pub cache_output_dirs: bool,
+    /// Record an [`InputManifest`] of everything that went into each digest.
+    pub manifests: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -46,7 +58,8 @@ pub struct ProjectTaskInfo<'a> {
     pub input_files: &'a [OmniPath],
     pub input_env_keys: &'a [String],
     pub env_vars: &'a Map<String, String>,
-    pub dependency_digests: &'a [DefaultHash],
+    /// Digests of the dependencies, keyed by their full task name.
+    pub dependency_digests: &'a Map<String, DefaultHash>,
     pub args: &'a Map<String, serde_json::Value>,
 }
 
@@ -64,6 +77,7 @@ pub struct CollectResult<'a> {
     pub roots: RootMap<'a>,
     pub digest: Option<DefaultHash>,
     pub cache_output_dir: Option<PathBuf>,
+    pub manifest: Option<InputManifest>,
 }
 
 struct HashInput<'a> {
@@ -76,7 +90,7 @@ struct HashInput<'a> {
     pub cached_output_files_glob: &'a [OmniPath],
     pub input_env_cache_keys: &'a [String],
     pub env_vars: &'a Map<String, String>,
-    pub dependency_digests: &'a [DefaultHash],
+    pub dependency_digests: &'a Map<String, DefaultHash>,
     pub args: &'a Map<String, serde_json::Value>,
 }
 
@@ -95,6 +109,7 @@ struct Holder<'a> {
     roots: RootMap<'a>,
     digest: Option<DefaultHash>,
     cache_output_dir: Option<PathBuf>,
+    manifest: Option<InputManifest>,
 }
 
 #[derive(Debug)]
@@ -140,13 +155,49 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
         Ok(output_dir)
     }
 
+    /// The salt env var and arg values are hashed with in the manifests of
+    /// this workspace, created on first use.
+    ///
+    /// Manifests are stored with the cache entries, so unsalted hashes of
+    /// short or well known values could be reversed by anyone reading them.
+    async fn manifest_salt(&self) -> Result<Vec<u8>, Error> {
+        let path = self.cache_dir.join(MANIFEST_SALT_FILE);
+
+        match tokio::fs::read(&path).await {
+            Ok(salt) if salt.len() == MANIFEST_SALT_LEN => return Ok(salt),
+            Ok(_) => tokio::fs::remove_file(&path).await?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        tokio::fs::create_dir_all(self.cache_dir).await?;
+
+        // Link a fully written file into place so that concurrent processes
+        // never read a partial salt and all end up with the same one.
+        let salt = uuid::Uuid::new_v4();
+        let tmp_path = self
+            .cache_dir
+            .join(format!("{MANIFEST_SALT_FILE}.{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&tmp_path, salt.as_bytes()).await?;
+        let linked = tokio::fs::hard_link(&tmp_path, &path).await;
+        tokio::fs::remove_file(&tmp_path).await?;
+        match linked {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(tokio::fs::read(&path).await?)
+    }
+
     async fn get_digest(
         &self,
         hash_input: &HashInput<'_>,
-    ) -> Result<DefaultHash, Error> {
-        let mut tree = self
+        manifest_salt: Option<&[u8]>,
+    ) -> Result<(DefaultHash, Option<InputManifest>), Error> {
+        let file_hashes = self
             .dir_hasher
-            .hash_tree::<DefaultHasher>(
+            .hash_files::<DefaultHasher>(
                 hash_input.project_name,
                 hash_input.project_dir,
                 hash_input.input_files,
@@ -154,10 +205,35 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
             .await
             .map_err(|e| ErrorInner::ProjectDirHasher(e.to_string()))?;
 
-        let mut dep_hashes = hash_input.dependency_digests.to_vec();
+        let mut manifest = manifest_salt.map(|_| InputManifest::default());
+
+        let mut leaves = Vec::with_capacity(file_hashes.len());
+        for (path, hash) in file_hashes {
+            if let Some(manifest) = manifest.as_mut() {
+                manifest.files.insert(path.to_string(), hash);
+            }
+            leaves.push(hash);
+        }
+
+        let mut tree = HashTree::<Compat<DefaultHasher>>::new();
+        tree.append(&mut leaves);
+
+        let mut dep_hashes = hash_input
+            .dependency_digests
+            .values()
+            .copied()
+            .collect::<Vec<_>>();
 
         dep_hashes.sort();
 
+        if let Some(manifest) = manifest.as_mut() {
+            manifest.dependency_digests = hash_input
+                .dependency_digests
+                .iter()
+                .map(|(name, digest)| (name.clone(), *digest))
+                .collect();
+        }
+
         for dep_hash in dep_hashes {
             tree.insert(dep_hash);
         }
@@ -180,6 +256,15 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
                     .unwrap_or("");
 
                 buff.push(format!("{env_key}={value}"));
+
+                if let Some(manifest) = manifest.as_mut()
+                    && let Some(salt) = manifest_salt
+                {
+                    manifest.env_vars.insert(
+                        env_key.to_string(),
+                        DefaultHasher::hash(&[salt, value.as_bytes()].concat()),
+                    );
+                }
             }
 
             let env_vars = buff.join("\n");
@@ -190,7 +275,18 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
         if !hash_input.args.is_empty() {
             let mut buff = vec![];
             for (key, value) in hash_input.args.iter() {
-                buff.push(format!("{key}={value:?}"));
+                let value = format!("{value:?}");
+
+                if let Some(manifest) = manifest.as_mut()
+                    && let Some(salt) = manifest_salt
+                {
+                    manifest.args.insert(
+                        key.clone(),
+                        DefaultHasher::hash(&[salt, value.as_bytes()].concat()),
+                    );
+                }
+
+                buff.push(format!("{key}={value}"));
             }
 
             let args = buff.join("\n");
@@ -207,9 +303,12 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
             sorted.sort();
 
             for path in sorted {
-                tree.insert(DefaultHasher::hash(
-                    path.to_string_lossy().as_bytes(),
-                ));
+                let path = path.to_string_lossy();
+                tree.insert(DefaultHasher::hash(path.as_bytes()));
+
+                if let Some(manifest) = manifest.as_mut() {
+                    manifest.output_globs.push(path.into_owned());
+                }
             }
         }
 
@@ -221,6 +320,10 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
             if !command.is_empty() {
                 let command_str = format!("command={command}");
                 tree.insert(DefaultHasher::hash(command_str.as_bytes()));
+
+                if let Some(manifest) = manifest.as_mut() {
+                    manifest.command = Some(command.into_owned());
+                }
             }
         }
         if let Some(retry_command) = hash_input.task_retry_exec {
@@ -229,6 +332,10 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
                 let retry_command_str =
                     format!("retry_command={retry_command}");
                 tree.insert(DefaultHasher::hash(retry_command_str.as_bytes()));
+
+                if let Some(manifest) = manifest.as_mut() {
+                    manifest.retry_command = Some(retry_command.into_owned());
+                }
             }
         }
 
@@ -236,7 +343,7 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
 
         tree.commit();
 
-        Ok(tree.root().expect("unable to get root"))
+        Ok((tree.root().expect("unable to get root"), manifest))
     }
 
     #[cfg_attr(
@@ -256,8 +363,10 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
 
         trace::trace!(?project_tasks, ?config, "begin_collect");
 
-        let should_collect_input_files =
-            config.input_files || config.digests || config.cache_output_dirs;
+        let should_collect_input_files = config.input_files
+            || config.digests
+            || config.cache_output_dirs
+            || config.manifests;
 
         let should_collect_output_files = config.output_files;
 
@@ -349,6 +458,7 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
                 roots,
                 cache_output_dir: None,
                 digest: None,
+                manifest: None,
             });
         }
 
@@ -503,28 +613,38 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
             }
         }
 
-        if config.digests || config.cache_output_dirs {
+        if config.digests || config.cache_output_dirs || config.manifests {
+            let manifest_salt = if config.manifests {
+                Some(self.manifest_salt().await?)
+            } else {
+                None
+            };
+
             for holder in &mut to_process {
-                let hash = self
-                    .get_digest(&HashInput {
-                        task_name: holder.task.task_name,
-                        task_exec: holder.task.task_exec,
-                        task_retry_exec: holder.task.task_retry_exec,
-                        project_name: holder.task.project_name,
-                        project_dir: holder.task.project_dir,
-                        input_files: holder
-                            .resolved_input_files
-                            .as_ref()
-                            .expect("should be some"),
-                        input_env_cache_keys: holder.task.input_env_keys,
-                        env_vars: holder.task.env_vars,
-                        dependency_digests: holder.task.dependency_digests,
-                        cached_output_files_glob: &holder.output_files_glob,
-                        args: holder.task.args,
-                    })
+                let (hash, manifest) = self
+                    .get_digest(
+                        &HashInput {
+                            task_name: holder.task.task_name,
+                            task_exec: holder.task.task_exec,
+                            task_retry_exec: holder.task.task_retry_exec,
+                            project_name: holder.task.project_name,
+                            project_dir: holder.task.project_dir,
+                            input_files: holder
+                                .resolved_input_files
+                                .as_ref()
+                                .expect("should be some"),
+                            input_env_cache_keys: holder.task.input_env_keys,
+                            env_vars: holder.task.env_vars,
+                            dependency_digests: holder.task.dependency_digests,
+                            cached_output_files_glob: &holder.output_files_glob,
+                            args: holder.task.args,
+                        },
+                        manifest_salt.as_deref(),
+                    )
                     .await?;
 
                 holder.digest = Some(hash);
+                holder.manifest = manifest;
             }
         }
 
@@ -550,6 +670,7 @@ impl<'a, TSys: CollectorSys> Collector<'a, TSys> {
                 roots: p.roots,
                 cache_output_dir: p.cache_output_dir,
                 digest: p.digest,
+                manifest: p.manifest,
             })
             .collect::<Vec<_>>())
     }
@@ -623,7 +744,7 @@ mod tests {
         output_files: Vec<OmniPath>,
         input_env_keys: Vec<String>,
         env_vars: Map<String, String>,
-        dependency_digests: Vec<DefaultHash>,
+        dependency_digests: Map<String, DefaultHash>,
         args: Map<String, serde_json::Value>,
     }
 
@@ -637,7 +758,7 @@ mod tests {
                 output_files: vec![],
                 input_env_keys: vec![],
                 env_vars: Map::default(),
-                dependency_digests: vec![],
+                dependency_digests: Map::default(),
                 args: Map::default(),
             }
         }
@@ -666,6 +787,7 @@ mod tests {
         output_files: Option<Vec<PathBuf>>,
         digest: Option<DefaultHash>,
         cache_output_dir: Option<PathBuf>,
+        manifest: Option<InputManifest>,
     }
 
     impl From<CollectResult<'_>> for Collected {
@@ -686,6 +808,7 @@ mod tests {
                 output_files: sorted(r.output_files),
                 digest: r.digest,
                 cache_output_dir: r.cache_output_dir,
+                manifest: r.manifest,
             }
         }
     }
@@ -1058,4 +1181,162 @@ mod tests {
             rel_paths(&["src/two.txt"])
         );
     }
+
+    #[tokio::test]
+    async fn manifest_records_inputs_without_changing_digest() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+
+        write_file(&root.join("proj/src/a.txt"), "a");
+
+        let mut project = Project::new(root, "proj");
+        project.input_files = vec![OmniPath::new("src/**/*.txt")];
+        project.input_env_keys = vec!["SECRET".to_string()];
+        project
+            .env_vars
+            .insert("SECRET".to_string(), "hunter2".to_string());
+
+        let without = collect_one(
+            root,
+            &fresh_cache_dir(root),
+            &project,
+            &CollectConfig {
+                digests: true,
+                ..Default::default()
+            },
+        )
+        .await;
+        let with = collect_one(
+            root,
+            &fresh_cache_dir(root),
+            &project,
+            &CollectConfig {
+                digests: true,
+                manifests: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert!(without.manifest.is_none());
+        assert_eq!(without.digest, with.digest);
+
+        let manifest = with.manifest.expect("manifest should be set");
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            vec!["@project/src/a.txt"]
+        );
+        let secret = manifest.env_vars.get("SECRET").expect("SECRET is hashed");
+        assert_ne!(
+            secret,
+            &DefaultHasher::hash(b"hunter2"),
+            "env var values should be salted"
+        );
+    }
+
+    #[tokio::test]
+    async fn manifest_arg_values_are_salted() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+
+        write_file(&root.join("proj/src/a.txt"), "a");
+
+        let mut project = Project::new(root, "proj");
+        project.args.insert(
+            "token".to_string(),
+            serde_json::Value::String("hunter2".to_string()),
+        );
+
+        let config = CollectConfig {
+            manifests: true,
+            ..Default::default()
+        };
+        let token_hash = |collected: Collected| {
+            collected.manifest.expect("manifest should be set").args["token"]
+        };
+
+        let cache_dir = fresh_cache_dir(root);
+        let first = collect_one(root, &cache_dir, &project, &config).await;
+        let second = collect_one(root, &cache_dir, &project, &config).await;
+        let other =
+            collect_one(root, &fresh_cache_dir(root), &project, &config).await;
+
+        let first = token_hash(first);
+        assert_ne!(
+            first,
+            DefaultHasher::hash(br#"String("hunter2")"#),
+            "arg values should be salted"
+        );
+        assert_eq!(first, token_hash(second));
+        assert_ne!(first, token_hash(other));
+    }
+
+    #[tokio::test]
+    async fn manifest_env_var_salt_is_kept_per_cache_dir() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+
+        write_file(&root.join("proj/src/a.txt"), "a");
+
+        let mut project = Project::new(root, "proj");
+        project.input_env_keys = vec!["SECRET".to_string()];
+        project
+            .env_vars
+            .insert("SECRET".to_string(), "hunter2".to_string());
+
+        let config = CollectConfig {
+            manifests: true,
+            ..Default::default()
+        };
+        let secret_hash = |collected: Collected| {
+            collected.manifest.expect("manifest should be set").env_vars["SECRET"]
+        };
+
+        let cache_dir = fresh_cache_dir(root);
+        let first = collect_one(root, &cache_dir, &project, &config).await;
+        let second = collect_one(root, &cache_dir, &project, &config).await;
+        let other =
+            collect_one(root, &fresh_cache_dir(root), &project, &config).await;
+
+        let first = secret_hash(first);
+        assert_eq!(first, secret_hash(second));
+        assert_ne!(first, secret_hash(other));
+    }
+
+    #[tokio::test]
+    async fn manifest_names_dependency_digests() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+
+        write_file(&root.join("proj/src/a.txt"), "a");
+
+        let mut project = Project::new(root, "proj");
+        project
+            .dependency_digests
+            .insert("lib#build".to_string(), [2; 32]);
+        project
+            .dependency_digests
+            .insert("codegen#build".to_string(), [1; 32]);
+
+        let collected = collect_one(
+            root,
+            &fresh_cache_dir(root),
+            &project,
+            &CollectConfig {
+                digests: true,
+                manifests: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let manifest = collected.manifest.expect("manifest should be set");
+        assert_eq!(
+            manifest.dependency_digests.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("codegen#build".to_string(), [1; 32]),
+                ("lib#build".to_string(), [2; 32]),
+            ]
+        );
+    }
 }
@@ -1,5 +1,7 @@
 mod collector;
 pub mod error;
+mod manifest;
 
 pub use collector::*;
 pub use error::*;
+pub use manifest::*;
@@ -0,0 +1,244 @@
+use maps::OrderedMap;
+use omni_hasher::impls::DefaultHash;
+use serde::{Deserialize, Serialize};
+
+/// A record of every input that went into a task's digest.
+///
+/// Two manifests can be diffed to explain why a task's digest changed. Env var
+/// and arg values are stored as hashes salted per workspace so that secrets
+/// never end up in the cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputManifest {
+    /// Hash of each input file (path and content), keyed by its rooted path.
+    pub files: OrderedMap<String, DefaultHash>,
+    /// Salted hash of the value of each env var that is part of the digest.
+    pub env_vars: OrderedMap<String, DefaultHash>,
+    /// Salted hash of the value of each arg that is part of the digest.
+    pub args: OrderedMap<String, DefaultHash>,
+    /// Digest of each dependency, keyed by its full task name.
+    pub dependency_digests: OrderedMap<String, DefaultHash>,
+    pub output_globs: Vec<String>,
+    pub command: Option<String>,
+    pub retry_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputChange<T> {
+    pub kind: InputChangeKind,
+    pub name: T,
+}
+
+impl<T> InputChange<T> {
+    fn new(kind: InputChangeKind, name: T) -> Self {
+        Self { kind, name }
+    }
+}
+
+/// The differences between two [`InputManifest`]s. Every list is empty when
+/// the manifests are equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputManifestDiff {
+    pub files: Vec<InputChange<String>>,
+    pub env_vars: Vec<InputChange<String>>,
+    pub args: Vec<InputChange<String>>,
+    pub dependency_digests: Vec<InputChange<String>>,
+    pub output_globs: Vec<InputChange<String>>,
+    pub command_changed: bool,
+    pub retry_command_changed: bool,
+}
+
+impl InputManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+            && self.env_vars.is_empty()
+            && self.args.is_empty()
+            && self.dependency_digests.is_empty()
+            && self.output_globs.is_empty()
+            && !self.command_changed
+            && !self.retry_command_changed
+    }
+}
+
+impl InputManifest {
+    /// Returns what changed going from `previous` to `self`.
+    pub fn diff(&self, previous: &InputManifest) -> InputManifestDiff {
+        InputManifestDiff {
+            files: diff_maps(&previous.files, &self.files),
+            env_vars: diff_maps(&previous.env_vars, &self.env_vars),
+            args: diff_maps(&previous.args, &self.args),
+            dependency_digests: diff_maps(
+                &previous.dependency_digests,
+                &self.dependency_digests,
+            ),
+            output_globs: diff_lists(
+                &previous.output_globs,
+                &self.output_globs,
+            ),
+            command_changed: previous.command != self.command,
+            retry_command_changed: previous.retry_command != self.retry_command,
+        }
+    }
+}
+
+fn diff_maps<V: PartialEq>(
+    previous: &OrderedMap<String, V>,
+    current: &OrderedMap<String, V>,
+) -> Vec<InputChange<String>> {
+    let mut changes = vec![];
+
+    for (key, value) in current {
+        match previous.get(key) {
+            None => changes
+                .push(InputChange::new(InputChangeKind::Added, key.clone())),
+            Some(prev) if prev != value => changes
+                .push(InputChange::new(InputChangeKind::Modified, key.clone())),
+            Some(_) => {}
+        }
+    }
+
+    for key in previous.keys() {
+        if !current.contains_key(key) {
+            changes
+                .push(InputChange::new(InputChangeKind::Removed, key.clone()));
+        }
+    }
+
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    changes
+}
+
+fn diff_lists<T: PartialEq + Clone>(
+    previous: &[T],
+    current: &[T],
+) -> Vec<InputChange<T>> {
+    let added = current
+        .iter()
+        .filter(|v| !previous.contains(v))
+        .map(|v| InputChange::new(InputChangeKind::Added, v.clone()));
+    let removed = previous
+        .iter()
+        .filter(|v| !current.contains(v))
+        .map(|v| InputChange::new(InputChangeKind::Removed, v.clone()));
+
+    added.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> InputManifest {
+        let mut manifest = InputManifest::default();
+        manifest
+            .files
+            .insert("@project/src/a.txt".to_string(), [1; 32]);
+        manifest
+            .files
+            .insert("@project/src/b.txt".to_string(), [2; 32]);
+        manifest.env_vars.insert("NODE_ENV".to_string(), [3; 32]);
+        manifest.args.insert("mode".to_string(), [5; 32]);
+        manifest
+            .dependency_digests
+            .insert("lib#build".to_string(), [4; 32]);
+        manifest.command = Some("cargo build".to_string());
+        manifest
+    }
+
+    #[test]
+    fn test_diff_equal_manifests_is_empty() {
+        let diff = manifest().diff(&manifest());
+
+        assert!(diff.is_empty(), "diff should be empty: {diff:?}");
+    }
+
+    #[test]
+    fn test_diff_reports_file_changes() {
+        let previous = manifest();
+        let mut current = manifest();
+        current
+            .files
+            .insert("@project/src/a.txt".to_string(), [9; 32]);
+        current.files.remove("@project/src/b.txt");
+        current
+            .files
+            .insert("@project/src/c.txt".to_string(), [5; 32]);
+
+        let diff = current.diff(&previous);
+
+        assert_eq!(
+            diff.files,
+            vec![
+                InputChange::new(
+                    InputChangeKind::Modified,
+                    "@project/src/a.txt".to_string()
+                ),
+                InputChange::new(
+                    InputChangeKind::Removed,
+                    "@project/src/b.txt".to_string()
+                ),
+                InputChange::new(
+                    InputChangeKind::Added,
+                    "@project/src/c.txt".to_string()
+                ),
+            ]
+        );
+        assert!(diff.env_vars.is_empty());
+        assert!(!diff.command_changed);
+    }
+
+    #[test]
+    fn test_diff_reports_env_args_deps_and_command() {
+        let previous = manifest();
+        let mut current = manifest();
+        current.env_vars.insert("NODE_ENV".to_string(), [8; 32]);
+        current.args.insert("mode".to_string(), [9; 32]);
+        current
+            .dependency_digests
+            .insert("lib#build".to_string(), [7; 32]);
+        current
+            .dependency_digests
+            .insert("codegen#build".to_string(), [6; 32]);
+        current.command = Some("cargo build --release".to_string());
+
+        let diff = current.diff(&previous);
+
+        assert_eq!(
+            diff.env_vars,
+            vec![InputChange::new(
+                InputChangeKind::Modified,
+                "NODE_ENV".to_string()
+            )]
+        );
+        assert_eq!(
+            diff.args,
+            vec![InputChange::new(
+                InputChangeKind::Modified,
+                "mode".to_string()
+            )]
+        );
+        assert_eq!(
+            diff.dependency_digests,
+            vec![
+                InputChange::new(
+                    InputChangeKind::Added,
+                    "codegen#build".to_string()
+                ),
+                InputChange::new(
+                    InputChangeKind::Modified,
+                    "lib#build".to_string()
+                ),
+            ]
+        );
+        assert!(diff.command_changed);
+        assert!(!diff.retry_command_changed);
+    }
+}
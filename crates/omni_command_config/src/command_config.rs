@@ -2,8 +2,9 @@ use std::borrow::Cow;
 
 use strum::EnumIs;
 
-/// A task command, expressed either as a shell-style string (split into argv
-/// exactly once via `shlex`) or as an explicit argv vector (never parsed).
+/// A task command, expressed either as a shell-style string (interpreted by the
+/// built-in shell, or split into argv via `shlex`, see [`crate::ShellMode`]) or
+/// as an explicit argv vector (never parsed).
 ///
 /// Deserialization is untagged: a scalar becomes [`CommandConfig::Shell`], a
 /// sequence becomes [`CommandConfig::Argv`]. Each string is validated as a Tera
@@ -14,7 +15,7 @@ use strum::EnumIs;
 #[cfg_attr(feature = "serde", serde(untagged))]
 #[cfg_attr(feature = "schemars", schemars(untagged))]
 pub enum CommandConfig {
-    /// A shell-style string, split into argv exactly once via `shlex`.
+    /// A shell-style string, resolved according to a [`crate::ShellMode`].
     Shell(String),
     /// An explicit argv; never parsed.
     Argv(Vec<String>),
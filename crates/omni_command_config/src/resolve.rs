@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 
 use maps::Map;
+use omni_shell::ShellErrorKind;
 
 use crate::CommandConfig;
 
@@ -15,6 +16,25 @@ pub enum ResolveError {
 
     #[error("command resolved to an empty argv")]
     EmptyArgv,
+
+    #[error(transparent)]
+    Shell(#[from] omni_shell::ShellError),
+}
+
+/// How [`CommandConfig::Shell`] strings are turned into a [`Command`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ShellMode {
+    /// Split into argv with `shlex`. Operators such as `&&`, `|` or `>` end up
+    /// as literal arguments.
+    #[default]
+    Shlex,
+    /// Run with the built-in shell of `omni_shell`. A script that is a single
+    /// plain command still resolves to that command's argv, so the real
+    /// program shows up in cache keys; any other script resolves to
+    /// `interpreter` followed by the script. Scripts using syntax the
+    /// built-in shell doesn't support, such as subshells or background jobs,
+    /// are run with the system shell instead.
+    Builtin { interpreter: Vec<String> },
 }
 
 /// A [`CommandConfig`] resolved into a program and its arguments.
@@ -125,8 +145,50 @@ pub fn resolve_command(
     tera_ctx: Option<&omni_tera::Context>,
     env_vars: Option<&Map<String, String>>,
 ) -> Result<Command, ResolveError> {
-    match spec {
-        CommandConfig::Shell(s) => {
+    resolve_command_with_shell(spec, tera_ctx, env_vars, &ShellMode::Shlex)
+}
+
+/// Same as [`resolve_command`], with [`CommandConfig::Shell`] strings resolved
+/// according to `shell`.
+///
+/// In [`ShellMode::Builtin`] the string is only Tera-rendered and parsed;
+/// variables are left for the shell to expand at run time. Strings the
+/// built-in shell can't run resolve to [`system_shell`] followed by the
+/// string.
+pub fn resolve_command_with_shell(
+    spec: &CommandConfig,
+    tera_ctx: Option<&omni_tera::Context>,
+    env_vars: Option<&Map<String, String>>,
+    shell: &ShellMode,
+) -> Result<Command, ResolveError> {
+    match (spec, shell) {
+        (CommandConfig::Shell(s), ShellMode::Builtin { interpreter }) => {
+            let s = maybe_render(s, tera_ctx)?;
+            let script = match omni_shell::parse(s.as_ref()) {
+                Ok(script) => script,
+                Err(e) if e.kind() == ShellErrorKind::Unsupported => {
+                    let mut argv = system_shell();
+                    argv.push(s.into_owned());
+
+                    return Ok(Command::from_argv(argv));
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            if script.items.is_empty() {
+                return Ok(Command::default());
+            }
+
+            if let Some(argv) = plain_argv(&script) {
+                return Ok(Command::from_argv(argv));
+            }
+
+            let mut argv = interpreter.clone();
+            argv.push(s.into_owned());
+
+            Ok(Command::from_argv(argv))
+        }
+        (CommandConfig::Shell(s), ShellMode::Shlex) => {
             let s = maybe_render(s, tera_ctx)?;
             let s = match env_vars {
                 Some(vars) => Cow::Owned(::env::expand(s.as_ref(), vars)),
@@ -137,7 +199,7 @@ pub fn resolve_command(
 
             Ok(Command::from_argv(argv))
         }
-        CommandConfig::Argv(items) => {
+        (CommandConfig::Argv(items), _) => {
             let argv = items
                 .iter()
                 .map(|e| {
@@ -154,6 +216,43 @@ pub fn resolve_command(
     }
 }
 
+/// The platform's shell and the flag that makes it run a script given as an
+/// argument.
+pub fn system_shell() -> Vec<String> {
+    let argv: &[&str] = if cfg!(windows) {
+        &["cmd", "/C"]
+    } else {
+        &["sh", "-c"]
+    };
+
+    argv.iter().map(|a| a.to_string()).collect()
+}
+
+/// The argv of `script` when it is a single command that can be spawned
+/// directly, without going through the shell.
+fn plain_argv(script: &omni_shell::Script) -> Option<Vec<String>> {
+    let command = script.as_simple_command()?;
+
+    if !command.assignments.is_empty() || !command.redirects.is_empty() {
+        return None;
+    }
+
+    let argv = command
+        .words
+        .iter()
+        .map(|w| w.as_literal().filter(|_| !w.has_glob()))
+        .collect::<Option<Vec<_>>>()?;
+
+    // Builtins that change the shell's state mean nothing to a lone process,
+    // and Windows has no executables for the others.
+    let prog = argv.first()?;
+    let needs_shell =
+        matches!(prog.as_str(), "cd" | "exit" | "export" | "unset")
+            || (cfg!(windows) && omni_shell::is_builtin(prog));
+
+    (!needs_shell).then_some(argv)
+}
+
 #[cfg(test)]
 mod tests {
     use maps::Map;
@@ -347,6 +446,122 @@ mod tests {
         assert_eq!(resolved.to_argv(), vec!["echo", "a", "b"]);
     }
 
+    fn builtin() -> ShellMode {
+        ShellMode::Builtin {
+            interpreter: vec![
+                "omni".to_string(),
+                "shell".to_string(),
+                "-c".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn builtin_shell_plain_command_resolves_to_argv() {
+        let resolved = resolve_command_with_shell(
+            &shell("bun exec 'cp -r ../a/dist ./dist'"),
+            None,
+            None,
+            &builtin(),
+        )
+        .unwrap();
+        assert_eq!(
+            resolved.to_argv(),
+            vec!["bun", "exec", "cp -r ../a/dist ./dist"]
+        );
+    }
+
+    #[test]
+    fn builtin_shell_wraps_scripts_in_interpreter() {
+        for script in [
+            "cargo build && cargo test",
+            "cat a | grep b",
+            "echo hi > out.txt",
+            "RUST_LOG=debug cargo run",
+            "rm *.tmp",
+            "echo $GREETING",
+            "cd sub",
+        ] {
+            let resolved = resolve_command_with_shell(
+                &shell(script),
+                None,
+                Some(&env(&[("GREETING", "hello")])),
+                &builtin(),
+            )
+            .unwrap();
+            assert_eq!(
+                resolved.to_argv(),
+                vec!["omni", "shell", "-c", script],
+                "{script}"
+            );
+        }
+    }
+
+    #[test]
+    fn builtin_shell_runs_parameter_expansions() {
+        let script = "cargo test -p app ${RUST_TEST_ADDITIONAL_ARGS:-}";
+        let resolved =
+            resolve_command_with_shell(&shell(script), None, None, &builtin())
+                .unwrap();
+        assert_eq!(resolved.to_argv(), vec!["omni", "shell", "-c", script]);
+    }
+
+    #[test]
+    fn builtin_shell_falls_back_to_system_shell() {
+        for script in [
+            "(cd sub && make)",
+            "server & sleep 1",
+            "echo $(git rev-parse HEAD)",
+            "echo ${NAME%.rs}",
+        ] {
+            let resolved = resolve_command_with_shell(
+                &shell(script),
+                None,
+                None,
+                &builtin(),
+            )
+            .unwrap();
+
+            let mut expected = system_shell();
+            expected.push(script.to_string());
+            assert_eq!(resolved.to_argv(), expected, "{script}");
+        }
+    }
+
+    #[test]
+    fn builtin_shell_renders_tera_first() {
+        let mut ctx = Context::new();
+        ctx.insert("target", "dist");
+        let resolved = resolve_command_with_shell(
+            &shell("mkdir {{ target }} && touch {{ target }}/.keep"),
+            Some(&ctx),
+            None,
+            &builtin(),
+        )
+        .unwrap();
+        assert_eq!(
+            resolved.to_argv(),
+            vec!["omni", "shell", "-c", "mkdir dist && touch dist/.keep"]
+        );
+    }
+
+    #[test]
+    fn builtin_shell_empty_and_invalid() {
+        let resolved =
+            resolve_command_with_shell(&shell("  "), None, None, &builtin())
+                .unwrap();
+        assert!(resolved.is_empty());
+
+        let err = resolve_command_with_shell(
+            &shell("echo 'unterminated"),
+            None,
+            None,
+            &builtin(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ResolveError::Shell(_)));
+    }
+
     #[test]
     fn to_argv_of_empty_is_empty() {
         let resolved = Command::default();
@@ -35,6 +35,8 @@ pub enum Subsystem {
     Generator,
     /// The tools subsystem (declared for tagging; not yet implemented).
     Tools,
+    /// Task processes spawned by `omni run`.
+    Tasks,
 }
 
 /// The scalar form of a [`SubsystemSelector`]: the `all` wildcard or a single
@@ -50,16 +52,17 @@ pub enum SubsystemScalar {
     All,
     Generator,
     Tools,
+    Tasks,
 }
 
 /// Which subsystems a workspace capability rule applies to.
 ///
 /// Accepts `"all"`, a single subsystem (`"tools"`), or a list
-/// (`["generator", "tools"]`).
+/// (`["generator", "tasks"]`).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum SubsystemSelector {
-    /// `"all"` | `"generator"` | `"tools"`.
+    /// `"all"` | `"generator"` | `"tools"` | `"tasks"`.
     Scalar(SubsystemScalar),
     /// `["generator", "tools"]`.
     List(Vec<Subsystem>),
@@ -84,6 +87,9 @@ impl SubsystemSelector {
             SubsystemSelector::Scalar(SubsystemScalar::Tools) => {
                 subsystem == Subsystem::Tools
             }
+            SubsystemSelector::Scalar(SubsystemScalar::Tasks) => {
+                subsystem == Subsystem::Tasks
+            }
             SubsystemSelector::List(list) => list.contains(&subsystem),
         }
     }
@@ -129,6 +135,7 @@ mod tests {
         let sel = SubsystemSelector::default();
         assert!(sel.includes(Subsystem::Generator));
         assert!(sel.includes(Subsystem::Tools));
+        assert!(sel.includes(Subsystem::Tasks));
     }
 
     #[test]
@@ -145,6 +152,11 @@ mod tests {
             serde_json::from_str(r#"["generator"]"#).unwrap();
         assert!(list.includes(Subsystem::Generator));
         assert!(!list.includes(Subsystem::Tools));
+
+        let tasks: SubsystemSelector =
+            serde_json::from_str(r#""tasks""#).unwrap();
+        assert!(tasks.includes(Subsystem::Tasks));
+        assert!(!tasks.includes(Subsystem::Generator));
     }
 
     #[test]
@@ -9,6 +9,8 @@ mod generator_source_configuration;
 mod meta_configuration;
 mod project_configuration;
 mod remote_cache_configuration;
+mod shell;
+mod task_capabilities;
 mod task_configuration;
 mod task_dependency_configuration;
 mod task_extension;
@@ -26,6 +28,8 @@ pub use meta_configuration::*;
 pub use omni_config_types as types;
 pub use project_configuration::*;
 pub use remote_cache_configuration::*;
+pub use shell::*;
+pub use task_capabilities::*;
 pub use task_configuration::*;
 pub use task_dependency_configuration::*;
 pub use task_extension::*;
@@ -9,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use system_traits::FsReadAsync;
 
 use crate::{
-    CacheConfiguration, MetaConfiguration,
+    CacheConfiguration, CapabilityPolicyConfig, MetaConfiguration, TaskProcess,
     utils::{self, fs::LoadConfigError, list_config_default},
 };
 
@@ -50,6 +50,12 @@ pub struct ProjectConfiguration {
     #[serde(default = "list_config_default::<Replace<String>>")]
     pub dependencies: ListConfig<Replace<String>>,
 
+    /// Free-form labels (e.g. `type:app`, `scope:web`) used to select projects
+    /// with `--tag` and to enforce workspace tag constraints. Omitting it in an
+    /// overriding layer keeps the inherited tags.
+    #[serde(default = "list_config_default::<Replace<String>>")]
+    pub tags: ListConfig<Replace<String>>,
+
     #[serde(default)]
     pub env: ProjectEnvConfiguration,
 
@@ -63,6 +69,16 @@ pub struct ProjectConfiguration {
     #[serde(default)]
     pub meta: MetaConfiguration,
 
+    /// Capability policy applied to every task process in this project.
+    /// Cascades under the workspace rules tagged `tasks`; a task's own
+    /// `capabilities` narrow it further.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::is_unset_capability_policy"
+    )]
+    #[merge(strategy = crate::task_capabilities::merge_capability_policy)]
+    pub capabilities: CapabilityPolicyConfig<TaskProcess>,
+
     #[serde(default)]
     pub tasks: DictConfig<TaskConfiguration>,
 }
@@ -114,6 +130,9 @@ pub struct ProjectEnvConfiguration {
 
 #[cfg(test)]
 mod tests {
+    use omni_command_config::{
+        CommandConfig, ShellMode, resolve_command_with_shell, system_shell,
+    };
     use omni_task_output_logs::{
         LogsDisplay, OutputLogsConfiguration, OutputLogsSplit,
     };
@@ -130,12 +149,14 @@ mod tests {
             name: "base".to_string(),
             description: None,
             dependencies: list_config_default(),
+            tags: list_config_default(),
             env: ProjectEnvConfiguration::default(),
             cache: CacheConfiguration::default(),
             output_logs: Some(OutputLogsConfiguration::Uniform(
                 LogsDisplay::Failed,
             )),
             meta: MetaConfiguration::default(),
+            capabilities: CapabilityPolicyConfig::default(),
             tasks: DictConfig::default(),
         };
 
@@ -147,6 +168,7 @@ mod tests {
             name: "derived".to_string(),
             description: None,
             dependencies: list_config_default(),
+            tags: list_config_default(),
             env: ProjectEnvConfiguration::default(),
             cache: CacheConfiguration::default(),
             output_logs: Some(OutputLogsConfiguration::Split(
@@ -156,6 +178,7 @@ mod tests {
                 },
             )),
             meta: MetaConfiguration::default(),
+            capabilities: CapabilityPolicyConfig::default(),
             tasks: DictConfig::default(),
         };
 
@@ -167,6 +190,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_merge_tags_inherits_when_omitted() {
+        let mut base = serde_json::from_str::<ProjectConfiguration>(
+            r#"{"name": "base", "tags": ["type:app"]}"#,
+        )
+        .expect("valid");
+        let derived = serde_json::from_str::<ProjectConfiguration>(
+            r#"{"name": "derived"}"#,
+        )
+        .expect("valid");
+
+        base.merge(derived);
+
+        assert_eq!(base.tags.to_vec_inner(), vec!["type:app".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_tags_append() {
+        let mut base = serde_json::from_str::<ProjectConfiguration>(
+            r#"{"name": "base", "tags": ["type:app"]}"#,
+        )
+        .expect("valid");
+        let derived = serde_json::from_str::<ProjectConfiguration>(
+            r#"{"name": "derived", "tags": {"append": ["scope:web"]}}"#,
+        )
+        .expect("valid");
+
+        base.merge(derived);
+
+        assert_eq!(
+            base.tags.to_vec_inner(),
+            vec!["type:app".to_string(), "scope:web".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_merge_capabilities_inherits_and_appends() {
+        let mut base = serde_json::from_str::<ProjectConfiguration>(
+            r#"{"name": "base", "capabilities": {"rules": [{"access": "allow", "domain": "fs.write", "patterns": ["@project/**"]}]}}"#,
+        )
+        .expect("valid");
+        let derived = serde_json::from_str::<ProjectConfiguration>(
+            r#"{"name": "derived", "capabilities": {"strictness": "require-floor"}}"#,
+        )
+        .expect("valid");
+
+        base.merge(derived);
+
+        assert_eq!(base.capabilities.rules.len(), 1);
+        assert_eq!(
+            base.capabilities.strictness,
+            crate::CapabilitiesStrictness::RequireFloor
+        );
+    }
+
     #[test]
     fn test_merge_output_logs_none_keeps_base() {
         let mut base = ProjectConfiguration {
@@ -177,12 +255,14 @@ mod tests {
             name: "base".to_string(),
             description: None,
             dependencies: list_config_default(),
+            tags: list_config_default(),
             env: ProjectEnvConfiguration::default(),
             cache: CacheConfiguration::default(),
             output_logs: Some(OutputLogsConfiguration::Uniform(
                 LogsDisplay::All,
             )),
             meta: MetaConfiguration::default(),
+            capabilities: CapabilityPolicyConfig::default(),
             tasks: DictConfig::default(),
         };
 
@@ -332,4 +412,64 @@ mod tests {
 
         assert_eq!(base.extends, Some(SingleOrMany::Many(vec![])));
     }
+
+    #[test]
+    fn test_shipped_presets_run_with_builtin_shell() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut files = std::fs::read_dir(root.join("omni/presets"))
+            .expect("should read presets dir")
+            .map(|e| e.expect("should read preset").path())
+            .collect::<Vec<_>>();
+        files.push(root.join("crates/omni_bench/project.omni.yaml"));
+
+        let builtin = ShellMode::Builtin {
+            interpreter: vec![
+                "omni".to_string(),
+                "shell".to_string(),
+                "-c".to_string(),
+            ],
+        };
+        let system_shell = system_shell();
+
+        for file in files {
+            let format = omni_file_data_serde::ext_to_format(
+                file.extension().unwrap_or_default(),
+            )
+            .expect("should be a config format");
+            let bytes = std::fs::read(&file).expect("should read preset");
+            let project = omni_file_data_serde::from_slice::<
+                ProjectConfiguration,
+            >(&bytes, format)
+            .unwrap_or_else(|e| panic!("{}: {e}", file.display()));
+
+            for (name, task) in project.tasks.as_map() {
+                let commands: Vec<&CommandConfig> = match task {
+                    TaskConfiguration::ShortForm(command) => vec![command],
+                    TaskConfiguration::LongForm(task) => {
+                        [&task.exec, &task.retry_exec]
+                            .into_iter()
+                            .flatten()
+                            .map(|c| &**c)
+                            .collect()
+                    }
+                };
+
+                for command in commands {
+                    let resolved = resolve_command_with_shell(
+                        command, None, None, &builtin,
+                    )
+                    .unwrap_or_else(|e| {
+                        panic!("{}#{name}: {e}", file.display())
+                    });
+
+                    assert_ne!(
+                        resolved.prog.as_ref(),
+                        system_shell.first(),
+                        "{}#{name} needs the system shell",
+                        file.display()
+                    );
+                }
+            }
+        }
+    }
 }
@@ -6,7 +6,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use system_traits::{FsRead, FsReadAsync};
 
-use crate::{LoadConfigError, utils};
+use crate::{LoadConfigError, utils, validators::validate_archive_compression};
 
 #[derive(
     Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq, Merge, Validate,
@@ -31,6 +31,51 @@ pub struct RemoteCacheConfiguration {
 
     #[merge(strategy = config_utils::replace_if_some)]
     pub environment_code: Option<String>,
+
+    /// Codec used to compress the artifacts uploaded to the remote cache.
+    /// Artifacts are decompressed with whatever codec they were written with,
+    /// so changing it doesn't invalidate existing entries.
+    #[serde(default, deserialize_with = "validate_archive_compression")]
+    #[merge(strategy = config_utils::replace)]
+    pub compression: ArchiveCompression,
+
+    /// Base64 encoded key of at least 32 bytes the remote cache artifacts are
+    /// signed with and verified against. When set, artifacts without a valid
+    /// signature are treated as cache misses. Everyone sharing the remote
+    /// cache needs the same key.
+    #[serde(default)]
+    #[merge(strategy = config_utils::replace_if_some)]
+    pub signing_key: Option<String>,
+}
+
+#[derive(
+    Deserialize, Serialize, JsonSchema, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(tag = "codec", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ArchiveCompression {
+    /// Gzip, readable by every omni version. `level` ranges from 0 to 9 and
+    /// defaults to 6.
+    Gzip {
+        #[serde(default)]
+        level: Option<u32>,
+    },
+    /// Zstandard, faster and smaller than gzip on typical build outputs.
+    /// `level` ranges from 1 to 22.
+    Zstd {
+        #[serde(default = "default_zstd_level")]
+        level: i32,
+    },
+}
+
+impl Default for ArchiveCompression {
+    fn default() -> Self {
+        Self::Gzip { level: None }
+    }
+}
+
+#[inline(always)]
+fn default_zstd_level() -> i32 {
+    3
 }
 
 impl RemoteCacheConfiguration {
@@ -50,3 +95,87 @@ impl RemoteCacheConfiguration {
         utils::fs::load_config(path, sys)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_defaults_to_gzip() {
+        let config: RemoteCacheConfiguration = serde_json::from_str(
+            r#"{
+                "api_key": "key",
+                "api_base_url": "http://localhost",
+                "tenant_code": "tenant",
+                "organization_code": "org",
+                "workspace_code": "ws"
+            }"#,
+        )
+        .expect("should parse");
+
+        assert_eq!(
+            config.compression,
+            ArchiveCompression::Gzip { level: None }
+        );
+    }
+
+    #[test]
+    fn test_compression_zstd_level() {
+        let compression: ArchiveCompression =
+            serde_json::from_str(r#"{ "codec": "zstd" }"#)
+                .expect("should parse");
+        assert_eq!(compression, ArchiveCompression::Zstd { level: 3 });
+
+        let compression: ArchiveCompression =
+            serde_json::from_str(r#"{ "codec": "zstd", "level": 19 }"#)
+                .expect("should parse");
+        assert_eq!(compression, ArchiveCompression::Zstd { level: 19 });
+    }
+
+    fn config_with_compression(
+        compression: &str,
+    ) -> Result<RemoteCacheConfiguration, serde_json::Error> {
+        serde_json::from_str(&format!(
+            r#"{{
+                "api_key": "key",
+                "api_base_url": "http://localhost",
+                "tenant_code": "tenant",
+                "organization_code": "org",
+                "workspace_code": "ws",
+                "compression": {compression}
+            }}"#
+        ))
+    }
+
+    #[test]
+    fn test_compression_level_in_range() {
+        let config =
+            config_with_compression(r#"{ "codec": "gzip", "level": 9 }"#)
+                .expect("should parse");
+        assert_eq!(
+            config.compression,
+            ArchiveCompression::Gzip { level: Some(9) }
+        );
+
+        let config =
+            config_with_compression(r#"{ "codec": "zstd", "level": 22 }"#)
+                .expect("should parse");
+        assert_eq!(config.compression, ArchiveCompression::Zstd { level: 22 });
+    }
+
+    #[test]
+    fn test_compression_level_out_of_range() {
+        for compression in [
+            r#"{ "codec": "gzip", "level": 10 }"#,
+            r#"{ "codec": "zstd", "level": 0 }"#,
+            r#"{ "codec": "zstd", "level": 23 }"#,
+        ] {
+            let error = config_with_compression(compression)
+                .expect_err("should reject the level");
+            assert!(
+                error.to_string().contains("compression level"),
+                "unexpected error: {error}"
+            );
+        }
+    }
+}
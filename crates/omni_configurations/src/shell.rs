@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIs, VariantArray};
+
+/// How shell-style (string) task commands are run.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+    VariantArray,
+    Serialize,
+    Deserialize,
+    Default,
+    JsonSchema,
+    PartialOrd,
+    Ord,
+    EnumIs,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Shell {
+    /// Run with omni's built-in cross-platform shell, which supports `&&`,
+    /// `||`, `;`, pipes, redirections, globs, `VAR=value` prefixes,
+    /// `${VAR:-default}` expansions and `cd`. Commands using other shell
+    /// syntax, such as subshells, are run with the system shell.
+    #[default]
+    #[strum(serialize = "builtin")]
+    Builtin,
+    /// Split the command into arguments with `shlex` and spawn it directly.
+    /// Shell operators are passed to the program as literal arguments.
+    #[strum(serialize = "shlex")]
+    Shlex,
+}
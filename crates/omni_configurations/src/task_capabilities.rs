@@ -0,0 +1,115 @@
+//! The **task** capability profile.
+//!
+//! This wires `omni run` task processes into `omni_capabilities` by
+//! implementing [`CapabilityProfile`] on the [`TaskProcess`] marker. A task is
+//! a native process, not a bridge script: there is no in-process broker or
+//! script shim to mediate it, so only the domains an OS sandbox can confine on
+//! its own are expressible — the filesystem (Landlock path rules) and outbound
+//! TCP connects (the Landlock connect floor). The policy cascade is workspace
+//! (rules tagged `tasks`) -> project -> task.
+
+use omni_capabilities::{
+    CapabilityDomain, CapabilityFloors, CapabilityProfile, NoExtra,
+};
+
+// Re-exported so the `omni_configurations::CapabilitiesStrictness` /
+// `CapabilityPolicyConfig` paths resolve without depending on
+// `omni_capabilities` directly.
+pub use omni_capabilities::{CapabilitiesStrictness, CapabilityPolicyConfig};
+
+/// Capability-policy marker for task processes.
+///
+/// Projects and tasks declare their policy as `CapabilityRules<TaskProcess>`.
+/// Like tools, tasks have no per-entry selector and an empty evaluation
+/// context.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskProcess;
+
+impl CapabilityProfile for TaskProcess {
+    const SUPPORTED: &'static [CapabilityDomain] = &[
+        CapabilityDomain::FsRead,
+        CapabilityDomain::FsWrite,
+        CapabilityDomain::Net,
+    ];
+    const NAME: &'static str = "task";
+
+    type AppliesTo = NoExtra;
+    type Extra = NoExtra;
+    type Context = ();
+}
+
+// Tasks are only confined when some level declares capabilities, so the
+// default floor chains are never the sole level; the shared defaults apply.
+impl CapabilityFloors for TaskProcess {}
+
+/// Whether a policy declares nothing (no rules and the default stance), so
+/// it can be omitted when serializing and skipped when confining.
+pub fn is_unset_capability_policy(
+    policy: &CapabilityPolicyConfig<TaskProcess>,
+) -> bool {
+    policy.rules.is_empty()
+        && policy.strictness == CapabilitiesStrictness::default()
+}
+
+/// Combine a deeper level's policy into an inherited one for configuration
+/// inheritance (`extends` and overriding layers): the rules are appended and
+/// the most-severe `strictness` wins. An overriding layer therefore can only
+/// add rules, never drop inherited ones.
+pub(crate) fn merge_capability_policy(
+    base: &mut CapabilityPolicyConfig<TaskProcess>,
+    other: CapabilityPolicyConfig<TaskProcess>,
+) {
+    merge::Merge::merge(&mut base.rules, other.rules);
+    base.strictness = base.strictness.max(other.strictness);
+}
+
+#[cfg(test)]
+mod tests {
+    use omni_capabilities::{CapabilityRules, validate};
+
+    use super::*;
+
+    fn parse(json: &str) -> CapabilityRules<TaskProcess> {
+        serde_json::from_str(json).expect("valid task capabilities")
+    }
+
+    #[test]
+    fn only_sandboxable_domains_are_supported() {
+        for domain in ["fs.read", "fs.write", "net"] {
+            let cfg = parse(&format!(
+                r#"[{{ "access": "allow", "domain": "{domain}", "patterns": ["**"] }}]"#
+            ));
+            validate(&cfg).unwrap_or_else(|_| {
+                panic!("{domain} is a supported task domain")
+            });
+        }
+
+        for domain in ["process", "env"] {
+            let cfg = parse(&format!(
+                r#"[{{ "access": "allow", "domain": "{domain}", "patterns": ["*"] }}]"#
+            ));
+            assert!(
+                validate(&cfg).is_err(),
+                "{domain} cannot be confined for a native task process"
+            );
+        }
+    }
+
+    #[test]
+    fn merge_appends_rules_and_keeps_the_strictest_stance() {
+        let mut base: CapabilityPolicyConfig<TaskProcess> =
+            serde_json::from_str(
+                r#"{ "rules": [{ "access": "allow", "domain": "fs.write", "patterns": ["@project/**"] }], "strictness": "require-floor" }"#,
+            )
+            .unwrap();
+        let other: CapabilityPolicyConfig<TaskProcess> = serde_json::from_str(
+            r#"{ "rules": [{ "access": "allow", "domain": "net", "patterns": ["*:443"] }] }"#,
+        )
+        .unwrap();
+
+        merge_capability_policy(&mut base, other);
+
+        assert_eq!(base.rules.len(), 2);
+        assert_eq!(base.strictness, CapabilitiesStrictness::RequireFloor);
+    }
+}
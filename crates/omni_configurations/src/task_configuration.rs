@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use config_utils::{DictConfig, DynValue, IntoInner, ListConfig, Replace};
+use config_utils::{
+    AsInner as _, DictConfig, DynValue, IntoInner, ListConfig, Replace,
+};
 use garde::Validate;
 use merge::Merge;
 use omni_command_config::CommandConfig;
@@ -10,7 +12,9 @@ use omni_task_output_logs::OutputLogsConfiguration;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{CacheConfiguration, MetaConfiguration};
+use crate::{
+    CacheConfiguration, CapabilityPolicyConfig, MetaConfiguration, TaskProcess,
+};
 
 use super::TaskDependencyConfiguration;
 
@@ -88,14 +92,39 @@ pub struct TaskConfigurationLongForm {
 
     #[serde(
         default,
-        with = "retry_interval",
+        with = "humantime_duration",
         skip_serializing_if = "Option::is_none"
     )]
     #[schemars(with = "Option<Replace<String>>")]
     pub retry_interval: Option<Replace<Duration>>,
+
+    #[serde(
+        default,
+        with = "humantime_duration",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(with = "Option<Replace<String>>")]
+    pub timeout: Option<Replace<Duration>>,
+
+    /// Scheduling priority of the task. When several tasks are ready to run,
+    /// the ones with a higher priority start first; tasks with the same
+    /// priority start by the estimated duration of their remaining critical
+    /// path, longest first. Defaults to `0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Replace<i32>>,
+
+    /// Capability policy confining the task process (filesystem and outbound
+    /// network access). Cascades under the workspace rules tagged `tasks` and
+    /// the project's policy; only enforced when the `capabilities`
+    /// experimental feature is enabled.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::is_unset_capability_policy"
+    )]
+    pub capabilities: CapabilityPolicyConfig<TaskProcess>,
 }
 
-mod retry_interval {
+mod humantime_duration {
     use std::time::Duration;
 
     use config_utils::{AsInner, Replace};
@@ -170,6 +199,9 @@ impl Default for TaskConfigurationLongForm {
             with: ListConfig::append(vec![]),
             max_retries: None,
             retry_interval: None,
+            timeout: None,
+            priority: None,
+            capabilities: CapabilityPolicyConfig::default(),
         }
     }
 }
@@ -254,6 +286,7 @@ impl TaskConfigurationLongForm {
             with,
             max_retries: retries,
             retry_interval,
+            timeout,
             ..
         } = self;
 
@@ -268,6 +301,7 @@ impl TaskConfigurationLongForm {
             with.iter().cloned().map(Into::into).collect(),
             retries.map(|e| e.into_inner()),
             retry_interval.map(|e| e.into_inner()),
+            timeout.map(|e| e.into_inner()),
         )
     }
 }
@@ -322,6 +356,16 @@ impl TaskConfiguration {
         }
     }
 
+    pub fn priority(&self) -> Option<i32> {
+        match self {
+            TaskConfiguration::ShortForm(_) => None,
+            TaskConfiguration::LongForm(box TaskConfigurationLongForm {
+                priority,
+                ..
+            }) => priority.as_ref().map(|p| *p.as_inner()),
+        }
+    }
+
     pub fn args(&self) -> Option<&DictConfig<DynValue>> {
         match self {
             TaskConfiguration::ShortForm(_) => None,
@@ -351,6 +395,16 @@ impl TaskConfiguration {
             }) => Some(meta),
         }
     }
+
+    pub fn capabilities(&self) -> Option<&CapabilityPolicyConfig<TaskProcess>> {
+        match self {
+            TaskConfiguration::ShortForm(_) => None,
+            TaskConfiguration::LongForm(box TaskConfigurationLongForm {
+                capabilities,
+                ..
+            }) => Some(capabilities),
+        }
+    }
 }
 
 impl Merge for TaskConfigurationLongForm {
@@ -372,7 +426,10 @@ impl Merge for TaskConfigurationLongForm {
             with: b_with,
             max_retries: b_retries,
             retry_interval: b_retry_interval,
+            timeout: b_timeout,
+            priority: b_priority,
             args: b_args,
+            capabilities: b_capabilities,
         } = other;
 
         // `base` is a per-declaration structural marker ("is this block a
@@ -402,6 +459,12 @@ impl Merge for TaskConfigurationLongForm {
         self.with.merge(b_with);
         merge::option::recurse(&mut self.max_retries, b_retries);
         merge::option::recurse(&mut self.retry_interval, b_retry_interval);
+        merge::option::recurse(&mut self.timeout, b_timeout);
+        merge::option::recurse(&mut self.priority, b_priority);
+        crate::task_capabilities::merge_capability_policy(
+            &mut self.capabilities,
+            b_capabilities,
+        );
     }
 }
 
@@ -868,4 +931,66 @@ mod tests {
         };
         assert_eq!(long.extends, Some(SingleOrMany::Single("y".to_string())));
     }
+
+    #[test]
+    fn test_timeout_parses_humantime_and_lowers_into_task() {
+        let config: TaskConfiguration =
+            serde_json::from_str(r#"{"exec":"cargo test","timeout":"1m 30s"}"#)
+                .unwrap();
+
+        let task = config.get_task("test");
+
+        assert_eq!(task.timeout, Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn test_merge_timeout_is_inherited_when_override_omits_it() {
+        let mut a = TaskConfiguration::long_form(TaskConfigurationLongForm {
+            timeout: Some(Replace::new(Duration::from_secs(60))),
+            ..Default::default()
+        });
+
+        let b = TaskConfiguration::long_form(TaskConfigurationLongForm {
+            exec: Some(Replace::new(CommandConfig::Shell("b".to_string()))),
+            ..Default::default()
+        });
+
+        a.merge(b);
+
+        assert_eq!(a.get_task("x").timeout, Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_merge_priority_is_inherited_when_override_omits_it() {
+        let mut a: TaskConfiguration =
+            serde_json::from_str(r#"{"exec":"a","priority":10}"#).unwrap();
+        let b: TaskConfiguration =
+            serde_json::from_str(r#"{"exec":"b"}"#).unwrap();
+
+        a.merge(b);
+
+        assert_eq!(a.priority(), Some(10));
+        assert_eq!(TaskConfiguration::short_form("a").priority(), None);
+    }
+
+    #[test]
+    fn test_merge_capabilities_appends_rules() {
+        let mut a: TaskConfiguration = serde_json::from_str(
+            r#"{"exec":"a","capabilities":{"rules":[{"access":"allow","domain":"fs.write","patterns":["@project/**"]}]}}"#,
+        )
+        .unwrap();
+        let b: TaskConfiguration = serde_json::from_str(
+            r#"{"capabilities":{"rules":[{"access":"allow","domain":"net","patterns":["*:443"]}],"strictness":"require-floor"}}"#,
+        )
+        .unwrap();
+
+        a.merge(b);
+
+        let capabilities = a.capabilities().expect("long form");
+        assert_eq!(capabilities.rules.len(), 2);
+        assert_eq!(
+            capabilities.strictness,
+            crate::CapabilitiesStrictness::RequireFloor
+        );
+    }
 }
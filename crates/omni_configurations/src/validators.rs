@@ -4,7 +4,9 @@ use lazy_regex::{Lazy, Regex, regex};
 use serde_validate::{StaticValidator, declare_static_validator};
 use sets::unordered_set;
 
-use crate::{GeneratorSourceConfiguration, ToolSourceConfiguration};
+use crate::{
+    ArchiveCompression, GeneratorSourceConfiguration, ToolSourceConfiguration,
+};
 
 #[derive(Debug, Clone, Copy, Default)]
 struct GeneratorSourcesValidator;
@@ -106,3 +108,35 @@ declare_static_validator!(
     validate_source_name,
     option_validate_source_name
 );
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ArchiveCompressionValidator;
+
+impl<T: Borrow<ArchiveCompression>> StaticValidator<T>
+    for ArchiveCompressionValidator
+{
+    fn validate_static(value: &T) -> Result<(), String> {
+        match *value.borrow() {
+            ArchiveCompression::Gzip { level: Some(level) } if level > 9 => {
+                Err(format!(
+                    "Invalid gzip compression level: {level}\nGzip compression level should be between 0 and 9"
+                ))
+            }
+            ArchiveCompression::Zstd { level }
+                if !(1..=22).contains(&level) =>
+            {
+                Err(format!(
+                    "Invalid zstd compression level: {level}\nZstd compression level should be between 1 and 22"
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+declare_static_validator!(
+    ArchiveCompressionValidator,
+    ArchiveCompression,
+    validate_archive_compression,
+    option_validate_archive_compression,
+);
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use system_traits::{FsRead, FsReadAsync};
 
 use crate::{
-    GeneratorSourceConfiguration, ToolSourceConfiguration, Ui,
+    GeneratorSourceConfiguration, Shell, ToolSourceConfiguration, Ui,
     constants::WORKSPACE_NAME_REGEX,
     utils::{self, fs::LoadConfigError},
 };
@@ -33,6 +33,12 @@ pub struct WorkspaceConfiguration {
     #[serde(default)]
     pub ui: Ui,
 
+    /// How shell-style task commands are run: `builtin` (the default) uses
+    /// omni's cross-platform shell, `shlex` only splits the command into
+    /// arguments.
+    #[serde(default)]
+    pub shell: Shell,
+
     #[serde(default, deserialize_with = "validate_generator_sources")]
     pub generators: Vec<GeneratorSourceConfiguration>,
 
@@ -45,6 +51,12 @@ pub struct WorkspaceConfiguration {
     #[serde(default)]
     pub env: WorkspaceEnvConfiguration,
 
+    /// Tag-based dependency rules checked when the project graph is built,
+    /// e.g. projects tagged `type:app` may not depend on other `type:app`
+    /// projects.
+    #[serde(default)]
+    pub constraints: Vec<omni_core::TagConstraint>,
+
     /// Opts this workspace into experimental / in-progress features.
     ///
     /// Accepts either a bare boolean (enable or disable *every* experimental
@@ -154,6 +166,22 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_constraints_parse() {
+        let cfg = serde_json::from_str::<WorkspaceConfiguration>(
+            r#"{"projects": [], "constraints": [{"tag": "type:app", "not_depend_on": ["type:app"]}]}"#,
+        )
+        .expect("valid");
+        assert_eq!(
+            cfg.constraints,
+            vec![omni_core::TagConstraint {
+                tag: "type:app".to_string(),
+                only_depend_on: vec![],
+                not_depend_on: vec!["type:app".to_string()],
+            }]
+        );
+    }
+
     #[test]
     fn test_capabilities_default_to_empty() {
         let cfg = serde_json::from_str::<WorkspaceConfiguration>(
@@ -93,9 +93,28 @@ impl<TSys: ContextSys> Context<TSys> {
 
         Ok(context)
     }
+
+    /// Create the context again with the same arguments, picking up changes
+    /// to the workspace and remote cache configuration files.
+    #[allow(clippy::result_large_err)]
+    pub fn reloaded(&self) -> Result<Self, ContextError> {
+        Self::new(
+            self.sys.clone(),
+            self.env.clone(),
+            &self.root_dir,
+            self.inherit_env_vars,
+            &self.env_root_dir_marker,
+            self.override_env_files.clone(),
+            &self.tracing_config,
+        )
+    }
 }
 
 impl<TSys: ContextSys> Context<TSys> {
+    pub fn env(&self) -> &str {
+        &self.env
+    }
+
     pub fn tracing_config(&self) -> &TracingConfig {
         &self.tracing_config
     }
@@ -304,6 +323,8 @@ impl<TSys: ContextSys> Context<TSys> {
                 rc.organization_code.as_str(),
                 rc.workspace_code.as_str(),
                 rc.environment_code.clone(),
+                rc.compression,
+                rc.signing_key.clone(),
             ))
         } else {
             RemoteConfig::new_disabled()
@@ -239,6 +239,7 @@ mod tests {
             vec![],
             None,
             None,
+            None,
         )
     }
 
@@ -259,6 +260,9 @@ mod tests {
             Default::default(),
             Default::default(),
             Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
         )
     }
 
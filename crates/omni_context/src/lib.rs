@@ -24,5 +24,6 @@ pub use context::*;
 pub use env_loader::*;
 pub use loaded_context::*;
 pub use maybe_loaded::*;
+pub use project_hasher::TaskInputs;
 pub use sys::*;
 pub use utils::{EnvVarsMap, EnvVarsOsMap};
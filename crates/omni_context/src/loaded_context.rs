@@ -9,7 +9,8 @@ use env::{
 };
 use env_loader::EnvLoaderError;
 use omni_configurations::{
-    MetaConfiguration, RemoteCacheConfiguration, WorkspaceConfiguration,
+    CapabilityPolicyConfig, MetaConfiguration, RemoteCacheConfiguration,
+    TaskProcess, WorkspaceConfiguration,
 };
 use omni_core::{Project, ProjectGraph, ProjectGraphError, TaskExecutionNode};
 use omni_execution_plan::DefaultExecutionPlanProvider;
@@ -24,7 +25,7 @@ use system_traits::impls::RealSys;
 use crate::{
     Context, ContextSys, EnvLoader, GetVarsArgs,
     project_data_extractor::ProjectDataExtractions,
-    project_hasher::{ProjectHasher, ProjectHasherError},
+    project_hasher::{ProjectHasher, ProjectHasherError, TaskInputs},
     project_query::ProjectQuery,
     utils::{EnvVarsMap, vars_os},
     workspace_hasher::{WorkspaceHasher, WorkspaceHasherError},
@@ -137,6 +138,41 @@ impl<TSys: ContextSys> LoadedContext<TSys> {
         self.extracted.project_meta_configs.get(project_name)
     }
 
+    /// The scheduling priority of the task, if it declares one.
+    pub fn get_task_priority(
+        &self,
+        project_name: &str,
+        task_name: &str,
+    ) -> Option<i32> {
+        self.extracted
+            .task_priorities
+            .get(&format!("{project_name}#{task_name}"))
+            .copied()
+    }
+
+    /// The project-level task capability policy, if the project declares one.
+    pub fn get_project_capabilities(
+        &self,
+        project_name: &str,
+    ) -> Option<&CapabilityPolicyConfig<TaskProcess>> {
+        self.extracted
+            .project_capabilities_configs
+            .get(project_name)
+    }
+
+    /// The task's own capability policy, if it declares one. This is a
+    /// separate level from [`Self::get_project_capabilities`]: the two
+    /// cascade by attenuation when the task sandbox is planned.
+    pub fn get_task_capabilities(
+        &self,
+        project_name: &str,
+        task_name: &str,
+    ) -> Option<&CapabilityPolicyConfig<TaskProcess>> {
+        self.extracted
+            .task_capabilities_configs
+            .get(&format!("{project_name}#{task_name}"))
+    }
+
     pub async fn get_workspace_hash(
         &self,
     ) -> Result<DefaultHash, LoadedContextError> {
@@ -183,6 +219,27 @@ impl<TSys: ContextSys> LoadedContext<TSys> {
             .await?)
     }
 
+    /// Computes the current digest of a task along with a manifest of its
+    /// inputs.
+    pub async fn get_task_inputs(
+        &self,
+        project_name: &str,
+        task_name: &str,
+    ) -> Result<TaskInputs, LoadedContextError> {
+        let cache_dir = self.cache_dir();
+        let hasher = self.get_project_hasher(&cache_dir)?;
+        let execution_plan_provider = self.get_execution_plan_provider();
+
+        Ok(hasher
+            .task_inputs(
+                project_name,
+                task_name,
+                self,
+                &execution_plan_provider,
+            )
+            .await?)
+    }
+
     pub async fn get_project_hash_string(
         &self,
         project_name: &str,
@@ -208,7 +265,10 @@ impl<TSys: ContextSys> LoadedContext<TSys> {
     ) -> Result<ProjectGraph, LoadedContextError> {
         let projects = self.projects().to_vec();
 
-        Ok(ProjectGraph::from_projects(projects)?)
+        Ok(ProjectGraph::from_projects_with_constraints(
+            projects,
+            &self.workspace_configuration().constraints,
+        )?)
     }
 
     pub fn get_env_vars(
@@ -9,7 +9,8 @@ use env_loader::EnvLoaderError;
 use maps::{Map, UnorderedMap};
 use merge::Merge as _;
 use omni_configurations::{
-    MetaConfiguration, ProjectConfiguration, WorkspaceConfiguration,
+    CapabilityPolicyConfig, MetaConfiguration, ProjectConfiguration,
+    TaskProcess, WorkspaceConfiguration, is_unset_capability_policy,
 };
 use omni_core::{
     ExtensionGraph, ExtensionGraphError, ExtensionGraphNode as _, Project, Task,
@@ -80,6 +81,9 @@ impl<'a, TSys: EnvCacheSys> ProjectDataExtractor<'a, TSys> {
         let mut task_env_var_overrides = maps::unordered_map![];
         let mut cache_infos = maps::unordered_map![];
         let mut output_logs_configs = maps::unordered_map![];
+        let mut project_capabilities_configs = maps::unordered_map![];
+        let mut task_capabilities_configs = maps::unordered_map![];
+        let mut task_priorities = maps::unordered_map![];
 
         let project_paths = project_paths
             .iter()
@@ -156,6 +160,15 @@ impl<'a, TSys: EnvCacheSys> ProjectDataExtractor<'a, TSys> {
             project_meta_configs
                 .insert(project_config.name.clone(), project_meta.clone());
 
+            // Capability levels are kept separate (not pre-merged) because they
+            // cascade by attenuation at plan time, not by config inheritance.
+            if !is_unset_capability_policy(&project_config.capabilities) {
+                project_capabilities_configs.insert(
+                    project_config.name.clone(),
+                    project_config.capabilities.clone(),
+                );
+            }
+
             for (name, task) in project_config.tasks.iter() {
                 let full_task_name =
                     format!("{}#{}", project_config.name, name);
@@ -166,6 +179,17 @@ impl<'a, TSys: EnvCacheSys> ProjectDataExtractor<'a, TSys> {
                         .insert(full_task_name.clone(), vars.to_map_to_inner());
                 }
 
+                if let Some(capabilities) = task.capabilities()
+                    && !is_unset_capability_policy(capabilities)
+                {
+                    task_capabilities_configs
+                        .insert(full_task_name.clone(), capabilities.clone());
+                }
+
+                if let Some(priority) = task.priority() {
+                    task_priorities.insert(full_task_name.clone(), priority);
+                }
+
                 let task_cache = task.cache();
                 let task_output_logs = task.output_logs();
 
@@ -248,7 +272,7 @@ impl<'a, TSys: EnvCacheSys> ProjectDataExtractor<'a, TSys> {
                 task_meta_configs.insert(full_task_name, task_meta);
             }
 
-            projects.push(Project::new(
+            let mut project = Project::new(
                 project_config.name.clone(),
                 dir.to_path_buf(),
                 project_config.dependencies.to_vec_inner(),
@@ -261,7 +285,10 @@ impl<'a, TSys: EnvCacheSys> ProjectDataExtractor<'a, TSys> {
                         (task_name.clone(), mapped)
                     })
                     .collect(),
-            ));
+            );
+            project.tags = project_config.tags.to_vec_inner();
+
+            projects.push(project);
         }
 
         Ok(ProjectDataExtractions::new(
@@ -271,6 +298,9 @@ impl<'a, TSys: EnvCacheSys> ProjectDataExtractor<'a, TSys> {
             project_meta_configs,
             task_meta_configs,
             output_logs_configs,
+            project_capabilities_configs,
+            task_capabilities_configs,
+            task_priorities,
         ))
     }
 }
@@ -283,6 +313,14 @@ pub struct ProjectDataExtractions {
     pub project_meta_configs: UnorderedMap<String, MetaConfiguration>,
     pub task_meta_configs: UnorderedMap<String, MetaConfiguration>,
     pub output_logs_configs: UnorderedMap<String, OutputLogsConfiguration>,
+    #[serde(default)]
+    pub project_capabilities_configs:
+        UnorderedMap<String, CapabilityPolicyConfig<TaskProcess>>,
+    #[serde(default)]
+    pub task_capabilities_configs:
+        UnorderedMap<String, CapabilityPolicyConfig<TaskProcess>>,
+    #[serde(default)]
+    pub task_priorities: UnorderedMap<String, i32>,
 }
 
 #[derive(Debug, thiserror::Error)]
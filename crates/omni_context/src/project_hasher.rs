@@ -2,8 +2,11 @@ use std::{collections::HashSet, path::Path, sync::Arc};
 
 use derive_new::new;
 use maps::{Map, UnorderedMap, hash::HashMapExt};
-use omni_collector::{CollectConfig, Collector, ProjectTaskInfo};
+use omni_collector::{
+    CollectConfig, Collector, InputManifest, ProjectTaskInfo,
+};
 use omni_command_config::CommandConfig;
+use omni_core::BatchedExecutionPlan;
 use omni_execution_plan::{Call, ExecutionPlanProvider};
 use omni_hasher::{
     Hasher as _,
@@ -16,6 +19,21 @@ use strum::{EnumDiscriminants, EnumIs, IntoDiscriminant as _};
 
 use crate::{ContextSys, LoadedContext, LoadedContextError};
 
+/// The digest of a task and the inputs that produced it.
+#[derive(Debug, Clone)]
+pub struct TaskInputs {
+    pub digest: DefaultHash,
+    pub manifest: InputManifest,
+}
+
+#[derive(Debug, Clone)]
+struct DigestInfo {
+    project_name: String,
+    task_name: String,
+    digest: DefaultHash,
+    manifest: Option<InputManifest>,
+}
+
 #[derive(Debug, Clone, new)]
 pub struct ProjectHasher<'a, TSys: ContextSys> {
     root_dir: &'a Path,
@@ -32,8 +50,6 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
         context: &LoadedContext<TSys>,
         execution_plan_provider: &T,
     ) -> Result<DefaultHash, ProjectHasherError> {
-        let projects = context.projects();
-
         let seed =
             DefaultHasher::hash(seed.unwrap_or("DEFAULT_SEED").as_bytes());
         let call = Call::new_tasks(if task_names.is_empty() {
@@ -49,6 +65,7 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
                 &[],
                 None,
                 None,
+                None,
                 false,
                 false,
             )
@@ -58,6 +75,108 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
                 )
             })?;
 
+        let task_result_digests =
+            self.collect_digests(&plan, context, false).await?;
+
+        let mut hash = Hash::<DefaultHasher>::new(seed);
+
+        let task_matcher = build_glob_set(task_names)?;
+
+        let mut digests = task_result_digests
+            .values()
+            .filter(|d| {
+                d.project_name == project_name
+                    && if !task_matcher.is_empty() {
+                        task_matcher.is_match(&d.task_name)
+                    } else {
+                        true
+                    }
+            })
+            .collect::<Vec<_>>();
+
+        digests.sort_by(|a, b| a.task_name.cmp(&b.task_name));
+
+        for d in digests {
+            hash.combine_in_place(d.digest);
+        }
+
+        Ok(hash.to_inner())
+    }
+
+    /// Computes the digest of a single task along with the manifest of the
+    /// inputs that went into it.
+    pub async fn task_inputs<T: ExecutionPlanProvider>(
+        &self,
+        project_name: &str,
+        task_name: &str,
+        context: &LoadedContext<TSys>,
+        execution_plan_provider: &T,
+    ) -> Result<TaskInputs, ProjectHasherError> {
+        let plan = execution_plan_provider
+            .get_execution_plan(
+                &Call::new_tasks(vec![task_name.to_string()]),
+                &[project_name],
+                &[],
+                None,
+                None,
+                None,
+                false,
+                false,
+            )
+            .map_err(|e| {
+                ProjectHasherErrorInner::ExecutionPlanProvider(
+                    eyre::Report::new(e),
+                )
+            })?;
+
+        let mut task_result_digests =
+            self.collect_digests(&plan, context, true).await?;
+
+        let info = task_result_digests
+            .remove(&format!("{project_name}#{task_name}"))
+            .ok_or_else(|| {
+                ProjectHasherErrorInner::TaskNotFound(
+                    project_name.to_owned(),
+                    task_name.to_owned(),
+                )
+            })?;
+
+        Ok(TaskInputs {
+            digest: info.digest,
+            manifest: info.manifest.expect("should have value"),
+        })
+    }
+
+    pub async fn hash_string<T: ExecutionPlanProvider>(
+        &self,
+        project_name: &str,
+        task_names: &[&str],
+        seed: Option<&str>,
+        context: &LoadedContext<TSys>,
+        execution_plan_provider: &T,
+    ) -> Result<String, ProjectHasherError> {
+        Ok(bs58::encode(
+            self.hash(
+                project_name,
+                task_names,
+                seed,
+                context,
+                execution_plan_provider,
+            )
+            .await?
+            .as_ref(),
+        )
+        .into_string())
+    }
+
+    async fn collect_digests(
+        &self,
+        plan: &BatchedExecutionPlan,
+        context: &LoadedContext<TSys>,
+        with_manifests: bool,
+    ) -> Result<UnorderedMap<String, DigestInfo>, ProjectHasherError> {
+        let projects = context.projects();
+
         struct ProjectTaskInfoTmp<'a> {
             project_name: &'a str,
             project_dir: &'a Path,
@@ -67,18 +186,11 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
             input_files: &'a [OmniPath],
             output_files: &'a [OmniPath],
             env_vars: Arc<Map<String, String>>,
-            dependency_digests: Vec<DefaultHash>,
+            dependencies: Map<String, DefaultHash>,
             input_env_keys: &'a [String],
             args: &'a Map<String, serde_json::Value>,
         }
 
-        #[derive(Debug, Clone)]
-        struct DigestInfo {
-            project_name: String,
-            task_name: String,
-            digest: DefaultHash,
-        }
-
         let mut task_result_digests = UnorderedMap::new();
 
         for batch in plan.iter() {
@@ -132,15 +244,15 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
                         .and_then(|t| t.retry_exec.as_ref()),
                     output_files: &ci.cache_output_files,
                     input_files: &ci.key_input_files,
-                    dependency_digests: task
+                    dependencies: task
                         .dependencies()
                         .iter()
                         .filter_map(|d| {
                             task_result_digests
                                 .get(d)
-                                .map(|x: &DigestInfo| x.digest)
+                                .map(|x: &DigestInfo| (d.clone(), x.digest))
                         })
-                        .collect::<Vec<_>>(),
+                        .collect::<Map<_, _>>(),
                     env_vars,
                     input_env_keys: &ci.key_env_keys,
                     args: &ci.args,
@@ -157,7 +269,7 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
                     task_retry_exec: p.task_retry_exec,
                     input_files: p.input_files,
                     output_files: p.output_files,
-                    dependency_digests: &p.dependency_digests,
+                    dependency_digests: &p.dependencies,
                     env_vars: &p.env_vars,
                     input_env_keys: p.input_env_keys,
                     args: p.args,
@@ -173,6 +285,7 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
                             output_files: false,
                             digests: true,
                             cache_output_dirs: false,
+                            manifests: with_manifests,
                         },
                     )
                     .await?;
@@ -186,56 +299,13 @@ impl<'a, TSys: ContextSys> ProjectHasher<'a, TSys> {
                         digest: c.digest.expect("should have value"),
                         project_name: c.task.project_name.to_string(),
                         task_name: c.task.task_name.to_string(),
+                        manifest: c.manifest,
                     },
                 );
             }
         }
 
-        let mut hash = Hash::<DefaultHasher>::new(seed);
-
-        let task_matcher = build_glob_set(task_names)?;
-
-        let mut digests = task_result_digests
-            .values()
-            .filter(|d| {
-                d.project_name == project_name
-                    && if !task_matcher.is_empty() {
-                        task_matcher.is_match(&d.task_name)
-                    } else {
-                        true
-                    }
-            })
-            .collect::<Vec<_>>();
-
-        digests.sort_by(|a, b| a.task_name.cmp(&b.task_name));
-
-        for d in digests {
-            hash.combine_in_place(d.digest);
-        }
-
-        Ok(hash.to_inner())
-    }
-
-    pub async fn hash_string<T: ExecutionPlanProvider>(
-        &self,
-        project_name: &str,
-        task_names: &[&str],
-        seed: Option<&str>,
-        context: &LoadedContext<TSys>,
-        execution_plan_provider: &T,
-    ) -> Result<String, ProjectHasherError> {
-        Ok(bs58::encode(
-            self.hash(
-                project_name,
-                task_names,
-                seed,
-                context,
-                execution_plan_provider,
-            )
-            .await?
-            .as_ref(),
-        )
-        .into_string())
+        Ok(task_result_digests)
     }
 }
 
@@ -276,6 +346,9 @@ pub(crate) enum ProjectHasherErrorInner {
     #[error("Cache info not found for project `{0}` and task `{1}`")]
     CacheInfoNotFound(String, String),
 
+    #[error("Task `{1}` not found in project `{0}`")]
+    TaskNotFound(String, String),
+
     #[error("Task env vars not found for project `{0}` and task `{1}`")]
     TaskEnvVarsNotFound(String, String),
 
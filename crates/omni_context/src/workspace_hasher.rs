@@ -76,6 +76,7 @@ impl<'a, TSys: CollectorSys + Clone> WorkspaceHasher<'a, TSys> {
         }
 
         let args = maps::map![];
+        let no_dependencies = maps::map![];
 
         let task_infos = task_infos_tmp
             .iter()
@@ -87,7 +88,7 @@ impl<'a, TSys: CollectorSys + Clone> WorkspaceHasher<'a, TSys> {
                 task_retry_exec: p.task_retry_exec,
                 input_files: &p.input_files,
                 output_files: &[],
-                dependency_digests: &[],
+                dependency_digests: &no_dependencies,
                 env_vars: &p.env_vars,
                 input_env_keys: &p.input_env_keys,
                 args: &args,
@@ -103,6 +104,7 @@ impl<'a, TSys: CollectorSys + Clone> WorkspaceHasher<'a, TSys> {
                         output_files: false,
                         digests: true,
                         cache_output_dirs: false,
+                        manifests: false,
                     },
                 )
                 .await?;
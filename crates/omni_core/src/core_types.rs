@@ -14,6 +14,47 @@ pub struct Project {
     pub dir: PathBuf,
     pub dependencies: Vec<String>,
     pub tasks: OrderedMap<String, Task>,
+    #[new(default)]
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Project {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Restricts which projects a project carrying `tag` may depend on.
+///
+/// A dependency is allowed only when the dependee has at least one of the
+/// `only_depend_on` tags (if any are given) and none of the `not_depend_on`
+/// tags.
+#[derive(
+    Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, new,
+)]
+#[serde(deny_unknown_fields)]
+pub struct TagConstraint {
+    #[new(into)]
+    pub tag: String,
+    #[serde(default)]
+    pub only_depend_on: Vec<String>,
+    #[serde(default)]
+    pub not_depend_on: Vec<String>,
+}
+
+impl TagConstraint {
+    /// Returns true if a project with this constraint's tag may depend on
+    /// `dependee`.
+    pub fn allows(&self, dependee: &Project) -> bool {
+        if !self.only_depend_on.is_empty()
+            && !self.only_depend_on.iter().any(|t| dependee.has_tag(t))
+        {
+            return false;
+        }
+
+        !self.not_depend_on.iter().any(|t| dependee.has_tag(t))
+    }
 }
 
 #[derive(
@@ -30,6 +71,7 @@ pub struct Task {
     pub siblings: Vec<TaskDependency>,
     pub max_retries: Option<u8>,
     pub retry_interval: Option<Duration>,
+    pub timeout: Option<Duration>,
 }
 
 #[cfg(test)]
@@ -44,6 +86,7 @@ pub(crate) struct TaskBuilder {
     siblings: Vec<TaskDependency>,
     max_retries: Option<u8>,
     retry_interval: Option<Duration>,
+    timeout: Option<Duration>,
 }
 
 #[cfg(test)]
@@ -60,6 +103,7 @@ impl TaskBuilder {
             siblings: Default::default(),
             max_retries: None,
             retry_interval: None,
+            timeout: None,
         }
     }
 
@@ -153,6 +197,7 @@ impl TaskBuilder {
             siblings: self.siblings,
             max_retries: self.max_retries,
             retry_interval: self.retry_interval,
+            timeout: self.timeout,
         }
     }
 }
@@ -212,7 +257,8 @@ mod tests {
             "persistent": false,
             "siblings": [],
             "max_retries": null,
-            "retry_interval": null
+            "retry_interval": null,
+            "timeout": null
         }"#;
 
         let task: Task = serde_json::from_str(json).unwrap();
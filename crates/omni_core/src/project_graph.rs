@@ -11,7 +11,9 @@ use serde::{Deserialize, Serialize};
 use strum::{EnumDiscriminants, IntoDiscriminant};
 use trace::Level;
 
-use crate::{Project, TaskExecutionGraph, TaskExecutionGraphResult};
+use crate::{
+    Project, TagConstraint, TaskExecutionGraph, TaskExecutionGraphResult,
+};
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -38,6 +40,14 @@ impl ProjectGraphError {
         Self(ProjectGraphErrorInner::CycleDetected { project })
     }
 
+    pub fn tag_constraint_violated(
+        from: String,
+        to: String,
+        tag: String,
+    ) -> Self {
+        Self(ProjectGraphErrorInner::TagConstraintViolated { from, to, tag })
+    }
+
     pub fn unknown(source: eyre::Report) -> Self {
         Self(ProjectGraphErrorInner::Unknown(source))
     }
@@ -71,6 +81,15 @@ pub(crate) enum ProjectGraphErrorInner {
     #[error("cycle detected")]
     CycleDetected { project: String },
 
+    #[error(
+        "project '{from}' can't depend on '{to}': violates constraint for tag '{tag}'"
+    )]
+    TagConstraintViolated {
+        from: String,
+        to: String,
+        tag: String,
+    },
+
     #[error(transparent)]
     Unknown(#[from] eyre::Report),
 }
@@ -92,6 +111,15 @@ impl ProjectGraph {
     }
 
     pub fn from_projects(projects: Vec<Project>) -> ProjectGraphResult<Self> {
+        Self::from_projects_with_constraints(projects, &[])
+    }
+
+    /// Builds the graph and checks every project dependency against the
+    /// given tag constraints.
+    pub fn from_projects_with_constraints(
+        projects: Vec<Project>,
+        constraints: &[TagConstraint],
+    ) -> ProjectGraphResult<Self> {
         let mut graph = Self::new();
 
         for project in projects.clone() {
@@ -104,8 +132,38 @@ impl ProjectGraph {
             }
         }
 
+        graph.validate_tag_constraints(constraints)?;
+
         Ok(graph)
     }
+
+    fn validate_tag_constraints(
+        &self,
+        constraints: &[TagConstraint],
+    ) -> ProjectGraphResult<()> {
+        if constraints.is_empty() {
+            return Ok(());
+        }
+
+        for edge in self.di_graph.edge_references() {
+            let dependee = &self.di_graph[edge.source()];
+            let dependent = &self.di_graph[edge.target()];
+
+            for constraint in constraints {
+                if dependent.has_tag(&constraint.tag)
+                    && !constraint.allows(dependee)
+                {
+                    return Err(ProjectGraphError::tag_constraint_violated(
+                        dependent.name.clone(),
+                        dependee.name.clone(),
+                        constraint.tag.clone(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl ProjectGraph {
@@ -246,6 +304,35 @@ impl ProjectGraph {
             .collect())
     }
 
+    #[inline(always)]
+    pub fn get_all_dependents_by_name(
+        &self,
+        project_name: &str,
+    ) -> ProjectGraphResult<Vec<(NodeIndex, Project)>> {
+        let project_index = self.get_project_index(project_name)?;
+        self.get_all_dependents(project_index)
+    }
+
+    /// Returns every project that directly or transitively depends on the
+    /// project at `project_index`.
+    pub fn get_all_dependents(
+        &self,
+        project_index: NodeIndex,
+    ) -> ProjectGraphResult<Vec<(NodeIndex, Project)>> {
+        let mut visited_idx = BTreeSet::new();
+        let mut dfs = DfsPostOrder::new(&self.di_graph, project_index);
+
+        while let Some(node_index) = dfs.next(&self.di_graph) {
+            visited_idx.insert(node_index);
+        }
+
+        Ok(visited_idx
+            .iter()
+            .filter(|ni| **ni != project_index)
+            .map(|node_index| (*node_index, self.di_graph[*node_index].clone()))
+            .collect())
+    }
+
     pub fn get_project(
         &self,
         project_index: NodeIndex,
@@ -322,6 +409,7 @@ mod tests {
             dir: Default::default(),
             dependencies: Default::default(),
             tasks: Default::default(),
+            tags: Default::default(),
         }
     }
 
@@ -576,6 +664,93 @@ mod tests {
         assert_eq!(dep1.1.name, "project4");
     }
 
+    #[test]
+    fn test_get_all_dependents() {
+        let graph = ProjectGraph::from_projects(vec![
+            tagged_project("app", &[], &["lib"]),
+            tagged_project("lib", &[], &["core"]),
+            tagged_project("core", &[], &[]),
+            tagged_project("other", &[], &[]),
+        ])
+        .expect("Can't create graph");
+
+        let mut dependents = graph
+            .get_all_dependents_by_name("core")
+            .expect("Can't get dependents")
+            .into_iter()
+            .map(|(_, p)| p.name)
+            .collect::<Vec<_>>();
+        dependents.sort();
+
+        assert_eq!(dependents, vec!["app".to_string(), "lib".to_string()]);
+        assert!(
+            graph
+                .get_all_dependents_by_name("app")
+                .expect("Can't get dependents")
+                .is_empty()
+        );
+    }
+
+    fn tagged_project(name: &str, tags: &[&str], deps: &[&str]) -> Project {
+        Project {
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..create_project(name)
+        }
+    }
+
+    #[test]
+    fn test_from_projects_with_constraints_rejects_violation() {
+        let projects = vec![
+            tagged_project("app1", &["type:app"], &["app2"]),
+            tagged_project("app2", &["type:app"], &[]),
+        ];
+        let constraints = vec![TagConstraint {
+            tag: "type:app".to_string(),
+            only_depend_on: vec![],
+            not_depend_on: vec!["type:app".to_string()],
+        }];
+
+        let error = ProjectGraph::from_projects_with_constraints(
+            projects,
+            &constraints,
+        )
+        .expect_err("should violate constraint");
+
+        assert_eq!(error.kind(), ProjectGraphErrorKind::TagConstraintViolated);
+        assert_eq!(
+            error.to_string(),
+            "project 'app1' can't depend on 'app2': violates constraint for tag 'type:app'"
+        );
+    }
+
+    #[test]
+    fn test_from_projects_with_constraints_only_depend_on() {
+        let constraints = vec![TagConstraint {
+            tag: "type:app".to_string(),
+            only_depend_on: vec!["type:lib".to_string()],
+            not_depend_on: vec![],
+        }];
+
+        let allowed = vec![
+            tagged_project("app", &["type:app"], &["lib"]),
+            tagged_project("lib", &["type:lib"], &[]),
+        ];
+        assert!(
+            ProjectGraph::from_projects_with_constraints(allowed, &constraints)
+                .is_ok()
+        );
+
+        let denied = vec![
+            tagged_project("app", &["type:app"], &["untagged"]),
+            tagged_project("untagged", &[], &[]),
+        ];
+        assert!(
+            ProjectGraph::from_projects_with_constraints(denied, &constraints)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_is_project_exists() {
         let mut graph = ProjectGraph::new();
@@ -39,11 +39,15 @@ pub struct TaskExecutionNode {
     project_dir: PathBuf,
     full_task_name: String,
     dependencies: Vec<String>,
+    /// Full task names this task is co-scheduled with (`Sibling` edges).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    siblings: Vec<String>,
     enabled: TeraExprBoolean,
     interactive: bool,
     persistent: bool,
     max_retries: Option<u8>,
     retry_interval: Option<Duration>,
+    timeout: Option<Duration>,
 }
 
 impl TaskExecutionNode {
@@ -60,6 +64,7 @@ impl TaskExecutionNode {
         persistent: bool,
         max_retries: Option<u8>,
         retry_interval: Option<Duration>,
+        timeout: Option<Duration>,
     ) -> Self {
         let project_name = project_name.into();
         let task_name = task_name.into();
@@ -71,11 +76,13 @@ impl TaskExecutionNode {
             project_name,
             project_dir: project_dir.into(),
             dependencies,
+            siblings: vec![],
             enabled,
             interactive,
             persistent,
             max_retries,
             retry_interval,
+            timeout,
         }
     }
 }
@@ -109,6 +116,10 @@ impl TaskExecutionNode {
         &self.dependencies
     }
 
+    pub fn siblings(&self) -> &[String] {
+        &self.siblings
+    }
+
     pub fn enabled(&self) -> &TeraExprBoolean {
         &self.enabled
     }
@@ -129,7 +140,11 @@ impl TaskExecutionNode {
         self.retry_interval
     }
 
-    /// (task_name, task_command, project_name, project_dir, full_task_name, dependencies, enabled, interactive, persistent, max_retries, retry_interval)
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// (task_name, task_command, project_name, project_dir, full_task_name, dependencies, enabled, interactive, persistent, max_retries, retry_interval, timeout)
     #[allow(clippy::type_complexity)]
     pub fn deconstruct(
         self,
@@ -146,6 +161,7 @@ impl TaskExecutionNode {
         bool,
         Option<u8>,
         Option<Duration>,
+        Option<Duration>,
     ) {
         (
             self.task_name,
@@ -160,6 +176,7 @@ impl TaskExecutionNode {
             self.persistent,
             self.max_retries,
             self.retry_interval,
+            self.timeout,
         )
     }
 }
@@ -228,6 +245,7 @@ impl TaskExecutionGraph {
                     task.1.persistent,
                     task.1.max_retries,
                     task.1.retry_interval,
+                    task.1.timeout,
                 );
 
                 let dep_node_index =
@@ -515,9 +533,14 @@ impl TaskExecutionGraph {
 
         self.di_graph.add_edge(to_idx, from_idx, edge_type);
 
-        if edge_type == EdgeType::Dependency {
-            let to_full_name = self.di_graph[to_idx].full_task_name.clone();
-            self.di_graph[from_idx].dependencies.push(to_full_name);
+        let to_full_name = self.di_graph[to_idx].full_task_name.clone();
+        match edge_type {
+            EdgeType::Dependency => {
+                self.di_graph[from_idx].dependencies.push(to_full_name);
+            }
+            EdgeType::Sibling => {
+                self.di_graph[from_idx].siblings.push(to_full_name);
+            }
         }
 
         Ok(())
@@ -1112,6 +1135,7 @@ mod tests {
             dir: Default::default(),
             dependencies: Default::default(),
             tasks: Default::default(),
+            tags: Default::default(),
         }
     }
 
@@ -1367,6 +1391,7 @@ mod tests {
             false,
             None,
             None,
+            None,
         )
     }
 
@@ -1429,12 +1454,19 @@ mod tests {
                     "project1",
                     &["project3#p3t1", "project1#shared-task-3"],
                 ),
-                node("sibling-2", "echo sibling-2", "project2"),
-                node("sibling-2.1", "echo sibling-2.1", "project2"),
-                node("sibling-3", "echo sibling-3", "project3"),
+                node_mut("sibling-2", "echo sibling-2", "project2", |n| {
+                    n.siblings = vec!["project2#sibling-2.1".to_string()]
+                }),
+                node_mut("sibling-2.1", "echo sibling-2.1", "project2", |n| {
+                    n.siblings = vec!["project3#sibling-3".to_string()]
+                }),
+                node_mut("sibling-3", "echo sibling-3", "project3", |n| {
+                    n.siblings = vec!["project4#sibling-4".to_string()]
+                }),
                 node("sibling-4", "echo sibling-4", "project4"),
                 node_mut("sibling-1", "echo sibling-1", "project1", |n| {
-                    n.persistent = true
+                    n.persistent = true;
+                    n.siblings = vec!["project2#sibling-2".to_string()];
                 }),
             ],
         ];
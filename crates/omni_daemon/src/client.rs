@@ -0,0 +1,143 @@
+use std::{io, time::Duration};
+
+use interprocess::local_socket::{tokio::Stream, traits::tokio::Stream as _};
+use omni_api::{
+    AffectedRequest, AffectedResponse, GraphRequest, GraphResponse,
+    HashResponse, ProjectListRequest,
+};
+use omni_context::{Context, ContextSys};
+use serde::de::DeserializeOwned;
+use tokio::io::BufReader;
+
+use crate::{
+    error::{DaemonError, DaemonErrorInner},
+    protocol::{
+        ContextKey, DaemonRequest, DaemonRequestBody, DaemonResponse,
+        DaemonStatus, PROTOCOL_VERSION, read_message, write_message,
+    },
+    socket::socket_name,
+};
+
+/// A daemon that doesn't accept the connection within this time is treated
+/// as not running.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Sends requests to the daemon of a workspace. Every request opens its own
+/// connection, so a client can be kept around while the daemon restarts.
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    key: ContextKey,
+}
+
+impl DaemonClient {
+    /// Create a client for the daemon serving `ctx`, or `None` if requests
+    /// for `ctx` must not be served by a daemon.
+    ///
+    /// Contexts inheriting the environment variables of the process are
+    /// never served, the daemon's environment is not the caller's.
+    pub fn for_context<TSys: ContextSys>(ctx: &Context<TSys>) -> Option<Self> {
+        if ctx.inherit_env_vars() {
+            return None;
+        }
+
+        Some(Self {
+            key: ContextKey::from_context(ctx),
+        })
+    }
+
+    pub async fn status(&self) -> Result<DaemonStatus, DaemonError> {
+        self.request(DaemonRequestBody::Status).await
+    }
+
+    pub async fn shutdown(&self) -> Result<(), DaemonError> {
+        self.request(DaemonRequestBody::Shutdown).await
+    }
+
+    pub async fn hash_workspace(&self) -> Result<HashResponse, DaemonError> {
+        self.request(DaemonRequestBody::HashWorkspace).await
+    }
+
+    pub async fn hash_project(
+        &self,
+        name: &str,
+        tasks: &[String],
+    ) -> Result<HashResponse, DaemonError> {
+        self.request(DaemonRequestBody::HashProject {
+            name: name.to_string(),
+            tasks: tasks.to_vec(),
+        })
+        .await
+    }
+
+    pub async fn project_list(
+        &self,
+        req: ProjectListRequest,
+    ) -> Result<Vec<String>, DaemonError> {
+        self.request(DaemonRequestBody::ProjectList(req)).await
+    }
+
+    pub async fn graph(
+        &self,
+        req: GraphRequest,
+    ) -> Result<GraphResponse, DaemonError> {
+        self.request(DaemonRequestBody::Graph(req)).await
+    }
+
+    pub async fn affected(
+        &self,
+        req: AffectedRequest,
+    ) -> Result<AffectedResponse, DaemonError> {
+        self.request(DaemonRequestBody::Affected(req)).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        body: DaemonRequestBody,
+    ) -> Result<T, DaemonError> {
+        let name = socket_name(&self.key.root_dir)?;
+        let conn = tokio::time::timeout(CONNECT_TIMEOUT, Stream::connect(name))
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::TimedOut))??;
+        let mut conn = BufReader::new(conn);
+
+        let request = DaemonRequest {
+            version: PROTOCOL_VERSION,
+            context: self.key.clone(),
+            body,
+        };
+        write_message(&mut conn, &request).await?;
+
+        match read_message(&mut conn).await? {
+            DaemonResponse::Ok { value } => Ok(serde_json::from_value(value)?),
+            DaemonResponse::Error { message } => {
+                Err(DaemonErrorInner::new_remote(message).into())
+            }
+            DaemonResponse::Mismatch { reason } => {
+                Err(DaemonErrorInner::new_mismatch(reason).into())
+            }
+        }
+    }
+}
+
+/// Serve a request through the daemon of `ctx` if one is running and able to
+/// serve it, otherwise do the work locally.
+pub async fn daemon_or_local<TSys, T, D, L>(
+    ctx: &Context<TSys>,
+    daemon: D,
+    local: L,
+) -> eyre::Result<T>
+where
+    TSys: ContextSys,
+    D: AsyncFnOnce(DaemonClient) -> Result<T, DaemonError>,
+    L: AsyncFnOnce() -> eyre::Result<T>,
+{
+    if let Some(client) = DaemonClient::for_context(ctx) {
+        match daemon(client).await {
+            Ok(value) => return Ok(value),
+            Err(e) if !e.is_unavailable() => return Err(e.into()),
+            Err(e) => log::debug!("Daemon unavailable, running locally: {e}"),
+        }
+    }
+
+    local().await
+}
@@ -0,0 +1,69 @@
+use derive_new::new;
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct DaemonError(pub(crate) DaemonErrorInner);
+
+impl DaemonError {
+    pub fn custom<T: Into<String>>(msg: T) -> Self {
+        Self(DaemonErrorInner::Custom(eyre::Report::msg(msg.into())))
+    }
+
+    pub fn kind(&self) -> DaemonErrorKind {
+        self.0.discriminant()
+    }
+
+    /// Whether the daemon could not serve the request at all, in which case
+    /// the caller should do the work itself. Errors returned by the operation
+    /// in the daemon are final.
+    pub fn is_unavailable(&self) -> bool {
+        !matches!(
+            self.kind(),
+            DaemonErrorKind::Remote | DaemonErrorKind::AlreadyRunning
+        )
+    }
+}
+
+impl<T: Into<DaemonErrorInner>> From<T> for DaemonError {
+    fn from(inner: T) -> Self {
+        Self(inner.into())
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants, new)]
+#[strum_discriminants(vis(pub), name(DaemonErrorKind))]
+pub(crate) enum DaemonErrorInner {
+    #[error(transparent)]
+    Custom(#[from] eyre::Report),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Watch(#[from] notify::Error),
+
+    #[error("the daemon closed the connection without responding")]
+    ConnectionClosed,
+
+    #[error("the daemon can't serve this request: {reason}")]
+    Mismatch {
+        #[new(into)]
+        reason: String,
+    },
+
+    #[error("{message}")]
+    Remote {
+        #[new(into)]
+        message: String,
+    },
+
+    #[error("a daemon is already running for the workspace at '{root_dir}'")]
+    AlreadyRunning {
+        #[new(into)]
+        root_dir: String,
+    },
+}
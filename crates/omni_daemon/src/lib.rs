@@ -0,0 +1,17 @@
+//! Opt-in background daemon that keeps a workspace loaded between commands.
+//!
+//! The daemon holds the loaded context of one workspace, invalidates what it
+//! computed from it as files change, and serves requests over a local
+//! socket. Clients fall back to doing the work themselves when no daemon is
+//! running, see [`daemon_or_local`].
+
+mod client;
+mod error;
+pub mod protocol;
+mod server;
+mod socket;
+
+pub use client::*;
+pub use error::*;
+pub use protocol::DaemonStatus;
+pub use server::*;
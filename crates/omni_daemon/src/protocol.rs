@@ -0,0 +1,238 @@
+//! Messages exchanged between the daemon and its clients.
+//!
+//! Every connection carries a single request and its response, each encoded
+//! as one line of JSON.
+
+use std::path::PathBuf;
+
+use omni_api::{AffectedRequest, GraphRequest, ProjectListRequest};
+use omni_context::{Context, ContextSys};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt as _, AsyncWrite, AsyncWriteExt as _,
+};
+
+use crate::error::{DaemonError, DaemonErrorInner};
+
+/// Bumped on every incompatible change to the messages below.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The arguments a context was created with. A daemon only serves clients
+/// whose context would be identical to its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextKey {
+    pub root_dir: PathBuf,
+    pub env: String,
+    pub env_files: Vec<PathBuf>,
+    pub env_root_dir_marker: String,
+}
+
+impl ContextKey {
+    pub fn from_context<TSys: ContextSys>(ctx: &Context<TSys>) -> Self {
+        Self {
+            root_dir: ctx.root_dir().to_path_buf(),
+            env: ctx.env().to_string(),
+            env_files: ctx.env_files().to_vec(),
+            env_root_dir_marker: ctx.env_root_dir_marker().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonRequest {
+    pub version: u32,
+    pub context: ContextKey,
+    pub body: DaemonRequestBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum DaemonRequestBody {
+    Status,
+    Shutdown,
+    HashWorkspace,
+    HashProject { name: String, tasks: Vec<String> },
+    ProjectList(ProjectListRequest),
+    Graph(GraphRequest),
+    Affected(AffectedRequest),
+}
+
+impl DaemonRequestBody {
+    /// Which file changes invalidate the response to this request.
+    pub(crate) fn lifetime(&self) -> ResponseLifetime {
+        match self {
+            Self::HashWorkspace | Self::HashProject { .. } => {
+                ResponseLifetime::UntilAnyChange
+            }
+            Self::ProjectList(_) => ResponseLifetime::UntilConfigChange,
+            Self::Graph(req)
+                if req.scm_affected.is_none()
+                    && req.scm_base.is_none()
+                    && req.scm_target.is_none() =>
+            {
+                ResponseLifetime::UntilConfigChange
+            }
+            // Affected results depend on the state of the repository, which
+            // isn't watched. A base or target commit alone filters the graph
+            // by the affected files too.
+            Self::Graph(_)
+            | Self::Affected(_)
+            | Self::Status
+            | Self::Shutdown => ResponseLifetime::Uncached,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResponseLifetime {
+    Uncached,
+    /// Valid until any file in the workspace changes.
+    UntilAnyChange,
+    /// Valid until a configuration or env file changes.
+    UntilConfigChange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum DaemonResponse {
+    Ok {
+        value: serde_json::Value,
+    },
+    Error {
+        message: String,
+    },
+    /// The daemon can't serve the request, either because it speaks another
+    /// protocol version or because it holds a different context.
+    Mismatch {
+        reason: String,
+    },
+}
+
+/// State reported by a running daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub protocol_version: u32,
+    pub root_dir: PathBuf,
+    pub env: String,
+    pub uptime_secs: u64,
+    /// Requests served since the daemon started.
+    pub requests: u64,
+    /// Times the context was reloaded after a configuration change.
+    pub reloads: u64,
+    pub cached_responses: usize,
+}
+
+pub(crate) async fn read_message<T, R>(reader: &mut R) -> Result<T, DaemonError>
+where
+    T: DeserializeOwned,
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Err(DaemonErrorInner::new_connection_closed().into());
+    }
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+pub(crate) async fn write_message<T, W>(
+    writer: &mut W,
+    message: &T,
+) -> Result<(), DaemonError>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::BufReader;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_message_roundtrip() {
+        let request = DaemonRequest {
+            version: PROTOCOL_VERSION,
+            context: ContextKey {
+                root_dir: PathBuf::from("/workspace"),
+                env: "development".to_string(),
+                env_files: vec![PathBuf::from(".env")],
+                env_root_dir_marker: "workspace.omni.yaml".to_string(),
+            },
+            body: DaemonRequestBody::HashProject {
+                name: "app".to_string(),
+                tasks: vec!["build".to_string()],
+            },
+        };
+
+        let mut buf = vec![];
+        write_message(&mut buf, &request)
+            .await
+            .expect("should write");
+        assert_eq!(buf.iter().filter(|b| **b == b'\n').count(), 1);
+
+        let read: DaemonRequest = read_message(&mut BufReader::new(&buf[..]))
+            .await
+            .expect("should read");
+
+        assert_eq!(read.context, request.context);
+        assert!(matches!(
+            read.body,
+            DaemonRequestBody::HashProject { ref name, ref tasks }
+                if name == "app" && tasks == &["build"]
+        ));
+    }
+
+    #[test]
+    fn test_graph_lifetime_with_scm_filter() {
+        let graph =
+            |req: GraphRequest| DaemonRequestBody::Graph(req).lifetime();
+
+        assert_eq!(
+            graph(GraphRequest::default()),
+            ResponseLifetime::UntilConfigChange
+        );
+        assert_eq!(
+            graph(GraphRequest {
+                scm_affected: serde_json::from_value(serde_json::json!("git"))
+                    .expect("should be a valid scm"),
+                ..Default::default()
+            }),
+            ResponseLifetime::Uncached
+        );
+        assert_eq!(
+            graph(GraphRequest {
+                scm_base: Some("main".to_string()),
+                ..Default::default()
+            }),
+            ResponseLifetime::Uncached
+        );
+        assert_eq!(
+            graph(GraphRequest {
+                scm_target: Some("HEAD".to_string()),
+                ..Default::default()
+            }),
+            ResponseLifetime::Uncached
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_message_from_closed_connection() {
+        let result: Result<DaemonResponse, _> =
+            read_message(&mut BufReader::new(&b""[..])).await;
+
+        assert_eq!(
+            result.expect_err("should fail").kind(),
+            crate::DaemonErrorKind::ConnectionClosed
+        );
+    }
+}
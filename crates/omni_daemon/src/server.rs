@@ -0,0 +1,569 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use interprocess::local_socket::{
+    ListenerOptions,
+    tokio::Stream,
+    traits::tokio::{Listener as _, Stream as _},
+};
+use maps::{UnorderedMap, unordered_map};
+use notify::{Event, EventKind, RecursiveMode, Watcher as _};
+use omni_api::OmniApi;
+use omni_context::{Context, constants};
+use omni_messages::NoopSubscriber;
+use system_traits::impls::RealSys;
+use tokio::{
+    io::BufReader,
+    sync::{Mutex, Notify, mpsc, watch},
+};
+
+use crate::{
+    error::{DaemonError, DaemonErrorInner},
+    protocol::{
+        ContextKey, DaemonRequest, DaemonRequestBody, DaemonResponse,
+        DaemonStatus, PROTOCOL_VERSION, ResponseLifetime, read_message,
+        write_message,
+    },
+    socket::socket_name,
+};
+
+type Api = OmniApi<RealSys, NoopSubscriber>;
+
+/// Serves requests for one workspace from a context that is loaded once and
+/// kept up to date by watching the workspace.
+pub struct DaemonServer {
+    ctx: Context<RealSys>,
+    debounce: Duration,
+}
+
+impl DaemonServer {
+    pub fn new(ctx: Context<RealSys>) -> Self {
+        Self {
+            ctx,
+            debounce: Duration::from_millis(200),
+        }
+    }
+
+    /// How long file system events are collected before invalidating.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Serve until a client asks the daemon to shut down or the process is
+    /// interrupted.
+    pub async fn run(self) -> Result<(), DaemonError> {
+        let root_dir = self.ctx.root_dir().to_path_buf();
+
+        if Stream::connect(socket_name(&root_dir)?).await.is_ok() {
+            return Err(DaemonErrorInner::new_already_running(
+                root_dir.to_string_lossy(),
+            )
+            .into());
+        }
+
+        let listener = ListenerOptions::new()
+            .name(socket_name(&root_dir)?)
+            .try_overwrite(true)
+            .create_tokio()?;
+
+        let ignored = ignored_dirs(&self.ctx);
+        let state = Arc::new(Mutex::new(State::new(self.ctx)));
+        let changes = Arc::new(Changes::new());
+        let shutdown = Arc::new(Notify::new());
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let watcher_changes = changes.clone();
+        let mut watcher = notify::recommended_watcher(
+            move |event: notify::Result<Event>| {
+                let seq = if is_change(&event, &ignored) {
+                    watcher_changes.received.fetch_add(1, Ordering::SeqCst) + 1
+                } else {
+                    0
+                };
+                let _ = tx.send((seq, event));
+            },
+        )?;
+        watcher.watch(&root_dir, RecursiveMode::Recursive)?;
+
+        let api = state.lock().await.api.clone();
+        if let Err(e) = api.load().await {
+            log::warn!("Failed to load the workspace: {e}");
+        }
+
+        tokio::spawn(watch_workspace(
+            rx,
+            state.clone(),
+            changes.clone(),
+            self.debounce,
+        ));
+
+        log::info!("Daemon listening for workspace {}", root_dir.display());
+
+        loop {
+            tokio::select! {
+                conn = listener.accept() => match conn {
+                    Ok(conn) => {
+                        tokio::spawn(handle_connection(
+                            conn,
+                            state.clone(),
+                            changes.clone(),
+                            shutdown.clone(),
+                        ));
+                    }
+                    Err(e) => log::warn!("Failed to accept connection: {e}"),
+                },
+                _ = shutdown.notified() => break,
+                _ = tokio::signal::ctrl_c() => break,
+            }
+        }
+
+        log::info!("Daemon stopped");
+
+        Ok(())
+    }
+}
+
+/// Tracks the file system events of the watcher until they are applied to
+/// the state, so that requests never see responses cached before a change
+/// the watcher already reported.
+struct Changes {
+    /// Sequence number of the last relevant event reported by the watcher.
+    received: AtomicU64,
+    /// Sequence number of the last event applied to the state.
+    applied: watch::Sender<u64>,
+    /// Asks the watcher to apply the events it collected without waiting for
+    /// the debounce to elapse.
+    flush: Notify,
+}
+
+impl Changes {
+    fn new() -> Self {
+        Self {
+            received: AtomicU64::new(0),
+            applied: watch::Sender::new(0),
+            flush: Notify::new(),
+        }
+    }
+
+    fn mark_applied(&self, seq: u64) {
+        self.applied.send_if_modified(|applied| {
+            let modified = seq > *applied;
+            *applied = (*applied).max(seq);
+            modified
+        });
+    }
+
+    /// Wait until every event reported so far is applied to the state.
+    async fn settle(&self) {
+        let received = self.received.load(Ordering::SeqCst);
+        if *self.applied.borrow() >= received {
+            return;
+        }
+
+        self.flush.notify_one();
+        let mut applied = self.applied.subscribe();
+        // the sender lives as long as `self`, so this can't fail
+        let _ = applied.wait_for(|applied| *applied >= received).await;
+    }
+}
+
+struct State {
+    key: ContextKey,
+    ctx: Context<RealSys>,
+    api: Arc<Api>,
+    /// Responses by serialized request.
+    responses: UnorderedMap<String, (ResponseLifetime, serde_json::Value)>,
+    /// Bumped on every invalidation, so that responses computed from stale
+    /// state aren't cached.
+    generation: u64,
+    started: Instant,
+    requests: u64,
+    reloads: u64,
+}
+
+impl State {
+    fn new(ctx: Context<RealSys>) -> Self {
+        Self {
+            key: ContextKey::from_context(&ctx),
+            api: Arc::new(OmniApi::new_with_sys(ctx.clone(), NoopSubscriber)),
+            ctx,
+            responses: unordered_map!(),
+            generation: 0,
+            started: Instant::now(),
+            requests: 0,
+            reloads: 0,
+        }
+    }
+
+    fn status(&self) -> DaemonStatus {
+        DaemonStatus {
+            pid: std::process::id(),
+            protocol_version: PROTOCOL_VERSION,
+            root_dir: self.ctx.root_dir().to_path_buf(),
+            env: self.key.env.clone(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            requests: self.requests,
+            reloads: self.reloads,
+            cached_responses: self.responses.len(),
+        }
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        is_ignored(path, &ignored_dirs(&self.ctx))
+    }
+
+    /// Whether a change to `path` can change the loaded projects or their
+    /// environment.
+    fn is_config_file(&self, path: &Path) -> bool {
+        let Some(file_name) = path.file_name() else {
+            return false;
+        };
+        let file_name = file_name.to_string_lossy();
+
+        let is_omni_config =
+            constants::SUPPORTED_EXTENSIONS.iter().any(|ext| {
+                file_name == constants::PROJECT_OMNI.replace("{ext}", ext)
+                    || file_name
+                        == constants::WORKSPACE_OMNI.replace("{ext}", ext)
+            });
+
+        is_omni_config
+            || file_name == constants::OMNI_IGNORE
+            || file_name == ".gitignore"
+            || self.ctx.env_files().iter().any(|f| {
+                f.file_name()
+                    .is_some_and(|f| f.to_string_lossy() == file_name)
+            })
+    }
+}
+
+/// Directories whose changes never affect the responses: the omni dir (which
+/// the runs themselves write to) and the git dir.
+fn ignored_dirs(ctx: &Context<RealSys>) -> [PathBuf; 2] {
+    [ctx.omni_dir().to_path_buf(), ctx.root_dir().join(".git")]
+}
+
+fn is_ignored(path: &Path, ignored: &[PathBuf]) -> bool {
+    ignored.iter().any(|dir| path.starts_with(dir))
+}
+
+/// Whether `event` may invalidate the responses.
+fn is_change(event: &notify::Result<Event>, ignored: &[PathBuf]) -> bool {
+    event.as_ref().is_ok_and(|event| {
+        !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|p| !is_ignored(p, ignored))
+    })
+}
+
+async fn handle_connection(
+    conn: Stream,
+    state: Arc<Mutex<State>>,
+    changes: Arc<Changes>,
+    shutdown: Arc<Notify>,
+) {
+    let mut conn = BufReader::new(conn);
+
+    let result = async {
+        let request: DaemonRequest = read_message(&mut conn).await?;
+        let shutting_down = matches!(request.body, DaemonRequestBody::Shutdown);
+        let response = serve(&state, &changes, request).await;
+
+        write_message(&mut conn, &response).await?;
+
+        if shutting_down && matches!(response, DaemonResponse::Ok { .. }) {
+            shutdown.notify_one();
+        }
+
+        Ok::<_, DaemonError>(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        log::warn!("Failed to serve daemon request: {e}");
+    }
+}
+
+async fn serve(
+    state: &Mutex<State>,
+    changes: &Changes,
+    request: DaemonRequest,
+) -> DaemonResponse {
+    // Changes are only applied once the watcher's debounce elapses, which
+    // would let a response cached before a file changed be served right
+    // after it changed.
+    changes.settle().await;
+
+    let (api, generation, received, cache_key) = {
+        let mut state = state.lock().await;
+
+        if request.version != PROTOCOL_VERSION {
+            return DaemonResponse::Mismatch {
+                reason: format!(
+                    "the daemon speaks protocol version {PROTOCOL_VERSION}, the client {}",
+                    request.version
+                ),
+            };
+        }
+
+        if request.context != state.key {
+            return DaemonResponse::Mismatch {
+                reason: "the daemon was started with different arguments"
+                    .to_string(),
+            };
+        }
+
+        state.requests += 1;
+
+        match request.body {
+            DaemonRequestBody::Status => {
+                return ok(state.status());
+            }
+            DaemonRequestBody::Shutdown => {
+                return ok(());
+            }
+            _ => {}
+        }
+
+        let cache_key = serde_json::to_string(&request.body)
+            .expect("requests should always serialize");
+
+        if let Some((_, value)) = state.responses.get(&cache_key) {
+            return DaemonResponse::Ok {
+                value: value.clone(),
+            };
+        }
+
+        (
+            state.api.clone(),
+            state.generation,
+            changes.received.load(Ordering::SeqCst),
+            cache_key,
+        )
+    };
+
+    let lifetime = request.body.lifetime();
+
+    match execute(&api, request.body).await {
+        Ok(value) => {
+            let mut state = state.lock().await;
+            // don't cache what may have been computed from files that
+            // changed meanwhile, even if the change isn't applied yet
+            if lifetime != ResponseLifetime::Uncached
+                && state.generation == generation
+                && changes.received.load(Ordering::SeqCst) == received
+            {
+                state.responses.insert(cache_key, (lifetime, value.clone()));
+            }
+
+            DaemonResponse::Ok { value }
+        }
+        Err(e) => DaemonResponse::Error {
+            message: format!("{e:#}"),
+        },
+    }
+}
+
+async fn execute(
+    api: &Api,
+    body: DaemonRequestBody,
+) -> eyre::Result<serde_json::Value> {
+    let value = match body {
+        DaemonRequestBody::HashWorkspace => {
+            serde_json::to_value(api.hash_workspace().await?)?
+        }
+        DaemonRequestBody::HashProject { name, tasks } => {
+            serde_json::to_value(api.hash_project(&name, &tasks).await?)?
+        }
+        DaemonRequestBody::ProjectList(req) => {
+            serde_json::to_value(api.project_list_filtered(req).await?)?
+        }
+        DaemonRequestBody::Graph(req) => {
+            serde_json::to_value(api.graph(req).await?)?
+        }
+        DaemonRequestBody::Affected(req) => {
+            serde_json::to_value(api.affected(req).await?)?
+        }
+        DaemonRequestBody::Status | DaemonRequestBody::Shutdown => {
+            eyre::bail!("request is handled by the daemon itself")
+        }
+    };
+
+    Ok(value)
+}
+
+fn ok<T: serde::Serialize>(value: T) -> DaemonResponse {
+    match serde_json::to_value(value) {
+        Ok(value) => DaemonResponse::Ok { value },
+        Err(e) => DaemonResponse::Error {
+            message: e.to_string(),
+        },
+    }
+}
+
+/// Invalidate the cached responses, and reload the context when the
+/// configuration changed, as files in the workspace change.
+async fn watch_workspace(
+    mut rx: mpsc::UnboundedReceiver<(u64, notify::Result<Event>)>,
+    state: Arc<Mutex<State>>,
+    changes: Arc<Changes>,
+    debounce: Duration,
+) {
+    while let Some((seq, paths)) =
+        next_changes(&mut rx, debounce, &changes.flush).await
+    {
+        let mut guard = state.lock().await;
+        let paths = paths
+            .into_iter()
+            .filter(|p| !guard.is_ignored(p))
+            .collect::<Vec<_>>();
+
+        if paths.is_empty() {
+            changes.mark_applied(seq);
+            continue;
+        }
+
+        guard.generation += 1;
+
+        if !paths.iter().any(|p| guard.is_config_file(p)) {
+            guard.responses.retain(|_, (lifetime, _)| {
+                *lifetime == ResponseLifetime::UntilConfigChange
+            });
+            changes.mark_applied(seq);
+            continue;
+        }
+
+        log::info!("Workspace configuration changed, reloading");
+
+        let ctx = match guard.ctx.reloaded() {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                log::warn!("Failed to reload the workspace configuration: {e}");
+                guard.ctx.clone()
+            }
+        };
+
+        let api = Arc::new(OmniApi::new_with_sys(ctx.clone(), NoopSubscriber));
+        guard.key = ContextKey::from_context(&ctx);
+        guard.ctx = ctx;
+        guard.api = api.clone();
+        guard.responses.clear();
+        guard.reloads += 1;
+        changes.mark_applied(seq);
+        drop(guard);
+
+        if let Err(e) = api.load().await {
+            log::warn!("Failed to load the workspace: {e}");
+        }
+    }
+}
+
+/// Wait for the next batch of changes, along with the sequence number of the
+/// last event in it. Events are collected until none arrived for `debounce`,
+/// or until `flush` is notified.
+async fn next_changes(
+    rx: &mut mpsc::UnboundedReceiver<(u64, notify::Result<Event>)>,
+    debounce: Duration,
+    flush: &Notify,
+) -> Option<(u64, Vec<PathBuf>)> {
+    let mut paths = vec![];
+    let (mut seq, event) = rx.recv().await?;
+    collect_paths(event, &mut paths);
+
+    loop {
+        tokio::select! {
+            event = tokio::time::timeout(debounce, rx.recv()) => {
+                let Ok(Some((event_seq, event))) = event else {
+                    break;
+                };
+                seq = seq.max(event_seq);
+                collect_paths(event, &mut paths);
+            }
+            _ = flush.notified() => {
+                while let Ok((event_seq, event)) = rx.try_recv() {
+                    seq = seq.max(event_seq);
+                    collect_paths(event, &mut paths);
+                }
+                break;
+            }
+        }
+    }
+
+    paths.sort();
+    paths.dedup();
+
+    Some((seq, paths))
+}
+
+fn collect_paths(event: notify::Result<Event>, paths: &mut Vec<PathBuf>) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            paths.extend(event.paths);
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("File watcher error: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modify(path: &str) -> (u64, notify::Result<Event>) {
+        (
+            1,
+            Ok(
+                Event::new(EventKind::Modify(notify::event::ModifyKind::Any))
+                    .add_path(PathBuf::from(path)),
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_next_changes_stops_debouncing_on_flush() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let flush = Notify::new();
+
+        tx.send(modify("/ws/a.txt")).unwrap();
+        let (seq, event) = modify("/ws/b.txt");
+        tx.send((seq + 1, event)).unwrap();
+        flush.notify_one();
+
+        let changes = tokio::time::timeout(
+            Duration::from_secs(5),
+            next_changes(&mut rx, Duration::from_secs(60), &flush),
+        )
+        .await
+        .expect("flush should end the debounce");
+
+        assert_eq!(
+            changes,
+            Some((
+                2,
+                vec![PathBuf::from("/ws/a.txt"), PathBuf::from("/ws/b.txt")]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_is_change_skips_ignored_dirs_and_access() {
+        let ignored = [PathBuf::from("/ws/.omni"), PathBuf::from("/ws/.git")];
+
+        assert!(is_change(&modify("/ws/src/lib.rs").1, &ignored));
+        assert!(!is_change(&modify("/ws/.omni/cache/x").1, &ignored));
+        assert!(!is_change(&modify("/ws/.git/index").1, &ignored));
+        assert!(!is_change(
+            &Ok(
+                Event::new(EventKind::Access(notify::event::AccessKind::Any))
+                    .add_path(PathBuf::from("/ws/src/lib.rs"))
+            ),
+            &ignored
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use std::{io, path::Path};
+
+use interprocess::local_socket::Name;
+
+/// Name of the socket the daemon of the workspace at `root_dir` listens on.
+///
+/// On unix the socket is a file in a directory only the current user can
+/// access, so that other users can neither connect to the daemon nor take
+/// its place. Elsewhere a namespaced socket is used.
+pub(crate) fn socket_name(root_dir: &Path) -> io::Result<Name<'static>> {
+    let file_name = format!("omni-daemon-{}.sock", workspace_id(root_dir));
+
+    #[cfg(unix)]
+    {
+        use interprocess::local_socket::{GenericFilePath, ToFsName as _};
+
+        let dir = unix::runtime_dir();
+        unix::ensure_private_dir(&dir)?;
+        dir.join(file_name).to_fs_name::<GenericFilePath>()
+    }
+
+    #[cfg(not(unix))]
+    {
+        use interprocess::local_socket::{GenericNamespaced, ToNsName as _};
+
+        file_name.to_ns_name::<GenericNamespaced>()
+    }
+}
+
+/// Short, stable identifier of the workspace at `root_dir`. Socket paths are
+/// limited to around a hundred bytes on some platforms, so the root dir
+/// can't be used as-is.
+fn workspace_id(root_dir: &Path) -> String {
+    let hash = blake3::hash(root_dir.as_os_str().as_encoded_bytes());
+    bs58::encode(&hash.as_bytes()[..16]).into_string()
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::{
+        fs::DirBuilder,
+        io,
+        os::unix::fs::{DirBuilderExt as _, MetadataExt as _},
+        path::{Path, PathBuf},
+    };
+
+    use nix::unistd::Uid;
+
+    /// `$XDG_RUNTIME_DIR/omni` when the session has a runtime dir, otherwise
+    /// a directory of the current user in the temp dir.
+    pub(super) fn runtime_dir() -> PathBuf {
+        match std::env::var_os("XDG_RUNTIME_DIR") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("omni"),
+            _ => std::env::temp_dir()
+                .join(format!("omni-daemon-{}", Uid::effective())),
+        }
+    }
+
+    /// Creates `dir` accessible to the current user only, and makes sure an
+    /// existing one wasn't created by someone else or opened up.
+    pub(super) fn ensure_private_dir(dir: &Path) -> io::Result<()> {
+        DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+        let metadata = std::fs::symlink_metadata(dir)?;
+        if !metadata.is_dir()
+            || metadata.uid() != Uid::effective().as_raw()
+            || metadata.mode() & 0o077 != 0
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "the daemon socket dir '{}' must be a directory owned \
+                     by and only accessible to the current user",
+                    dir.display()
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_id_is_stable_per_root_dir() {
+        let a = workspace_id(Path::new("/workspace/a"));
+
+        assert_eq!(a, workspace_id(Path::new("/workspace/a")));
+        assert_ne!(a, workspace_id(Path::new("/workspace/b")));
+        assert!(a.len() <= 22);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_socket_dir_is_private() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("omni");
+
+        unix::ensure_private_dir(&dir).expect("should create the dir");
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        let err = unix::ensure_private_dir(&dir)
+            .expect_err("a dir others can access should be rejected");
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+}
@@ -17,8 +17,8 @@ use strum::{EnumDiscriminants, IntoDiscriminant as _};
 use crate::{
     Call, Context, DefaultProjectFilter, DefaultTaskFilter,
     DefaultTaskScmAffectedFilter, ExecutionPlanProvider, FilterError,
-    ProjectFilter as _, ProjectFilterExt as _, ScmAffectedFilter,
-    TaskFilter as _,
+    ProjectFilter as _, ProjectFilterExt as _, ScmAffectedFilter, TagFilter,
+    TagMatcher, TaskFilter as _,
 };
 
 #[derive(Debug, new)]
@@ -38,6 +38,7 @@ impl<'a, TContext: Context> ExecutionPlanProvider
         project_filters: &[&str],
         dir_filters: &[&str],
         meta_filter: Option<&str>,
+        tag_filter: Option<&TagFilter>,
         affected_scm_filter: Option<&ScmAffectedFilter>,
         ignore_deps: bool,
         with_dependents: bool,
@@ -48,6 +49,7 @@ impl<'a, TContext: Context> ExecutionPlanProvider
                 project_filters,
                 dir_filters,
                 meta_filter,
+                tag_filter,
                 affected_scm_filter,
             )
         } else {
@@ -56,6 +58,7 @@ impl<'a, TContext: Context> ExecutionPlanProvider
                 project_filters,
                 dir_filters,
                 meta_filter,
+                tag_filter,
                 affected_scm_filter,
                 with_dependents,
             )
@@ -70,15 +73,19 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
         project_filters: &[&str],
         dir_filters: &[&str],
         meta_filter: Option<&str>,
+        tag_filter: Option<&TagFilter>,
         affected_scm_filter: Option<&ScmAffectedFilter>,
     ) -> Result<BatchedExecutionPlan, ExecutionPlanProviderError> {
-        let pf = DefaultProjectFilter::new(project_filters)?;
+        let pf = DefaultProjectFilter::new(project_filters, tag_filter)?;
         // Simple case: just get all matching tasks in one batch
         let projects = pf.filter_projects(self.context.projects());
 
-        if !project_filters.is_empty() && projects.is_empty() {
+        if (!project_filters.is_empty()
+            || tag_filter.is_some_and(|f| !f.is_empty()))
+            && projects.is_empty()
+        {
             Err(ExecutionPlanProviderErrorInner::NoProjectFoundForFilter {
-                filter: project_filters.join(", "),
+                filter: filter_description(project_filters, tag_filter),
             })?;
         }
 
@@ -96,6 +103,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                     project_filters,
                     dir_filters,
                     meta_filter,
+                    tag_filter,
                 )?;
 
                 let mut nodes = vec![];
@@ -113,6 +121,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                         false,
                         None,
                         None,
+                        None,
                     );
 
                     if task_filter.should_include_task(&node)? {
@@ -132,6 +141,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                     project_filters,
                     dir_filters,
                     meta_filter,
+                    tag_filter,
                 )?;
 
                 let tfscm =
@@ -169,6 +179,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                             task.persistent,
                             task.max_retries,
                             task.retry_interval,
+                            task.timeout,
                         );
 
                         if passes_tf(&node)? {
@@ -191,6 +202,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
         project_filters: &[&str],
         dir_filters: &[&str],
         meta_filter: Option<&str>,
+        tag_filter: Option<&TagFilter>,
     ) -> Result<
         DefaultTaskFilter<
             'a,
@@ -200,6 +212,14 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
     > {
         let root_dir = self.context.root_dir();
 
+        let allowed_projects = tag_filter
+            .filter(|f| !f.is_empty())
+            .map(|f| {
+                TagMatcher::new(f)
+                    .map(|m| m.matching_project_names(self.context.projects()))
+            })
+            .transpose()?;
+
         let tf = DefaultTaskFilter::new(
             task_names,
             project_filters,
@@ -214,7 +234,8 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                         .get_task_meta_config(n.project_name(), n.task_name())
                 }
             },
-        )?;
+        )?
+        .with_allowed_projects(allowed_projects);
         Ok(tf)
     }
 
@@ -246,23 +267,23 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
         project_filters: &[&str],
         dir_filters: &[&str],
         meta_filter: Option<&str>,
+        tag_filter: Option<&TagFilter>,
         affected_scm_filter: Option<&ScmAffectedFilter>,
         with_dependents: bool,
     ) -> Result<BatchedExecutionPlan, ExecutionPlanProviderError> {
-        let pf = DefaultProjectFilter::new(project_filters)?;
+        let pf = DefaultProjectFilter::new(project_filters, tag_filter)?;
 
-        if !project_filters.is_empty()
+        if (!project_filters.is_empty()
+            || tag_filter.is_some_and(|f| !f.is_empty()))
             && !self
                 .context
                 .projects()
                 .iter()
                 .any(|p| pf.should_include_project(p).unwrap_or(false))
         {
-            Err(
-                ExecutionPlanProviderErrorInner::NoProjectFoundForFilter {
-                    filter: project_filters.join(", "),
-                },
-            )?;
+            Err(ExecutionPlanProviderErrorInner::NoProjectFoundForFilter {
+                filter: filter_description(project_filters, tag_filter),
+            })?;
         }
 
         let mut project_graph = self
@@ -291,6 +312,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                             vec![],
                             None,
                             None,
+                            None,
                         ),
                     );
                 });
@@ -309,6 +331,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
             project_filters,
             dir_filters,
             meta_filter,
+            tag_filter,
         )?;
 
         let x_graph = project_graph.get_task_execution_graph()?;
@@ -326,6 +349,7 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
                 &[],
                 &[],
                 None,
+                None,
             )?;
 
             x_graph.get_batched_execution_plan_with_dependents(
@@ -352,6 +376,23 @@ impl<'a, TContext: Context> DefaultExecutionPlanProvider<'a, TContext> {
     }
 }
 
+fn filter_description(
+    project_filters: &[&str],
+    tag_filter: Option<&TagFilter>,
+) -> String {
+    let mut parts = project_filters
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>();
+
+    if let Some(tag_filter) = tag_filter {
+        parts.extend(tag_filter.include.iter().map(|t| format!("tag:{t}")));
+        parts.extend(tag_filter.exclude.iter().map(|t| format!("!tag:{t}")));
+    }
+
+    parts.join(", ")
+}
+
 fn temp_task_name(prefix: &str, command: &str, args: &[String]) -> String {
     // utilize default hasher so that the hash is consistent across platforms and versions
     let mut hasher = ahash::AHasher::default();
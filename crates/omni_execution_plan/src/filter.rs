@@ -10,32 +10,115 @@ use omni_core::{Project, TaskExecutionNode};
 use omni_expressions::Evaluator;
 use omni_scm::{Scm, get_scm_implementation};
 use omni_types::{OmniPath, Root, enum_map};
-use omni_utils::glob::build_glob_set;
+use omni_utils::glob::{NegatableGlobSet, build_glob_set};
+use sets::UnorderedSet;
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 
-use crate::{ProjectFilter, ScmAffectedFilter, TaskFilter};
+use crate::{ProjectFilter, ScmAffectedFilter, TagFilter, TaskFilter};
+
+/// Compiled form of a [`TagFilter`].
+pub struct TagMatcher {
+    include: Option<Arc<GlobSet>>,
+    exclude: Option<Arc<GlobSet>>,
+}
+
+impl TagMatcher {
+    pub fn new(tag_filter: &TagFilter) -> Result<Self, FilterError> {
+        let build = |patterns: &[String]| {
+            if patterns.is_empty() {
+                Ok(None)
+            } else {
+                build_glob_set(patterns)
+                    .map(Some)
+                    .map_err(FilterErrorInner::Glob)
+            }
+        };
+
+        Ok(Self {
+            include: build(&tag_filter.include)?,
+            exclude: build(&tag_filter.exclude)?,
+        })
+    }
+
+    pub fn is_match(&self, project: &Project) -> bool {
+        self.is_match_tags(&project.tags)
+    }
+
+    pub fn is_match_tags(&self, tags: &[String]) -> bool {
+        if let Some(include) = &self.include
+            && !tags.iter().any(|t| include.is_match(t))
+        {
+            return false;
+        }
+
+        if let Some(exclude) = &self.exclude
+            && tags.iter().any(|t| exclude.is_match(t))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Names of the projects whose tags match.
+    pub fn matching_project_names(
+        &self,
+        projects: &[Project],
+    ) -> UnorderedSet<String> {
+        projects
+            .iter()
+            .filter(|p| self.is_match(p))
+            .map(|p| p.name.clone())
+            .collect()
+    }
+}
 
 pub struct DefaultProjectFilter {
-    project_matcher: Option<Arc<GlobSet>>,
+    project_matcher: Option<NegatableGlobSet>,
+    tag_matcher: Option<TagMatcher>,
+    dir_matcher: Option<NegatableGlobSet>,
     fast_path_include_all: bool,
 }
 
 impl DefaultProjectFilter {
-    pub fn new(project_filters: &[&str]) -> Result<Self, FilterError> {
+    pub fn new(
+        project_filters: &[&str],
+        tag_filter: Option<&TagFilter>,
+    ) -> Result<Self, FilterError> {
         let project_matcher = if project_filters.is_empty() {
             None
         } else {
             Some(
-                build_glob_set(project_filters)
+                NegatableGlobSet::new(project_filters)
                     .map_err(FilterErrorInner::Glob)?,
             )
         };
 
+        let tag_matcher = tag_filter
+            .filter(|f| !f.is_empty())
+            .map(TagMatcher::new)
+            .transpose()?;
+
         Ok(Self {
+            fast_path_include_all: project_filters.is_empty()
+                && tag_matcher.is_none(),
             project_matcher,
-            fast_path_include_all: project_filters.is_empty(),
+            tag_matcher,
+            dir_matcher: None,
         })
     }
+
+    /// Also restricts the filter to projects whose directory matches
+    /// `dir_filters`, the same way [`DefaultTaskFilter`] matches them.
+    pub fn with_dir_filters(
+        mut self,
+        dir_filters: &[&str],
+        workspace_root_dir: &Path,
+    ) -> Result<Self, FilterError> {
+        self.dir_matcher = dir_matcher(dir_filters, workspace_root_dir)?;
+        self.fast_path_include_all &= self.dir_matcher.is_none();
+        Ok(self)
+    }
 }
 
 impl ProjectFilter for DefaultProjectFilter {
@@ -49,6 +132,18 @@ impl ProjectFilter for DefaultProjectFilter {
             return Ok(true);
         }
 
+        if let Some(matcher) = &self.tag_matcher
+            && !matcher.is_match(project)
+        {
+            return Ok(false);
+        }
+
+        if let Some(matcher) = &self.dir_matcher
+            && !matcher.is_match(project.dir.to_string_lossy().as_ref())
+        {
+            return Ok(false);
+        }
+
         if let Some(matcher) = &self.project_matcher {
             Ok(matcher.is_match(&project.name))
         } else {
@@ -57,15 +152,47 @@ impl ProjectFilter for DefaultProjectFilter {
     }
 }
 
+/// Matches project directories against `dir_filters`. Filters that aren't
+/// rooted are relative to the workspace root.
+fn dir_matcher(
+    dir_filters: &[&str],
+    workspace_root_dir: &Path,
+) -> Result<Option<NegatableGlobSet>, FilterError> {
+    if dir_filters.is_empty() {
+        return Ok(None);
+    }
+
+    let string = workspace_root_dir.to_string_lossy();
+    let workspace_root_dir_str: Cow<str> = {
+        if cfg!(windows) {
+            Cow::Owned(string.replace("\\", "/"))
+        } else {
+            Cow::Borrowed(&string)
+        }
+    };
+
+    Ok(Some(
+        NegatableGlobSet::new_with(dir_filters, |filter| {
+            if filter.starts_with('/') {
+                filter.to_string()
+            } else {
+                format!("{}/{}", workspace_root_dir_str, filter)
+            }
+        })
+        .map_err(FilterErrorInner::Glob)?,
+    ))
+}
+
 pub struct DefaultTaskFilter<'b, TGetTaskMetaFn>
 where
     TGetTaskMetaFn:
         for<'a> Fn(&'a TaskExecutionNode) -> Option<&'b MetaConfiguration>,
 {
-    task_matcher: Option<Arc<GlobSet>>,
+    task_matcher: Option<NegatableGlobSet>,
     meta_filter: Option<Evaluator>,
-    dir_matcher: Option<Arc<GlobSet>>,
-    project_matcher: Option<Arc<GlobSet>>,
+    dir_matcher: Option<NegatableGlobSet>,
+    project_matcher: Option<NegatableGlobSet>,
+    allowed_projects: Option<UnorderedSet<String>>,
     get_task_meta: TGetTaskMetaFn,
 }
 
@@ -85,7 +212,10 @@ where
         let task_matcher = if task_filters.is_empty() {
             None
         } else {
-            Some(build_glob_set(task_filters).map_err(FilterErrorInner::Glob)?)
+            Some(
+                NegatableGlobSet::new(task_filters)
+                    .map_err(FilterErrorInner::Glob)?,
+            )
         };
 
         let meta_filter = meta_filter
@@ -99,36 +229,7 @@ where
             None
         } else {
             Some(
-                build_glob_set(project_filters)
-                    .map_err(FilterErrorInner::Glob)?,
-            )
-        };
-
-        let string = workspace_root_dir.to_string_lossy();
-        let workspace_root_dir_str: Cow<str> = {
-            if cfg!(windows) {
-                Cow::Owned(string.replace("\\", "/"))
-            } else {
-                Cow::Borrowed(&string)
-            }
-        };
-
-        let dir_matcher = if dir_filters.is_empty() {
-            None
-        } else {
-            let dir_patterns = dir_filters
-                .iter()
-                .map(|filter| {
-                    if filter.starts_with('/') {
-                        (*filter).to_string()
-                    } else {
-                        format!("{}/{}", workspace_root_dir_str, filter)
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            Some(
-                build_glob_set(&dir_patterns)
+                NegatableGlobSet::new(project_filters)
                     .map_err(FilterErrorInner::Glob)?,
             )
         };
@@ -137,10 +238,21 @@ where
             task_matcher,
             meta_filter,
             project_matcher,
-            dir_matcher,
+            dir_matcher: dir_matcher(dir_filters, workspace_root_dir)?,
+            allowed_projects: None,
             get_task_meta,
         })
     }
+
+    /// Restricts the filter to tasks of the given projects, e.g. the ones
+    /// selected by a [`TagFilter`].
+    pub fn with_allowed_projects(
+        mut self,
+        allowed_projects: Option<UnorderedSet<String>>,
+    ) -> Self {
+        self.allowed_projects = allowed_projects;
+        self
+    }
 }
 
 impl<'b, TGetTaskMetaFn> TaskFilter for DefaultTaskFilter<'b, TGetTaskMetaFn>
@@ -158,6 +270,7 @@ where
             && self.meta_filter.is_none()
             && self.project_matcher.is_none()
             && self.dir_matcher.is_none()
+            && self.allowed_projects.is_none()
         {
             return Ok(true);
         }
@@ -176,6 +289,12 @@ where
             return Ok(false);
         }
 
+        if let Some(ap) = &self.allowed_projects
+            && !ap.contains(node.project_name())
+        {
+            return Ok(false);
+        }
+
         if let Some(tm) = &self.task_matcher
             && !tm.is_match(node.task_name())
         {
@@ -397,6 +516,99 @@ mod tests {
     use omni_command_config::CommandConfig;
     use omni_core::TaskExecutionNode;
 
+    fn tagged_project(name: &str, tags: &[&str]) -> Project {
+        let mut project = Project::new(
+            name,
+            std::path::PathBuf::from(""),
+            vec![],
+            Default::default(),
+        );
+        project.tags = tags.iter().map(|t| t.to_string()).collect();
+        project
+    }
+
+    #[test_log::test]
+    fn test_default_project_filter_tag_include_and_exclude() {
+        let tag_filter = TagFilter::new(
+            vec!["type:*".to_string()],
+            vec!["scope:legacy".to_string()],
+        );
+        let filter = DefaultProjectFilter::new(&[], Some(&tag_filter)).unwrap();
+
+        let app = tagged_project("app", &["type:app", "scope:web"]);
+        let legacy = tagged_project("legacy", &["type:app", "scope:legacy"]);
+        let untagged = tagged_project("untagged", &[]);
+
+        assert!(filter.should_include_project(&app).unwrap());
+        assert!(!filter.should_include_project(&legacy).unwrap());
+        assert!(!filter.should_include_project(&untagged).unwrap());
+    }
+
+    #[test_log::test]
+    fn test_default_project_filter_tags_combine_with_name_filter() {
+        let tag_filter = TagFilter::new(vec!["type:lib".to_string()], vec![]);
+        let filter =
+            DefaultProjectFilter::new(&["lib-*"], Some(&tag_filter)).unwrap();
+
+        assert!(
+            filter
+                .should_include_project(&tagged_project("lib-a", &["type:lib"]))
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .should_include_project(&tagged_project("lib-b", &["type:app"]))
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .should_include_project(&tagged_project("other", &["type:lib"]))
+                .unwrap()
+        );
+    }
+
+    #[test_log::test]
+    fn test_default_task_filter_allowed_projects() {
+        let tag_filter = TagFilter::new(vec!["type:app".to_string()], vec![]);
+        let allowed = TagMatcher::new(&tag_filter)
+            .unwrap()
+            .matching_project_names(&[
+                tagged_project("project1", &["type:app"]),
+                tagged_project("project2", &["type:lib"]),
+            ]);
+
+        let filter = DefaultTaskFilter::new(
+            &["test"],
+            &[],
+            &[],
+            Path::new(""),
+            None,
+            |_| None,
+        )
+        .unwrap()
+        .with_allowed_projects(Some(allowed));
+
+        let node = |project: &str| {
+            TaskExecutionNode::new(
+                "test".to_string(),
+                Some(CommandConfig::Shell("echo test".to_string())),
+                None,
+                project.to_string(),
+                std::path::PathBuf::from(""),
+                vec![],
+                true.into(),
+                false,
+                false,
+                None,
+                None,
+                None,
+            )
+        };
+
+        assert!(filter.should_include_task(&node("project1")).unwrap());
+        assert!(!filter.should_include_task(&node("project2")).unwrap());
+    }
+
     #[test_log::test]
     fn test_default_task_filter_project_name_and_meta_filter_matching_all() {
         let meta = MetaConfiguration::new(DictConfig::value(maps::map! {
@@ -425,6 +637,7 @@ mod tests {
             false,
             None,
             None,
+            None,
         );
         assert!(
             filter
@@ -458,6 +671,7 @@ mod tests {
             false,
             None,
             None,
+            None,
         );
 
         assert!(
@@ -492,6 +706,7 @@ mod tests {
             false,
             None,
             None,
+            None,
         );
 
         assert!(
@@ -502,6 +717,106 @@ mod tests {
         );
     }
 
+    #[test_log::test]
+    fn test_default_project_filter_dir_filter() {
+        let filter = DefaultProjectFilter::new(&[], None)
+            .unwrap()
+            .with_dir_filters(
+                &["packages/*", "!packages/legacy"],
+                Path::new("/ws"),
+            )
+            .unwrap();
+
+        let project = |dir: &str| {
+            Project::new(
+                "project",
+                std::path::PathBuf::from(dir),
+                vec![],
+                Default::default(),
+            )
+        };
+
+        assert!(
+            filter
+                .should_include_project(&project("/ws/packages/foo"))
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .should_include_project(&project("/ws/packages/legacy"))
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .should_include_project(&project("/ws/apps/foo"))
+                .unwrap()
+        );
+    }
+
+    #[test_log::test]
+    fn test_default_project_filter_negated_pattern() {
+        let filter =
+            DefaultProjectFilter::new(&["*", "!legacy-*"], None).unwrap();
+
+        assert!(
+            filter
+                .should_include_project(&tagged_project("app", &[]))
+                .unwrap()
+        );
+        assert!(
+            !filter
+                .should_include_project(&tagged_project("legacy-app", &[]))
+                .unwrap()
+        );
+    }
+
+    #[test_log::test]
+    fn test_default_task_filter_negated_task_project_and_dir_filters() {
+        let filter = DefaultTaskFilter::new(
+            &["!lint"],
+            &["!legacy-*"],
+            &["packages/*", "!packages/old"],
+            Path::new("/ws"),
+            None,
+            |_| None,
+        )
+        .unwrap();
+
+        let node = |task: &str, project: &str, dir: &str| {
+            TaskExecutionNode::new(
+                task.to_string(),
+                Some(CommandConfig::Shell("echo test".to_string())),
+                None,
+                project.to_string(),
+                std::path::PathBuf::from(dir),
+                vec![],
+                true.into(),
+                false,
+                false,
+                None,
+                None,
+                None,
+            )
+        };
+
+        let included = node("test", "app", "/ws/packages/app");
+        assert!(filter.should_include_task(&included).unwrap());
+
+        for excluded in [
+            node("lint", "app", "/ws/packages/app"),
+            node("test", "legacy-app", "/ws/packages/legacy-app"),
+            node("test", "app", "/ws/packages/old"),
+            node("test", "app", "/ws/apps/app"),
+        ] {
+            assert!(
+                !filter.should_include_task(&excluded).unwrap(),
+                "{} in {} should be excluded",
+                excluded.full_task_name(),
+                excluded.project_dir().display()
+            );
+        }
+    }
+
     #[test_log::test]
     fn test_default_task_filter_not_matching_dir_filter() {
         let filter = DefaultTaskFilter::new(
@@ -526,6 +841,7 @@ mod tests {
             false,
             None,
             None,
+            None,
         );
 
         assert!(
@@ -4,10 +4,12 @@ mod call;
 mod execution_plan_provider;
 mod filter;
 mod scm_config;
+mod tag_filter;
 mod traits;
 
 pub use call::*;
 pub use execution_plan_provider::*;
 pub use filter::*;
 pub use scm_config::*;
+pub use tag_filter::*;
 pub use traits::*;
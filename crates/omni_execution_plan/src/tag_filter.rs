@@ -0,0 +1,19 @@
+use derive_new::new;
+
+/// Selects projects by their tags. Both lists hold glob patterns matched
+/// against each tag, so `type:*` selects every project with a `type:` tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, new)]
+pub struct TagFilter {
+    /// A project is included if any of its tags matches any of these
+    /// patterns. Empty means every project is included.
+    pub include: Vec<String>,
+    /// A project is excluded if any of its tags matches any of these
+    /// patterns, even if it was included.
+    pub exclude: Vec<String>,
+}
+
+impl TagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+}
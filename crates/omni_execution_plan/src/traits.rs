@@ -4,7 +4,7 @@ use omni_configurations::MetaConfiguration;
 use omni_core::{Project, ProjectGraph, TaskExecutionNode};
 use omni_types::OmniPath;
 
-use crate::{Call, ScmAffectedFilter};
+use crate::{Call, ScmAffectedFilter, TagFilter};
 
 pub trait ExecutionPlanProvider {
     type Error: Error + Send + Sync + 'static;
@@ -16,6 +16,7 @@ pub trait ExecutionPlanProvider {
         project_filters: &[&str],
         dir_filters: &[&str],
         meta_filter: Option<&str>,
+        tag_filter: Option<&TagFilter>,
         scm_affected_filter: Option<&ScmAffectedFilter>,
         ignore_deps: bool,
         with_dependents: bool,
@@ -69,6 +69,17 @@ pub mod blake3 {
     ) -> Result<<Blake3Hasher as Hasher>::Hash, HasherError> {
         super::hash_bytes::<Blake3Hasher>(bytes)
     }
+
+    /// Same as [`hash_file_in_path`], but the file is streamed through the
+    /// hasher instead of being read into memory as a whole.
+    pub fn hash_file_streaming(
+        path: &Path,
+    ) -> Result<<Blake3Hasher as Hasher>::Hash, HasherError> {
+        let mut hasher = ::blake3::Hasher::new();
+        hasher.update_reader(std::fs::File::open(path)?)?;
+
+        Ok(*hasher.finalize().as_bytes())
+    }
 }
 
 pub mod default {
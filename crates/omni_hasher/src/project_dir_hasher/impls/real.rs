@@ -1,16 +1,12 @@
 use std::path::{Path, PathBuf};
 
-use crate::{
-    Hasher,
-    project_dir_hasher::{Compat, ProjectDirHasher},
-};
+use crate::{Hasher, project_dir_hasher::ProjectDirHasher};
 
 use super::utils;
 use derive_builder::Builder;
 use derive_new::new;
 use omni_types::{OmniPath, Root, enum_map};
 use path_clean::clean;
-use rs_merkle::MerkleTree;
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 use system_traits::impls::RealSys;
 
@@ -35,12 +31,12 @@ impl RealDirHasher {
 impl ProjectDirHasher for RealDirHasher {
     type Error = RealDirHasherError;
 
-    async fn hash_tree<THasher: Hasher>(
+    async fn hash_files<THasher: Hasher>(
         &self,
         project_name: &str,
         project_dir: &Path,
         files: &[OmniPath],
-    ) -> Result<MerkleTree<Compat<THasher>>, Self::Error> {
+    ) -> Result<Vec<(OmniPath, THasher::Hash)>, Self::Error> {
         let proj_dir = clean(project_dir);
         let ws_dir = clean(&self.workspace_root_dir);
 
@@ -75,8 +71,7 @@ impl ProjectDirHasher for RealDirHasher {
         // Sort from longest to shortest paths
         paths.sort_by(|a, b| b.cmp(a));
 
-        // Build a merkle tree of the paths
-        let tree = utils::build_merkle_tree::<THasher>(
+        let hashes = utils::hash_files::<THasher>(
             project_name,
             &bases,
             &paths,
@@ -85,7 +80,7 @@ impl ProjectDirHasher for RealDirHasher {
         )
         .await?;
 
-        Ok(tree)
+        Ok(hashes)
     }
 }
 
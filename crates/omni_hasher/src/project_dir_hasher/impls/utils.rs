@@ -9,7 +9,6 @@ use byteorder::{ByteOrder, LittleEndian};
 use futures::future::try_join_all;
 use omni_types::{OmniPath, Root, RootMap};
 use omni_utils::path::path_safe;
-use rs_merkle::MerkleTree;
 use serde::{Deserialize, Serialize};
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 use system_traits::{
@@ -17,10 +16,7 @@ use system_traits::{
     FsRenameAsync, FsWriteAsync, auto_impl,
 };
 
-use crate::{
-    Hasher, hash_file_in_path_async,
-    project_dir_hasher::{Compat, Hash},
-};
+use crate::{Hasher, hash_file_in_path_async, project_dir_hasher::Hash};
 
 #[auto_impl]
 pub trait UtilSys:
@@ -83,13 +79,15 @@ impl<THasher: Hasher> PartialEq for FileEntry<THasher> {
     }
 }
 
-pub async fn build_merkle_tree<THasher: Hasher>(
+/// Hash every file in `paths`, returning the hash of each file (which covers
+/// both its path and its content) in the order of `paths`.
+pub async fn hash_files<THasher: Hasher>(
     project_name: &str,
     root_map: &RootMap<'_>,
     paths: &[OmniPath],
     index_dir: &Path,
     sys: impl UtilSys,
-) -> Result<MerkleTree<Compat<THasher>>, BuildMerkleTreeError> {
+) -> Result<Vec<(OmniPath, THasher::Hash)>, BuildMerkleTreeError> {
     let project_dir_name = path_safe(project_name);
     let project_dir_path = index_dir.join(project_dir_name);
 
@@ -206,11 +204,7 @@ pub async fn build_merkle_tree<THasher: Hasher>(
         })?;
     }
 
-    let mut tree = MerkleTree::new();
-
-    tree.append(&mut new_hashes.iter().map(|h| h.hash).collect::<Vec<_>>());
-
-    Ok(tree)
+    Ok(new_hashes.into_iter().map(|h| (h.path, h.hash)).collect())
 }
 
 /// Generates a process-and-task-unique suffix for temp files so that
@@ -36,5 +36,23 @@ pub trait ProjectDirHasher {
         project_name: &str,
         project_dir: &Path,
         files: &[OmniPath],
-    ) -> Result<HashTree<Compat<THasher>>, Self::Error>;
+    ) -> Result<HashTree<Compat<THasher>>, Self::Error> {
+        let hashes = self
+            .hash_files::<THasher>(project_name, project_dir, files)
+            .await?;
+        let mut leaves = hashes.into_iter().map(|(_, h)| h).collect::<Vec<_>>();
+        let mut tree = HashTree::new();
+        tree.append(&mut leaves);
+
+        Ok(tree)
+    }
+
+    /// Returns the hash of each file, keyed by its rooted path, in the order
+    /// used to build the tree's leaves.
+    async fn hash_files<THasher: Hasher>(
+        &self,
+        project_name: &str,
+        project_dir: &Path,
+        files: &[OmniPath],
+    ) -> Result<Vec<(OmniPath, THasher::Hash)>, Self::Error>;
 }
@@ -0,0 +1,51 @@
+use omni_api::{GraphFormat, GraphResponse};
+use omni_scm::SelectScm;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GraphParams {
+    /// Tasks whose resolved execution graph to export. When empty, the
+    /// project graph is exported.
+    #[serde(default)]
+    pub tasks: Vec<String>,
+    /// Project filter, accepts glob patterns.
+    #[serde(default)]
+    pub project: Vec<String>,
+    /// Directory filter, accepts glob patterns.
+    #[serde(default)]
+    pub dir: Vec<String>,
+    /// CEL expression to match against task meta configuration. Without
+    /// tasks, it is matched against project meta configuration.
+    #[serde(default)]
+    pub meta: Option<String>,
+    /// Resolve the tasks without their dependencies.
+    #[serde(default)]
+    pub ignore_dependencies: bool,
+    /// Include the dependents of the matching tasks.
+    #[serde(default)]
+    pub with_dependents: bool,
+    /// SCM base commit for affected-files filtering.
+    #[serde(default)]
+    pub scm_base: Option<String>,
+    /// SCM target commit for affected-files filtering.
+    #[serde(default)]
+    pub scm_target: Option<String>,
+    /// SCM strategy for affected-files detection: `auto`, `git` or `none`.
+    /// A base or target commit without a strategy detects the SCM
+    /// automatically.
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub scm_affected: Option<SelectScm>,
+    /// Also render the graph as `dot` or `mermaid` text.
+    #[serde(default)]
+    pub format: Option<GraphFormat>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GraphResult {
+    #[serde(flatten)]
+    pub graph: GraphResponse,
+    /// The graph rendered in the requested format, if any.
+    pub rendered: Option<String>,
+}
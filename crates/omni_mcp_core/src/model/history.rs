@@ -0,0 +1,57 @@
+use omni_run_history::{Regression, RunRecord, TaskDuration};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunHistoryView {
+    /// The latest runs, newest first.
+    #[default]
+    List,
+    /// A single run with all of its tasks, the latest one if `run_id` is not
+    /// set.
+    Show,
+    /// The durations of `task` across the latest runs.
+    Task,
+    /// The tasks whose latest execution was slower than their previous ones.
+    Regressions,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunHistoryParams {
+    #[serde(default)]
+    pub view: RunHistoryView,
+    /// The run to show with the `show` view.
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// The full task name, `project#task`, required by the `task` view.
+    #[serde(default)]
+    pub task: Option<String>,
+    /// How many of the latest runs to look at with the `list` and `task`
+    /// views.
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// How many times slower than its baseline a task has to be with the
+    /// `regressions` view, `1.5` by default.
+    #[serde(default)]
+    pub threshold: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "view", rename_all = "snake_case")]
+pub enum RunHistoryResult {
+    List {
+        runs: Vec<RunRecord>,
+    },
+    Show {
+        run: Option<RunRecord>,
+    },
+    Task {
+        task: String,
+        durations: Vec<TaskDuration>,
+    },
+    Regressions {
+        runs: usize,
+        regressions: Vec<Regression>,
+    },
+}
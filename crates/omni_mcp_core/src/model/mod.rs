@@ -1,6 +1,8 @@
 pub mod cache;
 pub mod generator;
+pub mod graph;
 pub mod hash;
+pub mod history;
 pub mod project;
 pub mod task;
 pub mod tool;
@@ -8,7 +10,9 @@ pub mod workspace;
 
 pub use cache::*;
 pub use generator::*;
+pub use graph::*;
 pub use hash::*;
+pub use history::*;
 pub use project::*;
 pub use task::*;
 pub use tool::*;
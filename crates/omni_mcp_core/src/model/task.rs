@@ -6,10 +6,12 @@ use serde::{Deserialize, Serialize};
 pub struct TaskRunParams {
     /// Tasks to run.
     pub tasks: Vec<String>,
-    /// Project filter where tasks will be executed.
+    /// Project filter where tasks will be executed. Glob patterns, prefix
+    /// with `!` to exclude.
     #[serde(default)]
     pub project: Vec<String>,
-    /// Directory filter where tasks will be executed.
+    /// Directory filter where tasks will be executed. Glob patterns, prefix
+    /// with `!` to exclude.
     #[serde(default)]
     pub dir: Vec<String>,
     /// Dry run mode.
@@ -48,10 +50,12 @@ pub struct TaskExecutionSummary {
 pub struct ExecCommandParams {
     /// Command to execute
     pub cmd: Vec<String>,
-    /// Project filter where command will be executed
+    /// Project filter where command will be executed. Glob patterns, prefix
+    /// with `!` to exclude.
     #[serde(default)]
     pub project: Vec<String>,
-    /// Directory filter where command will be executed.
+    /// Directory filter where command will be executed. Glob patterns, prefix
+    /// with `!` to exclude.
     #[serde(default)]
     pub dir: Vec<String>,
     /// Dry run mode.
@@ -24,6 +24,9 @@ use system_traits::BaseFsMetadataAsync;
 /// workspace files are always read from disk and never stale. The stored
 /// subscriber is used only at the server level; tool operations always use
 /// [`NoopSubscriber`] so that their futures are unconditionally `Send`.
+///
+/// Read-only tools are served by the daemon of the workspace instead when one
+/// is running, see [`omni_daemon::daemon_or_local`].
 pub struct OmniMcpServer<TSys: ContextSys> {
     pub(crate) ctx: Context<TSys>,
 }
@@ -128,6 +131,7 @@ where
             "project_config" => {
                 call1(args, |p| self.tool_project_config(p)).await
             }
+            "graph" => call1(args, |p| self.tool_graph(p)).await,
             "generator_list" => call0(self.tool_generator_list()).await,
             "generator_inspect" => {
                 call1(args, |p| self.tool_generator_inspect(p)).await
@@ -142,6 +146,7 @@ where
             "hash_project" => call1(args, |p| self.tool_hash_project(p)).await,
             "cache_stats" => call1(args, |p| self.tool_cache_stats(p)).await,
             "cache_prune" => call1(args, |p| self.tool_cache_prune(p)).await,
+            "run_history" => call1(args, |p| self.tool_run_history(p)).await,
             "task_run" => call1(args, |p| self.tool_task_run(p)).await,
             "exec_command" => call1(args, |p| self.tool_exec_command(p)).await,
             "tool_list" => call0(self.tool_tool_list()).await,
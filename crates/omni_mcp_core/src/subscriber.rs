@@ -150,7 +150,7 @@ impl GeneratorEventSubscriber for McpSubscriber {}
 #[cfg(test)]
 mod tests {
     use super::*;
-    use omni_messages::TaskOutputStream;
+    use omni_messages::{TaskFailureReason, TaskOutputStream};
     use omni_task_output_logs::EffectiveOutputLogs;
     use std::time::Duration;
 
@@ -197,6 +197,7 @@ mod tests {
             task_id: task_id.to_string(),
             project: "proj".to_string(),
             task: "task".to_string(),
+            reason: TaskFailureReason::Error,
             error: "boom".to_string(),
             tries: 1,
         }
@@ -0,0 +1,51 @@
+use omni_api::{GraphFormat, GraphRequest};
+use omni_context::ContextSys;
+use omni_daemon::daemon_or_local;
+use omni_generator::GeneratorSys;
+use omni_scm::SelectScm;
+use omni_task_executor::TaskExecutorSys;
+
+use crate::{
+    model::{GraphParams, GraphResult},
+    server::OmniMcpServer,
+};
+
+impl<TSys> OmniMcpServer<TSys>
+where
+    TSys: ContextSys
+        + GeneratorSys
+        + TaskExecutorSys
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    pub(crate) async fn tool_graph(
+        &self,
+        params: GraphParams,
+    ) -> eyre::Result<GraphResult> {
+        let req = GraphRequest {
+            tasks: params.tasks,
+            ignore_dependencies: params.ignore_dependencies,
+            with_dependents: params.with_dependents,
+            meta: params.meta,
+            project: params.project,
+            dir: params.dir,
+            scm_base: params.scm_base,
+            scm_target: params.scm_target,
+            scm_affected: params.scm_affected.unwrap_or(SelectScm::None),
+        };
+        let graph = daemon_or_local(
+            &self.ctx,
+            async |daemon| daemon.graph(req.clone()).await,
+            async || self.make_api().graph(req.clone()).await,
+        )
+        .await?;
+        // The structured graph already is the JSON rendering.
+        let rendered = match params.format {
+            None | Some(GraphFormat::Json) => None,
+            Some(format) => Some(graph.render(format)?),
+        };
+        Ok(GraphResult { graph, rendered })
+    }
+}
@@ -1,4 +1,5 @@
 use omni_context::ContextSys;
+use omni_daemon::daemon_or_local;
 use omni_generator::GeneratorSys;
 use omni_task_executor::TaskExecutorSys;
 
@@ -18,7 +19,12 @@ where
         + 'static,
 {
     pub(crate) async fn tool_hash_workspace(&self) -> eyre::Result<HashResult> {
-        let response = self.make_api().hash_workspace().await?;
+        let response = daemon_or_local(
+            &self.ctx,
+            async |daemon| daemon.hash_workspace().await,
+            async || self.make_api().hash_workspace().await,
+        )
+        .await?;
         Ok(HashResult {
             hash: response.hash,
         })
@@ -28,10 +34,18 @@ where
         &self,
         params: HashProjectParams,
     ) -> eyre::Result<HashResult> {
-        let response = self
-            .make_api()
-            .hash_project(&params.name, &params.tasks)
-            .await?;
+        let response = daemon_or_local(
+            &self.ctx,
+            async |daemon| {
+                daemon.hash_project(&params.name, &params.tasks).await
+            },
+            async || {
+                self.make_api()
+                    .hash_project(&params.name, &params.tasks)
+                    .await
+            },
+        )
+        .await?;
         Ok(HashResult {
             hash: response.hash,
         })
@@ -0,0 +1,71 @@
+use omni_api::{
+    HistoryListRequest, HistoryRegressionsRequest, HistoryTaskRequest,
+};
+use omni_context::ContextSys;
+use omni_generator::GeneratorSys;
+use omni_task_executor::TaskExecutorSys;
+
+use crate::{
+    model::{RunHistoryParams, RunHistoryResult, RunHistoryView},
+    server::OmniMcpServer,
+};
+
+/// How many runs the `list` and `task` views look at by default.
+const DEFAULT_LIMIT: usize = 20;
+
+impl<TSys> OmniMcpServer<TSys>
+where
+    TSys: ContextSys
+        + GeneratorSys
+        + TaskExecutorSys
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    pub(crate) async fn tool_run_history(
+        &self,
+        params: RunHistoryParams,
+    ) -> eyre::Result<RunHistoryResult> {
+        let api = self.make_api();
+        let limit = Some(params.limit.unwrap_or(DEFAULT_LIMIT));
+
+        match params.view {
+            RunHistoryView::List => {
+                let response =
+                    api.history_list(HistoryListRequest { limit }).await?;
+                Ok(RunHistoryResult::List {
+                    runs: response.runs,
+                })
+            }
+            RunHistoryView::Show => {
+                let run = api.history_show(params.run_id.as_deref()).await?;
+                Ok(RunHistoryResult::Show { run })
+            }
+            RunHistoryView::Task => {
+                let Some(task) = params.task else {
+                    eyre::bail!("the 'task' view requires a task");
+                };
+                let response = api
+                    .history_task(HistoryTaskRequest { task, limit })
+                    .await?;
+                Ok(RunHistoryResult::Task {
+                    task: response.task,
+                    durations: response.durations,
+                })
+            }
+            RunHistoryView::Regressions => {
+                let response = api
+                    .history_regressions(HistoryRegressionsRequest {
+                        threshold: params.threshold,
+                        window: None,
+                    })
+                    .await?;
+                Ok(RunHistoryResult::Regressions {
+                    runs: response.runs,
+                    regressions: response.regressions,
+                })
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 pub mod cache;
 pub mod generator;
+pub mod graph;
 pub mod hash;
+pub mod history;
 pub mod project;
 pub mod task;
 pub mod tool;
@@ -25,6 +27,11 @@ pub fn tool_list() -> Vec<rmcp::model::Tool> {
             "Return full configuration for a named project including its tasks",
             true,
         ),
+        tool_typed::<GraphParams>(
+            "graph",
+            "Return the project graph, or the resolved task graph (with dependency and sibling edges) for the given tasks and filters",
+            true,
+        ),
         tool_noargs(
             "generator_list",
             "List all available generators in the workspace",
@@ -65,6 +72,11 @@ pub fn tool_list() -> Vec<rmcp::model::Tool> {
             "Prune stale cache entries. dry_run=true (default) shows what would be deleted without deleting",
             false,
         ),
+        tool_typed::<RunHistoryParams>(
+            "run_history",
+            "Inspect the recorded task runs: list the latest runs, show a run, follow the duration of a task across runs, or find the tasks that got slower",
+            true,
+        ),
         tool_typed::<TaskRunParams>(
             "task_run",
             "Execute named tasks with optional project/dir/dry_run filters",
@@ -1,4 +1,5 @@
 use omni_context::ContextSys;
+use omni_daemon::daemon_or_local;
 use omni_generator::GeneratorSys;
 use omni_task_executor::TaskExecutorSys;
 
@@ -23,7 +24,12 @@ where
     pub(crate) async fn tool_project_list(
         &self,
     ) -> eyre::Result<ProjectListResult> {
-        let projects = self.make_api().project_list().await?;
+        let projects = daemon_or_local(
+            &self.ctx,
+            async |daemon| daemon.project_list(Default::default()).await,
+            async || self.make_api().project_list().await,
+        )
+        .await?;
         Ok(ProjectListResult { projects })
     }
 
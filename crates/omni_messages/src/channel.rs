@@ -5,9 +5,9 @@ use tokio::sync::mpsc::{
 use crate::diagnostic::{DiagnosticEvent, DiagnosticSubscriber};
 use crate::execution::{
     CacheHitEvent, ExecutionCompleteEvent, ExecutionEventSubscriber,
-    ExecutionPlanReadyEvent, TaskCompletedEvent, TaskFailedEvent,
-    TaskOutputStreamEvent, TaskRetryingEvent, TaskSkippedEvent,
-    TaskStartedEvent,
+    ExecutionPlanReadyEvent, PhaseCompletedEvent, TaskCompletedEvent,
+    TaskFailedEvent, TaskOutputStreamEvent, TaskRetryingEvent,
+    TaskSkippedEvent, TaskStartedEvent,
 };
 use crate::generator::events::{
     GeneratorActionFailedEvent, GeneratorActionInProgressEvent,
@@ -33,6 +33,7 @@ pub enum OmniEventKind {
     CacheHit(CacheHitEvent),
     ExecutionComplete(ExecutionCompleteEvent),
     ExecutionPlanReady(ExecutionPlanReadyEvent),
+    PhaseCompleted(PhaseCompletedEvent),
     // Shared diagnostic
     Diagnostic(DiagnosticEvent),
     // Generator lifecycle
@@ -115,6 +116,9 @@ impl ExecutionEventSubscriber for ChannelSubscriber {
     async fn on_execution_plan_ready(&self, e: ExecutionPlanReadyEvent) {
         self.send(OmniEventKind::ExecutionPlanReady(e));
     }
+    async fn on_phase_completed(&self, e: PhaseCompletedEvent) {
+        self.send(OmniEventKind::PhaseCompleted(e));
+    }
 }
 
 impl GeneratorEventSubscriber for ChannelSubscriber {
@@ -27,6 +27,7 @@ pub struct TaskFailedEvent {
     pub task_id: String,
     pub project: String,
     pub task: String,
+    pub reason: TaskFailureReason,
     pub error: String,
     pub tries: u8,
 }
@@ -87,9 +88,59 @@ pub struct ExecutionCompleteEvent {
     pub total_time_saved: Duration,
 }
 
+/// Emitted when a step of the run finished, so that profilers can show where
+/// the time went. The event is emitted right after the phase ended; it
+/// started `elapsed` earlier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseCompletedEvent {
+    pub phase: ExecutionPhase,
+    /// The task the phase ran for, `None` for phases covering a whole batch
+    /// or run.
+    pub task_id: Option<String>,
+    pub elapsed: Duration,
+}
+
+// ─── Phase ───────────────────────────────────────────────────────────────────
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExecutionPhase {
+    /// Loading the workspace and discovering its projects. Reported by callers
+    /// that load the context themselves, the executor starts from a loaded
+    /// one.
+    #[strum(to_string = "discovery")]
+    Discovery,
+    /// Building the execution plan.
+    #[strum(to_string = "planning")]
+    Planning,
+    /// Resolving the contexts of the tasks of a batch.
+    #[strum(to_string = "task contexts")]
+    TaskContexts,
+    /// Hashing the inputs of the tasks of a batch and looking up their cached
+    /// results, including fetching them from the remote cache.
+    #[strum(to_string = "cache lookup")]
+    CacheLookup,
+    /// Replaying the logs and restoring the output files of a cached task.
+    #[strum(to_string = "cache restore")]
+    CacheRestore,
+    /// Preparing the command and sandbox of a task before its process runs.
+    #[strum(to_string = "spawn")]
+    Spawn,
+    /// One attempt of running the process of a task.
+    #[strum(to_string = "process")]
+    Process,
+    /// Archiving the outputs of the tasks of a batch into the cache.
+    #[strum(to_string = "cache store")]
+    CacheStore,
+}
+
 // ─── Skip reason ─────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Serialize, Deserialize, strum::Display)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, strum::Display,
+)]
 pub enum TaskSkipReason {
     #[strum(to_string = "task in a previous batch failed")]
     PreviousBatchFailure,
@@ -101,8 +152,24 @@ pub enum TaskSkipReason {
     NoCommand,
 }
 
+// ─── Failure reason ──────────────────────────────────────────────────────────
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display,
+)]
+pub enum TaskFailureReason {
+    #[strum(to_string = "exited with a non-zero exit code")]
+    ExitCode,
+    #[strum(to_string = "failed to run")]
+    Error,
+    #[strum(to_string = "timed out")]
+    TimedOut,
+}
+
+/// Sent once per run, before the scheduler starts the first task.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BatchStartEvent {}
+pub struct SchedulingStartEvent {}
 
+/// Sent once per run, after the last task of the plan finished.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BatchCompletedEvent {}
+pub struct SchedulingCompletedEvent {}
@@ -3,8 +3,9 @@ pub mod stream;
 pub mod subscriber;
 
 pub use events::{
-    CacheHitEvent, ExecutionCompleteEvent, ExecutionPlanReadyEvent,
-    TaskCompletedEvent, TaskFailedEvent, TaskRetryingEvent, TaskSkipReason,
+    CacheHitEvent, ExecutionCompleteEvent, ExecutionPhase,
+    ExecutionPlanReadyEvent, PhaseCompletedEvent, TaskCompletedEvent,
+    TaskFailedEvent, TaskFailureReason, TaskRetryingEvent, TaskSkipReason,
     TaskSkippedEvent, TaskStartedEvent,
 };
 pub use stream::{TaskOutputStream, TaskOutputStreamEvent};
@@ -1,10 +1,12 @@
 use crate::diagnostic::DiagnosticSubscriber;
-use crate::execution::events::{BatchCompletedEvent, BatchStartEvent};
+use crate::execution::events::{
+    SchedulingCompletedEvent, SchedulingStartEvent,
+};
 
 use super::events::{
     CacheHitEvent, ExecutionCompleteEvent, ExecutionPlanReadyEvent,
-    TaskCompletedEvent, TaskFailedEvent, TaskRetryingEvent, TaskSkippedEvent,
-    TaskStartedEvent,
+    PhaseCompletedEvent, TaskCompletedEvent, TaskFailedEvent,
+    TaskRetryingEvent, TaskSkippedEvent, TaskStartedEvent,
 };
 use super::stream::TaskOutputStreamEvent;
 
@@ -55,8 +57,18 @@ pub trait ExecutionEventSubscriber: DiagnosticSubscriber {
     async fn on_task_retrying(&self, _event: TaskRetryingEvent) {}
     async fn on_cache_hit(&self, _event: CacheHitEvent) {}
     async fn on_execution_complete(&self, _event: ExecutionCompleteEvent) {}
-    async fn on_batch_start(&self, _event: BatchStartEvent) {}
-    async fn on_batch_completed(&self, _event: BatchCompletedEvent) {}
+
+    /// Called once per run, before the first task starts. Tasks are started
+    /// as soon as their dependencies complete, so a run has no batches of
+    /// tasks to report.
+    async fn on_scheduling_start(&self, _event: SchedulingStartEvent) {}
+
+    /// Called once per run, after every task of the plan finished.
+    async fn on_scheduling_completed(&self, _event: SchedulingCompletedEvent) {}
+
+    /// Called after each internal phase of the run, see
+    /// [`ExecutionPhase`](super::events::ExecutionPhase).
+    async fn on_phase_completed(&self, _event: PhaseCompletedEvent) {}
 }
 
 /// Forward all subscriber calls through a shared reference.
@@ -98,11 +110,14 @@ impl<S: ExecutionEventSubscriber> ExecutionEventSubscriber for &S {
     async fn on_execution_complete(&self, event: ExecutionCompleteEvent) {
         S::on_execution_complete(*self, event).await
     }
-    async fn on_batch_completed(&self, _event: BatchCompletedEvent) {
-        S::on_batch_completed(*self, _event).await
+    async fn on_scheduling_completed(&self, _event: SchedulingCompletedEvent) {
+        S::on_scheduling_completed(*self, _event).await
+    }
+    async fn on_scheduling_start(&self, _event: SchedulingStartEvent) {
+        S::on_scheduling_start(*self, _event).await
     }
-    async fn on_batch_start(&self, _event: BatchStartEvent) {
-        S::on_batch_start(*self, _event).await
+    async fn on_phase_completed(&self, event: PhaseCompletedEvent) {
+        S::on_phase_completed(*self, event).await
     }
 }
 
@@ -34,9 +34,10 @@ pub use channel::{ChannelSubscriber, OmniEventKind};
 pub use diagnostic::{DiagnosticEvent, DiagnosticLevel, DiagnosticSubscriber};
 pub use execution::{
     CacheHitEvent, ExecutionCompleteEvent, ExecutionEventSubscriber,
-    ExecutionPlanReadyEvent, TaskCompletedEvent, TaskFailedEvent,
-    TaskOutputStream, TaskOutputStreamEvent, TaskRetryingEvent, TaskSkipReason,
-    TaskSkippedEvent, TaskStartedEvent,
+    ExecutionPhase, ExecutionPlanReadyEvent, PhaseCompletedEvent,
+    TaskCompletedEvent, TaskFailedEvent, TaskFailureReason, TaskOutputStream,
+    TaskOutputStreamEvent, TaskRetryingEvent, TaskSkipReason, TaskSkippedEvent,
+    TaskStartedEvent,
 };
 pub use generator::{
     GeneratorCompletedEvent, GeneratorEventSubscriber, GeneratorStartEvent,
@@ -21,8 +21,8 @@ mod tracing_impl {
     };
     use crate::execution::{
         CacheHitEvent, ExecutionCompleteEvent, ExecutionEventSubscriber,
-        ExecutionPlanReadyEvent, TaskCompletedEvent, TaskFailedEvent,
-        TaskRetryingEvent, TaskSkippedEvent, TaskStartedEvent,
+        ExecutionPlanReadyEvent, PhaseCompletedEvent, TaskCompletedEvent,
+        TaskFailedEvent, TaskRetryingEvent, TaskSkippedEvent, TaskStartedEvent,
     };
     use crate::generator::events::{
         GeneratorActionFailedEvent, GeneratorActionInProgressEvent,
@@ -76,7 +76,7 @@ mod tracing_impl {
             );
         }
         async fn on_task_failed(&self, e: TaskFailedEvent) {
-            t::debug!(task_id = %e.task_id, error = %e.error, tries = e.tries, "task_failed");
+            t::debug!(task_id = %e.task_id, reason = %e.reason, error = %e.error, tries = e.tries, "task_failed");
         }
         async fn on_task_skipped(&self, e: TaskSkippedEvent) {
             t::debug!(task_id = %e.task_id, reason = %e.reason, "task_skipped");
@@ -103,6 +103,14 @@ mod tracing_impl {
                 "execution_complete"
             );
         }
+        async fn on_phase_completed(&self, e: PhaseCompletedEvent) {
+            t::debug!(
+                phase = %e.phase,
+                task_id = e.task_id.as_deref(),
+                elapsed_ms = e.elapsed.as_millis(),
+                "phase_completed"
+            );
+        }
     }
 
     impl GeneratorEventSubscriber for TracingSubscriber {
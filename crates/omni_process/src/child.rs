@@ -4,6 +4,7 @@ use std::{
 };
 
 use derive_new::new;
+use omni_capability_enforcement::OsSandboxSpec;
 use portable_pty::{MasterPty, SlavePty, native_pty_system};
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 use system_traits::auto_impl;
@@ -39,6 +40,7 @@ impl Child {
         args: impl Into<Vec<String>>,
         cwd: impl Into<PathBuf>,
         env: impl Into<HashMap<OsString, OsString>>,
+        os_sandbox: Option<OsSandboxSpec>,
     ) -> Result<Self, ChildError> {
         let command = command.into();
         let args = args.into();
@@ -50,7 +52,8 @@ impl Child {
             span.record("args", format_args!("{args:?}"));
         }
 
-        let child = create_inner(command, args, cwd.into(), env.into())?;
+        let child =
+            create_inner(command, args, cwd.into(), env.into(), os_sandbox)?;
 
         match child {
             Command::Pty(child) => Self::spawn_pty(child),
@@ -357,6 +360,9 @@ pub(crate) enum ChildErrorInner {
     #[error("can't take input writer: {0}")]
     CantTakeInputWriter(String),
 
+    #[error("can't confine the process: {0}")]
+    CantConfine(String),
+
     #[error(transparent)]
     Io(#[from] io::Error),
 
@@ -372,8 +378,17 @@ fn create_inner(
     args: Vec<String>,
     cwd: PathBuf,
     env: HashMap<OsString, OsString>,
+    os_sandbox: Option<OsSandboxSpec>,
 ) -> Result<Command, ChildErrorInner> {
-    if should_use_pty() {
+    // The OS sandbox is installed as a `pre_exec` hook on a std `Command`,
+    // which a pty `CommandBuilder` does not expose, so a confined child always
+    // runs piped.
+    let os_sandbox = os_sandbox.filter(|spec| !spec.is_empty());
+    if os_sandbox.is_some() {
+        log::debug!("os sandbox requested; not using a pty");
+    }
+
+    if os_sandbox.is_none() && should_use_pty() {
         // Only consume command/args/env/cwd once we know the pty actually
         // opened; otherwise fall through to the piped path with them intact
         // (e.g. Windows builds older than 1809 with no ConPTY).
@@ -423,6 +438,25 @@ fn create_inner(
     std_cmd.current_dir(cwd);
     std_cmd.envs(env);
 
+    if let Some(spec) = os_sandbox {
+        // AppContainer is attached at process creation rather than installed
+        // onto a `Command`, so it needs a dedicated spawn path that tasks do
+        // not have yet; refuse rather than run unconfined.
+        if cfg!(windows)
+            && std::env::var_os("OMNI_DISABLE_OS_SANDBOX").is_none()
+        {
+            return Err(ChildErrorInner::CantConfine(
+                "the OS sandbox is not supported for task processes on Windows"
+                    .to_string(),
+            ));
+        }
+        omni_capability_enforcement::install_os_sandbox(
+            std_cmd.as_std_mut(),
+            &spec,
+        )
+        .map_err(|e| ChildErrorInner::CantConfine(e.to_string()))?;
+    }
+
     Ok(Command::Normal(StdCommand { cmd: std_cmd }))
 }
 
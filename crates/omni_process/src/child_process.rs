@@ -1,9 +1,13 @@
-use std::{collections::HashMap, ffi::OsString, path::PathBuf, pin::Pin};
+use std::{
+    collections::HashMap, ffi::OsString, path::PathBuf, pin::Pin,
+    time::Duration,
+};
 
 use bytes::{BufMut as _, Bytes, BytesMut};
 use derive_new::new;
 use futures::future::try_join_all;
 use maps::Map;
+use omni_capability_enforcement::OsSandboxSpec;
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 use system_traits::auto_impl;
 use tokio::io::{
@@ -12,7 +16,11 @@ use tokio::io::{
 };
 use trace::Level;
 
-use crate::{Child, ChildError};
+use crate::{Child, ChildError, utils::signal_process_tree};
+
+/// How long a timed-out process tree gets to exit after `SIGTERM` before it
+/// is killed.
+pub const DEFAULT_KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
 
 #[auto_impl]
 pub trait ChildProcessWriter: AsyncWrite + Send {}
@@ -48,6 +56,15 @@ pub struct ChildProcess {
 
     #[new(default)]
     empty_command_is_success: bool,
+
+    #[new(default)]
+    timeout: Option<Duration>,
+
+    #[new(default)]
+    kill_grace_period: Option<Duration>,
+
+    #[new(default)]
+    os_sandbox: Option<OsSandboxSpec>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, new)]
@@ -112,6 +129,26 @@ impl ChildProcess {
         self
     }
 
+    /// Terminate the process tree if it is still running after `timeout`.
+    pub fn timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How long to wait between the graceful and the forceful kill of a
+    /// timed-out process tree, defaults to [`DEFAULT_KILL_GRACE_PERIOD`].
+    pub fn kill_grace_period(&mut self, grace_period: Duration) -> &mut Self {
+        self.kill_grace_period = Some(grace_period);
+        self
+    }
+
+    /// Confine the process with the OS sandbox described by `spec`. A confined
+    /// process is always spawned with piped stdio rather than a pty.
+    pub fn os_sandbox(&mut self, spec: Option<OsSandboxSpec>) -> &mut Self {
+        self.os_sandbox = spec;
+        self
+    }
+
     #[cfg_attr(
         feature = "enable-tracing",
         tracing::instrument(level = Level::DEBUG, skip_all)
@@ -150,6 +187,7 @@ impl ChildProcess {
             args,
             std::mem::take(&mut self.current_dir),
             self.env_vars.take().unwrap_or_default(),
+            self.os_sandbox.take(),
         )?;
 
         let stdout = child
@@ -166,6 +204,11 @@ impl ChildProcess {
 
         let mut tasks = vec![];
 
+        let timeout = self.timeout;
+        let kill_grace_period =
+            self.kill_grace_period.unwrap_or(DEFAULT_KILL_GRACE_PERIOD);
+        let pid = child.pid();
+
         let mut writer = self.output_writer.take();
         let logs_output_task = tokio::spawn(async move {
             if !self.record_logs && writer.is_none() {
@@ -255,13 +298,32 @@ impl ChildProcess {
 
         let all_tasks = try_join_all(tasks);
 
-        let (logs_output, vec_result, exit_status) =
-            tokio::join!(logs_output_task, all_tasks, child.wait());
+        // Dropping this future before the child exits must not leak the
+        // process tree, e.g. when a watch loop restarts a persistent task.
+        let guard = pid.map(|pid| ProcessTreeGuard {
+            pid,
+            grace_period: kill_grace_period,
+        });
+
+        let (logs_output, vec_result, exit_status) = tokio::join!(
+            logs_output_task,
+            all_tasks,
+            wait_with_timeout(child, pid, timeout, kill_grace_period)
+        );
+
+        if let Some(guard) = guard {
+            guard.disarm();
+        }
 
         let _ = vec_result?;
         let logs = logs_output??;
 
-        let exit_code = exit_status?;
+        let exit_code = match exit_status? {
+            WaitOutcome::Exited(exit_code) => exit_code,
+            WaitOutcome::TimedOut(timeout) => {
+                return Err(ChildProcessError::timed_out(timeout));
+            }
+        };
 
         let elapsed = start_time.elapsed();
 
@@ -273,6 +335,81 @@ impl ChildProcess {
     }
 }
 
+/// Terminates the process tree of a child when dropped, unless disarmed once
+/// the child has exited.
+struct ProcessTreeGuard {
+    pid: u32,
+    grace_period: Duration,
+}
+
+impl ProcessTreeGuard {
+    fn disarm(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for ProcessTreeGuard {
+    fn drop(&mut self) {
+        let pid = self.pid;
+        let grace_period = self.grace_period;
+
+        log::debug!("process {pid} dropped while running, terminating");
+        signal_process_tree(pid, false);
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move {
+                tokio::time::sleep(grace_period).await;
+                signal_process_tree(pid, true);
+            });
+        }
+    }
+}
+
+enum WaitOutcome {
+    Exited(u32),
+    TimedOut(Duration),
+}
+
+/// Wait for `child` to exit. Once `timeout` elapses the process tree is asked
+/// to terminate, and killed if it is still alive after `grace_period`.
+async fn wait_with_timeout(
+    child: Child,
+    pid: Option<u32>,
+    timeout: Option<Duration>,
+    grace_period: Duration,
+) -> Result<WaitOutcome, ChildError> {
+    let wait = child.wait();
+
+    let (Some(timeout), Some(pid)) = (timeout, pid) else {
+        return wait.await.map(WaitOutcome::Exited);
+    };
+
+    tokio::pin!(wait);
+
+    tokio::select! {
+        exit_code = &mut wait => return exit_code.map(WaitOutcome::Exited),
+        _ = tokio::time::sleep(timeout) => {}
+    }
+
+    log::debug!("process {pid} timed out after {timeout:?}, terminating");
+    signal_process_tree(pid, false);
+
+    tokio::select! {
+        exit_code = &mut wait => {
+            exit_code?;
+        }
+        _ = tokio::time::sleep(grace_period) => {
+            log::debug!(
+                "process {pid} still running after {grace_period:?}, killing"
+            );
+            signal_process_tree(pid, true);
+            wait.await?;
+        }
+    }
+
+    Ok(WaitOutcome::TimedOut(timeout))
+}
+
 fn vars_os(vars: &Map<String, String>) -> HashMap<OsString, OsString> {
     vars.iter()
         .map(|(k, v)| (k.into(), v.into()))
@@ -301,6 +438,10 @@ impl ChildProcessError {
             args: args.into(),
         })
     }
+
+    pub fn timed_out(timeout: Duration) -> Self {
+        Self(ChildProcessErrorInner::TimedOut { timeout })
+    }
 }
 
 impl<T: Into<ChildProcessErrorInner>> From<T> for ChildProcessError {
@@ -354,6 +495,9 @@ pub(crate) enum ChildProcessErrorInner {
 
     #[error("empty program with args: program=\'{program}\' args={args:?}")]
     EmptyProgramWithArgs { program: String, args: Vec<String> },
+
+    #[error("timed out after {timeout:?}")]
+    TimedOut { timeout: Duration },
 }
 
 #[cfg(test)]
@@ -424,4 +568,82 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn times_out_and_terminates_long_running_process() {
+        let cwd = std::env::current_dir().unwrap();
+        let mut child = ChildProcess::new(
+            "sh",
+            vec!["-c".to_string(), "sleep 30".to_string()],
+            cwd,
+        );
+        child.timeout(Some(Duration::from_millis(200)));
+
+        let start = std::time::Instant::now();
+        let err = child.exec().await.expect_err("should time out");
+
+        assert_eq!(err.kind(), ChildProcessErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn kills_process_that_ignores_sigterm_after_grace_period() {
+        let cwd = std::env::current_dir().unwrap();
+        let mut child = ChildProcess::new(
+            "sh",
+            vec!["-c".to_string(), "trap '' TERM; sleep 30".to_string()],
+            cwd,
+        );
+        child
+            .timeout(Some(Duration::from_millis(200)))
+            .kill_grace_period(Duration::from_millis(200));
+
+        let start = std::time::Instant::now();
+        let err = child.exec().await.expect_err("should time out");
+
+        assert_eq!(err.kind(), ChildProcessErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn dropping_exec_terminates_process_tree() {
+        let cwd = std::env::current_dir().unwrap();
+        let marker = std::env::temp_dir()
+            .join(format!("omni-process-drop-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+
+        let mut child = ChildProcess::new(
+            "sh",
+            vec![
+                "-c".to_string(),
+                format!("sleep 1; touch '{}'", marker.display()),
+            ],
+            cwd,
+        );
+        child.kill_grace_period(Duration::from_millis(200));
+
+        let result =
+            tokio::time::timeout(Duration::from_millis(200), child.exec())
+                .await;
+        assert!(result.is_err(), "exec should still be running");
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        assert!(!marker.exists(), "process tree should have been terminated");
+    }
+
+    #[tokio::test]
+    async fn finishes_before_timeout_is_success() {
+        let cwd = std::env::current_dir().unwrap();
+        let (bin, args) = exit_zero_argv();
+        let mut child = ChildProcess::new(bin, args, cwd);
+        child.timeout(Some(Duration::from_secs(30)));
+
+        let result = child.exec().await.expect("should exit before timeout");
+
+        assert!(result.success());
+    }
 }
@@ -1,6 +1,9 @@
+use std::time::Duration;
+
 use bytes::Bytes;
 use derive_new::new;
 use maps::Map;
+use omni_capability_enforcement::OsSandboxSpec;
 use omni_core::TaskExecutionNode;
 use system_traits::auto_impl;
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -102,6 +105,22 @@ impl TaskChildProcess {
         self
     }
 
+    /// Terminate the task's process tree if it is still running after
+    /// `timeout`: `SIGTERM` first, then `SIGKILL` once the grace period ends.
+    pub fn timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.child_process.timeout(timeout);
+
+        self
+    }
+
+    /// Confine the task process with the OS sandbox lowered from its
+    /// capability policy. A confined task runs without a pty.
+    pub fn os_sandbox(&mut self, spec: Option<OsSandboxSpec>) -> &mut Self {
+        self.child_process.os_sandbox(spec);
+
+        self
+    }
+
     #[cfg_attr(
         feature = "enable-tracing",
         tracing::instrument(level = Level::DEBUG, skip_all, fields(task = self.task.full_task_name()))
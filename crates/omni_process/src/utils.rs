@@ -35,3 +35,52 @@ pub fn should_use_pty() -> bool {
 
     atty::is(atty::Stream::Stdout)
 }
+
+/// Ask the process tree rooted at `pid` to stop, or kill it outright when
+/// `force` is set.
+///
+/// Spawned children lead their own process group (`setsid`), so on unix the
+/// whole group is signalled with `SIGTERM`/`SIGKILL`. On Windows the tree is
+/// walked by `taskkill /T`. A tree that already exited is not an error.
+pub fn signal_process_tree(pid: u32, force: bool) {
+    #[cfg(unix)]
+    {
+        use nix::{
+            errno::Errno,
+            sys::signal::{Signal, killpg},
+            unistd::Pid,
+        };
+
+        let signal = if force {
+            Signal::SIGKILL
+        } else {
+            Signal::SIGTERM
+        };
+
+        match killpg(Pid::from_raw(pid as i32), signal) {
+            Ok(()) | Err(Errno::ESRCH) => {}
+            Err(e) => {
+                log::debug!(
+                    "failed to send {signal} to process group {pid}: {e}"
+                )
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        let mut cmd = std::process::Command::new("taskkill");
+        cmd.args(["/T", "/PID", &pid.to_string()]);
+        if force {
+            cmd.arg("/F");
+        }
+
+        if let Err(e) = cmd
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+        {
+            log::debug!("failed to run taskkill for process {pid}: {e}");
+        }
+    }
+}
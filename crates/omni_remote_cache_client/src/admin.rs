@@ -0,0 +1,307 @@
+use std::fmt;
+
+use derive_new::new;
+use reqwest::Response;
+use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use time::OffsetDateTime;
+
+use crate::RemoteCacheClientError;
+
+/// Connection details of the admin API of a remote cache service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, new)]
+pub struct RemoteAdminArgs<'a> {
+    pub api_base_url: &'a str,
+    /// The service's `--admin-api-key`
+    pub admin_api_key: &'a str,
+}
+
+/// A tenant, organization, workspace or environment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminEntity {
+    pub code: String,
+    pub display_name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, new)]
+pub struct NewAdminEntity {
+    pub code: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum All {
+    #[default]
+    #[serde(rename = "all")]
+    All,
+}
+
+/// Either every item, serialized as `"all"`, or the listed ones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AllOrSpecific {
+    All(All),
+    Specific(Vec<String>),
+}
+
+impl Default for AllOrSpecific {
+    fn default() -> Self {
+        Self::All(All::All)
+    }
+}
+
+impl From<Vec<String>> for AllOrSpecific {
+    /// An empty list means all of them.
+    fn from(items: Vec<String>) -> Self {
+        if items.is_empty() {
+            Self::default()
+        } else {
+            Self::Specific(items)
+        }
+    }
+}
+
+impl fmt::Display for AllOrSpecific {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All(_) => write!(f, "all"),
+            Self::Specific(items) => write!(f, "{}", items.join(", ")),
+        }
+    }
+}
+
+/// A stored API key. The key itself is never returned after it is minted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminApiKey {
+    pub id: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub scopes: AllOrSpecific,
+    pub tenants: AllOrSpecific,
+    pub organizations: AllOrSpecific,
+    pub workspaces: AllOrSpecific,
+    pub environments: AllOrSpecific,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct NewAdminApiKey {
+    pub description: Option<String>,
+    pub scopes: AllOrSpecific,
+    pub tenants: AllOrSpecific,
+    pub organizations: AllOrSpecific,
+    pub workspaces: AllOrSpecific,
+    pub environments: AllOrSpecific,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintedApiKey {
+    pub id: String,
+    pub key: String,
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+/// Client for the admin API of the remote cache service, which manages
+/// tenants, organizations, workspaces, environments and API keys.
+#[derive(Debug, Clone, new)]
+pub struct RemoteCacheAdminClient {
+    client: ClientWithMiddleware,
+}
+
+impl Default for RemoteCacheAdminClient {
+    fn default() -> Self {
+        Self::new(crate::default_impl::default_client())
+    }
+}
+
+fn admin_url(admin: &RemoteAdminArgs, path: &str) -> String {
+    format!("{}/v1/admin{path}", admin.api_base_url)
+}
+
+fn organizations_path(tenant: &str) -> String {
+    format!("/tenants/{tenant}/organizations")
+}
+
+fn workspaces_path(tenant: &str, org: &str) -> String {
+    format!("{}/{org}/workspaces", organizations_path(tenant))
+}
+
+fn environments_path(tenant: &str, org: &str, ws: &str) -> String {
+    format!("{}/{ws}/environments", workspaces_path(tenant, org))
+}
+
+async fn send(
+    admin: &RemoteAdminArgs<'_>,
+    request: RequestBuilder,
+) -> Result<Response, RemoteCacheClientError> {
+    let response = request
+        .header("X-API-KEY", admin.admin_api_key)
+        .send()
+        .await
+        .map_err(RemoteCacheClientError::custom)
+        .inspect_err(|e| {
+            trace::error!(error = ?e, "admin_request_failed");
+        })?;
+
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    #[derive(Deserialize)]
+    struct Problem {
+        detail: Option<String>,
+    }
+
+    let status = response.status();
+    let detail = response
+        .json::<Problem>()
+        .await
+        .ok()
+        .and_then(|p| p.detail)
+        .unwrap_or_default();
+
+    Err(RemoteCacheClientError::custom(eyre::eyre!(
+        "admin request failed with {status}: {detail}"
+    )))
+}
+
+async fn data<T: DeserializeOwned>(
+    response: Response,
+) -> Result<T, RemoteCacheClientError> {
+    Ok(response
+        .json::<Data<T>>()
+        .await
+        .map_err(RemoteCacheClientError::custom)?
+        .data)
+}
+
+impl RemoteCacheAdminClient {
+    async fn list<T: DeserializeOwned>(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        path: &str,
+    ) -> Result<T, RemoteCacheClientError> {
+        let request = self.client.get(admin_url(admin, path));
+        data(send(admin, request).await?).await
+    }
+
+    async fn create<B: Serialize, T: DeserializeOwned>(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        path: &str,
+        body: &B,
+    ) -> Result<T, RemoteCacheClientError> {
+        let request = self.client.post(admin_url(admin, path)).json(body);
+        data(send(admin, request).await?).await
+    }
+
+    pub async fn list_tenants(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+    ) -> Result<Vec<AdminEntity>, RemoteCacheClientError> {
+        self.list(admin, "/tenants").await
+    }
+
+    pub async fn create_tenant(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &NewAdminEntity,
+    ) -> Result<AdminEntity, RemoteCacheClientError> {
+        self.create(admin, "/tenants", tenant).await
+    }
+
+    pub async fn list_organizations(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &str,
+    ) -> Result<Vec<AdminEntity>, RemoteCacheClientError> {
+        self.list(admin, &organizations_path(tenant)).await
+    }
+
+    pub async fn create_organization(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &str,
+        org: &NewAdminEntity,
+    ) -> Result<AdminEntity, RemoteCacheClientError> {
+        self.create(admin, &organizations_path(tenant), org).await
+    }
+
+    pub async fn list_workspaces(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &str,
+        org: &str,
+    ) -> Result<Vec<AdminEntity>, RemoteCacheClientError> {
+        self.list(admin, &workspaces_path(tenant, org)).await
+    }
+
+    pub async fn create_workspace(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &str,
+        org: &str,
+        ws: &NewAdminEntity,
+    ) -> Result<AdminEntity, RemoteCacheClientError> {
+        self.create(admin, &workspaces_path(tenant, org), ws).await
+    }
+
+    pub async fn list_environments(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &str,
+        org: &str,
+        ws: &str,
+    ) -> Result<Vec<AdminEntity>, RemoteCacheClientError> {
+        self.list(admin, &environments_path(tenant, org, ws)).await
+    }
+
+    pub async fn create_environment(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        tenant: &str,
+        org: &str,
+        ws: &str,
+        env: &NewAdminEntity,
+    ) -> Result<AdminEntity, RemoteCacheClientError> {
+        self.create(admin, &environments_path(tenant, org, ws), env)
+            .await
+    }
+
+    pub async fn list_api_keys(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+    ) -> Result<Vec<AdminApiKey>, RemoteCacheClientError> {
+        self.list(admin, "/api-keys").await
+    }
+
+    /// Mint a new API key; the returned key can't be retrieved again.
+    pub async fn create_api_key(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        api_key: &NewAdminApiKey,
+    ) -> Result<MintedApiKey, RemoteCacheClientError> {
+        self.create(admin, "/api-keys", api_key).await
+    }
+
+    pub async fn revoke_api_key(
+        &self,
+        admin: &RemoteAdminArgs<'_>,
+        id: &str,
+    ) -> Result<(), RemoteCacheClientError> {
+        let request = self
+            .client
+            .post(admin_url(admin, &format!("/api-keys/{id}/revoke")));
+        send(admin, request).await?;
+        Ok(())
+    }
+}
@@ -3,36 +3,78 @@ use std::time::Duration;
 use async_trait::async_trait;
 use bytes::Bytes;
 use derive_new::new;
+use futures::StreamExt as _;
 use http::{Extensions, StatusCode, header};
 use reqwest::{Client, Request, Response, redirect::Policy};
 use reqwest_middleware::{
-    ClientBuilder, ClientWithMiddleware, Middleware, Next,
+    ClientBuilder, ClientWithMiddleware, Middleware, Next, RequestBuilder,
 };
 use reqwest_tracing::TracingMiddleware;
+use tokio::io::AsyncReadExt as _;
 
 use crate::{
-    RemoteAccessArgs, RemoteCacheClient, RemoteCacheClientError,
-    ValidateAccessResult,
+    ArtifactReader, ArtifactStream, RemoteAccessArgs, RemoteCacheClient,
+    RemoteCacheClientError, ValidateAccessResult,
 };
 
+/// Streamed artifacts larger than this are uploaded in chunks of this size.
+pub const DEFAULT_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+const DEFAULT_MAX_CHUNK_RETRIES: u32 = 3;
+
+/// Set by the service on `HEAD` of an uploaded chunk.
+const CHUNK_SIZE_HEADER: &str = "X-OMNI-CHUNK-SIZE";
+
+/// Set by the service on `HEAD` of an uploaded chunk, the hex encoded sha256
+/// digest of its content.
+const CHUNK_DIGEST_HEADER: &str = "X-OMNI-CHUNK-DIGEST";
+
+const TASK_HEADER: &str = "X-OMNI-TASK";
+
 #[derive(Debug, Clone, new)]
 pub struct DefaultRemoteCacheClient {
     client: ClientWithMiddleware,
+    #[new(value = "DEFAULT_CHUNK_SIZE")]
+    chunk_size: usize,
+    #[new(value = "DEFAULT_MAX_CHUNK_RETRIES")]
+    max_chunk_retries: u32,
+}
+
+impl DefaultRemoteCacheClient {
+    /// Split streamed uploads into chunks of `chunk_size` bytes; at most one
+    /// chunk is held in memory at a time.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// How many times a chunk that failed to upload with a transient error is
+    /// retried before the upload is abandoned.
+    pub fn with_max_chunk_retries(mut self, max_chunk_retries: u32) -> Self {
+        self.max_chunk_retries = max_chunk_retries;
+        self
+    }
+}
+
+pub(crate) fn default_client() -> ClientWithMiddleware {
+    ClientBuilder::new(
+        Client::builder()
+            .redirect(Policy::default())
+            .connect_timeout(Duration::from_secs(30))
+            .build()
+            .expect("must be able to build Client"),
+    )
+    .with(TracingMiddleware::default())
+    .with(LogRequestMiddleware)
+    .build()
 }
 
 impl Default for DefaultRemoteCacheClient {
     fn default() -> Self {
         Self {
-            client: ClientBuilder::new(
-                Client::builder()
-                    .redirect(Policy::default())
-                    .connect_timeout(Duration::from_secs(30))
-                    .build()
-                    .expect("must be able to build Client"),
-            )
-            .with(TracingMiddleware::default())
-            .with(LogRequestMiddleware)
-            .build(),
+            client: default_client(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            max_chunk_retries: DEFAULT_MAX_CHUNK_RETRIES,
         }
     }
 }
@@ -76,6 +118,18 @@ impl Middleware for LogRequestMiddleware {
     }
 }
 
+/// Tell the service which task an uploaded artifact belongs to, for its
+/// per-task retention caps.
+fn with_task(
+    request: RequestBuilder,
+    remote: &RemoteAccessArgs,
+) -> RequestBuilder {
+    match remote.task {
+        Some(task) => request.header(TASK_HEADER, task),
+        None => request,
+    }
+}
+
 fn create_url(remote: &RemoteAccessArgs, digest: &str) -> String {
     format!(
         "{base_url}/v1/artifacts/{digest}?org={org}&ws={ws}&env={env}",
@@ -97,6 +151,134 @@ fn create_url_no_digest(remote: &RemoteAccessArgs) -> String {
     )
 }
 
+fn create_chunk_url(
+    remote: &RemoteAccessArgs,
+    digest: &str,
+    index: u32,
+) -> String {
+    format!(
+        "{base_url}/v1/artifacts/{digest}/chunks/{index}?org={org}&ws={ws}&env={env}",
+        base_url = remote.api_base_url,
+        org = remote.org,
+        ws = remote.ws,
+        env = remote.env,
+    )
+}
+
+fn create_complete_chunks_url(
+    remote: &RemoteAccessArgs,
+    digest: &str,
+    count: u32,
+) -> String {
+    format!(
+        "{base_url}/v1/artifacts/{digest}/chunks?org={org}&ws={ws}&env={env}&count={count}",
+        base_url = remote.api_base_url,
+        org = remote.org,
+        ws = remote.ws,
+        env = remote.env,
+    )
+}
+
+/// Read up to `size` bytes; fewer are only returned at the end of `reader`.
+async fn read_chunk(
+    reader: &mut ArtifactReader,
+    size: usize,
+) -> std::io::Result<Bytes> {
+    let mut buf = Vec::with_capacity(size);
+    reader
+        .as_mut()
+        .take(size as u64)
+        .read_to_end(&mut buf)
+        .await?;
+
+    Ok(Bytes::from(buf))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+impl DefaultRemoteCacheClient {
+    async fn put_artifact_chunk(
+        &self,
+        remote: &RemoteAccessArgs<'_>,
+        digest: &str,
+        index: u32,
+        chunk: Bytes,
+    ) -> Result<(), RemoteCacheClientError> {
+        let url = create_chunk_url(remote, digest, index);
+
+        // Resume an interrupted upload: a chunk the service already holds is
+        // not sent again. It may be left over from an upload of other content
+        // of the same size, so the content is compared by its digest.
+        let existing = self
+            .client
+            .head(&url)
+            .header("X-API-KEY", remote.api_key)
+            .header("X-OMNI-TENANT", remote.tenant)
+            .send()
+            .await;
+
+        if let Ok(response) = existing
+            && response.status().is_success()
+            && response
+                .headers()
+                .get(CHUNK_SIZE_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .and_then(|s| s.parse::<usize>().ok())
+                == Some(chunk.len())
+            && response
+                .headers()
+                .get(CHUNK_DIGEST_HEADER)
+                .and_then(|h| h.to_str().ok())
+                .is_some_and(|d| d.eq_ignore_ascii_case(&sha256_hex(&chunk)))
+        {
+            trace::debug!(index, "artifact_chunk_already_uploaded");
+            return Ok(());
+        }
+
+        let mut attempt = 0;
+        loop {
+            let response = self
+                .client
+                .put(&url)
+                .header("X-API-KEY", remote.api_key)
+                .header("X-OMNI-TENANT", remote.tenant)
+                .body(chunk.clone())
+                .send()
+                .await;
+
+            let retryable = match &response {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(());
+                }
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            };
+
+            if !retryable || attempt >= self.max_chunk_retries {
+                let error = match response {
+                    Ok(response) => eyre::eyre!(
+                        "put artifact chunk {} failed: status code {}",
+                        index,
+                        response.status()
+                    ),
+                    Err(e) => eyre::Report::new(e),
+                };
+                trace::error!(error = ?error, index, "put_artifact_chunk_failed");
+                return Err(RemoteCacheClientError::custom(error));
+            }
+
+            attempt += 1;
+            tokio::time::sleep(Duration::from_millis(200 << attempt)).await;
+        }
+    }
+}
+
 #[async_trait]
 impl RemoteCacheClient for DefaultRemoteCacheClient {
     async fn get_artifact(
@@ -216,12 +398,13 @@ impl RemoteCacheClient for DefaultRemoteCacheClient {
     ) -> Result<(), RemoteCacheClientError> {
         let url = create_url(remote, digest);
 
-        let response = self
+        let request = self
             .client
             .put(url)
             .header("X-API-KEY", remote.api_key)
             .header("X-OMNI-TENANT", remote.tenant)
-            .body(artifact)
+            .body(artifact);
+        let response = with_task(request, remote)
             .send()
             .await
             .map_err(RemoteCacheClientError::custom)
@@ -238,6 +421,101 @@ impl RemoteCacheClient for DefaultRemoteCacheClient {
             )))
         }
     }
+
+    async fn get_artifact_stream(
+        &self,
+        remote: &RemoteAccessArgs,
+        digest: &str,
+    ) -> Result<Option<ArtifactStream>, RemoteCacheClientError> {
+        let url = create_url(remote, digest);
+
+        let response = self
+            .client
+            .get(url)
+            .header("X-API-KEY", remote.api_key)
+            .header("X-OMNI-TENANT", remote.tenant)
+            .send()
+            .await
+            .map_err(RemoteCacheClientError::custom)
+            .inspect_err(|e| {
+                trace::error!(error = ?e, "get_artifact_stream_failed");
+            })?;
+
+        let status = response.status();
+
+        if status.is_success() {
+            Ok(Some(
+                response
+                    .bytes_stream()
+                    .map(|r| r.map_err(RemoteCacheClientError::custom))
+                    .boxed(),
+            ))
+        } else {
+            match status {
+                StatusCode::NOT_FOUND => Ok(None),
+                _ => Err(RemoteCacheClientError::custom(eyre::eyre!(
+                    "get artifact failed: status code {}",
+                    status
+                ))),
+            }
+        }
+    }
+
+    /// Artifacts that fit in a single chunk are sent in one request. Larger
+    /// ones are uploaded chunk by chunk, each retried on transient failures
+    /// and skipped when a previous, interrupted upload already delivered it,
+    /// then assembled by the service.
+    async fn put_artifact_stream(
+        &self,
+        remote: &RemoteAccessArgs,
+        digest: &str,
+        mut artifact: ArtifactReader,
+    ) -> Result<(), RemoteCacheClientError> {
+        let mut count = 0u32;
+
+        loop {
+            let chunk = read_chunk(&mut artifact, self.chunk_size)
+                .await
+                .map_err(RemoteCacheClientError::custom)?;
+            let is_last = chunk.len() < self.chunk_size;
+
+            if count == 0 && is_last {
+                return self.put_artifact(remote, digest, chunk).await;
+            }
+
+            if !chunk.is_empty() {
+                self.put_artifact_chunk(remote, digest, count, chunk)
+                    .await?;
+                count += 1;
+            }
+
+            if is_last {
+                break;
+            }
+        }
+
+        let request = self
+            .client
+            .post(create_complete_chunks_url(remote, digest, count))
+            .header("X-API-KEY", remote.api_key)
+            .header("X-OMNI-TENANT", remote.tenant);
+        let response = with_task(request, remote)
+            .send()
+            .await
+            .map_err(RemoteCacheClientError::custom)
+            .inspect_err(|e| {
+                trace::error!(error = ?e, "complete_artifact_chunks_failed");
+            })?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(RemoteCacheClientError::custom(eyre::eyre!(
+                "completing chunked upload failed: status code {}",
+                response.status()
+            )))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -246,6 +524,7 @@ mod tests {
 
     use bytes::Bytes;
     use eyre::Context as _;
+    use futures::TryStreamExt as _;
     use ntest::timeout;
 
     use crate::{
@@ -270,6 +549,7 @@ mod tests {
             org: DEFAULT_ORG,
             tenant: DEFAULT_TENANT,
             ws: DEFAULT_WS,
+            task: None,
         }
     }
 
@@ -411,4 +691,121 @@ mod tests {
         assert!(resp.is_ok(), "validate_access failed: {:?}", resp);
         assert!(!resp.unwrap().is_valid);
     }
+
+    #[tokio::test]
+    #[test_log::test]
+    #[timeout(5000)]
+    async fn test_put_artifact_stream_single_chunk() {
+        let guard = ChildProcessGuard::new(&HOST).await;
+        let client = DefaultRemoteCacheClient::default();
+        let remote = def_remote_access_args(&guard.api_base_url);
+
+        let resp = client
+            .put_artifact_stream(
+                &remote,
+                DEFAULT_DIGEST,
+                Box::pin(&DEFAULT_BODY[..]),
+            )
+            .await;
+
+        assert!(resp.is_ok(), "put_artifact_stream failed: {:?}", resp);
+
+        let resp = client.get_artifact(&remote, DEFAULT_DIGEST).await;
+
+        assert_eq!(resp.unwrap().unwrap(), DEFAULT_BODY);
+    }
+
+    #[tokio::test]
+    #[test_log::test]
+    #[timeout(5000)]
+    async fn test_put_artifact_stream_chunked() {
+        let guard = ChildProcessGuard::new(&HOST).await;
+        let client = DefaultRemoteCacheClient::default().with_chunk_size(4);
+        let remote = def_remote_access_args(&guard.api_base_url);
+
+        let resp = client
+            .put_artifact_stream(
+                &remote,
+                DEFAULT_DIGEST,
+                Box::pin(&DEFAULT_BODY[..]),
+            )
+            .await;
+
+        assert!(resp.is_ok(), "put_artifact_stream failed: {:?}", resp);
+
+        let resp = client.get_artifact(&remote, DEFAULT_DIGEST).await;
+
+        assert_eq!(resp.unwrap().unwrap(), DEFAULT_BODY);
+    }
+
+    #[tokio::test]
+    #[test_log::test]
+    #[timeout(5000)]
+    async fn test_put_artifact_stream_replaces_stale_chunks() {
+        let guard = ChildProcessGuard::new(&HOST).await;
+        let client = DefaultRemoteCacheClient::default().with_chunk_size(4);
+        let remote = def_remote_access_args(&guard.api_base_url);
+
+        // left over from an interrupted upload of other content
+        client
+            .put_artifact_chunk(
+                &remote,
+                DEFAULT_DIGEST,
+                0,
+                Bytes::from_static(b"XXXX"),
+            )
+            .await
+            .expect("put_artifact_chunk failed");
+
+        client
+            .put_artifact_stream(
+                &remote,
+                DEFAULT_DIGEST,
+                Box::pin(&DEFAULT_BODY[..]),
+            )
+            .await
+            .expect("put_artifact_stream failed");
+
+        let resp = client.get_artifact(&remote, DEFAULT_DIGEST).await;
+
+        assert_eq!(resp.unwrap().unwrap(), DEFAULT_BODY);
+    }
+
+    #[tokio::test]
+    #[test_log::test]
+    #[timeout(5000)]
+    async fn test_get_artifact_stream() {
+        let guard = ChildProcessGuard::new(&HOST).await;
+        let client = DefaultRemoteCacheClient::default();
+        let remote = def_remote_access_args(&guard.api_base_url);
+
+        client
+            .put_artifact(&remote, DEFAULT_DIGEST, DEFAULT_BODY)
+            .await
+            .expect("put_artifact failed");
+
+        let stream = client
+            .get_artifact_stream(&remote, DEFAULT_DIGEST)
+            .await
+            .expect("get_artifact_stream failed")
+            .expect("artifact not found");
+        let chunks: Vec<Bytes> =
+            stream.try_collect().await.expect("stream failed");
+
+        assert_eq!(chunks.concat(), DEFAULT_BODY);
+    }
+
+    #[tokio::test]
+    #[test_log::test]
+    #[timeout(5000)]
+    async fn test_get_artifact_stream_not_found() {
+        let guard = ChildProcessGuard::new(&HOST).await;
+        let client = DefaultRemoteCacheClient::default();
+        let remote = def_remote_access_args(&guard.api_base_url);
+
+        let resp = client.get_artifact_stream(&remote, DEFAULT_DIGEST).await;
+
+        assert!(resp.is_ok(), "get_artifact_stream failed: {:?}", resp.err());
+        assert!(resp.unwrap().is_none());
+    }
 }
@@ -1,9 +1,11 @@
 #![allow(clippy::redundant_field_names)]
 
+mod admin;
 mod default_impl;
 #[cfg(test)]
 mod test_utils;
 mod traits;
 
+pub use admin::*;
 pub use default_impl::*;
 pub use traits::*;
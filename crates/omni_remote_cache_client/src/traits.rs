@@ -1,7 +1,11 @@
+use std::pin::Pin;
+
 use async_trait::async_trait;
 use bytes::Bytes;
 use derive_new::new;
+use futures::{Stream, StreamExt as _};
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
+use tokio::io::{AsyncRead, AsyncReadExt as _};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, new)]
 pub struct RemoteAccessArgs<'a> {
@@ -11,6 +15,10 @@ pub struct RemoteAccessArgs<'a> {
     pub org: &'a str,
     pub ws: &'a str,
     pub env: &'a str,
+    /// The task an uploaded artifact belongs to, in the form of
+    /// `project#task`
+    #[new(default)]
+    pub task: Option<&'a str>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, new)]
@@ -19,6 +27,13 @@ pub struct ValidateAccessResult {
     pub message: Option<String>,
 }
 
+/// The body of a downloaded artifact, yielded as it arrives.
+pub type ArtifactStream =
+    Pin<Box<dyn Stream<Item = Result<Bytes, RemoteCacheClientError>> + Send>>;
+
+/// The contents of an artifact to upload, read as it is sent.
+pub type ArtifactReader = Pin<Box<dyn AsyncRead + Send>>;
+
 #[async_trait]
 pub trait RemoteCacheClient: Send + Sync + 'static {
     async fn validate_access(
@@ -44,6 +59,42 @@ pub trait RemoteCacheClient: Send + Sync + 'static {
         digest: &str,
         artifact: Bytes,
     ) -> Result<(), RemoteCacheClientError>;
+
+    /// Like [`RemoteCacheClient::get_artifact`], but without buffering the
+    /// whole artifact in memory.
+    ///
+    /// The default implementation falls back to `get_artifact`.
+    async fn get_artifact_stream(
+        &self,
+        remote: &RemoteAccessArgs,
+        digest: &str,
+    ) -> Result<Option<ArtifactStream>, RemoteCacheClientError> {
+        let artifact = self.get_artifact(remote, digest).await?;
+
+        Ok(artifact.map(|bytes| {
+            futures::stream::once(async move { Ok(bytes) }).boxed()
+        }))
+    }
+
+    /// Like [`RemoteCacheClient::put_artifact`], but without buffering the
+    /// whole artifact in memory.
+    ///
+    /// The default implementation reads the artifact to the end and falls
+    /// back to `put_artifact`.
+    async fn put_artifact_stream(
+        &self,
+        remote: &RemoteAccessArgs,
+        digest: &str,
+        mut artifact: ArtifactReader,
+    ) -> Result<(), RemoteCacheClientError> {
+        let mut bytes = Vec::new();
+        artifact
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(RemoteCacheClientError::custom)?;
+
+        self.put_artifact(remote, digest, Bytes::from(bytes)).await
+    }
 }
 
 #[derive(Debug, thiserror::Error, new)]
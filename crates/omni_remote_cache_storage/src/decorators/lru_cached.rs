@@ -103,6 +103,13 @@ where
         self.inner.list(container).await
     }
 
+    async fn list_containers(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<String>, Error> {
+        self.inner.list_containers(prefix).await
+    }
+
     async fn paged_list(
         &self,
         container: Option<&str>,
@@ -135,6 +135,58 @@ impl RemoteCacheStorageBackend for LocalDiskCacheBackend {
         Ok(items)
     }
 
+    async fn list_containers(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<String>, Error> {
+        // only the directory the prefix ends in has to be walked
+        let start = prefix.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let mut dirs = vec![self.root.join(start)];
+        let mut containers = Vec::new();
+
+        while let Some(dir) = dirs.pop() {
+            if !tokio::fs::try_exists(&dir).await.map_err(Error::custom)? {
+                continue;
+            }
+
+            let mut read_dir =
+                tokio::fs::read_dir(&dir).await.map_err(Error::custom)?;
+            let mut has_items = false;
+
+            while let Some(entry) =
+                read_dir.next_entry().await.map_err(Error::custom)?
+            {
+                if entry.file_type().await.map_err(Error::custom)?.is_dir() {
+                    dirs.push(entry.path());
+                } else {
+                    has_items = true;
+                }
+            }
+
+            if !has_items {
+                continue;
+            }
+
+            let container = dir
+                .strip_prefix(&self.root)
+                .map_err(Error::custom)?
+                .components()
+                .map(|c| {
+                    c.as_os_str().to_str().ok_or_else(|| {
+                        Error::custom(eyre::eyre!("invalid path"))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .join("/");
+
+            if container.starts_with(prefix) {
+                containers.push(container);
+            }
+        }
+
+        Ok(containers)
+    }
+
     async fn paged_list(
         &self,
         container: Option<&str>,
@@ -105,6 +105,22 @@ impl RemoteCacheStorageBackend for InMemoryBackend {
         Ok(paged_result)
     }
 
+    async fn list_containers(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<String>, Error> {
+        Ok(self
+            .containers
+            .lock()
+            .await
+            .iter()
+            .filter(|(name, items)| {
+                name.starts_with(prefix) && !items.is_empty()
+            })
+            .map(|(name, _)| name.clone())
+            .collect())
+    }
+
     async fn paged_list(
         &self,
         container: Option<&str>,
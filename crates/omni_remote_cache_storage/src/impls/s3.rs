@@ -295,6 +295,57 @@ impl RemoteCacheStorageBackend for S3CacheBackend {
         Ok(items)
     }
 
+    async fn list_containers(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<String>, Error> {
+        if !self.multi_bucket {
+            let buckets = self
+                .client
+                .list_buckets()
+                .send()
+                .await
+                .map_err(Error::custom)?;
+
+            return Ok(buckets
+                .buckets
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|bucket| bucket.name)
+                .filter(|name| name.starts_with(prefix))
+                .collect());
+        }
+
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.default_bucket)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+        let mut containers = Vec::<String>::new();
+
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(Error::custom)?;
+
+            for item in page.contents.unwrap_or_default() {
+                let Some((container, _)) =
+                    item.key.as_deref().and_then(|key| key.rsplit_once('/'))
+                else {
+                    continue;
+                };
+
+                // keys are listed in order, so the keys of a container are
+                // listed together
+                if containers.last().is_none_or(|last| last != container) {
+                    containers.push(container.to_string());
+                }
+            }
+        }
+
+        Ok(containers)
+    }
+
     async fn paged_list(
         &self,
         container: Option<&str>,
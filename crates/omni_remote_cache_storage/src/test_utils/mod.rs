@@ -142,6 +142,32 @@ macro_rules! decl_remote_cache_storage_backend_tests {
             assert_eq!(list[1].size, ByteSize::b(6));
         }
 
+        #[tokio::test]
+        async fn test_list_containers() {
+            let backend = backend().await;
+
+            backend
+                .save(Some("container1/nested"), "key1", Bytes::from("value1"))
+                .await
+                .unwrap();
+
+            let mut all = backend
+                .list_containers("")
+                .await
+                .expect("should have no error");
+            all.sort();
+
+            assert_eq!(all, ["container1", "container1/nested", "default"]);
+
+            let mut nested = backend
+                .list_containers("container1/")
+                .await
+                .expect("should have no error");
+            nested.sort();
+
+            assert_eq!(nested, ["container1/nested"]);
+        }
+
         #[tokio::test]
         async fn test_save() {
             let backend = backend().await;
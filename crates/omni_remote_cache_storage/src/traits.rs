@@ -50,6 +50,11 @@ pub trait RemoteCacheStorageBackend {
         page_options: PageOptions,
     ) -> Result<Vec<ListItem>, Error>;
 
+    /// The names of the stored containers starting with `prefix`. Containers
+    /// nested in a listed container are listed on their own.
+    async fn list_containers(&self, prefix: &str)
+    -> Result<Vec<String>, Error>;
+
     async fn save(
         &self,
         container: Option<&str>,
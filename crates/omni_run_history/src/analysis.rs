@@ -0,0 +1,242 @@
+use std::{collections::HashMap, time::Duration};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::{RunRecord, TaskRunStatus};
+
+/// The duration of a task in one run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskDuration {
+    pub run_id: String,
+    #[schemars(with = "String")]
+    pub started_at: OffsetDateTime,
+    pub status: TaskRunStatus,
+    pub elapsed: Option<Duration>,
+    pub cache_hit: bool,
+}
+
+/// The durations of `task` across `runs`, in the order of `runs`.
+pub fn task_durations(runs: &[RunRecord], task: &str) -> Vec<TaskDuration> {
+    runs.iter()
+        .filter_map(|run| {
+            run.task(task).map(|t| TaskDuration {
+                run_id: run.id.clone(),
+                started_at: run.started_at,
+                status: t.status,
+                elapsed: t.elapsed,
+                cache_hit: t.cache_hit,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegressionOptions {
+    /// How many times slower than its baseline a task has to be.
+    pub threshold: f64,
+    /// How many of the previous executions make up the baseline.
+    pub window: usize,
+    /// How many previous executions are needed before a task is compared.
+    pub min_samples: usize,
+    /// How much slower than its baseline a task has to be, so that short
+    /// tasks don't get reported for noise.
+    pub min_delta: Duration,
+}
+
+impl Default for RegressionOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 1.5,
+            window: 10,
+            min_samples: 3,
+            min_delta: Duration::from_secs(1),
+        }
+    }
+}
+
+/// A task whose latest execution was slower than its baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Regression {
+    pub task: String,
+    /// The run of the latest execution.
+    pub run_id: String,
+    pub elapsed: Duration,
+    /// The median duration of the previous executions.
+    pub baseline: Duration,
+    pub ratio: f64,
+}
+
+/// Compare the latest execution of every task in `runs`, newest first, to
+/// the median of its previous executions. Cache hits and failed executions
+/// are left out since their durations don't reflect the work of the task.
+///
+/// Regressions are sorted by their ratio, largest first.
+pub fn regressions(
+    runs: &[RunRecord],
+    options: &RegressionOptions,
+) -> Vec<Regression> {
+    let mut samples: HashMap<&str, Vec<(&str, Duration)>> = HashMap::new();
+
+    for run in runs {
+        for task in &run.tasks {
+            if let (TaskRunStatus::Succeeded, false, Some(elapsed)) =
+                (task.status, task.cache_hit, task.elapsed)
+            {
+                samples
+                    .entry(task.task.as_str())
+                    .or_default()
+                    .push((run.id.as_str(), elapsed));
+            }
+        }
+    }
+
+    let mut regressions = samples
+        .into_iter()
+        .filter_map(|(task, samples)| {
+            let ((run_id, elapsed), previous) = samples.split_first()?;
+            let previous = &previous[..previous.len().min(options.window)];
+            if previous.len() < options.min_samples.max(1) {
+                return None;
+            }
+
+            let baseline = median(previous.iter().map(|(_, d)| *d).collect());
+            if elapsed.saturating_sub(baseline) < options.min_delta {
+                return None;
+            }
+
+            let ratio = elapsed.as_secs_f64()
+                / baseline.as_secs_f64().max(f64::EPSILON);
+            (ratio >= options.threshold).then(|| Regression {
+                task: task.to_string(),
+                run_id: run_id.to_string(),
+                elapsed: *elapsed,
+                baseline,
+                ratio,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    regressions.sort_by(|a, b| {
+        b.ratio
+            .total_cmp(&a.ratio)
+            .then_with(|| a.task.cmp(&b.task))
+    });
+
+    regressions
+}
+
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort_unstable();
+
+    let mid = durations.len() / 2;
+    if durations.len() % 2 == 0 {
+        (durations[mid - 1] + durations[mid]) / 2
+    } else {
+        durations[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RunFilters, TaskRunRecord};
+
+    fn task(name: &str, secs: u64, cache_hit: bool) -> TaskRunRecord {
+        TaskRunRecord {
+            task: name.to_string(),
+            status: TaskRunStatus::Succeeded,
+            exit_code: Some(0),
+            elapsed: Some(Duration::from_secs(secs)),
+            cache_hit,
+            hash: None,
+            tries: 1,
+            skip_reason: None,
+            skipped_due_to_failure: false,
+        }
+    }
+
+    /// Runs with the given tasks, the last one is the newest.
+    fn runs(tasks: Vec<Vec<TaskRunRecord>>) -> Vec<RunRecord> {
+        let mut runs = tasks
+            .into_iter()
+            .enumerate()
+            .map(|(i, tasks)| {
+                let mut run = RunRecord::new(
+                    OffsetDateTime::UNIX_EPOCH,
+                    None,
+                    "build",
+                    RunFilters::default(),
+                    Duration::ZERO,
+                    tasks,
+                );
+                run.id = i.to_string();
+                run
+            })
+            .collect::<Vec<_>>();
+        runs.reverse();
+        runs
+    }
+
+    #[test]
+    fn test_task_durations() {
+        let runs = runs(vec![
+            vec![task("a#build", 2, false)],
+            vec![task("b#build", 1, false)],
+            vec![task("a#build", 0, true)],
+        ]);
+
+        let durations = task_durations(&runs, "a#build");
+
+        assert_eq!(
+            durations
+                .iter()
+                .map(|d| (d.run_id.as_str(), d.cache_hit))
+                .collect::<Vec<_>>(),
+            [("2", true), ("0", false)]
+        );
+    }
+
+    #[test]
+    fn test_regressions() {
+        let runs = runs(vec![
+            vec![task("a#build", 10, false), task("b#build", 10, false)],
+            vec![task("a#build", 12, false), task("b#build", 10, false)],
+            vec![task("a#build", 10, false), task("b#build", 11, false)],
+            // cache hits aren't compared
+            vec![task("a#build", 0, true), task("b#build", 0, true)],
+            vec![task("a#build", 20, false), task("b#build", 12, false)],
+        ]);
+
+        let regressions = regressions(&runs, &RegressionOptions::default());
+
+        assert_eq!(
+            regressions,
+            [Regression {
+                task: "a#build".to_string(),
+                run_id: "4".to_string(),
+                elapsed: Duration::from_secs(20),
+                baseline: Duration::from_secs(10),
+                ratio: 2.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_regressions_need_enough_samples_and_delta() {
+        let runs = runs(vec![
+            vec![task("a#build", 10, false), task("b#build", 1, false)],
+            vec![task("a#build", 10, false), task("b#build", 1, false)],
+            vec![task("a#build", 30, false), task("b#build", 1, false)],
+            vec![task("b#build", 1, false)],
+            // almost twice as slow but by less than a second
+            vec![TaskRunRecord {
+                elapsed: Some(Duration::from_millis(1800)),
+                ..task("b#build", 0, false)
+            }],
+        ]);
+
+        assert!(regressions(&runs, &RegressionOptions::default()).is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+use derive_new::new;
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct RunHistoryError(pub(crate) RunHistoryErrorInner);
+
+impl RunHistoryError {
+    pub fn custom<T: Into<String>>(msg: T) -> Self {
+        Self(RunHistoryErrorInner::Custom(eyre::Report::msg(msg.into())))
+    }
+
+    pub fn kind(&self) -> RunHistoryErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<RunHistoryErrorInner>> From<T> for RunHistoryError {
+    fn from(inner: T) -> Self {
+        Self(inner.into())
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants, new)]
+#[strum_discriminants(vis(pub), name(RunHistoryErrorKind))]
+pub(crate) enum RunHistoryErrorInner {
+    #[error(transparent)]
+    Custom(#[from] eyre::Report),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid run id '{id}'")]
+    InvalidId {
+        #[new(into)]
+        id: String,
+    },
+
+    #[error("run '{path}' is corrupted: {source}")]
+    Corrupted {
+        #[new(into)]
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
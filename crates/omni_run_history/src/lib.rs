@@ -0,0 +1,16 @@
+//! History of the task runs of a workspace.
+//!
+//! Every run is stored as a [`RunRecord`] in the cache dir of the workspace,
+//! see [`RunHistory`]. The records can be compared across runs to follow how
+//! the duration of a task evolves and to find the tasks that got slower, see
+//! [`task_durations`] and [`regressions`].
+
+mod analysis;
+mod error;
+mod record;
+mod store;
+
+pub use analysis::*;
+pub use error::*;
+pub use record::*;
+pub use store::*;
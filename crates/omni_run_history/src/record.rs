@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIs};
+use time::OffsetDateTime;
+
+/// A run of tasks as it is kept in the history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RunRecord {
+    /// Unique and sortable by start time, see [`RunRecord::new`].
+    pub id: String,
+    #[schemars(with = "String")]
+    pub started_at: OffsetDateTime,
+    /// How the run was invoked, if it was invoked from a command line. The
+    /// values of `KEY=value` arguments are masked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_line: Option<String>,
+    /// The tasks or the command that was run.
+    pub call: String,
+    #[serde(default)]
+    pub filters: RunFilters,
+    pub elapsed: Duration,
+    pub tasks: Vec<TaskRunRecord>,
+}
+
+impl RunRecord {
+    pub fn new(
+        started_at: OffsetDateTime,
+        command_line: Option<String>,
+        call: impl Into<String>,
+        filters: RunFilters,
+        elapsed: Duration,
+        tasks: Vec<TaskRunRecord>,
+    ) -> Self {
+        let millis = started_at.unix_timestamp_nanos() / 1_000_000;
+
+        Self {
+            // the pid keeps runs started in the same millisecond apart
+            id: format!("{millis:013}-{}", std::process::id()),
+            started_at,
+            command_line,
+            call: call.into(),
+            filters,
+            elapsed,
+            tasks,
+        }
+    }
+
+    pub fn task(&self, task: &str) -> Option<&TaskRunRecord> {
+        self.tasks.iter().find(|t| t.task == task)
+    }
+
+    pub fn count(&self, status: TaskRunStatus) -> usize {
+        self.tasks.iter().filter(|t| t.status == status).count()
+    }
+
+    pub fn cache_hits(&self) -> usize {
+        self.tasks.iter().filter(|t| t.cache_hit).count()
+    }
+
+    /// Whether any task failed, errored or was skipped because of a failure.
+    pub fn is_failure(&self) -> bool {
+        self.tasks.iter().any(|t| {
+            (!t.status.is_succeeded() && !t.status.is_skipped())
+                || t.skipped_due_to_failure
+        })
+    }
+}
+
+/// The filters that selected the projects of a run.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+pub struct RunFilters {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub project: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dir: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tag: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_tag: Vec<String>,
+}
+
+impl RunFilters {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// The outcome of one task of a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TaskRunRecord {
+    /// The full task name, `project#task`.
+    pub task: String,
+    pub status: TaskRunStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<u32>,
+    /// How long the task ran, or the run it was restored from ran for cache
+    /// hits. `None` for tasks that didn't run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed: Option<Duration>,
+    #[serde(default)]
+    pub cache_hit: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub tries: u8,
+    /// Why the task was skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+    /// Whether the task was skipped because another task failed.
+    #[serde(default)]
+    pub skipped_due_to_failure: bool,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Display,
+    EnumIs,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum TaskRunStatus {
+    Succeeded,
+    /// The task exited with a non-zero exit code.
+    Failed,
+    /// The task could not be run.
+    Errored,
+    Skipped,
+}
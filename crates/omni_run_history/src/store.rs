@@ -0,0 +1,281 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    RunHistoryError, RunHistoryErrorInner, RunHistoryErrorKind, RunRecord,
+};
+
+/// How many runs are kept, older runs are removed when a new one is added.
+pub const MAX_RUNS: usize = 250;
+
+const HISTORY_DIR: &str = "history";
+const EXTENSION: &str = "json";
+
+/// The runs of a workspace, stored as one JSON file per run in the `history`
+/// directory of its cache dir.
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    dir: PathBuf,
+}
+
+impl RunHistory {
+    pub fn new(cache_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: cache_dir.as_ref().join(HISTORY_DIR),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Add `run` to the history, then remove the runs beyond [`MAX_RUNS`].
+    pub async fn append(&self, run: &RunRecord) -> Result<(), RunHistoryError> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let json = serde_json::to_vec(run)?;
+        // write to a temp file first so readers never see a partial run
+        let path = self.path(&run.id);
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+
+        let ids = self.ids().await?;
+        if ids.len() > MAX_RUNS {
+            for id in &ids[MAX_RUNS..] {
+                // a concurrent run may have pruned it already
+                match tokio::fs::remove_file(self.path(id)).await {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// All runs, newest first.
+    pub async fn runs(&self) -> Result<Vec<RunRecord>, RunHistoryError> {
+        self.latest(usize::MAX).await
+    }
+
+    /// At most `limit` runs, newest first. Runs that were removed while
+    /// listing or that can't be read are skipped with a warning.
+    pub async fn latest(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<RunRecord>, RunHistoryError> {
+        let mut runs = vec![];
+
+        for id in self.ids().await? {
+            if runs.len() >= limit {
+                break;
+            }
+
+            match self.read(&id).await {
+                Ok(run) => runs.push(run),
+                Err(e) if is_not_found(&e) => {}
+                Err(e) if e.kind() == RunHistoryErrorKind::Corrupted => {
+                    log::warn!("skipping run {id}: {e}");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(runs)
+    }
+
+    /// The run with `id`, or the latest run if `id` is `None`.
+    pub async fn get(
+        &self,
+        id: Option<&str>,
+    ) -> Result<Option<RunRecord>, RunHistoryError> {
+        let Some(id) = id else {
+            return Ok(self.latest(1).await?.into_iter().next());
+        };
+
+        if !is_valid_id(id) {
+            return Err(RunHistoryErrorInner::new_invalid_id(id).into());
+        }
+
+        match self.read(id).await {
+            Ok(run) => Ok(Some(run)),
+            Err(e) if is_not_found(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Only called with ids checked by [`is_valid_id`], so the path can't
+    /// point outside of the history dir.
+    fn path(&self, id: &str) -> PathBuf {
+        debug_assert!(is_valid_id(id));
+        self.dir.join(format!("{id}.{EXTENSION}"))
+    }
+
+    async fn read(&self, id: &str) -> Result<RunRecord, RunHistoryError> {
+        let path = self.path(id);
+        let bytes = tokio::fs::read(&path).await?;
+
+        serde_json::from_slice(&bytes).map_err(|e| {
+            RunHistoryErrorInner::new_corrupted(path.display().to_string(), e)
+                .into()
+        })
+    }
+
+    /// The ids of the stored runs, newest first.
+    async fn ids(&self) -> Result<Vec<String>, RunHistoryError> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(vec![]);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut ids = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == EXTENSION)
+                && let Some(id) = path.file_stem().and_then(|s| s.to_str())
+                && is_valid_id(id)
+            {
+                ids.push(id.to_string());
+            }
+        }
+
+        // ids start with the zero padded start time, so they sort by it
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+
+        Ok(ids)
+    }
+}
+
+/// Whether `id` has the `<13 digit start millis>-<pid>` shape of the ids
+/// created by [`RunRecord::new`].
+fn is_valid_id(id: &str) -> bool {
+    let Some((millis, pid)) = id.split_once('-') else {
+        return false;
+    };
+
+    millis.len() == 13
+        && millis.bytes().all(|b| b.is_ascii_digit())
+        && !pid.is_empty()
+        && pid.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn is_not_found(error: &RunHistoryError) -> bool {
+    matches!(
+        &error.0,
+        RunHistoryErrorInner::Io(e) if e.kind() == std::io::ErrorKind::NotFound
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::{RunFilters, TaskRunRecord, TaskRunStatus};
+
+    fn run(id: &str) -> RunRecord {
+        let mut run = RunRecord::new(
+            OffsetDateTime::UNIX_EPOCH,
+            Some("omni run build".to_string()),
+            "build",
+            RunFilters::default(),
+            Duration::from_secs(2),
+            vec![TaskRunRecord {
+                task: "a#build".to_string(),
+                status: TaskRunStatus::Succeeded,
+                exit_code: Some(0),
+                elapsed: Some(Duration::from_millis(1500)),
+                cache_hit: false,
+                hash: Some("abc".to_string()),
+                tries: 1,
+                skip_reason: None,
+                skipped_due_to_failure: false,
+            }],
+        );
+        run.id = id.to_string();
+        run
+    }
+
+    #[tokio::test]
+    async fn test_append_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path());
+
+        assert_eq!(history.get(None).await.unwrap(), None);
+
+        history.append(&run("0000000000001-1")).await.unwrap();
+        history.append(&run("0000000000002-1")).await.unwrap();
+
+        let runs = history.runs().await.unwrap();
+        assert_eq!(
+            runs.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            ["0000000000002-1", "0000000000001-1"]
+        );
+        assert_eq!(
+            history.get(None).await.unwrap(),
+            Some(run("0000000000002-1"))
+        );
+        assert_eq!(
+            history.get(Some("0000000000001-1")).await.unwrap(),
+            Some(run("0000000000001-1"))
+        );
+        assert_eq!(history.get(Some("0000000000003-1")).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_rejects_invalid_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path());
+        tokio::fs::write(dir.path().join("secret.json"), "{}")
+            .await
+            .unwrap();
+
+        for id in ["../secret", "missing", "1-1", "0000000000001-", "/etc/x"] {
+            let error = history.get(Some(id)).await.unwrap_err();
+            assert_eq!(error.kind(), RunHistoryErrorKind::InvalidId, "{id}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_append_prunes_oldest_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path());
+
+        for i in 0..MAX_RUNS + 2 {
+            history.append(&run(&format!("{i:013}-1"))).await.unwrap();
+        }
+
+        let runs = history.runs().await.unwrap();
+        assert_eq!(runs.len(), MAX_RUNS);
+        assert_eq!(runs.last().unwrap().id, format!("{:013}-1", 2));
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path());
+        history.append(&run("0000000000001-1")).await.unwrap();
+        tokio::fs::write(history.dir().join("0000000000002-1.json"), "{")
+            .await
+            .unwrap();
+
+        let runs = history.runs().await.unwrap();
+        assert_eq!(
+            runs.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            ["0000000000001-1"]
+        );
+        assert_eq!(
+            history.get(None).await.unwrap(),
+            Some(run("0000000000001-1"))
+        );
+
+        let error = history.get(Some("0000000000002-1")).await.unwrap_err();
+        assert_eq!(error.kind(), RunHistoryErrorKind::Corrupted);
+    }
+}
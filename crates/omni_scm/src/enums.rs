@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIs, VariantArray};
 
 #[derive(
@@ -11,7 +12,10 @@ use strum::{Display, EnumIs, VariantArray};
     EnumIs,
     Display,
     VariantArray,
+    Serialize,
+    Deserialize,
 )]
+#[serde(rename_all = "lowercase")]
 pub enum SelectScm {
     /// Use the auto-detected scm
     #[strum(serialize = "auto")]
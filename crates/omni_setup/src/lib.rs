@@ -8,10 +8,12 @@ mod get_remote_caching_config;
 mod init;
 mod secret_key;
 mod setup_remote_caching_config;
+mod signing_key;
 mod sys;
 mod util;
 
 pub use get_remote_caching_config::*;
 pub use init::*;
 pub use setup_remote_caching_config::*;
+pub use signing_key::*;
 pub use sys::*;
@@ -13,6 +13,7 @@ use strum::{EnumDiscriminants, EnumIs, IntoDiscriminant as _};
 
 use crate::{
     crypto, derive_key::derive_key_from_seed, secret_key::get_secret_key,
+    signing_key::is_valid_signing_key,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -26,9 +27,18 @@ pub async fn setup_remote_caching_config_async<TClient: RemoteCacheClient>(
     organization_code: &str,
     workspace_code: &str,
     environment_code: Option<&str>,
+    signing_key: Option<&str>,
     encrypt: bool,
     sys: &impl SetupSys,
 ) -> Result<(), SetupRemoteCachingConfigError> {
+    if let Some(signing_key) = signing_key
+        && !is_valid_signing_key(signing_key)
+    {
+        return Err(
+            SetupRemoteCachingConfigErrorInner::InvalidSigningKey.into()
+        );
+    }
+
     let result = client
         .validate_access(&RemoteAccessArgs {
             api_base_url,
@@ -37,6 +47,7 @@ pub async fn setup_remote_caching_config_async<TClient: RemoteCacheClient>(
             org: organization_code,
             tenant: tenant_code,
             ws: workspace_code,
+            task: None,
         })
         .await?;
 
@@ -60,6 +71,8 @@ pub async fn setup_remote_caching_config_async<TClient: RemoteCacheClient>(
         organization_code: organization_code.to_string(),
         workspace_code: workspace_code.to_string(),
         environment_code: environment_code.map(|s| s.to_string()),
+        compression: Default::default(),
+        signing_key: signing_key.map(|s| s.to_string()),
     };
 
     let parent = remote_config_path.parent().expect("should have parent");
@@ -145,4 +158,7 @@ enum SetupRemoteCachingConfigErrorInner {
 
     #[error(transparent)]
     Env(#[from] std::env::VarError),
+
+    #[error("signing key must be base64 encoded and at least 32 bytes long")]
+    InvalidSigningKey,
 }
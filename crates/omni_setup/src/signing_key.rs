@@ -0,0 +1,41 @@
+use base64::Engine;
+use rand::Rng;
+
+/// Length in bytes of the keys generated by [`generate_signing_key`], which is
+/// also the minimum accepted by [`is_valid_signing_key`].
+const SIGNING_KEY_LEN: usize = 32;
+
+/// Generate a random, base64 encoded key to sign the remote cache artifacts
+/// with.
+pub fn generate_signing_key() -> String {
+    let mut key = [0u8; SIGNING_KEY_LEN];
+    rand::rng().fill_bytes(&mut key);
+    base64::engine::general_purpose::STANDARD.encode(key)
+}
+
+/// Whether `key` is a base64 encoded key of at least [`SIGNING_KEY_LEN`]
+/// bytes.
+pub fn is_valid_signing_key(key: &str) -> bool {
+    base64::engine::general_purpose::STANDARD
+        .decode(key.trim())
+        .is_ok_and(|key| key.len() >= SIGNING_KEY_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_signing_key() {
+        let key = generate_signing_key();
+
+        assert!(is_valid_signing_key(&key), "generated key should be valid");
+        assert_ne!(key, generate_signing_key(), "keys should be random");
+    }
+
+    #[test]
+    fn test_is_valid_signing_key() {
+        assert!(!is_valid_signing_key("not base64!"));
+        assert!(!is_valid_signing_key("c2hvcnQ="));
+    }
+}
@@ -0,0 +1,215 @@
+/// A parsed script: and/or lists separated by `;` or newlines.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Script {
+    pub items: Vec<AndOrList>,
+}
+
+impl Script {
+    /// The script's only command, if it is a single simple command that is
+    /// not part of a pipeline or an and/or list.
+    pub fn as_simple_command(&self) -> Option<&SimpleCommand> {
+        match self.items.as_slice() {
+            [item] if item.rest.is_empty() => {
+                match item.first.commands.as_slice() {
+                    [command] if !item.first.negated => Some(command),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The external programs the script runs, in order of first use. Builtins
+    /// and commands whose name is only known once expanded are left out.
+    pub fn programs(&self) -> Vec<String> {
+        let mut programs = Vec::<String>::new();
+        let pipelines = self.items.iter().flat_map(|item| {
+            std::iter::once(&item.first).chain(item.rest.iter().map(|(_, p)| p))
+        });
+
+        for command in pipelines.flat_map(|p| &p.commands) {
+            if let Some(prog) = command.words.first().and_then(Word::as_literal)
+                && !crate::is_builtin(&prog)
+                && !programs.contains(&prog)
+            {
+                programs.push(prog);
+            }
+        }
+
+        programs
+    }
+}
+
+/// Pipelines joined by `&&` and `||`, evaluated left to right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AndOrList {
+    pub first: Pipeline,
+    pub rest: Vec<(AndOrOp, Pipeline)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AndOrOp {
+    And,
+    Or,
+}
+
+/// Commands joined by `|`. The exit code of a pipeline is the one of its last
+/// command, inverted when the pipeline starts with `!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    pub negated: bool,
+    pub commands: Vec<SimpleCommand>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SimpleCommand {
+    /// `NAME=value` prefixes, applied to the command's environment only, or
+    /// to the shell itself when there is no command.
+    pub assignments: Vec<Assignment>,
+    pub words: Vec<Word>,
+    pub redirects: Vec<Redirect>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub name: String,
+    pub value: Word,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// The redirected file descriptor: 0 for stdin, 1 for stdout and 2 for
+    /// stderr.
+    pub fd: u32,
+    pub kind: RedirectKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedirectKind {
+    /// `< file`
+    Read(Word),
+    /// `> file`
+    Write(Word),
+    /// `>> file`
+    Append(Word),
+    /// `>&fd`, e.g. `2>&1`
+    Duplicate(u32),
+}
+
+/// A word, made of parts that are expanded and concatenated.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordPart {
+    /// Literal text. Quoted text is never split or glob-expanded.
+    Literal { text: String, quoted: bool },
+    /// `$NAME`, `${NAME}` or `$?`.
+    Var { name: String, quoted: bool },
+    /// `${NAME:-word}`, `${NAME-word}`, `${NAME:+word}` or `${NAME+word}`.
+    /// The parts of `word` are quoted when the expansion itself is.
+    Param {
+        name: String,
+        op: ParamOp,
+        word: Word,
+        quoted: bool,
+    },
+}
+
+/// The operator of a `${NAME<op>word}` parameter expansion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamOp {
+    /// `:-`, expands to `word` when the variable is unset or empty.
+    DefaultIfEmpty,
+    /// `-`, expands to `word` when the variable is unset.
+    DefaultIfUnset,
+    /// `:+`, expands to `word` when the variable is set and not empty.
+    AlternativeIfNonEmpty,
+    /// `+`, expands to `word` when the variable is set.
+    AlternativeIfSet,
+}
+
+impl ParamOp {
+    /// Whether the expansion is replaced by its word, given the variable's
+    /// value.
+    pub fn uses_word(self, value: Option<&str>) -> bool {
+        match self {
+            Self::DefaultIfEmpty => value.is_none_or(str::is_empty),
+            Self::DefaultIfUnset => value.is_none(),
+            Self::AlternativeIfNonEmpty => value.is_some_and(|v| !v.is_empty()),
+            Self::AlternativeIfSet => value.is_some(),
+        }
+    }
+
+    /// Whether the expansion falls back to the variable's value when it
+    /// doesn't use its word. Alternatives expand to nothing instead.
+    pub fn is_default(self) -> bool {
+        matches!(self, Self::DefaultIfEmpty | Self::DefaultIfUnset)
+    }
+}
+
+impl Word {
+    pub fn literal(text: impl Into<String>) -> Self {
+        Self {
+            parts: vec![WordPart::Literal {
+                text: text.into(),
+                quoted: false,
+            }],
+        }
+    }
+
+    /// Whether the word contains unquoted glob characters (`*`, `?` or `[`).
+    pub fn has_glob(&self) -> bool {
+        self.parts.iter().any(|p| match p {
+            WordPart::Literal {
+                text,
+                quoted: false,
+            } => text.contains(['*', '?', '[']),
+            _ => false,
+        })
+    }
+
+    /// Whether the word references a variable.
+    pub fn has_vars(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|p| matches!(p, WordPart::Var { .. } | WordPart::Param { .. }))
+    }
+
+    /// The word's text when it is made of literal parts only.
+    pub fn as_literal(&self) -> Option<String> {
+        let mut text = String::new();
+        for part in &self.parts {
+            match part {
+                WordPart::Literal { text: t, .. } => text.push_str(t),
+                WordPart::Var { .. } | WordPart::Param { .. } => return None,
+            }
+        }
+        Some(text)
+    }
+
+    pub(crate) fn push_literal(&mut self, c: char, quoted: bool) {
+        if let Some(WordPart::Literal { text, quoted: q }) =
+            self.parts.last_mut()
+            && *q == quoted
+        {
+            text.push(c);
+            return;
+        }
+
+        self.parts.push(WordPart::Literal {
+            text: c.to_string(),
+            quoted,
+        });
+    }
+
+    pub(crate) fn push_var(&mut self, name: String, quoted: bool) {
+        self.parts.push(WordPart::Var { name, quoted });
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+}
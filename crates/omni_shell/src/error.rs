@@ -0,0 +1,49 @@
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct ShellError(pub(crate) ShellErrorInner);
+
+impl ShellError {
+    #[allow(unused)]
+    pub fn kind(&self) -> ShellErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl ShellError {
+    pub(crate) fn syntax(message: impl Into<String>, offset: usize) -> Self {
+        Self(ShellErrorInner::Syntax {
+            message: message.into(),
+            offset,
+        })
+    }
+
+    pub(crate) fn unsupported(feature: &'static str, offset: usize) -> Self {
+        Self(ShellErrorInner::Unsupported { feature, offset })
+    }
+}
+
+impl<T: Into<ShellErrorInner>> From<T> for ShellError {
+    #[inline(always)]
+    fn from(inner: T) -> Self {
+        let error = inner.into();
+        Self(error)
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants)]
+#[strum_discriminants(vis(pub), name(ShellErrorKind))]
+pub(crate) enum ShellErrorInner {
+    #[error("syntax error at offset {offset}: {message}")]
+    Syntax { message: String, offset: usize },
+
+    #[error("{feature} is not supported, at offset {offset}")]
+    Unsupported {
+        feature: &'static str,
+        offset: usize,
+    },
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
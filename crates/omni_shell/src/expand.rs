@@ -0,0 +1,458 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::{Word, WordPart};
+
+/// A field produced by expanding a word.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Field {
+    pub text: String,
+    /// Glob pattern equivalent to `text`, with quoted characters escaped.
+    /// `None` when the field has no unquoted glob characters.
+    pub pattern: Option<String>,
+}
+
+#[derive(Default)]
+struct FieldBuilder {
+    text: String,
+    pattern: String,
+    has_glob: bool,
+}
+
+impl FieldBuilder {
+    fn push_quoted(&mut self, text: &str) {
+        self.text.push_str(text);
+        for c in text.chars() {
+            if matches!(c, '*' | '?' | '[' | ']' | '{' | '}') {
+                self.pattern.push('[');
+                self.pattern.push(c);
+                self.pattern.push(']');
+            } else {
+                self.pattern.push(c);
+            }
+        }
+    }
+
+    fn push_unquoted(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.pattern.push_str(text);
+        self.has_glob |= text.contains(['*', '?', '[']);
+    }
+
+    fn finish(self) -> Field {
+        Field {
+            pattern: self.has_glob.then_some(self.pattern),
+            text: self.text,
+        }
+    }
+}
+
+/// Fields being produced by the expansion of a word.
+#[derive(Default)]
+struct Fields {
+    fields: Vec<Field>,
+    current: FieldBuilder,
+    started: bool,
+}
+
+impl Fields {
+    fn push_quoted(&mut self, text: &str) {
+        self.current.push_quoted(text);
+        self.started = true;
+    }
+
+    fn push_unquoted(&mut self, text: &str) {
+        self.current.push_unquoted(text);
+        self.started = true;
+    }
+
+    /// Push an unquoted expansion result, split on whitespace into fields.
+    fn push_split(&mut self, value: &str) {
+        let mut pieces = value.split_whitespace().peekable();
+
+        if value.starts_with(char::is_whitespace) && self.started {
+            self.end_field();
+        }
+
+        while let Some(piece) = pieces.next() {
+            self.push_quoted(piece);
+
+            if pieces.peek().is_some() {
+                self.end_field();
+            }
+        }
+
+        if value.ends_with(char::is_whitespace) && self.started {
+            self.end_field();
+        }
+    }
+
+    fn end_field(&mut self) {
+        self.fields.push(std::mem::take(&mut self.current).finish());
+        self.started = false;
+    }
+
+    fn finish(mut self) -> Vec<Field> {
+        if self.started {
+            self.end_field();
+        }
+        self.fields
+    }
+}
+
+/// Expand the variables of `word`. Unquoted variable values are split on
+/// whitespace, but never glob-expanded. A word made only of unquoted
+/// variables that expand to nothing produces no field.
+pub(crate) fn expand_word(
+    word: &Word,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Vec<Field> {
+    let mut fields = Fields::default();
+    expand_parts(&word.parts, lookup, false, &mut fields);
+    fields.finish()
+}
+
+/// Expand `parts` into `fields`. The unquoted text of the word of a
+/// `${NAME:-word}` expansion is `nested`, and split like a variable value.
+fn expand_parts(
+    parts: &[WordPart],
+    lookup: &impl Fn(&str) -> Option<String>,
+    nested: bool,
+    fields: &mut Fields,
+) {
+    for part in parts {
+        match part {
+            WordPart::Literal { text, quoted: true } => {
+                fields.push_quoted(text)
+            }
+            WordPart::Literal {
+                text,
+                quoted: false,
+            } => {
+                if nested {
+                    fields.push_split(text);
+                } else {
+                    fields.push_unquoted(text);
+                }
+            }
+            WordPart::Var { name, quoted } => {
+                let value = lookup(name).unwrap_or_default();
+                push_value(&value, *quoted, fields);
+            }
+            WordPart::Param {
+                name,
+                op,
+                word,
+                quoted,
+            } => {
+                let value = lookup(name);
+                if op.uses_word(value.as_deref()) {
+                    expand_parts(&word.parts, lookup, true, fields);
+                } else if op.is_default() {
+                    push_value(&value.unwrap_or_default(), *quoted, fields);
+                }
+            }
+        }
+    }
+}
+
+fn push_value(value: &str, quoted: bool, fields: &mut Fields) {
+    if quoted {
+        fields.push_quoted(value);
+    } else {
+        fields.push_split(value);
+    }
+}
+
+/// Expand a glob `pattern` relative to `cwd`. Matches are sorted and keep the
+/// pattern's form, i.e. relative patterns yield relative paths. Hidden files
+/// only match components that start with a `.`. Returns an empty list when
+/// nothing matches.
+pub(crate) fn expand_glob(cwd: &Path, pattern: &str) -> Vec<String> {
+    let absolute = Path::new(pattern).is_absolute();
+    let mut components = pattern.split('/');
+
+    // (display path, filesystem path)
+    let mut candidates: Vec<(String, PathBuf)> = if absolute {
+        let root = components.next().unwrap_or_default();
+        vec![(format!("{root}/"), PathBuf::from(format!("{root}/")))]
+    } else {
+        vec![(String::new(), cwd.to_path_buf())]
+    };
+
+    for component in components {
+        if component.is_empty() {
+            continue;
+        }
+
+        if component == "." {
+            candidates = candidates
+                .into_iter()
+                .map(|(d, p)| (join_display(&d, component), p))
+                .collect();
+            continue;
+        }
+
+        if component == "**" {
+            let mut expanded = vec![];
+            for (display, path) in candidates {
+                expanded.push((display.clone(), path.clone()));
+                walk_dirs(&display, &path, &mut expanded);
+            }
+            candidates = expanded;
+            continue;
+        }
+
+        let Some(matcher) = component_matcher(component) else {
+            candidates = candidates
+                .into_iter()
+                .map(|(d, p)| {
+                    (
+                        join_display(&d, &unescape(component)),
+                        p.join(unescape(component)),
+                    )
+                })
+                .collect();
+            continue;
+        };
+
+        let mut matched = vec![];
+        for (display, path) in candidates {
+            let Ok(entries) = fs::read_dir(&path) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name();
+                let Some(name) = name.to_str() else {
+                    continue;
+                };
+
+                if name.starts_with('.') && !component.starts_with('.') {
+                    continue;
+                }
+
+                if matcher.is_match(name) {
+                    matched.push((join_display(&display, name), entry.path()));
+                }
+            }
+        }
+        candidates = matched;
+    }
+
+    let mut matches = candidates
+        .into_iter()
+        .filter(|(d, p)| !d.is_empty() && p.symlink_metadata().is_ok())
+        .map(|(d, _)| d)
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+fn join_display(base: &str, name: &str) -> String {
+    if base.is_empty() {
+        name.to_string()
+    } else if base.ends_with('/') {
+        format!("{base}{name}")
+    } else {
+        format!("{base}/{name}")
+    }
+}
+
+fn walk_dirs(display: &str, path: &Path, out: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+
+        if name.starts_with('.') || !entry.file_type().is_ok_and(|t| t.is_dir())
+        {
+            continue;
+        }
+
+        let display = join_display(display, name);
+        let path = entry.path();
+        out.push((display.clone(), path.clone()));
+        walk_dirs(&display, &path, out);
+    }
+}
+
+/// A matcher for a single path component, `None` when it has no glob
+/// characters left once escapes are taken into account.
+fn component_matcher(component: &str) -> Option<GlobMatcher> {
+    if !has_unescaped_glob(component) {
+        return None;
+    }
+
+    GlobBuilder::new(component)
+        .literal_separator(true)
+        .backslash_escape(false)
+        .build()
+        .ok()
+        .map(|g| g.compile_matcher())
+}
+
+/// Whether `component` has glob characters outside of the `[c]` escapes
+/// produced for quoted text.
+fn has_unescaped_glob(component: &str) -> bool {
+    let chars = component.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        if is_escape_at(&chars, i) {
+            i += 3;
+            continue;
+        }
+        if matches!(chars[i], '*' | '?' | '[') {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+fn is_escape_at(chars: &[char], i: usize) -> bool {
+    chars.get(i) == Some(&'[')
+        && chars.get(i + 2) == Some(&']')
+        && chars
+            .get(i + 1)
+            .is_some_and(|c| matches!(c, '*' | '?' | '[' | ']' | '{' | '}'))
+}
+
+/// Undo the `[c]` escapes produced for quoted text.
+fn unescape(component: &str) -> String {
+    let chars = component.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(component.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if is_escape_at(&chars, i) {
+            out.push(chars[i + 1]);
+            i += 3;
+        } else {
+            out.push(chars[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::parse;
+
+    fn expand(src: &str, vars: &[(&str, &str)]) -> Vec<String> {
+        let script = parse(src).expect("should parse");
+        let command = script.as_simple_command().expect("should be simple");
+        let lookup = |name: &str| {
+            vars.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        };
+
+        command
+            .words
+            .iter()
+            .flat_map(|w| expand_word(w, &lookup))
+            .map(|f| f.text)
+            .collect()
+    }
+
+    #[test]
+    fn test_unquoted_vars_are_split() {
+        assert_eq!(
+            expand("echo $FLAGS end", &[("FLAGS", "-a  -b")]),
+            vec!["echo", "-a", "-b", "end"]
+        );
+        assert_eq!(
+            expand("echo x$FLAGS", &[("FLAGS", "-a -b")]),
+            vec!["echo", "x-a", "-b"]
+        );
+    }
+
+    #[test]
+    fn test_quoted_vars_are_not_split() {
+        assert_eq!(
+            expand(r#"echo "$FLAGS""#, &[("FLAGS", "-a -b")]),
+            vec!["echo", "-a -b"]
+        );
+    }
+
+    #[test]
+    fn test_empty_vars() {
+        assert_eq!(expand("echo $NOPE", &[]), vec!["echo"]);
+        assert_eq!(expand(r#"echo "$NOPE""#, &[]), vec!["echo", ""]);
+    }
+
+    #[test]
+    fn test_parameter_expansions() {
+        let vars = [("SET", "a b"), ("EMPTY", "")];
+
+        assert_eq!(expand("echo ${NOPE:-} end", &vars), vec!["echo", "end"]);
+        assert_eq!(expand("echo ${EMPTY:-x y}", &vars), vec!["echo", "x", "y"]);
+        assert_eq!(expand("echo ${EMPTY-x}", &vars), vec!["echo"]);
+        assert_eq!(expand("echo ${NOPE-x}", &vars), vec!["echo", "x"]);
+        assert_eq!(expand("echo ${SET:-x}", &vars), vec!["echo", "a", "b"]);
+        assert_eq!(expand(r#"echo "${SET:-x}""#, &vars), vec!["echo", "a b"]);
+        assert_eq!(
+            expand(r#"echo ${NOPE:-"x y"}"#, &vars),
+            vec!["echo", "x y"]
+        );
+        assert_eq!(expand("echo ${SET:+-v}", &vars), vec!["echo", "-v"]);
+        assert_eq!(expand("echo ${EMPTY:+-v}", &vars), vec!["echo"]);
+        assert_eq!(expand("echo ${EMPTY+-v}", &vars), vec!["echo", "-v"]);
+        assert_eq!(expand("echo ${NOPE+-v}", &vars), vec!["echo"]);
+        assert_eq!(
+            expand("echo ${NOPE:-${SET}_x}", &vars),
+            vec!["echo", "a", "b_x"]
+        );
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let script = parse(r#"ls src/*.rs "*.md" a'*'"#).expect("should parse");
+        let command = script.as_simple_command().expect("should be simple");
+        let lookup = |_: &str| None;
+
+        let fields = command
+            .words
+            .iter()
+            .flat_map(|w| expand_word(w, &lookup))
+            .collect::<Vec<_>>();
+
+        assert_eq!(fields[1].pattern.as_deref(), Some("src/*.rs"));
+        assert_eq!(fields[2].pattern, None);
+        assert_eq!(fields[3].pattern, None);
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = tempdir().expect("failed to create tempdir");
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/a.rs"), "").unwrap();
+        fs::write(root.join("src/b.rs"), "").unwrap();
+        fs::write(root.join("src/c.md"), "").unwrap();
+        fs::write(root.join("src/.hidden.rs"), "").unwrap();
+        fs::write(root.join("src/nested/d.rs"), "").unwrap();
+
+        assert_eq!(expand_glob(root, "src/*.rs"), vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(
+            expand_glob(root, "src/**/*.rs"),
+            vec!["src/a.rs", "src/b.rs", "src/nested/d.rs"]
+        );
+        assert_eq!(expand_glob(root, "src/.*.rs"), vec!["src/.hidden.rs"]);
+        assert!(expand_glob(root, "*.txt").is_empty());
+    }
+}
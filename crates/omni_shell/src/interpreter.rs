@@ -0,0 +1,586 @@
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::{Component, Path, PathBuf},
+    process::{Child, Command, ExitStatus},
+};
+
+use maps::Map;
+
+use crate::{
+    AndOrList, AndOrOp, Pipeline, Redirect, RedirectKind, Script, ShellError,
+    SimpleCommand, Word,
+    expand::{expand_glob, expand_word},
+    parse,
+    stream::{Stream, Streams},
+};
+
+/// Commands implemented by the shell itself, so they behave the same on
+/// every platform.
+pub const BUILTINS: &[&str] = &[
+    "cd", "echo", "exit", "export", "false", "pwd", "true", "unset",
+];
+
+pub fn is_builtin(name: &str) -> bool {
+    BUILTINS.contains(&name)
+}
+
+/// A portable interpreter for the scripts accepted by [`parse`].
+///
+/// External programs are looked up in `PATH` (honoring `PATHEXT` on
+/// Windows) and spawned directly, never through a system shell. Errors such
+/// as a missing program or an unreadable redirection target are reported on
+/// stderr and turned into exit codes, like a POSIX shell does.
+#[derive(Debug, Clone)]
+pub struct Shell {
+    cwd: PathBuf,
+    vars: Map<String, String>,
+    status: i32,
+}
+
+enum Flow {
+    Next,
+    Exit,
+}
+
+enum Spawned {
+    Done(i32),
+    Child(Child),
+    Exit(i32),
+}
+
+impl Shell {
+    /// A shell running in `cwd` with the environment of the current process.
+    pub fn new(cwd: impl Into<PathBuf>) -> Self {
+        Self::with_vars(cwd, std::env::vars().collect())
+    }
+
+    pub fn with_vars(
+        cwd: impl Into<PathBuf>,
+        vars: Map<String, String>,
+    ) -> Self {
+        Self {
+            cwd: cwd.into(),
+            vars,
+            status: 0,
+        }
+    }
+
+    pub fn cwd(&self) -> &Path {
+        &self.cwd
+    }
+
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+
+    /// Parse and run `src`, returning the exit code of the script.
+    pub fn run_str(&mut self, src: &str) -> Result<i32, ShellError> {
+        let script = parse(src)?;
+        self.run(&script)
+    }
+
+    /// Run `script`, returning the exit code of the last command, or the one
+    /// given to `exit`.
+    pub fn run(&mut self, script: &Script) -> Result<i32, ShellError> {
+        for item in &script.items {
+            if let Flow::Exit = self.run_and_or(item)? {
+                break;
+            }
+        }
+
+        Ok(self.status)
+    }
+
+    fn run_and_or(&mut self, list: &AndOrList) -> Result<Flow, ShellError> {
+        if let Flow::Exit = self.run_pipeline(&list.first)? {
+            return Ok(Flow::Exit);
+        }
+
+        for (op, pipeline) in &list.rest {
+            let should_run = match op {
+                AndOrOp::And => self.status == 0,
+                AndOrOp::Or => self.status != 0,
+            };
+
+            if should_run && let Flow::Exit = self.run_pipeline(pipeline)? {
+                return Ok(Flow::Exit);
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn run_pipeline(
+        &mut self,
+        pipeline: &Pipeline,
+    ) -> Result<Flow, ShellError> {
+        if let [command] = pipeline.commands.as_slice() {
+            match self.spawn(command, Streams::default())? {
+                Spawned::Done(status) => self.status = status,
+                Spawned::Child(mut child) => {
+                    self.status = exit_code(child.wait()?)
+                }
+                Spawned::Exit(status) => {
+                    self.status = status;
+                    return Ok(Flow::Exit);
+                }
+            }
+        } else {
+            self.status = self.run_multi_pipeline(pipeline)?;
+        }
+
+        if pipeline.negated {
+            self.status = i32::from(self.status == 0);
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn run_multi_pipeline(
+        &mut self,
+        pipeline: &Pipeline,
+    ) -> Result<i32, ShellError> {
+        let count = pipeline.commands.len();
+
+        let mut streams =
+            (0..count).map(|_| Streams::default()).collect::<Vec<_>>();
+        for i in 1..count {
+            let (reader, writer) = io::pipe()?;
+            streams[i - 1].stdout = Stream::PipeWriter(writer);
+            streams[i].stdin = Stream::PipeReader(reader);
+        }
+
+        // Spawn from the end so every pipe already has a reader when a
+        // builtin writes to it. Each command runs in a copy of the shell, as
+        // in a subshell, so `cd` or `exit` in a pipeline don't affect it.
+        let mut spawned = (0..count).map(|_| None).collect::<Vec<_>>();
+        for (i, (command, streams)) in
+            pipeline.commands.iter().zip(streams).enumerate().rev()
+        {
+            spawned[i] = Some(self.clone().spawn(command, streams)?);
+        }
+
+        let mut status = 0;
+        for spawned in spawned.into_iter().flatten() {
+            status = match spawned {
+                Spawned::Done(status) | Spawned::Exit(status) => status,
+                Spawned::Child(mut child) => exit_code(child.wait()?),
+            };
+        }
+
+        Ok(status)
+    }
+
+    fn spawn(
+        &mut self,
+        command: &SimpleCommand,
+        mut streams: Streams,
+    ) -> Result<Spawned, ShellError> {
+        let (argv, env) = {
+            let lookup = |name: &str| self.lookup(name);
+
+            let env = command
+                .assignments
+                .iter()
+                .map(|a| (a.name.clone(), expand_to_string(&a.value, &lookup)))
+                .collect::<Vec<_>>();
+
+            let mut argv = vec![];
+            for word in &command.words {
+                for field in expand_word(word, &lookup) {
+                    let matches = field
+                        .pattern
+                        .map(|p| expand_glob(&self.cwd, &p))
+                        .unwrap_or_default();
+
+                    if matches.is_empty() {
+                        argv.push(field.text);
+                    } else {
+                        argv.extend(matches);
+                    }
+                }
+            }
+
+            (argv, env)
+        };
+
+        if let Err(message) =
+            self.apply_redirects(&command.redirects, &mut streams)
+        {
+            report(&mut streams, &message);
+            return Ok(Spawned::Done(1));
+        }
+
+        let Some(program) = argv.first() else {
+            self.vars.extend(env);
+            return Ok(Spawned::Done(0));
+        };
+
+        if is_builtin(program) {
+            return Ok(self.run_builtin(&argv, &mut streams));
+        }
+
+        let mut stderr = streams.stderr.try_clone()?;
+        let mut process = Command::new(self.resolve_program(program));
+        process
+            .args(&argv[1..])
+            .current_dir(&self.cwd)
+            .env_clear()
+            .envs(&self.vars)
+            .envs(env)
+            .stdin(streams.stdin.into_stdio(0)?)
+            .stdout(streams.stdout.into_stdio(1)?)
+            .stderr(streams.stderr.into_stdio(2)?);
+
+        match process.spawn() {
+            Ok(child) => Ok(Spawned::Child(child)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                report_to(
+                    &mut stderr,
+                    &format!("{program}: command not found"),
+                );
+                Ok(Spawned::Done(127))
+            }
+            Err(e) => {
+                report_to(&mut stderr, &format!("{program}: {e}"));
+                Ok(Spawned::Done(126))
+            }
+        }
+    }
+
+    fn apply_redirects(
+        &self,
+        redirects: &[Redirect],
+        streams: &mut Streams,
+    ) -> Result<(), String> {
+        for redirect in redirects {
+            let stream = match &redirect.kind {
+                RedirectKind::Read(word) => {
+                    let path = self.redirect_path(word)?;
+                    Stream::File(
+                        File::open(&path)
+                            .map_err(|e| format!("{}: {e}", path.display()))?,
+                    )
+                }
+                RedirectKind::Write(word) => {
+                    let path = self.redirect_path(word)?;
+                    Stream::File(
+                        File::create(&path)
+                            .map_err(|e| format!("{}: {e}", path.display()))?,
+                    )
+                }
+                RedirectKind::Append(word) => {
+                    let path = self.redirect_path(word)?;
+                    Stream::File(
+                        OpenOptions::new()
+                            .append(true)
+                            .create(true)
+                            .open(&path)
+                            .map_err(|e| format!("{}: {e}", path.display()))?,
+                    )
+                }
+                RedirectKind::Duplicate(fd) => streams
+                    .get(*fd)
+                    .ok_or_else(|| format!("{fd}: bad file descriptor"))?
+                    .try_clone()
+                    .map_err(|e| e.to_string())?,
+            };
+
+            if !streams.set(redirect.fd, stream) {
+                return Err(format!("{}: bad file descriptor", redirect.fd));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn redirect_path(&self, word: &Word) -> Result<PathBuf, String> {
+        let lookup = |name: &str| self.lookup(name);
+        let fields = expand_word(word, &lookup);
+
+        let [field] = fields.as_slice() else {
+            return Err("ambiguous redirect".to_string());
+        };
+
+        if cfg!(windows) && field.text == "/dev/null" {
+            return Ok(PathBuf::from("NUL"));
+        }
+
+        Ok(self.cwd.join(&field.text))
+    }
+
+    fn run_builtin(
+        &mut self,
+        argv: &[String],
+        streams: &mut Streams,
+    ) -> Spawned {
+        let args = &argv[1..];
+
+        let status = match argv[0].as_str() {
+            "true" => 0,
+            "false" => 1,
+            "echo" => {
+                let (newline, args) = match args.first() {
+                    Some(flag) if flag == "-n" => (false, &args[1..]),
+                    _ => (true, args),
+                };
+
+                let mut out = args.join(" ");
+                if newline {
+                    out.push('\n');
+                }
+
+                write_out(streams, &out)
+            }
+            "pwd" => write_out(streams, &format!("{}\n", self.cwd.display())),
+            "cd" => self.cd(args.first().map(String::as_str), streams),
+            "exit" => {
+                let status = match args.first() {
+                    None => self.status,
+                    Some(code) => code.parse().unwrap_or_else(|_| {
+                        report(
+                            streams,
+                            &format!("exit: {code}: numeric argument required"),
+                        );
+                        2
+                    }),
+                };
+
+                return Spawned::Exit(status);
+            }
+            "export" => {
+                for arg in args {
+                    // Every variable is already exported to child processes.
+                    if let Some((name, value)) = arg.split_once('=') {
+                        self.vars.insert(name.to_string(), value.to_string());
+                    }
+                }
+                0
+            }
+            "unset" => {
+                for arg in args {
+                    self.vars.shift_remove(arg);
+                }
+                0
+            }
+            _ => unreachable!("not a builtin: {}", argv[0]),
+        };
+
+        Spawned::Done(status)
+    }
+
+    fn cd(&mut self, target: Option<&str>, streams: &mut Streams) -> i32 {
+        let Some(target) = target
+            .or_else(|| self.var("HOME"))
+            .or_else(|| self.var("USERPROFILE"))
+            .map(str::to_string)
+        else {
+            report(streams, "cd: HOME not set");
+            return 1;
+        };
+
+        let dir = normalize(&self.cwd.join(&target));
+        if !dir.is_dir() {
+            report(streams, &format!("cd: {target}: no such directory"));
+            return 1;
+        }
+
+        self.vars
+            .insert("PWD".to_string(), dir.to_string_lossy().into_owned());
+        self.cwd = dir;
+
+        0
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        if name == "?" {
+            return Some(self.status.to_string());
+        }
+
+        self.vars.get(name).cloned()
+    }
+
+    fn resolve_program(&self, program: &str) -> PathBuf {
+        if program.contains(['/', '\\']) {
+            return self.cwd.join(program);
+        }
+
+        // Variable names are case-insensitive on Windows, where it is
+        // usually spelled `Path`.
+        let path = self
+            .vars
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case("PATH"))
+            .map(|(_, v)| v.as_str());
+
+        which::which_in(program, path, &self.cwd)
+            .unwrap_or_else(|_| PathBuf::from(program))
+    }
+}
+
+fn expand_to_string(
+    word: &Word,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> String {
+    expand_word(word, lookup)
+        .into_iter()
+        .map(|f| f.text)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn write_out(streams: &mut Streams, text: &str) -> i32 {
+    match streams.stdout.write_all(text.as_bytes()) {
+        Ok(()) => 0,
+        Err(e) => {
+            report(streams, &format!("write error: {e}"));
+            1
+        }
+    }
+}
+
+fn report(streams: &mut Streams, message: &str) {
+    report_to(&mut streams.stderr, message);
+}
+
+fn report_to(stderr: &mut Stream, message: &str) {
+    // Nowhere left to report a failure to write to stderr.
+    let _ = stderr.write_all(format!("omni shell: {message}\n").as_bytes());
+}
+
+/// Lexically resolve the `.` and `..` components of `path`.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+
+    normalized
+}
+
+fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt as _;
+
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+
+    status.code().unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn run(dir: &Path, src: &str) -> i32 {
+        Shell::new(dir).run_str(src).expect("should run")
+    }
+
+    fn read(path: PathBuf) -> String {
+        fs::read_to_string(path).expect("failed to read file")
+    }
+
+    #[test]
+    fn test_sequencing_and_redirects() {
+        let dir = tempdir().expect("failed to create tempdir");
+
+        let status = run(
+            dir.path(),
+            "echo hello > out.txt && echo world >> out.txt; false || echo -n fallback > fallback.txt",
+        );
+
+        assert_eq!(status, 0);
+        assert_eq!(read(dir.path().join("out.txt")), "hello\nworld\n");
+        assert_eq!(read(dir.path().join("fallback.txt")), "fallback");
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let dir = tempdir().expect("failed to create tempdir");
+
+        assert_eq!(run(dir.path(), "true && false"), 1);
+        assert_eq!(run(dir.path(), "false && true"), 1);
+        assert_eq!(run(dir.path(), "! false"), 0);
+        assert_eq!(run(dir.path(), "false; echo $? > status.txt"), 0);
+        assert_eq!(read(dir.path().join("status.txt")), "1\n");
+    }
+
+    #[test]
+    fn test_exit_stops_the_script() {
+        let dir = tempdir().expect("failed to create tempdir");
+
+        assert_eq!(run(dir.path(), "exit 3; echo no > out.txt"), 3);
+        assert!(!dir.path().join("out.txt").exists());
+    }
+
+    #[test]
+    fn test_cd_and_variables() {
+        let dir = tempdir().expect("failed to create tempdir");
+        fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let mut shell = Shell::new(dir.path());
+        let status = shell
+            .run_str("GREETING='hello there'; cd sub && echo \"$GREETING\" > out.txt")
+            .expect("should run");
+
+        assert_eq!(status, 0);
+        assert_eq!(shell.cwd(), normalize(&dir.path().join("sub")));
+        assert_eq!(shell.var("GREETING"), Some("hello there"));
+        assert_eq!(read(dir.path().join("sub/out.txt")), "hello there\n");
+        assert_eq!(run(dir.path(), "cd missing"), 1);
+    }
+
+    #[test]
+    fn test_globs() {
+        let dir = tempdir().expect("failed to create tempdir");
+        fs::write(dir.path().join("b.txt"), "").unwrap();
+        fs::write(dir.path().join("a.txt"), "").unwrap();
+
+        run(dir.path(), "echo *.txt '*.txt' *.md > out");
+
+        assert_eq!(read(dir.path().join("out")), "a.txt b.txt *.txt *.md\n");
+    }
+
+    #[test]
+    fn test_missing_program() {
+        let dir = tempdir().expect("failed to create tempdir");
+
+        assert_eq!(
+            run(dir.path(), "definitely-not-a-program-omni 2> err.txt"),
+            127
+        );
+        assert!(read(dir.path().join("err.txt")).contains("command not found"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipes_and_external_programs() {
+        let dir = tempdir().expect("failed to create tempdir");
+
+        let status = run(
+            dir.path(),
+            "echo b a c | tr ' ' '\\n' | sort > sorted.txt; FOO=bar sh -c 'echo $FOO; echo err >&2' > out.txt 2>&1",
+        );
+
+        assert_eq!(status, 0);
+        assert_eq!(read(dir.path().join("sorted.txt")), "a\nb\nc\n");
+        assert_eq!(read(dir.path().join("out.txt")), "bar\nerr\n");
+        assert_eq!(run(dir.path(), "sh -c 'exit 4'"), 4);
+        assert_eq!(run(dir.path(), "sh -c 'exit 4' | true"), 0);
+    }
+}
@@ -0,0 +1,13 @@
+#![allow(clippy::redundant_field_names)]
+
+mod ast;
+mod error;
+mod expand;
+mod interpreter;
+mod parser;
+mod stream;
+
+pub use ast::*;
+pub use error::*;
+pub use interpreter::*;
+pub use parser::parse;
@@ -0,0 +1,829 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crate::{
+    AndOrList, AndOrOp, Assignment, ParamOp, Pipeline, Redirect, RedirectKind,
+    Script, ShellError, SimpleCommand, Word, WordPart,
+};
+
+/// Parse a script written in the subset of the POSIX shell language
+/// supported by [`Shell`](crate::Shell): `;`, `&&`, `||`, `|`, `!`, the
+/// `<`, `>`, `>>`, `>&` and `&>` redirections, `NAME=value` prefixes, single
+/// and double quotes, backslash escapes, `$NAME`, `${NAME}`, `$?`, the
+/// `${NAME:-word}`, `${NAME-word}`, `${NAME:+word}` and `${NAME+word}`
+/// expansions and comments.
+pub fn parse(src: &str) -> Result<Script, ShellError> {
+    Parser {
+        tokens: Lexer::new(src).tokenize()?,
+        pos: 0,
+    }
+    .parse_script()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(Word),
+    /// A redirection operator, with its explicit file descriptor if any.
+    Redirect(Option<u32>, RedirectOp),
+    And,
+    Or,
+    Pipe,
+    /// `;` or a newline.
+    Separator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RedirectOp {
+    Read,
+    Write,
+    Append,
+    Duplicate,
+    /// `&>`, stdout and stderr to the same file.
+    WriteAll,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    tokens: Vec<(usize, Token)>,
+    word: Word,
+    word_start: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            chars: src.char_indices().peekable(),
+            tokens: vec![],
+            word: Word::default(),
+            word_start: 0,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<(usize, Token)>, ShellError> {
+        while let Some((offset, c)) = self.chars.next() {
+            if self.word.is_empty() {
+                self.word_start = offset;
+            }
+
+            match c {
+                ' ' | '\t' | '\r' => self.finish_word(),
+                '\n' | ';' => {
+                    self.finish_word();
+                    self.tokens.push((offset, Token::Separator));
+                }
+                '#' if self.word.is_empty() => {
+                    while self.chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                }
+                '&' => {
+                    if self.chars.next_if(|(_, c)| *c == '&').is_some() {
+                        self.finish_word();
+                        self.tokens.push((offset, Token::And));
+                    } else if self.chars.next_if(|(_, c)| *c == '>').is_some() {
+                        self.finish_word();
+                        self.tokens.push((
+                            offset,
+                            Token::Redirect(None, RedirectOp::WriteAll),
+                        ));
+                    } else {
+                        return Err(ShellError::unsupported(
+                            "running commands in the background",
+                            offset,
+                        ));
+                    }
+                }
+                '|' => {
+                    self.finish_word();
+                    if self.chars.next_if(|(_, c)| *c == '|').is_some() {
+                        self.tokens.push((offset, Token::Or));
+                    } else {
+                        self.tokens.push((offset, Token::Pipe));
+                    }
+                }
+                '>' | '<' => {
+                    let fd = self.take_fd();
+                    self.finish_word();
+
+                    let op = if c == '<' {
+                        RedirectOp::Read
+                    } else if self.chars.next_if(|(_, c)| *c == '>').is_some() {
+                        RedirectOp::Append
+                    } else if self.chars.next_if(|(_, c)| *c == '&').is_some() {
+                        RedirectOp::Duplicate
+                    } else {
+                        RedirectOp::Write
+                    };
+
+                    self.tokens.push((offset, Token::Redirect(fd, op)));
+                }
+                '(' | ')' => {
+                    return Err(ShellError::unsupported("subshells", offset));
+                }
+                '`' => {
+                    return Err(ShellError::unsupported(
+                        "command substitution",
+                        offset,
+                    ));
+                }
+                '\'' => self.lex_single_quoted(offset)?,
+                '"' => self.lex_double_quoted(offset)?,
+                '\\' => match self.chars.next() {
+                    // Line continuation.
+                    Some((_, '\n')) => {}
+                    Some((_, c)) => self.word.push_literal(c, true),
+                    None => self.word.push_literal('\\', false),
+                },
+                '$' => self.lex_var(offset, false)?,
+                c => self.word.push_literal(c, false),
+            }
+        }
+
+        self.finish_word();
+
+        Ok(self.tokens)
+    }
+
+    fn lex_single_quoted(&mut self, offset: usize) -> Result<(), ShellError> {
+        self.word.parts.push(WordPart::Literal {
+            text: String::new(),
+            quoted: true,
+        });
+        loop {
+            match self.chars.next() {
+                Some((_, '\'')) => break,
+                Some((_, c)) => self.word.push_literal(c, true),
+                None => {
+                    return Err(ShellError::syntax(
+                        "unterminated single quote",
+                        offset,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lex_double_quoted(&mut self, offset: usize) -> Result<(), ShellError> {
+        self.word.parts.push(WordPart::Literal {
+            text: String::new(),
+            quoted: true,
+        });
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => break,
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c @ ('"' | '\\' | '$' | '`'))) => {
+                        self.word.push_literal(c, true)
+                    }
+                    Some((_, '\n')) => {}
+                    Some((_, c)) => {
+                        self.word.push_literal('\\', true);
+                        self.word.push_literal(c, true);
+                    }
+                    None => {
+                        return Err(ShellError::syntax(
+                            "unterminated double quote",
+                            offset,
+                        ));
+                    }
+                },
+                Some((offset, '$')) => self.lex_var(offset, true)?,
+                Some((offset, '`')) => {
+                    return Err(ShellError::unsupported(
+                        "command substitution",
+                        offset,
+                    ));
+                }
+                Some((_, c)) => self.word.push_literal(c, true),
+                None => {
+                    return Err(ShellError::syntax(
+                        "unterminated double quote",
+                        offset,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn lex_var(
+        &mut self,
+        offset: usize,
+        quoted: bool,
+    ) -> Result<(), ShellError> {
+        if self.chars.next_if(|(_, c)| *c == '{').is_some() {
+            let mut name = String::new();
+            while let Some((_, c)) =
+                self.chars.next_if(|(_, c)| is_name_char(*c) || *c == '?')
+            {
+                name.push(c);
+            }
+
+            let op = match self.chars.next() {
+                Some((_, '}')) => None,
+                Some((_, ':')) => match self.chars.next() {
+                    Some((_, '-')) => Some(ParamOp::DefaultIfEmpty),
+                    Some((_, '+')) => Some(ParamOp::AlternativeIfNonEmpty),
+                    Some((offset, _)) => {
+                        return Err(ShellError::unsupported(
+                            "parameter expansion operators",
+                            offset,
+                        ));
+                    }
+                    None => {
+                        return Err(ShellError::syntax(
+                            "unterminated `${`",
+                            offset,
+                        ));
+                    }
+                },
+                Some((_, '-')) => Some(ParamOp::DefaultIfUnset),
+                Some((_, '+')) => Some(ParamOp::AlternativeIfSet),
+                Some((offset, _)) => {
+                    return Err(ShellError::unsupported(
+                        "parameter expansion operators",
+                        offset,
+                    ));
+                }
+                None => {
+                    return Err(ShellError::syntax(
+                        "unterminated `${`",
+                        offset,
+                    ));
+                }
+            };
+
+            if name.is_empty() {
+                return Err(ShellError::syntax("empty `${}`", offset));
+            }
+
+            match op {
+                None => self.word.push_var(name, quoted),
+                Some(op) => {
+                    let word = self.lex_param_word(offset, quoted)?;
+                    self.word.parts.push(WordPart::Param {
+                        name,
+                        op,
+                        word,
+                        quoted,
+                    });
+                }
+            }
+        } else if self.chars.next_if(|(_, c)| *c == '?').is_some() {
+            self.word.push_var("?".to_string(), quoted);
+        } else if self.chars.next_if(|(_, c)| *c == '(').is_some() {
+            return Err(ShellError::unsupported(
+                "command substitution",
+                offset,
+            ));
+        } else if self
+            .chars
+            .peek()
+            .is_some_and(|(_, c)| c.is_ascii_alphabetic() || *c == '_')
+        {
+            let mut name = String::new();
+            while let Some((_, c)) =
+                self.chars.next_if(|(_, c)| is_name_char(*c))
+            {
+                name.push(c);
+            }
+            self.word.push_var(name, quoted);
+        } else {
+            self.word.push_literal('$', quoted);
+        }
+
+        Ok(())
+    }
+
+    /// Lex the word of a `${NAME:-word}` expansion, up to its closing `}`.
+    /// Inside double quotes, the word's text is quoted too.
+    fn lex_param_word(
+        &mut self,
+        offset: usize,
+        quoted: bool,
+    ) -> Result<Word, ShellError> {
+        let outer = std::mem::take(&mut self.word);
+
+        loop {
+            match self.chars.next() {
+                Some((_, '}')) => break,
+                Some((offset, '$')) => self.lex_var(offset, quoted)?,
+                Some((offset, '"')) => self.lex_double_quoted(offset)?,
+                Some((offset, '\'')) if !quoted => {
+                    self.lex_single_quoted(offset)?
+                }
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, '\n')) => {}
+                    Some((_, c))
+                        if !quoted || matches!(c, '"' | '\\' | '$' | '}') =>
+                    {
+                        self.word.push_literal(c, true)
+                    }
+                    Some((_, c)) => {
+                        self.word.push_literal('\\', true);
+                        self.word.push_literal(c, true);
+                    }
+                    None => {
+                        return Err(ShellError::syntax(
+                            "unterminated `${`",
+                            offset,
+                        ));
+                    }
+                },
+                Some((offset, '`')) => {
+                    return Err(ShellError::unsupported(
+                        "command substitution",
+                        offset,
+                    ));
+                }
+                Some((_, c)) => self.word.push_literal(c, quoted),
+                None => {
+                    return Err(ShellError::syntax(
+                        "unterminated `${`",
+                        offset,
+                    ));
+                }
+            }
+        }
+
+        Ok(std::mem::replace(&mut self.word, outer))
+    }
+
+    /// Take the current word as the file descriptor of a redirection when it
+    /// is made of unquoted digits only, e.g. the `2` of `2>&1`.
+    fn take_fd(&mut self) -> Option<u32> {
+        let fd = match self.word.parts.as_slice() {
+            [
+                WordPart::Literal {
+                    text,
+                    quoted: false,
+                },
+            ] if text.chars().all(|c| c.is_ascii_digit()) => text.parse().ok(),
+            _ => None,
+        };
+
+        if fd.is_some() {
+            self.word = Word::default();
+        }
+
+        fd
+    }
+
+    fn finish_word(&mut self) {
+        if !self.word.is_empty() {
+            let word = std::mem::take(&mut self.word);
+            self.tokens.push((self.word_start, Token::Word(word)));
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && name.chars().all(is_name_char)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map(|(o, _)| *o)
+            .unwrap_or_default()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn parse_script(mut self) -> Result<Script, ShellError> {
+        let mut items = vec![];
+
+        loop {
+            while self.peek() == Some(&Token::Separator) {
+                self.pos += 1;
+            }
+
+            if self.peek().is_none() {
+                break;
+            }
+
+            items.push(self.parse_and_or()?);
+
+            match self.peek() {
+                None | Some(Token::Separator) => {}
+                Some(_) => {
+                    return Err(ShellError::syntax(
+                        "unexpected token",
+                        self.offset(),
+                    ));
+                }
+            }
+        }
+
+        Ok(Script { items })
+    }
+
+    fn parse_and_or(&mut self) -> Result<AndOrList, ShellError> {
+        let first = self.parse_pipeline()?;
+        let mut rest = vec![];
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::And) => AndOrOp::And,
+                Some(Token::Or) => AndOrOp::Or,
+                _ => break,
+            };
+            self.pos += 1;
+
+            // A command may continue on the next line after `&&` or `||`.
+            while self.peek() == Some(&Token::Separator) {
+                self.pos += 1;
+            }
+
+            rest.push((op, self.parse_pipeline()?));
+        }
+
+        Ok(AndOrList { first, rest })
+    }
+
+    fn parse_pipeline(&mut self) -> Result<Pipeline, ShellError> {
+        let negated = matches!(
+            self.peek(),
+            Some(Token::Word(w)) if w.parts == Word::literal("!").parts
+        );
+        if negated {
+            self.pos += 1;
+        }
+
+        let mut commands = vec![self.parse_command()?];
+
+        while self.peek() == Some(&Token::Pipe) {
+            self.pos += 1;
+            commands.push(self.parse_command()?);
+        }
+
+        Ok(Pipeline { negated, commands })
+    }
+
+    fn parse_command(&mut self) -> Result<SimpleCommand, ShellError> {
+        let mut command = SimpleCommand::default();
+
+        loop {
+            match self.peek() {
+                Some(Token::Word(_)) => {
+                    let Some(Token::Word(word)) = self.next() else {
+                        unreachable!()
+                    };
+
+                    if command.words.is_empty()
+                        && let Some(assignment) = as_assignment(&word)
+                    {
+                        command.assignments.push(assignment);
+                    } else {
+                        command.words.push(word);
+                    }
+                }
+                Some(Token::Redirect(..)) => {
+                    let offset = self.offset();
+                    let Some(Token::Redirect(fd, op)) = self.next() else {
+                        unreachable!()
+                    };
+                    self.parse_redirect(&mut command, fd, op, offset)?;
+                }
+                _ => break,
+            }
+        }
+
+        if command.assignments.is_empty()
+            && command.words.is_empty()
+            && command.redirects.is_empty()
+        {
+            return Err(ShellError::syntax(
+                "expected a command",
+                self.offset(),
+            ));
+        }
+
+        Ok(command)
+    }
+
+    fn parse_redirect(
+        &mut self,
+        command: &mut SimpleCommand,
+        fd: Option<u32>,
+        op: RedirectOp,
+        offset: usize,
+    ) -> Result<(), ShellError> {
+        let Some(Token::Word(target)) = self.next() else {
+            return Err(ShellError::syntax(
+                "expected a redirection target",
+                offset,
+            ));
+        };
+
+        match op {
+            RedirectOp::Read => command.redirects.push(Redirect {
+                fd: fd.unwrap_or(0),
+                kind: RedirectKind::Read(target),
+            }),
+            RedirectOp::Write => command.redirects.push(Redirect {
+                fd: fd.unwrap_or(1),
+                kind: RedirectKind::Write(target),
+            }),
+            RedirectOp::Append => command.redirects.push(Redirect {
+                fd: fd.unwrap_or(1),
+                kind: RedirectKind::Append(target),
+            }),
+            RedirectOp::Duplicate => {
+                let target_fd = target
+                    .as_literal()
+                    .and_then(|t| t.parse().ok())
+                    .ok_or_else(|| {
+                        ShellError::syntax(
+                            "expected a file descriptor after `>&`",
+                            offset,
+                        )
+                    })?;
+
+                command.redirects.push(Redirect {
+                    fd: fd.unwrap_or(1),
+                    kind: RedirectKind::Duplicate(target_fd),
+                });
+            }
+            RedirectOp::WriteAll => {
+                command.redirects.push(Redirect {
+                    fd: 1,
+                    kind: RedirectKind::Write(target),
+                });
+                command.redirects.push(Redirect {
+                    fd: 2,
+                    kind: RedirectKind::Duplicate(1),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Split a `NAME=value` word into an assignment. The name must be unquoted.
+fn as_assignment(word: &Word) -> Option<Assignment> {
+    let Some(WordPart::Literal {
+        text,
+        quoted: false,
+    }) = word.parts.first()
+    else {
+        return None;
+    };
+
+    let (name, value) = text.split_once('=')?;
+    if !is_name(name) {
+        return None;
+    }
+
+    let mut parts = vec![];
+    if !value.is_empty() {
+        parts.push(WordPart::Literal {
+            text: value.to_string(),
+            quoted: false,
+        });
+    }
+    parts.extend(word.parts[1..].iter().cloned());
+
+    Some(Assignment {
+        name: name.to_string(),
+        value: Word { parts },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quoted(text: &str) -> WordPart {
+        WordPart::Literal {
+            text: text.to_string(),
+            quoted: true,
+        }
+    }
+
+    fn words(command: &SimpleCommand) -> Vec<String> {
+        command
+            .words
+            .iter()
+            .map(|w| w.as_literal().expect("should be literal"))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_command() {
+        let script = parse("cargo build --release").expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        assert_eq!(words(command), vec!["cargo", "build", "--release"]);
+    }
+
+    #[test]
+    fn test_script_programs() {
+        let script = parse(
+            "cargo fmt && echo ok | tee log; FOO=1 cargo test || $RUNNER x",
+        )
+        .expect("should parse");
+
+        assert_eq!(script.programs(), vec!["cargo", "tee"]);
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        let script =
+            parse(r#"echo 'a b' "c $HOME d" e\ f """#).expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        assert_eq!(command.words[1].parts, vec![quoted("a b")]);
+        assert_eq!(
+            command.words[2].parts,
+            vec![
+                quoted("c "),
+                WordPart::Var {
+                    name: "HOME".to_string(),
+                    quoted: true
+                },
+                quoted(" d"),
+            ]
+        );
+        assert_eq!(command.words[3].as_literal().as_deref(), Some("e f"));
+        assert_eq!(command.words[4].as_literal().as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_operators() {
+        let script = parse("a && b || c; d | e\nf").expect("should parse");
+
+        assert_eq!(script.items.len(), 3);
+        assert_eq!(
+            script.items[0]
+                .rest
+                .iter()
+                .map(|(op, _)| *op)
+                .collect::<Vec<_>>(),
+            vec![AndOrOp::And, AndOrOp::Or]
+        );
+        assert_eq!(script.items[1].first.commands.len(), 2);
+        assert!(script.as_simple_command().is_none());
+    }
+
+    #[test]
+    fn test_redirects() {
+        let script = parse("cmd < in.txt > out.txt 2>&1 >> log.txt")
+            .expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        assert_eq!(words(command), vec!["cmd"]);
+        assert_eq!(
+            command.redirects,
+            vec![
+                Redirect {
+                    fd: 0,
+                    kind: RedirectKind::Read(Word::literal("in.txt"))
+                },
+                Redirect {
+                    fd: 1,
+                    kind: RedirectKind::Write(Word::literal("out.txt"))
+                },
+                Redirect {
+                    fd: 2,
+                    kind: RedirectKind::Duplicate(1)
+                },
+                Redirect {
+                    fd: 1,
+                    kind: RedirectKind::Append(Word::literal("log.txt"))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assignments() {
+        let script = parse("NODE_ENV=production FOO= vite build A=b")
+            .expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        assert_eq!(command.assignments.len(), 2);
+        assert_eq!(command.assignments[0].name, "NODE_ENV");
+        assert_eq!(
+            command.assignments[0].value.as_literal().as_deref(),
+            Some("production")
+        );
+        assert!(command.assignments[1].value.parts.is_empty());
+        assert_eq!(words(command), vec!["vite", "build", "A=b"]);
+    }
+
+    #[test]
+    fn test_negation_and_comments() {
+        let script = parse("! grep foo # not found\n").expect("should parse");
+
+        assert!(script.items[0].first.negated);
+        assert_eq!(
+            words(&script.items[0].first.commands[0]),
+            vec!["grep", "foo"]
+        );
+    }
+
+    #[test]
+    fn test_globs_are_detected_unquoted_only() {
+        let script = parse("ls *.rs '*.md'").expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        assert!(command.words[1].has_glob());
+        assert!(!command.words[2].has_glob());
+    }
+
+    #[test]
+    fn test_parameter_expansions() {
+        let script =
+            parse(r#"cargo test ${ARGS:-} "${A-a b}" ${B:+-v}${C+"x"}"#)
+                .expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        let ops = command.words[2..]
+            .iter()
+            .flat_map(|w| &w.parts)
+            .filter_map(|p| match p {
+                WordPart::Param { name, op, .. } => Some((name.as_str(), *op)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ops,
+            vec![
+                ("ARGS", ParamOp::DefaultIfEmpty),
+                ("A", ParamOp::DefaultIfUnset),
+                ("B", ParamOp::AlternativeIfNonEmpty),
+                ("C", ParamOp::AlternativeIfSet),
+            ]
+        );
+
+        let WordPart::Param {
+            word,
+            quoted: in_quotes,
+            ..
+        } = &command.words[3].parts[1]
+        else {
+            panic!("should be a parameter expansion");
+        };
+        assert!(in_quotes);
+        assert_eq!(word.parts, vec![quoted("a b")]);
+    }
+
+    #[test]
+    fn test_nested_parameter_expansions() {
+        let script = parse("cargo bench --bench ${bench:-${NAME}_benchmarks}")
+            .expect("should parse");
+
+        let command = script.as_simple_command().expect("should be simple");
+        let WordPart::Param { word, .. } = &command.words[3].parts[0] else {
+            panic!("should be a parameter expansion");
+        };
+        assert_eq!(
+            word.parts,
+            vec![
+                WordPart::Var {
+                    name: "NAME".to_string(),
+                    quoted: false
+                },
+                WordPart::Literal {
+                    text: "_benchmarks".to_string(),
+                    quoted: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse("echo 'unterminated").is_err());
+        assert!(parse("a &&").is_err());
+        assert!(parse("| a").is_err());
+        assert!(parse("sleep 1 &").is_err());
+        assert!(parse("echo $(pwd)").is_err());
+        assert!(parse("cmd >").is_err());
+        assert!(parse("echo ${A:-unterminated").is_err());
+        assert!(parse("echo ${#A}").is_err());
+        assert!(parse("echo ${:-a}").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    fs::File,
+    io::{self, PipeReader, PipeWriter, Write as _},
+    process::Stdio,
+};
+
+/// One of the process' own standard streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StdStream {
+    Stdin,
+    Stdout,
+    Stderr,
+}
+
+/// Where a standard stream of a command is connected to.
+#[derive(Debug)]
+pub(crate) enum Stream {
+    Inherit(StdStream),
+    File(File),
+    PipeReader(PipeReader),
+    PipeWriter(PipeWriter),
+}
+
+impl Stream {
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(match self {
+            Stream::Inherit(s) => Stream::Inherit(*s),
+            Stream::File(f) => Stream::File(f.try_clone()?),
+            Stream::PipeReader(r) => Stream::PipeReader(r.try_clone()?),
+            Stream::PipeWriter(w) => Stream::PipeWriter(w.try_clone()?),
+        })
+    }
+
+    /// Convert into a [`Stdio`] for the stream at `fd` of a child process.
+    pub fn into_stdio(self, fd: u32) -> io::Result<Stdio> {
+        Ok(match self {
+            Stream::Inherit(s) if s.fd() == fd => Stdio::inherit(),
+            // e.g. the stderr of `cmd 2>&1`, which has to be a copy of the
+            // process' stdout.
+            Stream::Inherit(s) => s.duplicate()?,
+            Stream::File(f) => f.into(),
+            Stream::PipeReader(r) => r.into(),
+            Stream::PipeWriter(w) => w.into(),
+        })
+    }
+
+    /// Write `bytes` to the stream, used for the output of builtins.
+    pub fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Stream::Inherit(StdStream::Stderr) => io::stderr().write_all(bytes),
+            Stream::Inherit(_) => {
+                let mut stdout = io::stdout();
+                stdout.write_all(bytes)?;
+                stdout.flush()
+            }
+            Stream::File(f) => f.write_all(bytes),
+            Stream::PipeWriter(w) => w.write_all(bytes),
+            Stream::PipeReader(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "can't write to the read end of a pipe",
+            )),
+        }
+    }
+}
+
+impl StdStream {
+    fn fd(self) -> u32 {
+        match self {
+            StdStream::Stdin => 0,
+            StdStream::Stdout => 1,
+            StdStream::Stderr => 2,
+        }
+    }
+
+    #[cfg(unix)]
+    fn duplicate(self) -> io::Result<Stdio> {
+        use std::os::fd::AsFd as _;
+
+        let fd = match self {
+            StdStream::Stdin => io::stdin().as_fd().try_clone_to_owned()?,
+            StdStream::Stdout => io::stdout().as_fd().try_clone_to_owned()?,
+            StdStream::Stderr => io::stderr().as_fd().try_clone_to_owned()?,
+        };
+
+        Ok(fd.into())
+    }
+
+    #[cfg(windows)]
+    fn duplicate(self) -> io::Result<Stdio> {
+        use std::os::windows::io::AsHandle as _;
+
+        let handle = match self {
+            StdStream::Stdin => io::stdin().as_handle().try_clone_to_owned()?,
+            StdStream::Stdout => {
+                io::stdout().as_handle().try_clone_to_owned()?
+            }
+            StdStream::Stderr => {
+                io::stderr().as_handle().try_clone_to_owned()?
+            }
+        };
+
+        Ok(handle.into())
+    }
+}
+
+/// The standard streams of a command.
+#[derive(Debug)]
+pub(crate) struct Streams {
+    pub stdin: Stream,
+    pub stdout: Stream,
+    pub stderr: Stream,
+}
+
+impl Default for Streams {
+    fn default() -> Self {
+        Self {
+            stdin: Stream::Inherit(StdStream::Stdin),
+            stdout: Stream::Inherit(StdStream::Stdout),
+            stderr: Stream::Inherit(StdStream::Stderr),
+        }
+    }
+}
+
+impl Streams {
+    pub fn get(&self, fd: u32) -> Option<&Stream> {
+        match fd {
+            0 => Some(&self.stdin),
+            1 => Some(&self.stdout),
+            2 => Some(&self.stderr),
+            _ => None,
+        }
+    }
+
+    pub fn set(&mut self, fd: u32, stream: Stream) -> bool {
+        match fd {
+            0 => self.stdin = stream,
+            1 => self.stdout = stream,
+            2 => self.stderr = stream,
+            _ => return false,
+        }
+        true
+    }
+}
@@ -58,10 +58,13 @@ impl<'a, THashProvider: TaskHashProvider, TContext: Context>
         let dependency_hashes = if !ignore_dependencies {
             node.dependencies()
                 .iter()
-                .filter_map(|d| self.hash_provider.get_task_hash(d))
-                .collect::<Vec<_>>()
+                .filter_map(|d| {
+                    let hash = self.hash_provider.get_task_hash(d)?;
+                    Some((d.clone(), hash))
+                })
+                .collect::<Map<_, _>>()
         } else {
-            vec![]
+            Map::default()
         };
         let template_context = create_template_context(
             &env_vars,
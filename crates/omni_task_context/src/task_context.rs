@@ -1,5 +1,6 @@
 use std::{borrow::Cow, sync::Arc};
 
+use maps::Map;
 use omni_core::TaskExecutionNode;
 use omni_hasher::impls::DefaultHash;
 
@@ -8,7 +9,8 @@ use crate::{CacheInfo, aliases::EnvVars};
 #[derive(Debug, Clone)]
 pub struct TaskContext<'a> {
     pub node: &'a TaskExecutionNode,
-    pub dependency_hashes: Vec<DefaultHash>,
+    /// Digests of the dependencies, keyed by their full task name.
+    pub dependency_hashes: Map<String, DefaultHash>,
     pub env_vars: Arc<EnvVars>,
     pub cache_info: Option<Cow<'a, CacheInfo>>,
     pub output_logs: Option<omni_task_output_logs::OutputLogsConfiguration>,
@@ -88,6 +88,7 @@ fn prepare(rt: &Runtime) -> Vec<Prepared> {
                     &[],
                     None,
                     None,
+                    None,
                     false,
                     false,
                 )
@@ -113,7 +114,8 @@ fn benches(c: &mut Criterion) {
     for p in &prepared {
         let overall: maps::UnorderedMap<String, TaskExecutionResult> =
             maps::unordered_map!();
-        let provider = DefaultTaskContextProvider::new(&p.ctx, &overall, None);
+        let provider =
+            DefaultTaskContextProvider::new(&p.ctx, &overall, None, None);
         let contexts: Vec<Cow<TaskContext>> = p
             .nodes
             .iter()
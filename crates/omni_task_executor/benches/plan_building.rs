@@ -84,6 +84,7 @@ fn benches(c: &mut Criterion) {
                             &[],
                             None,
                             None,
+                            None,
                             false,
                             false,
                         )
@@ -1,32 +1,53 @@
-use std::{borrow::Cow, future::Future, time::Duration};
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    future::Future,
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use futures::stream::{FuturesUnordered, StreamExt as _};
 use maps::{UnorderedMap, unordered_map};
 use omni_cache::{CachedTaskExecution, TaskExecutionCacheStore};
-use omni_command_config::{Command, CommandConfig, resolve_command};
+use omni_capabilities::{NoExtra, PathRoots};
+use omni_capability_enforcement::OsSandboxSpec;
+use omni_command_config::{
+    Command, CommandConfig, ShellMode, resolve_command_with_shell,
+};
 use omni_config_types::TeraExprBoolean;
+use omni_configurations::{Shell, Subsystem, TaskProcess};
 use omni_context::LoadedContext;
 use omni_core::TaskExecutionNode;
-use omni_hasher::impls::DefaultHash;
+use omni_hasher::{default::hash_file_streaming, impls::DefaultHash};
 use omni_messages::{
-    CacheHitEvent, DiagnosticLevel, ExecutionEventSubscriber,
-    TaskCompletedEvent, TaskFailedEvent, TaskOutputStream,
-    TaskOutputStreamEvent, TaskRetryingEvent, TaskSkipReason, TaskSkippedEvent,
-    TaskStartedEvent, publish::diagnostic,
+    CacheHitEvent, DiagnosticLevel, ExecutionEventSubscriber, ExecutionPhase,
+    PhaseCompletedEvent, TaskCompletedEvent, TaskFailedEvent,
+    TaskFailureReason, TaskOutputStream, TaskOutputStreamEvent,
+    TaskRetryingEvent, TaskSkipReason, TaskSkippedEvent, TaskStartedEvent,
+    publish::diagnostic,
 };
 use omni_process::{
-    ChildProcessError, TaskChildProcess, TaskChildProcessResult,
+    ChildProcessError, ChildProcessErrorKind, TaskChildProcess,
+    TaskChildProcessResult,
 };
 use omni_task_context::{TaskContext, TaskContextProviderExt as _};
 use omni_task_output_logs::{EffectiveOutputLogs, LogsDisplay};
 use omni_types::{OmniPath, Root, RootMap, enum_map};
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 use trace::Level;
+use tracing::{Instrument as _, field::Empty};
 
 use crate::{
     OnFailure, SkipReason, TaskDetails, TaskExecutionResult, TaskExecutorSys,
     cache_manager::{CacheManager, TaskResultContext},
+    io_audit::IoAudit,
+    ranked_semaphore::RankedSemaphore,
+    scheduler::Rank,
     task_context_provider::DefaultTaskContextProvider,
+    task_sandbox::{
+        TaskSandboxError, plan_task_sandbox, with_program_essentials,
+        with_script_programs,
+    },
 };
 
 pub struct BatchExecutor<'s, TCacheStore, TSys, S>
@@ -41,7 +62,10 @@ where
     subscriber: &'s S,
     wants_task_output_stream: bool,
     wants_task_input_stream: bool,
-    max_concurrent_tasks: usize,
+    /// Shared by every batch in flight so that `max_concurrency` bounds the
+    /// whole run rather than each batch on its own. Free permits go to the
+    /// highest ranked batch waiting on them.
+    semaphore: RankedSemaphore<Rank>,
     ignore_dependencies: bool,
     on_failure: OnFailure,
     dry_run: bool,
@@ -49,9 +73,12 @@ where
     output_cached_logs: Option<LogsDisplay>,
     max_retries: Option<u8>,
     retry_interval: Option<Duration>,
+    timeout: Option<Duration>,
     no_cache: bool,
     add_task_details: bool,
+    audit_io: bool,
     args: &'s UnorderedMap<String, serde_json::Value>,
+    dependency_hashes: Option<&'s UnorderedMap<String, DefaultHash>>,
 }
 
 impl<'s, TCacheStore, TSys, S> BatchExecutor<'s, TCacheStore, TSys, S>
@@ -74,9 +101,12 @@ where
         output_cached_logs: Option<LogsDisplay>,
         max_retries: Option<u8>,
         retry_interval: Option<Duration>,
+        timeout: Option<Duration>,
         no_cache: bool,
         add_task_details: bool,
+        audit_io: bool,
         args: &'s UnorderedMap<String, serde_json::Value>,
+        dependency_hashes: Option<&'s UnorderedMap<String, DefaultHash>>,
     ) -> Self {
         Self {
             context,
@@ -85,7 +115,7 @@ where
             wants_task_output_stream: subscriber.wants_task_output_stream(),
             wants_task_input_stream: subscriber.wants_task_input_stream(),
             subscriber,
-            max_concurrent_tasks,
+            semaphore: task_semaphore(max_concurrent_tasks),
             ignore_dependencies,
             on_failure,
             dry_run,
@@ -93,20 +123,15 @@ where
             output_cached_logs,
             max_retries,
             retry_interval,
+            timeout,
             no_cache,
             add_task_details,
+            audit_io,
             args,
+            dependency_hashes,
         }
     }
 
-    fn should_skip_batch_on_error(
-        &self,
-        overall_results: &UnorderedMap<String, TaskExecutionResult>,
-    ) -> bool {
-        self.on_failure.is_skip_next_batches()
-            && overall_results.values().any(|r| r.is_failure())
-    }
-
     fn skipped_results_due_to_error_for_batch(
         &self,
         batch: &[TaskExecutionNode],
@@ -142,6 +167,92 @@ where
             .cloned()
     }
 
+    /// How shell-style task commands are resolved, per the workspace's
+    /// `shell` setting. Scripts that need the built-in shell are run through
+    /// `omni shell -c` of the current executable.
+    fn shell_mode(&self) -> ShellMode {
+        match self.context.workspace_configuration().shell {
+            Shell::Shlex => ShellMode::Shlex,
+            Shell::Builtin => {
+                let exe = std::env::current_exe()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .unwrap_or_else(|_| "omni".to_string());
+
+                ShellMode::Builtin {
+                    interpreter: vec![
+                        exe,
+                        "shell".to_string(),
+                        "-c".to_string(),
+                    ],
+                }
+            }
+        }
+    }
+
+    /// The OS sandbox for a task, cascaded from the workspace rules tagged
+    /// `tasks`, the project's policy and the task's own. `None` unless the
+    /// workspace opted into the experimental capabilities feature and some
+    /// level declares a policy.
+    fn task_sandbox(
+        &self,
+        task_ctx: &TaskContext<'_>,
+    ) -> Result<Option<OsSandboxSpec>, TaskSandboxError> {
+        let ws = self.context.workspace_configuration();
+        if !ws.enable_experimental.capabilities() {
+            return Ok(None);
+        }
+
+        let project = task_ctx.node.project_name();
+        let task = task_ctx.node.task_name();
+        let project_policy = self.context.get_project_capabilities(project);
+        let task_policy = self.context.get_task_capabilities(project, task);
+
+        let workspace_floor =
+            ws.capabilities.rules.clone().reinterpret::<TaskProcess, _>(
+                |scope| {
+                    scope
+                        .subsystem
+                        .includes(Subsystem::Tasks)
+                        .then(NoExtra::default)
+                },
+            );
+
+        let mut levels = vec![workspace_floor];
+        let mut strictness = ws.capabilities.strictness;
+        for policy in [project_policy, task_policy].into_iter().flatten() {
+            levels.push(policy.rules.clone());
+            strictness = strictness.max(policy.strictness);
+        }
+
+        let roots = PathRoots::new()
+            .with(
+                omni_capabilities::Root::Workspace,
+                self.context.root_dir().to_path_buf(),
+            )
+            .with(
+                omni_capabilities::Root::Project,
+                task_ctx.node.project_dir().to_path_buf(),
+            );
+
+        let disabled = std::env::var_os("OMNI_DISABLE_OS_SANDBOX").is_some();
+        let Some(plan) =
+            plan_task_sandbox(&levels, strictness, &roots, disabled)?
+        else {
+            return Ok(None);
+        };
+
+        for warning in &plan.warnings {
+            diagnostic!(
+                self.subscriber,
+                DiagnosticLevel::Warn,
+                "task '{}': {warning}",
+                task_ctx.node.full_task_name(),
+            );
+        }
+
+        Ok(plan.spec)
+    }
+
     fn resolve_output_logs(
         &self,
         task_ctx: &TaskContext<'_>,
@@ -199,21 +310,57 @@ where
                 .await;
         }
 
-        // hard link the cached files to the original file paths if they don't exist
+        // hard link the cached files to the original file paths unless the
+        // files on disk already have the cached content
         if !self.dry_run {
+            let mut current = Vec::with_capacity(res.files.len());
             for file in res.files.iter() {
                 let original_path =
                     file.original_path.path().expect("should be resolved");
 
-                if self.sys.fs_exists_async(original_path).await? {
+                current.push(
+                    if self.sys.fs_exists_async(original_path).await? {
+                        let path = original_path.to_path_buf();
+                        Some(
+                            tokio::task::spawn_blocking(move || {
+                                hash_file_streaming(&path)
+                            })
+                            .await
+                            .map_err(std::io::Error::other)??,
+                        )
+                    } else {
+                        None
+                    },
+                );
+            }
+
+            if res.outputs_up_to_date(&current) {
+                diagnostic!(
+                    self.subscriber,
+                    DiagnosticLevel::Debug,
+                    "outputs of {} are up to date, skipping cache restore",
+                    task_ctx.node.full_task_name(),
+                );
+                return Ok(());
+            }
+
+            for (file, current) in res.files.iter().zip(&current) {
+                let original_path =
+                    file.original_path.path().expect("should be resolved");
+
+                if !file.needs_restore(current.as_ref()) {
                     diagnostic!(
                         self.subscriber,
                         DiagnosticLevel::Debug,
-                        "file already exists {original_path:?}, skipping cache restore",
+                        "file {original_path:?} is up to date, skipping cache restore",
                     );
                     continue;
                 }
 
+                if current.is_some() {
+                    self.sys.fs_remove_file_async(original_path).await?;
+                }
+
                 let dir = original_path.parent().expect("should have parent");
                 if !self.sys.fs_exists_async(dir).await? {
                     self.sys.fs_create_dir_all_async(dir).await?;
@@ -367,44 +514,77 @@ where
         }
     }
 
+    /// Skips every task in `batch` because an earlier task failed and
+    /// `on_failure` is set to `skip-next-batches`.
+    pub async fn skip_batch(
+        &self,
+        batch: &[TaskExecutionNode],
+    ) -> UnorderedMap<String, TaskExecutionResult> {
+        for task in batch {
+            self.subscriber
+                .on_task_skipped(TaskSkippedEvent {
+                    task_id: task.full_task_name().to_string(),
+                    project: task.project_name().to_string(),
+                    task: task.task_name().to_string(),
+                    reason: TaskSkipReason::PreviousBatchFailure,
+                    dependency: None,
+                })
+                .await;
+        }
+
+        self.skipped_results_due_to_error_for_batch(batch)
+    }
+
     async fn execute_batch_inner<'a>(
-        &mut self,
-        batch: &'a [TaskExecutionNode],
+        &self,
         task_contexts: &'a [Cow<'a, TaskContext<'a>>],
+        rank: Rank,
         overall_results: &'a UnorderedMap<String, TaskExecutionResult>,
     ) -> Result<UnorderedMap<String, TaskExecutionResult>, BatchExecutorError>
     where
         's: 'a,
     {
-        // skip this batch if any error was encountered in a previous batch
-        // when on_failure is set to skip_next_batches
-        if self.should_skip_batch_on_error(overall_results) {
-            for task in batch {
-                self.subscriber
-                    .on_task_skipped(TaskSkippedEvent {
-                        task_id: task.full_task_name().to_string(),
-                        project: task.project_name().to_string(),
-                        task: task.task_name().to_string(),
-                        reason: TaskSkipReason::PreviousBatchFailure,
-                        dependency: None,
-                    })
-                    .await;
-            }
-
-            let skipped_results =
-                self.skipped_results_due_to_error_for_batch(batch);
-            return Ok(skipped_results);
-        }
-
+        let lookup_started = Instant::now();
+        let lookup_span = tracing::info_span!(
+            "cache_lookup",
+            otel.name = "cache lookup",
+            omni.tasks = task_contexts.len(),
+            omni.cache_hits = Empty,
+        );
         let cached_results = self
             .cache_manager
             .get_cached_results(task_contexts)
+            .instrument(lookup_span.clone())
             .await
             .map_err(BatchExecutorErrorInner::new_cant_get_cached_results)?;
+        lookup_span.record("omni.cache_hits", cached_results.len());
+        drop(lookup_span);
+        phase_completed(
+            self.subscriber,
+            ExecutionPhase::CacheLookup,
+            None,
+            lookup_started,
+        )
+        .await;
+
+        for rejected in self.cache_manager.take_rejected_artifacts() {
+            diagnostic!(
+                self.subscriber,
+                DiagnosticLevel::Warn,
+                "remote cache artifact {} of task '{}#{}' was rejected because {}, treating it as a cache miss",
+                rejected.digest,
+                rejected.project_name,
+                rejected.task_name,
+                rejected.reason,
+            );
+        }
 
         let mut new_results = unordered_map!(cap: task_contexts.len());
         let mut fut_results = Vec::with_capacity(task_contexts.len());
         let mut futs = Vec::with_capacity(task_contexts.len());
+        // kept open until the hashes of the executed tasks are known
+        let mut task_spans = unordered_map!(cap: task_contexts.len());
+        let shell = self.shell_mode();
 
         for task_ctx in task_contexts {
             if task_ctx.node.task_exec_config().is_none() {
@@ -490,7 +670,33 @@ where
                     ),
                 );
 
-                self.replay_cached_results(task_ctx, cached_result).await?;
+                let span = task_span(task_ctx);
+                span.record("omni.cache_hit", true);
+                span.record(
+                    "omni.hash",
+                    bs58::encode(cached_result.digest).into_string(),
+                );
+                span.record("omni.exit_code", cached_result.exit_code);
+                span.record(
+                    "omni.retries",
+                    cached_result.tries.saturating_sub(1),
+                );
+
+                let restore_started = Instant::now();
+                self.replay_cached_results(task_ctx, cached_result)
+                    .instrument(tracing::info_span!(
+                        parent: &span,
+                        "cache_restore",
+                        otel.name = "cache restore",
+                    ))
+                    .await?;
+                phase_completed(
+                    self.subscriber,
+                    ExecutionPhase::CacheRestore,
+                    Some(task_ctx.node.full_task_name()),
+                    restore_started,
+                )
+                .await;
 
                 continue;
             }
@@ -521,41 +727,69 @@ where
                 let override_command = resolve_task_command(
                     task_ctx.node.task_exec_config(),
                     task_ctx,
+                    &shell,
                 )?;
 
                 let override_retry_command = resolve_task_command(
                     task_ctx.node.task_retry_exec_config(),
                     task_ctx,
+                    &shell,
                 )?;
-                futs.push(run_process(
-                    self.subscriber,
-                    self.wants_task_output_stream,
-                    self.wants_task_input_stream,
-                    task_ctx,
-                    override_command,
-                    override_retry_command,
-                    record_logs,
-                    self.resolve_output_logs(task_ctx),
-                    self.max_retries
-                        .unwrap_or(task_ctx.node.max_retries().unwrap_or(0)),
-                    self.retry_interval.or(task_ctx.node.retry_interval()),
-                ));
+                let span = task_span(task_ctx);
+                task_spans.insert(
+                    task_ctx.node.full_task_name().to_string(),
+                    span.clone(),
+                );
+                futs.push(
+                    run_process(
+                        self.subscriber,
+                        self.wants_task_output_stream,
+                        self.wants_task_input_stream,
+                        task_ctx,
+                        override_command,
+                        override_retry_command,
+                        record_logs,
+                        self.resolve_output_logs(task_ctx),
+                        self.max_retries.unwrap_or(
+                            task_ctx.node.max_retries().unwrap_or(0),
+                        ),
+                        self.retry_interval.or(task_ctx.node.retry_interval()),
+                        self.timeout.or(task_ctx.node.timeout()),
+                        &shell,
+                        || self.task_sandbox(task_ctx),
+                        self.audit_io
+                            .then(|| IoAudit::new(self.context.root_dir())),
+                    )
+                    .instrument(span),
+                );
             }
         }
 
-        // Run the batch's tasks with at most `max_concurrent_tasks` in flight,
-        // refilling a slot the instant any task finishes (no `join_all` convoy
-        // stalls where a straggler idles the other slots). The inter-batch
-        // barrier is unaffected: the pipeline still awaits the whole batch
-        // before starting the next one, so cross-batch dependency ordering is
-        // preserved exactly as before.
-        fut_results.extend(run_bounded(futs, self.max_concurrent_tasks).await);
+        // Run the batch's tasks while holding a permit of the shared
+        // semaphore, refilling a slot the instant any task finishes. Other
+        // batches started by the scheduler draw from the same permits, so the
+        // concurrency bound holds across the whole run, and a freed permit
+        // goes to the batch the scheduler ranked highest.
+        fut_results.extend(run_bounded(futs, rank, &self.semaphore).await);
 
+        let store_started = Instant::now();
         let hashes = self
             .cache_manager
             .cache_results(&fut_results)
+            .instrument(tracing::info_span!(
+                "cache_store",
+                otel.name = "cache store",
+                omni.tasks = fut_results.len(),
+            ))
             .await
             .map_err(BatchExecutorErrorInner::new_cant_cache_results)?;
+        phase_completed(
+            self.subscriber,
+            ExecutionPhase::CacheStore,
+            None,
+            store_started,
+        )
+        .await;
 
         for fut_result in &fut_results {
             let fname =
@@ -569,6 +803,8 @@ where
                     .cache_info
                     .as_ref()
                     .is_some_and(|ci| !ci.cache_enabled)
+                // never cache tasks that were killed for running too long
+                || fut_result.timed_out()
             {
                 DefaultHash::default()
             } else {
@@ -587,6 +823,12 @@ where
                 })?
             };
 
+            if let Some(span) = task_spans.get(&fname)
+                && hash != DefaultHash::default()
+            {
+                span.record("omni.hash", bs58::encode(hash).into_string());
+            }
+
             let result = match fut_result {
                 TaskResultContext::Completed {
                     task_context,
@@ -626,24 +868,34 @@ where
         )
     )]
     pub async fn execute_batch<'a>(
-        &mut self,
+        &self,
         batch: &'a [TaskExecutionNode],
+        rank: Rank,
         overall_results: &'a UnorderedMap<String, TaskExecutionResult>,
     ) -> Result<UnorderedMap<String, TaskExecutionResult>, BatchExecutorError>
     {
         let ctx_provider = DefaultTaskContextProvider::new(
             self.context,
             overall_results,
+            self.dependency_hashes,
             Some(self.args),
         );
 
+        let contexts_started = Instant::now();
         let tmp_task_contexts = ctx_provider
             .get_task_contexts(batch, self.ignore_dependencies)
             .map_err(BatchExecutorErrorInner::new_cant_get_task_contexts)?;
         let task_contexts = self.expand_templates(&tmp_task_contexts)?;
+        phase_completed(
+            self.subscriber,
+            ExecutionPhase::TaskContexts,
+            None,
+            contexts_started,
+        )
+        .await;
 
         let mut new_results = self
-            .execute_batch_inner(batch, &task_contexts, overall_results)
+            .execute_batch_inner(&task_contexts, rank, overall_results)
             .await?;
 
         if self.add_task_details {
@@ -680,17 +932,36 @@ where
 fn resolve_task_command<'a>(
     spec: Option<&CommandConfig>,
     task_ctx: &Cow<'_, TaskContext<'a>>,
+    shell: &ShellMode,
 ) -> Result<Option<Command>, BatchExecutorError> {
     match spec {
-        Some(spec) => Ok(Some(resolve_command(
+        Some(spec) => Ok(Some(resolve_command_with_shell(
             spec,
             Some(&task_ctx.template_context),
             Some(&task_ctx.env_vars),
+            shell,
         )?)),
         None => Ok(None),
     }
 }
 
+/// The script of a command that runs through the built-in shell's
+/// interpreter.
+fn builtin_script<'c>(
+    shell: &ShellMode,
+    prog: &str,
+    args: &'c [String],
+) -> Option<&'c str> {
+    let ShellMode::Builtin { interpreter } = shell else {
+        return None;
+    };
+    let (interpreter_prog, interpreter_args) = interpreter.split_first()?;
+    let (script, args) = args.split_last()?;
+
+    (prog == interpreter_prog && args == interpreter_args)
+        .then_some(script.as_str())
+}
+
 fn evaluate_bool_expr(
     expr: &TeraExprBoolean,
     context: &omni_tera::Context,
@@ -735,9 +1006,16 @@ fn expand_omni_path(
     })
 }
 
-/// Drives every future in `futures` to completion while keeping at most
-/// `max_concurrency` (clamped to a minimum of 1) in flight at once, returning
-/// all of their outputs.
+/// Creates the semaphore bounding concurrently running tasks, clamping
+/// `max_concurrency` to a minimum of 1 so a run can always make progress.
+fn task_semaphore<R: Ord>(max_concurrency: usize) -> RankedSemaphore<R> {
+    RankedSemaphore::new(max_concurrency.max(1))
+}
+
+/// Drives every future in `futures` to completion while only letting those
+/// holding a permit of `semaphore` make progress, returning all of their
+/// outputs. The permits are requested with `rank`, so while the semaphore is
+/// saturated the futures of higher ranked calls sharing it start first.
 ///
 /// A permit is acquired before a future is allowed to make progress and is
 /// released the instant it completes, so a freed slot is refilled immediately
@@ -748,32 +1026,31 @@ fn expand_omni_path(
 /// which tasks happen to finish.
 ///
 /// Invariants (see tests):
-/// - the number of futures past the permit gate never exceeds
-///   `max(max_concurrency, 1)`;
+/// - the number of futures past the permit gate never exceeds the permits of
+///   `semaphore`, even when several calls share it;
 /// - every future is polled to completion exactly once and all outputs are
 ///   returned;
-/// - an empty input completes immediately without acquiring anything.
-async fn run_bounded<F>(
+/// - an empty input completes immediately without acquiring anything;
+/// - a freed permit goes to the highest ranked waiting future.
+async fn run_bounded<R, F>(
     futures: Vec<F>,
-    max_concurrency: usize,
+    rank: R,
+    semaphore: &RankedSemaphore<R>,
 ) -> Vec<F::Output>
 where
+    R: Ord + Clone,
     F: Future,
 {
     if futures.is_empty() {
         return Vec::new();
     }
 
-    let semaphore = tokio::sync::Semaphore::new(max_concurrency.max(1));
     let mut running = FuturesUnordered::new();
 
     for fut in futures {
-        let semaphore = &semaphore;
+        let rank = rank.clone();
         running.push(async move {
-            let _permit = semaphore
-                .acquire()
-                .await
-                .expect("task semaphore is never closed");
+            let _permit = semaphore.acquire(rank).await;
             fut.await
         });
     }
@@ -785,8 +1062,39 @@ where
     results
 }
 
+/// The span of a task. Its hash, retries and exit code are recorded as they
+/// become known.
+fn task_span(task_ctx: &TaskContext<'_>) -> tracing::Span {
+    tracing::info_span!(
+        "task",
+        otel.name = %task_ctx.node.full_task_name(),
+        otel.status_code = Empty,
+        omni.project = %task_ctx.node.project_name(),
+        omni.task = %task_ctx.node.task_name(),
+        omni.hash = Empty,
+        omni.cache_hit = false,
+        omni.retries = Empty,
+        omni.exit_code = Empty,
+    )
+}
+
+async fn phase_completed<S: ExecutionEventSubscriber>(
+    subscriber: &S,
+    phase: ExecutionPhase,
+    task_id: Option<&str>,
+    started: Instant,
+) {
+    subscriber
+        .on_phase_completed(PhaseCompletedEvent {
+            phase,
+            task_id: task_id.map(str::to_string),
+            elapsed: started.elapsed(),
+        })
+        .await;
+}
+
 #[allow(clippy::too_many_arguments)]
-async fn run_process<'a, S: ExecutionEventSubscriber>(
+async fn run_process<'a, S, F>(
     subscriber: &'a S,
     wants_task_output_stream: bool,
     wants_task_input_stream: bool,
@@ -797,7 +1105,15 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
     output_logs: EffectiveOutputLogs,
     max_retries: u8,
     retry_duration: Option<Duration>,
-) -> TaskResultContext<'a> {
+    timeout: Option<Duration>,
+    shell: &ShellMode,
+    os_sandbox: F,
+    io_audit: Option<IoAudit<'_>>,
+) -> TaskResultContext<'a>
+where
+    S: ExecutionEventSubscriber,
+    F: FnOnce() -> Result<Option<OsSandboxSpec>, TaskSandboxError>,
+{
     let mut tries = 0u8;
 
     let reg_cmd: Option<&Command> = override_command.as_ref();
@@ -812,8 +1128,47 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
         })
         .await;
 
+    // An unenforceable policy fails the task closed before anything spawns.
+    let os_sandbox = match os_sandbox() {
+        Ok(spec) => spec,
+        Err(e) => {
+            subscriber
+                .on_task_failed(TaskFailedEvent {
+                    task_id: task_ctx.node.full_task_name().to_string(),
+                    project: task_ctx.node.project_name().to_string(),
+                    task: task_ctx.node.task_name().to_string(),
+                    reason: TaskFailureReason::Error,
+                    error: e.to_string(),
+                    tries: 0,
+                })
+                .await;
+            return TaskResultContext::new_error(
+                task_ctx,
+                ChildProcessError::custom(e),
+                0,
+            );
+        }
+    };
+
+    let mut audit_log = None;
+
+    // Let tasks that emit spans themselves join the trace of the run.
+    let env_vars =
+        match omni_tracing_subscriber::traceparent(&tracing::Span::current()) {
+            Some(traceparent) => {
+                let mut env_vars = (*task_ctx.env_vars).clone();
+                env_vars.insert(
+                    omni_tracing_subscriber::TRACEPARENT.to_string(),
+                    traceparent,
+                );
+                Cow::Owned(env_vars)
+            }
+            None => Cow::Borrowed(&*task_ctx.env_vars),
+        };
+
     let result = loop {
         tries += 1;
+        let spawn_started = Instant::now();
 
         let command = if tries > 1 { retry_cmd } else { reg_cmd };
 
@@ -832,6 +1187,49 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
             None => (String::new(), Vec::new()),
         };
 
+        // Grant the resolved program's own directory so the confined child can
+        // `execve` it. The built-in shell spawns the programs of its script
+        // itself, so their directories are granted too.
+        let mut task_sandbox = os_sandbox.clone().map(|spec| {
+            let path_env = task_ctx.env_vars.get("PATH").map(OsStr::new);
+            let cwd = task_ctx.node.project_dir();
+            let spec = with_program_essentials(spec, &prog, path_env, cwd);
+
+            match builtin_script(shell, &prog, &args) {
+                Some(script) => {
+                    with_script_programs(spec, script, path_env, cwd)
+                }
+                None => spec,
+            }
+        });
+
+        // Under `--audit-io` the task runs as a child of the tracing wrapper,
+        // which the sandbox must also let start.
+        let (prog, args) = match &io_audit {
+            Some(audit) if !prog.is_empty() => match audit.wrap(prog, args) {
+                Ok((log, wrapper, wrapped)) => {
+                    audit_log = Some(log);
+                    task_sandbox = task_sandbox.map(|spec| {
+                        with_program_essentials(
+                            spec,
+                            &wrapper,
+                            None,
+                            task_ctx.node.project_dir(),
+                        )
+                    });
+                    (wrapper, wrapped)
+                }
+                Err(e) => {
+                    return TaskResultContext::new_error(
+                        task_ctx,
+                        ChildProcessError::custom(e),
+                        tries,
+                    );
+                }
+            },
+            _ => (prog, args),
+        };
+
         let mut proc =
             match TaskChildProcess::new(task_ctx.node.clone(), prog, args) {
                 Ok(o) => o,
@@ -888,12 +1286,30 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
         }
 
         proc.record_logs(record_logs)
-            .env_vars(&task_ctx.env_vars)
+            .timeout(timeout)
+            .os_sandbox(task_sandbox)
+            .env_vars(&env_vars)
             .keep_stdin_open(
                 task_ctx.node.persistent() || task_ctx.node.interactive(),
             );
 
+        phase_completed(
+            subscriber,
+            ExecutionPhase::Spawn,
+            Some(task_ctx.node.full_task_name()),
+            spawn_started,
+        )
+        .await;
+
+        let process_started = Instant::now();
         let result = proc.exec().await;
+        phase_completed(
+            subscriber,
+            ExecutionPhase::Process,
+            Some(task_ctx.node.full_task_name()),
+            process_started,
+        )
+        .await;
 
         if (result.is_err() || result.as_ref().is_ok_and(|f| !f.success()))
             && tries <= max_retries
@@ -928,6 +1344,24 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
         break result;
     };
 
+    if let (Some(audit), Some(log), Ok(_)) = (&io_audit, &audit_log, &result) {
+        report_io_audit(subscriber, task_ctx, audit, log);
+    }
+
+    let span = tracing::Span::current();
+    span.record("omni.retries", tries.saturating_sub(1));
+    match &result {
+        Ok(t) => {
+            span.record("omni.exit_code", t.exit_code());
+            if !t.success() {
+                span.record("otel.status_code", "ERROR");
+            }
+        }
+        Err(_) => {
+            span.record("otel.status_code", "ERROR");
+        }
+    }
+
     match result {
         Ok(t) => {
             let elapsed = t.elapsed;
@@ -950,6 +1384,7 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
                         task_id: task_ctx.node.full_task_name().to_string(),
                         project: task_ctx.node.project_name().to_string(),
                         task: task_ctx.node.task_name().to_string(),
+                        reason: TaskFailureReason::ExitCode,
                         error: format!("exit code '{}'", exit_code),
                         tries,
                     })
@@ -958,11 +1393,17 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
             TaskResultContext::new_completed(task_ctx, t, tries)
         }
         Err(e) => {
+            let reason = if e.kind() == ChildProcessErrorKind::TimedOut {
+                TaskFailureReason::TimedOut
+            } else {
+                TaskFailureReason::Error
+            };
             subscriber
                 .on_task_failed(TaskFailedEvent {
                     task_id: task_ctx.node.full_task_name().to_string(),
                     project: task_ctx.node.project_name().to_string(),
                     task: task_ctx.node.task_name().to_string(),
+                    reason,
                     error: e.to_string(),
                     tries,
                 })
@@ -972,6 +1413,60 @@ async fn run_process<'a, S: ExecutionEventSubscriber>(
     }
 }
 
+/// Surface the `--audit-io` findings for a finished task as warnings.
+fn report_io_audit<S: ExecutionEventSubscriber>(
+    subscriber: &S,
+    task_ctx: &TaskContext<'_>,
+    audit: &IoAudit<'_>,
+    log: &Path,
+) {
+    let task = task_ctx.node.full_task_name();
+    let report = match audit.audit(task_ctx, log) {
+        Ok(report) => report,
+        Err(e) => {
+            diagnostic!(
+                subscriber,
+                DiagnosticLevel::Warn,
+                "task '{task}': could not audit file accesses: {e}",
+            );
+            return;
+        }
+    };
+
+    let lists = [
+        (
+            "reads undeclared inputs",
+            report
+                .undeclared_reads
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "writes undeclared outputs",
+            report
+                .undeclared_writes
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+        ),
+        ("never reads declared inputs", report.untouched_inputs),
+    ];
+
+    for (what, paths) in lists {
+        if paths.is_empty() {
+            continue;
+        }
+
+        diagnostic!(
+            subscriber,
+            DiagnosticLevel::Warn,
+            "task '{task}' {what}:\n  {}",
+            paths.join("\n  "),
+        );
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub struct BatchExecutorError(pub(crate) BatchExecutorErrorInner);
@@ -1020,6 +1515,9 @@ pub(crate) enum BatchExecutorErrorInner {
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
+    #[error(transparent)]
+    Hasher(#[from] omni_hasher::HasherError),
+
     #[error(transparent)]
     Tera(#[from] omni_tera::Error),
 
@@ -1152,7 +1650,8 @@ mod tests {
         let max_seen = Arc::new(AtomicUsize::new(0));
         let futs = instrumented_futs(n, 8, current, max_seen, |_| 2);
 
-        let mut out = super::run_bounded(futs, 8).await;
+        let mut out =
+            super::run_bounded(futs, (), &super::task_semaphore(8)).await;
         out.sort();
 
         // Completeness: no dropped or duplicated tasks.
@@ -1168,7 +1667,8 @@ mod tests {
         let futs =
             instrumented_futs(n, limit, current, max_seen.clone(), |_| 3);
 
-        let out = super::run_bounded(futs, limit).await;
+        let out =
+            super::run_bounded(futs, (), &super::task_semaphore(limit)).await;
 
         assert_eq!(out.len(), n, "all tasks completed");
         // The in-task assertion already guards the upper bound; also assert the
@@ -1187,7 +1687,7 @@ mod tests {
         let max_seen = Arc::new(AtomicUsize::new(0));
         let futs = instrumented_futs(n, 1, current, max_seen.clone(), |_| 2);
 
-        let out = super::run_bounded(futs, 1).await;
+        let out = super::run_bounded(futs, (), &super::task_semaphore(1)).await;
 
         assert_eq!(out.len(), n);
         assert_eq!(
@@ -1204,7 +1704,8 @@ mod tests {
         let max_seen = Arc::new(AtomicUsize::new(0));
         let futs = instrumented_futs(n, n, current, max_seen.clone(), |_| 2);
 
-        let out = super::run_bounded(futs, 100).await;
+        let out =
+            super::run_bounded(futs, (), &super::task_semaphore(100)).await;
 
         assert_eq!(out.len(), n);
         assert_eq!(max_seen.load(Ordering::SeqCst), n);
@@ -1219,17 +1720,72 @@ mod tests {
         let futs = instrumented_futs(n, 1, current, max_seen.clone(), |_| 1);
 
         // Must make progress (not deadlock) despite a 0 request.
-        let out = super::run_bounded(futs, 0).await;
+        let out = super::run_bounded(futs, (), &super::task_semaphore(0)).await;
 
         assert_eq!(out.len(), n);
         assert_eq!(max_seen.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn run_bounded_shared_semaphore_bounds_concurrent_batches() {
+        let limit = 3;
+        let current = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+        let first = instrumented_futs(
+            6,
+            limit,
+            current.clone(),
+            max_seen.clone(),
+            |_| 3,
+        );
+        let second =
+            instrumented_futs(6, limit, current, max_seen.clone(), |_| 2);
+
+        let semaphore = super::task_semaphore(limit);
+        let (a, b) = tokio::join!(
+            super::run_bounded(first, (), &semaphore),
+            super::run_bounded(second, (), &semaphore),
+        );
+
+        assert_eq!(a.len() + b.len(), 12);
+        assert_eq!(
+            max_seen.load(Ordering::SeqCst),
+            limit,
+            "batches sharing a semaphore must share its {limit} slots"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_bounded_saturated_semaphore_starts_batches_by_rank() {
+        let started = Arc::new(std::sync::Mutex::new(vec![]));
+        let batch = |rank: i32| {
+            let started = started.clone();
+            vec![async move {
+                started.lock().unwrap().push(rank);
+                for _ in 0..3 {
+                    tokio::task::yield_now().await;
+                }
+            }]
+        };
+
+        // The first batch takes the only permit, the others queue up behind it
+        // in an order that doesn't match their rank.
+        let semaphore = super::task_semaphore(1);
+        tokio::join!(
+            super::run_bounded(batch(0), 0, &semaphore),
+            super::run_bounded(batch(1), 1, &semaphore),
+            super::run_bounded(batch(3), 3, &semaphore),
+            super::run_bounded(batch(2), 2, &semaphore),
+        );
+
+        assert_eq!(*started.lock().unwrap(), [0, 3, 2, 1]);
+    }
+
     #[tokio::test]
     async fn run_bounded_empty_input_returns_empty() {
         let futs: Vec<std::pin::Pin<Box<dyn Future<Output = usize>>>> =
             Vec::new();
-        let out = super::run_bounded(futs, 8).await;
+        let out = super::run_bounded(futs, (), &super::task_semaphore(8)).await;
         assert!(out.is_empty());
     }
 
@@ -1242,14 +1798,18 @@ mod tests {
         let c1 = Arc::new(AtomicUsize::new(0));
         let m1 = Arc::new(AtomicUsize::new(0));
         let forward = instrumented_futs(n, limit, c1, m1, |i| i % 4);
-        let mut forward_out = super::run_bounded(forward, limit).await;
+        let mut forward_out =
+            super::run_bounded(forward, (), &super::task_semaphore(limit))
+                .await;
         forward_out.sort();
 
         // Reverse: later tasks finish first (more yields for low indices).
         let c2 = Arc::new(AtomicUsize::new(0));
         let m2 = Arc::new(AtomicUsize::new(0));
         let reverse = instrumented_futs(n, limit, c2, m2, |i| (n - i) % 4);
-        let mut reverse_out = super::run_bounded(reverse, limit).await;
+        let mut reverse_out =
+            super::run_bounded(reverse, (), &super::task_semaphore(limit))
+                .await;
         reverse_out.sort();
 
         // Regardless of completion order, the complete set is returned; the
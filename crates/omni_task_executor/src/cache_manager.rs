@@ -5,10 +5,12 @@ use derive_new::new;
 use maps::{UnorderedMap, unordered_map};
 use omni_cache::{
     CachedTaskExecution, CachedTaskExecutionHash, NewCacheInfo,
-    TaskExecutionCacheStore, TaskExecutionInfoExt as _,
+    RejectedArtifact, TaskExecutionCacheStore, TaskExecutionInfoExt as _,
 };
 use omni_collector::{CollectConfig, Collector, CollectorSys, ProjectTaskInfo};
-use omni_process::{ChildProcessError, TaskChildProcessResult};
+use omni_process::{
+    ChildProcessError, ChildProcessErrorKind, TaskChildProcessResult,
+};
 use omni_task_context::TaskContext;
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 
@@ -87,6 +89,17 @@ impl<'a> TaskResultContext<'a> {
             TaskResultContext::Error { .. } => None,
         }
     }
+
+    /// Whether the task was killed for exceeding its timeout. Such runs say
+    /// nothing about the task's inputs and must never be cached.
+    pub fn timed_out(&self) -> bool {
+        match self {
+            TaskResultContext::Completed { .. } => false,
+            TaskResultContext::Error { error, .. } => {
+                error.kind() == ChildProcessErrorKind::TimedOut
+            }
+        }
+    }
 }
 
 impl<TCacheStore, TSys> CacheManager<TCacheStore, TSys>
@@ -130,6 +143,22 @@ where
             .collect())
     }
 
+    /// The remote artifacts rejected by integrity verification while getting
+    /// cached results since the last call.
+    pub fn take_rejected_artifacts(&self) -> Vec<RejectedArtifact> {
+        self.store.take_rejected_artifacts()
+    }
+
+    /// How long the tasks took the last time they ran successfully, by full
+    /// task name.
+    pub async fn get_execution_durations(
+        &self,
+    ) -> Result<UnorderedMap<String, Duration>, CacheManagerError> {
+        Ok(self.store.get_execution_durations().await.map_err(|e| {
+            CacheManagerErrorInner::GetDurationsFailed { source: e.into() }
+        })?)
+    }
+
     pub async fn cache_results<'a>(
         &'a self,
         cache_contexts: &'a [TaskResultContext<'a>],
@@ -149,6 +178,7 @@ where
                     .as_ref()
                     .is_some_and(|ci| ci.cache_enabled)
                     && !r.task_context().node.persistent()
+                    && !r.timed_out()
                     && let Some(exec_info) = r.task_context().execution_info()
                 {
                     Some(NewCacheInfo {
@@ -260,6 +290,12 @@ pub(crate) enum CacheManagerErrorInner {
         source: eyre::Report,
     },
 
+    #[error("failed to get execution durations")]
+    GetDurationsFailed {
+        #[source]
+        source: eyre::Report,
+    },
+
     #[error(transparent)]
     Collector(#[from] omni_collector::error::Error),
 }
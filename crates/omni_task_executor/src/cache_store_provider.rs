@@ -37,6 +37,8 @@ impl<'a, TSys: ContextSys> CacheStoreProvider
                     rc.organization_code.as_str(),
                     rc.workspace_code.as_str(),
                     rc.environment_code.clone(),
+                    rc.compression,
+                    rc.signing_key.clone(),
                 ))
             } else {
                 RemoteConfig::new_disabled()
@@ -3,8 +3,10 @@ use std::time::Duration;
 use derive_builder::Builder;
 use getset::{CloneGetters, CopyGetters, Getters};
 use maps::UnorderedMap;
+use omni_hasher::impls::DefaultHash;
+use sets::UnorderedSet;
 
-use omni_execution_plan::{Call, ScmAffectedFilter};
+use omni_execution_plan::{Call, ScmAffectedFilter, TagFilter};
 use omni_task_output_logs::LogsDisplay;
 
 use crate::{Force, OnFailure};
@@ -34,6 +36,11 @@ pub struct ExecutionConfig {
     #[getset(get = "pub")]
     meta_filter: Option<String>,
 
+    /// Filter the projects based on their tags
+    #[builder(default)]
+    #[getset(get = "pub")]
+    tag_filter: Option<TagFilter>,
+
     /// Glob pattern to filter the directories
     #[builder(default)]
     #[getset(get = "pub")]
@@ -86,13 +93,47 @@ pub struct ExecutionConfig {
     #[getset(get_copy = "pub")]
     retry_interval: Option<Duration>,
 
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    timeout: Option<Duration>,
+
+    /// Trace the files each task process reads and writes and report the
+    /// ones its cache configuration does not declare
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    audit_io: bool,
+
     #[builder(default)]
     #[getset(get = "pub")]
     scm_affected_filter: Option<ScmAffectedFilter>,
 
+    /// Restrict the resolved plan to these tasks, by full task name. Their
+    /// dependencies outside of the set are assumed to be up to date
+    #[builder(default)]
+    #[getset(get = "pub")]
+    only_tasks: Option<UnorderedSet<String>>,
+
+    /// The hashes of tasks from an earlier run, by full task name. They stand
+    /// in for the dependencies outside of `only_tasks`, so the tasks that run
+    /// get the same cache keys as they would in a full run
+    #[builder(default)]
+    #[getset(get = "pub")]
+    dependency_hashes: Option<UnorderedMap<String, DefaultHash>>,
+
     #[builder(default)]
     #[getset(get = "pub")]
     args: UnorderedMap<String, serde_json::Value>,
+
+    /// Add the run to the run history of the workspace once it completes
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    record_history: bool,
+
+    /// The command line the run was started from, kept in the run history.
+    /// Values that may carry secrets should be masked by the caller.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    command_line: Option<String>,
 }
 
 impl ExecutionConfigBuilder {
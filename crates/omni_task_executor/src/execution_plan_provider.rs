@@ -4,6 +4,7 @@ use omni_core::BatchedExecutionPlan;
 use omni_execution_plan::{
     Call, Context as ContextTrait, DefaultExecutionPlanProvider,
     ExecutionPlanProvider, ExecutionPlanProviderError, ScmAffectedFilter,
+    TagFilter,
 };
 use omni_types::OmniPath;
 
@@ -34,6 +35,7 @@ impl<'a, TSys: ContextSys> ExecutionPlanProvider
         project_filters: &[&str],
         dir_filters: &[&str],
         meta_filter: Option<&str>,
+        tag_filter: Option<&TagFilter>,
         scm_affected_filter: Option<&ScmAffectedFilter>,
         ignore_deps: bool,
         with_dependents: bool,
@@ -43,6 +45,7 @@ impl<'a, TSys: ContextSys> ExecutionPlanProvider
             project_filters,
             dir_filters,
             meta_filter,
+            tag_filter,
             scm_affected_filter,
             ignore_deps,
             with_dependents,
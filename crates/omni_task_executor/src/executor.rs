@@ -8,9 +8,15 @@ use omni_execution_plan::{
 };
 use omni_messages::{
     DiagnosticLevel, ExecutionCompleteEvent, ExecutionEventSubscriber,
-    ExecutionPlanReadyEvent, TracingSubscriber, diagnostic,
+    ExecutionPhase, ExecutionPlanReadyEvent, PhaseCompletedEvent,
+    TracingSubscriber, diagnostic,
+};
+use omni_run_history::{
+    RunFilters, RunHistory, RunRecord, TaskRunRecord, TaskRunStatus,
 };
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
+use time::OffsetDateTime;
+use tracing::{Instrument as _, field::Empty};
 
 use derive_new::new;
 
@@ -47,8 +53,24 @@ impl<'a, TSys: TaskExecutorSys, S: ExecutionEventSubscriber>
 
     pub async fn run(
         &self,
+    ) -> Result<Vec<TaskExecutionResult>, TaskExecutorError> {
+        let span = tracing::info_span!(
+            "run",
+            otel.name = "omni run",
+            omni.call = %self.config.call(),
+            omni.total = Empty,
+            omni.failed = Empty,
+            omni.cache_hits = Empty,
+        );
+
+        self.run_inner().instrument(span).await
+    }
+
+    async fn run_inner(
+        &self,
     ) -> Result<Vec<TaskExecutionResult>, TaskExecutorError> {
         let start_time = std::time::Instant::now();
+        let started_at = OffsetDateTime::now_utc();
 
         if self.config.dry_run() {
             diagnostic!(
@@ -58,7 +80,8 @@ impl<'a, TSys: TaskExecutorSys, S: ExecutionEventSubscriber>
             );
         }
 
-        let plan = ContextExecutionPlanProvider::new(self.context)
+        let planning_started = std::time::Instant::now();
+        let mut plan = ContextExecutionPlanProvider::new(self.context)
             .get_execution_plan(
                 self.config.call(),
                 self.config
@@ -74,11 +97,27 @@ impl<'a, TSys: TaskExecutorSys, S: ExecutionEventSubscriber>
                     .collect::<Vec<_>>()
                     .as_slice(),
                 self.config.meta_filter().as_deref(),
+                self.config.tag_filter().as_ref(),
                 self.config.scm_affected_filter().as_ref(),
                 self.config.ignore_dependencies(),
                 self.config.with_dependents(),
             )?;
 
+        if let Some(only_tasks) = self.config.only_tasks() {
+            for batch in plan.iter_mut() {
+                batch.retain(|t| only_tasks.contains(t.full_task_name()));
+            }
+            plan.retain(|b| !b.is_empty());
+        }
+
+        self.subscriber
+            .on_phase_completed(PhaseCompletedEvent {
+                phase: ExecutionPhase::Planning,
+                task_id: None,
+                elapsed: planning_started.elapsed(),
+            })
+            .await;
+
         let empty = plan.is_empty() || plan.iter().all(|b| b.is_empty());
 
         if empty {
@@ -129,34 +168,130 @@ impl<'a, TSys: TaskExecutorSys, S: ExecutionEventSubscriber>
             })
             .sum();
 
-        self.subscriber
-            .on_execution_complete(ExecutionCompleteEvent {
-                total: results.len(),
-                succeeded: results.iter().filter(|r| r.success()).count(),
-                failed: results
-                    .iter()
-                    .filter(|r| !r.is_skipped() && !r.success())
-                    .count(),
-                skipped: results.iter().filter(|r| r.is_skipped()).count(),
-                cache_hits: results
-                    .iter()
-                    .filter(|r| {
-                        matches!(
-                            r,
-                            crate::TaskExecutionResult::Completed {
-                                cache_hit: true,
-                                ..
-                            }
-                        )
-                    })
-                    .count(),
-                elapsed: start_time.elapsed(),
-                total_time_saved,
-            })
-            .await;
+        let event = ExecutionCompleteEvent {
+            total: results.len(),
+            succeeded: results.iter().filter(|r| r.success()).count(),
+            failed: results
+                .iter()
+                .filter(|r| !r.is_skipped() && !r.success())
+                .count(),
+            skipped: results.iter().filter(|r| r.is_skipped()).count(),
+            cache_hits: results
+                .iter()
+                .filter(|r| {
+                    matches!(
+                        r,
+                        crate::TaskExecutionResult::Completed {
+                            cache_hit: true,
+                            ..
+                        }
+                    )
+                })
+                .count(),
+            elapsed: start_time.elapsed(),
+            total_time_saved,
+        };
+
+        let span = tracing::Span::current();
+        span.record("omni.total", event.total);
+        span.record("omni.failed", event.failed);
+        span.record("omni.cache_hits", event.cache_hits);
+
+        self.subscriber.on_execution_complete(event).await;
+
+        if self.config.record_history() && !self.config.dry_run() {
+            self.record_history(started_at, start_time.elapsed(), &results)
+                .await;
+        }
 
         Ok(results)
     }
+
+    /// Failing to record the run doesn't fail it, the history is only
+    /// informational.
+    async fn record_history(
+        &self,
+        started_at: OffsetDateTime,
+        elapsed: Duration,
+        results: &[TaskExecutionResult],
+    ) {
+        let tag_filter = self.config.tag_filter().clone().unwrap_or_default();
+        let run = RunRecord::new(
+            started_at,
+            self.config.command_line().clone(),
+            self.config.call().to_string(),
+            RunFilters {
+                project: self.config.project_filters().clone(),
+                dir: self.config.dir_filters().clone(),
+                meta: self.config.meta_filter().clone(),
+                tag: tag_filter.include,
+                exclude_tag: tag_filter.exclude,
+            },
+            elapsed,
+            results.iter().map(task_run_record).collect(),
+        );
+
+        if let Err(e) =
+            RunHistory::new(self.context.cache_dir()).append(&run).await
+        {
+            diagnostic!(
+                self.subscriber,
+                DiagnosticLevel::Warn,
+                "Failed to record the run in the run history: {e}",
+            );
+        }
+    }
+}
+
+fn task_run_record(result: &TaskExecutionResult) -> TaskRunRecord {
+    let task = result.task().full_task_name().to_string();
+
+    match result {
+        TaskExecutionResult::Completed {
+            hash,
+            exit_code,
+            elapsed,
+            cache_hit,
+            tries,
+            ..
+        } => TaskRunRecord {
+            task,
+            status: if *exit_code == 0 {
+                TaskRunStatus::Succeeded
+            } else {
+                TaskRunStatus::Failed
+            },
+            exit_code: Some(*exit_code),
+            elapsed: Some(*elapsed),
+            cache_hit: *cache_hit,
+            hash: Some(bs58::encode(hash).into_string()),
+            tries: *tries,
+            skip_reason: None,
+            skipped_due_to_failure: false,
+        },
+        TaskExecutionResult::Errored { tries, .. } => TaskRunRecord {
+            task,
+            status: TaskRunStatus::Errored,
+            exit_code: None,
+            elapsed: None,
+            cache_hit: false,
+            hash: None,
+            tries: *tries,
+            skip_reason: None,
+            skipped_due_to_failure: false,
+        },
+        TaskExecutionResult::Skipped { skip_reason, .. } => TaskRunRecord {
+            task,
+            status: TaskRunStatus::Skipped,
+            exit_code: None,
+            elapsed: None,
+            cache_hit: false,
+            hash: None,
+            tries: 0,
+            skip_reason: Some(skip_reason.to_string()),
+            skipped_due_to_failure: result.is_skipped_due_to_error(),
+        },
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -0,0 +1,317 @@
+//! Hermeticity audit for `omni run --audit-io`.
+//!
+//! Each task process is launched through `omni audit-exec`, which traces the
+//! files the process tree actually reads and writes (see
+//! [`omni_capability_enforcement::access_trace`]) and writes them to a log.
+//! After the task finishes the log is compared against the task's cache
+//! configuration:
+//!
+//! * **undeclared reads** — workspace files read that no `cache.key.files`
+//!   pattern covers, so changing them does not invalidate the cache;
+//! * **undeclared writes** — workspace files written that no
+//!   `cache.output.files` pattern covers, so a cache hit does not restore them;
+//! * **untouched inputs** — input patterns that matched no file the task read,
+//!   which only widen the cache key.
+//!
+//! Only files inside the workspace are considered, and omni's own `.omni/`
+//! and the `.git/` directories are ignored.
+
+use std::path::{Path, PathBuf};
+
+use omni_capability_enforcement::AccessLog;
+use omni_task_context::TaskContext;
+use omni_types::{OmniPath, Root, enum_map};
+use omni_utils::glob::build_glob_set;
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+use tempfile::TempPath;
+
+/// Directories at the workspace root whose contents are never reported.
+const IGNORED_DIRS: &[&str] = &[".omni", ".git"];
+
+/// Launches task processes under the access tracer and audits their logs.
+pub(crate) struct IoAudit<'a> {
+    root_dir: &'a Path,
+    exe: String,
+}
+
+impl<'a> IoAudit<'a> {
+    pub fn new(root_dir: &'a Path) -> Self {
+        let exe = std::env::current_exe()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| "omni".to_string());
+
+        Self { root_dir, exe }
+    }
+
+    /// Rewrite `program args...` into `omni audit-exec --log <log> -- program
+    /// args...`, returning the log the wrapper writes when the process exits.
+    pub fn wrap(
+        &self,
+        program: String,
+        args: Vec<String>,
+    ) -> Result<(TempPath, String, Vec<String>), IoAuditError> {
+        let log = tempfile::Builder::new()
+            .prefix("omni-audit-")
+            .suffix(".json")
+            .tempfile()?
+            .into_temp_path();
+
+        let mut wrapped = vec![
+            "audit-exec".to_string(),
+            "--log".to_string(),
+            log.to_string_lossy().into_owned(),
+            "--".to_string(),
+            program,
+        ];
+        wrapped.extend(args);
+
+        Ok((log, self.exe.clone(), wrapped))
+    }
+
+    /// Compare the access log written by the wrapper against the task's
+    /// declared inputs and outputs.
+    pub fn audit(
+        &self,
+        task_ctx: &TaskContext<'_>,
+        log: &Path,
+    ) -> Result<IoAuditReport, IoAuditError> {
+        let bytes = std::fs::read(log)?;
+        let log: AccessLog = serde_json::from_slice(&bytes)?;
+
+        let root_dir = canonical(self.root_dir);
+        let project_dir = canonical(task_ctx.node.project_dir());
+        let (inputs, outputs) = match task_ctx.cache_info.as_ref() {
+            Some(ci) => (
+                resolve_patterns(&root_dir, &project_dir, &ci.key_input_files),
+                resolve_patterns(
+                    &root_dir,
+                    &project_dir,
+                    &ci.cache_output_files,
+                ),
+            ),
+            None => (vec![], vec![]),
+        };
+
+        Ok(check(&log, &root_dir, &inputs, &outputs)?)
+    }
+}
+
+/// What a task touched that its cache configuration does not account for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct IoAuditReport {
+    pub undeclared_reads: Vec<PathBuf>,
+    pub undeclared_writes: Vec<PathBuf>,
+    pub untouched_inputs: Vec<String>,
+}
+
+/// Audit `log` against absolute input and output glob patterns.
+///
+/// Files the task wrote itself are not reported as undeclared reads, and
+/// writes to files that no longer exist (temporaries) are not reported as
+/// undeclared writes.
+fn check(
+    log: &AccessLog,
+    root_dir: &Path,
+    inputs: &[String],
+    outputs: &[String],
+) -> Result<IoAuditReport, globset::Error> {
+    let input_set = build_glob_set(inputs)?;
+    let output_set = build_glob_set(outputs)?;
+
+    let in_workspace = |path: &Path| {
+        path.strip_prefix(root_dir).is_ok_and(|rel| {
+            !rel.components().next().is_some_and(|first| {
+                IGNORED_DIRS.iter().any(|d| first.as_os_str() == *d)
+            })
+        })
+    };
+
+    let mut touched = vec![false; inputs.len()];
+    let mut undeclared_reads = Vec::new();
+    for path in log.reads.iter().filter(|p| in_workspace(p)) {
+        let matched = input_set.matches(path);
+        for idx in &matched {
+            touched[*idx] = true;
+        }
+
+        if matched.is_empty()
+            && !log.writes.contains(path)
+            && !output_set.is_match(path)
+            && !path.is_dir()
+        {
+            undeclared_reads.push(path.clone());
+        }
+    }
+
+    let undeclared_writes = log
+        .writes
+        .iter()
+        .filter(|p| in_workspace(p) && !output_set.is_match(p) && p.is_file())
+        .cloned()
+        .collect();
+
+    // `cache.key.defaults` adds the project's own configuration files, which
+    // omni reads rather than the task.
+    let untouched_inputs = inputs
+        .iter()
+        .zip(touched)
+        .filter(|(pattern, touched)| !touched && !is_config_file(pattern))
+        .map(|(pattern, _)| pattern.clone())
+        .collect();
+
+    Ok(IoAuditReport {
+        undeclared_reads,
+        undeclared_writes,
+        untouched_inputs,
+    })
+}
+
+fn is_config_file(pattern: &str) -> bool {
+    Path::new(pattern)
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().contains(".omni."))
+}
+
+fn resolve_patterns(
+    root_dir: &Path,
+    project_dir: &Path,
+    files: &[OmniPath],
+) -> Vec<String> {
+    let root_map = enum_map! {
+        Root::Project => project_dir,
+        Root::Workspace => root_dir,
+    };
+
+    files
+        .iter()
+        .map(|file| {
+            let resolved = file.resolve(&root_map);
+            let path = if resolved.is_relative() {
+                let joined = project_dir.join(resolved);
+                std::path::absolute(&joined).unwrap_or(joined)
+            } else {
+                resolved.into_owned()
+            };
+            path.to_string_lossy().into_owned()
+        })
+        .collect()
+}
+
+/// Traced paths are canonical (they come from `/proc`), so the roots they are
+/// compared against must be too.
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct IoAuditError(pub(crate) IoAuditErrorInner);
+
+impl IoAuditError {
+    #[allow(unused)]
+    pub fn kind(&self) -> IoAuditErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<IoAuditErrorInner>> From<T> for IoAuditError {
+    fn from(value: T) -> Self {
+        let inner = value.into();
+        Self(inner)
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants)]
+#[strum_discriminants(name(IoAuditErrorKind), vis(pub))]
+pub(crate) enum IoAuditErrorInner {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid access log: {0}")]
+    Log(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Glob(#[from] globset::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(reads: &[&str], writes: &[&str]) -> AccessLog {
+        AccessLog {
+            reads: reads.iter().map(PathBuf::from).collect(),
+            writes: writes.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_reads_outside_declared_inputs() {
+        let report = check(
+            &log(
+                &["/repo/app/src/a.ts", "/repo/app/.env", "/usr/bin/node"],
+                &[],
+            ),
+            Path::new("/repo"),
+            &patterns(&["/repo/app/src/**"]),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(
+            report.undeclared_reads,
+            vec![PathBuf::from("/repo/app/.env")]
+        );
+        assert!(report.untouched_inputs.is_empty());
+    }
+
+    #[test]
+    fn ignores_omni_and_git_dirs_and_files_the_task_wrote() {
+        let report = check(
+            &log(
+                &["/repo/.omni/cache/x", "/repo/.git/HEAD", "/repo/app/gen.ts"],
+                &["/repo/app/gen.ts"],
+            ),
+            Path::new("/repo"),
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        assert!(report.undeclared_reads.is_empty());
+    }
+
+    #[test]
+    fn reports_declared_inputs_never_read_except_config_files() {
+        let report = check(
+            &log(&["/repo/app/src/a.ts"], &[]),
+            Path::new("/repo"),
+            &patterns(&[
+                "/repo/app/src/**",
+                "/repo/app/assets/**",
+                "/repo/app/project.omni.yaml",
+            ]),
+            &[],
+        )
+        .unwrap();
+
+        assert_eq!(report.untouched_inputs, patterns(&["/repo/app/assets/**"]));
+    }
+
+    #[test]
+    fn writes_to_missing_files_are_not_reported() {
+        let report = check(
+            &log(&[], &["/repo/app/dist/.tmp-123"]),
+            Path::new("/repo"),
+            &[],
+            &[],
+        )
+        .unwrap();
+
+        assert!(report.undeclared_writes.is_empty());
+    }
+}
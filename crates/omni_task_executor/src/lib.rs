@@ -7,16 +7,21 @@ mod config;
 mod execution_plan_provider;
 mod executor;
 mod force;
+mod io_audit;
 
 mod on_failure;
 mod pipeline;
+mod ranked_semaphore;
 mod result;
+mod scheduler;
 mod serde_impls;
 mod sys;
 mod task_context_provider;
+mod task_sandbox;
 mod utils;
 
 pub use config::*;
+pub use execution_plan_provider::ContextExecutionPlanProvider;
 pub use executor::*;
 pub use force::*;
 pub use omni_execution_plan::Call;
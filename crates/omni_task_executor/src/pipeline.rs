@@ -1,10 +1,13 @@
-use maps::unordered_map;
+use futures::stream::{FuturesUnordered, StreamExt as _};
+use maps::{UnorderedMap, unordered_map};
 use omni_cache::impls::HybridTaskExecutionCacheStore;
+use omni_capability_enforcement::access_trace;
 use omni_context::LoadedContext;
-use omni_core::BatchedExecutionPlan;
+use omni_core::{BatchedExecutionPlan, TaskExecutionNode};
 use omni_messages::{
-    ExecutionEventSubscriber,
-    execution::events::{BatchCompletedEvent, BatchStartEvent},
+    DiagnosticLevel, ExecutionEventSubscriber,
+    execution::events::{SchedulingCompletedEvent, SchedulingStartEvent},
+    publish::diagnostic,
 };
 use strum::{EnumDiscriminants, IntoDiscriminant as _};
 
@@ -13,6 +16,7 @@ use crate::{
     batch_executor::{BatchExecutor, BatchExecutorError},
     cache_manager::CacheManager,
     cache_store_provider::{CacheStoreProvider, ContextCacheStoreProvider},
+    scheduler::{DagScheduler, SchedulingHints},
 };
 
 pub struct ExecutionPipeline<
@@ -64,13 +68,31 @@ impl<'a, TSys: TaskExecutorSys, S: ExecutionEventSubscriber>
                 .sys(self.context.sys().clone())
                 .build();
 
-        let mut results_accumulator = unordered_map!(cap: task_count);
+        let mut results_accumulator: UnorderedMap<String, TaskExecutionResult> =
+            unordered_map!(cap: task_count);
 
         if self.context.remote_cache_configuration().is_some() {
             log::info!("Remote caching enabled");
         }
 
-        let mut batch_exec = BatchExecutor::new(
+        let audit_io = self.config.audit_io()
+            && {
+                let supported = access_trace::is_supported();
+                if !supported {
+                    diagnostic!(
+                        self.subscriber,
+                        DiagnosticLevel::Warn,
+                        "--audit-io is not supported on this platform; tasks run without tracing",
+                    );
+                }
+                supported
+            };
+
+        let scheduling_hints =
+            scheduling_hints(&execution_plan, self.context, &cache_manager)
+                .await;
+
+        let batch_exec = BatchExecutor::new(
             self.context,
             cache_manager,
             self.context.sys().clone(),
@@ -83,29 +105,124 @@ impl<'a, TSys: TaskExecutorSys, S: ExecutionEventSubscriber>
             self.config.output_cached_logs(),
             self.config.max_retries(),
             self.config.retry_interval(),
+            self.config.timeout(),
             self.config.no_cache(),
             self.config.add_task_details(),
+            audit_io,
             self.config.args(),
+            self.config.dependency_hashes().as_ref(),
         );
 
-        for batch in &execution_plan {
-            self.subscriber.on_batch_start(BatchStartEvent {}).await;
-
-            let results = batch_exec
-                .execute_batch(batch, &results_accumulator)
-                .await?;
+        // Tasks are started as soon as their own dependencies complete
+        // instead of waiting for the whole level of the plan to finish. Each
+        // ready unit (a task, or a group of co-scheduled siblings) runs as its
+        // own batch; the executor's shared semaphore keeps the total number of
+        // running tasks within `max_concurrency` and hands the free slots out
+        // by the rank the scheduler released the units with.
+        let mut scheduler = DagScheduler::new(execution_plan, scheduling_hints);
+        let mut running = FuturesUnordered::new();
+
+        self.subscriber
+            .on_scheduling_start(SchedulingStartEvent {})
+            .await;
+
+        loop {
+            let ready = scheduler.take_ready();
+
+            if ready.is_empty() {
+                let Some(results) = running.next().await else {
+                    break;
+                };
+
+                let results = results?;
+                for task in results.keys() {
+                    scheduler.complete(task);
+                }
+                results_accumulator.extend(results);
+                continue;
+            }
+
+            for (rank, unit) in ready {
+                if self.config.on_failure().is_skip_next_batches()
+                    && results_accumulator.values().any(|r| r.is_failure())
+                {
+                    let results = batch_exec.skip_batch(&unit).await;
+                    for task in results.keys() {
+                        scheduler.complete(task);
+                    }
+                    results_accumulator.extend(results);
+                    continue;
+                }
+
+                let dependency_results =
+                    dependency_results(&unit, &results_accumulator);
+                let batch_exec = &batch_exec;
+                running.push(async move {
+                    batch_exec
+                        .execute_batch(&unit, rank, &dependency_results)
+                        .await
+                });
+            }
+        }
 
-            self.subscriber
-                .on_batch_completed(BatchCompletedEvent {})
-                .await;
+        self.subscriber
+            .on_scheduling_completed(SchedulingCompletedEvent {})
+            .await;
 
-            results_accumulator.extend(results);
+        if !scheduler.is_done() {
+            Err(ExecutionPipelineErrorInner::Stalled {
+                tasks: scheduler.pending_tasks(),
+            })?;
         }
 
         Ok(results_accumulator.into_values().collect())
     }
 }
 
+/// Gathers the durations of the previous runs and the configured priorities
+/// of the tasks in `plan`. The durations only improve the order tasks start
+/// in, so failing to read them doesn't fail the run.
+async fn scheduling_hints<TSys: TaskExecutorSys>(
+    plan: &BatchedExecutionPlan,
+    context: &LoadedContext<TSys>,
+    cache_manager: &CacheManager<HybridTaskExecutionCacheStore, TSys>,
+) -> SchedulingHints {
+    let durations = match cache_manager.get_execution_durations().await {
+        Ok(durations) => durations,
+        Err(e) => {
+            log::debug!("failed to get the execution durations: {e}");
+            unordered_map!()
+        }
+    };
+
+    let priorities = plan
+        .iter()
+        .flatten()
+        .filter_map(|node| {
+            context
+                .get_task_priority(node.project_name(), node.task_name())
+                .map(|p| (node.full_task_name().to_string(), p))
+        })
+        .collect();
+
+    SchedulingHints {
+        durations,
+        priorities,
+    }
+}
+
+/// Collects the results of the dependencies of `unit`, which is everything a
+/// batch needs to resolve its tasks' contexts and failure handling.
+fn dependency_results(
+    unit: &[TaskExecutionNode],
+    results: &UnorderedMap<String, TaskExecutionResult>,
+) -> UnorderedMap<String, TaskExecutionResult> {
+    unit.iter()
+        .flat_map(|t| t.dependencies())
+        .filter_map(|d| results.get(d).map(|r| (d.clone(), r.clone())))
+        .collect()
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub struct ExecutionPipelineError(ExecutionPipelineErrorInner);
@@ -129,4 +246,7 @@ impl<T: Into<ExecutionPipelineErrorInner>> From<T> for ExecutionPipelineError {
 enum ExecutionPipelineErrorInner {
     #[error(transparent)]
     BatchExecutor(#[from] BatchExecutorError),
+
+    #[error("tasks never became ready to run: {tasks:?}")]
+    Stalled { tasks: Vec<String> },
 }
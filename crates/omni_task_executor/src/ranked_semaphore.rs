@@ -0,0 +1,193 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    sync::Mutex,
+};
+
+use tokio::sync::oneshot;
+
+/// Bounds how many tasks run at once, like a semaphore, but hands a freed
+/// permit to the waiter with the highest rank rather than the one that has
+/// waited the longest. Waiters of the same rank are served in arrival order.
+///
+/// Tasks reach the permit gate only after their context and cache lookups, so
+/// the order they arrive in doesn't follow the order the scheduler released
+/// them in. Ranking the waiters restores it whenever the run is saturated.
+pub struct RankedSemaphore<R: Ord> {
+    state: Mutex<State<R>>,
+}
+
+struct State<R: Ord> {
+    available: usize,
+    waiters: BinaryHeap<Waiter<R>>,
+    next_seq: u64,
+}
+
+struct Waiter<R> {
+    rank: R,
+    seq: Reverse<u64>,
+    grant: oneshot::Sender<()>,
+}
+
+impl<R: Ord> PartialEq for Waiter<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<R: Ord> Eq for Waiter<R> {}
+
+impl<R: Ord> PartialOrd for Waiter<R> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<R: Ord> Ord for Waiter<R> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.rank, self.seq).cmp(&(&other.rank, other.seq))
+    }
+}
+
+/// Held while a task runs, the permit is handed on when it is dropped.
+pub struct RankedPermit<'a, R: Ord> {
+    semaphore: &'a RankedSemaphore<R>,
+}
+
+impl<R: Ord> Drop for RankedPermit<'_, R> {
+    fn drop(&mut self) {
+        self.semaphore.release();
+    }
+}
+
+/// Gives the permit back if the acquiring future is dropped after the permit
+/// was granted to it but before it was received.
+struct PendingGrant<'a, R: Ord> {
+    semaphore: &'a RankedSemaphore<R>,
+    grant: oneshot::Receiver<()>,
+    received: bool,
+}
+
+impl<R: Ord> Drop for PendingGrant<'_, R> {
+    fn drop(&mut self) {
+        if self.received {
+            return;
+        }
+        self.grant.close();
+        if self.grant.try_recv().is_ok() {
+            self.semaphore.release();
+        }
+    }
+}
+
+impl<R: Ord> RankedSemaphore<R> {
+    pub fn new(permits: usize) -> Self {
+        Self {
+            state: Mutex::new(State {
+                available: permits,
+                waiters: BinaryHeap::new(),
+                next_seq: 0,
+            }),
+        }
+    }
+
+    /// Wait for a permit. While permits are available they are taken right
+    /// away, otherwise the highest `rank` waiting gets the next one freed.
+    pub async fn acquire(&self, rank: R) -> RankedPermit<'_, R> {
+        let grant = {
+            let mut state = self.state.lock().expect("poisoned");
+            if state.available > 0 {
+                state.available -= 1;
+                return RankedPermit { semaphore: self };
+            }
+
+            let (tx, rx) = oneshot::channel();
+            let seq = Reverse(state.next_seq);
+            state.next_seq += 1;
+            state.waiters.push(Waiter {
+                rank,
+                seq,
+                grant: tx,
+            });
+            rx
+        };
+
+        let mut pending = PendingGrant {
+            semaphore: self,
+            grant,
+            received: false,
+        };
+        (&mut pending.grant)
+            .await
+            .expect("waiters are only dropped when granted");
+        // the permit is owned by the returned guard from here on
+        pending.received = true;
+
+        RankedPermit { semaphore: self }
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock().expect("poisoned");
+
+        // waiters that gave up have dropped their receiver
+        while let Some(waiter) = state.waiters.pop() {
+            if waiter.grant.send(()).is_ok() {
+                return;
+            }
+        }
+
+        state.available += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_grants_freed_permits_by_rank() {
+        let semaphore = RankedSemaphore::new(1);
+        let started = Arc::new(Mutex::new(vec![]));
+
+        let holder = semaphore.acquire(0).await;
+
+        let waiter = |rank: i32| {
+            let semaphore = &semaphore;
+            let started = started.clone();
+            async move {
+                let _permit = semaphore.acquire(rank).await;
+                started.lock().unwrap().push(rank);
+                tokio::task::yield_now().await;
+            }
+        };
+
+        let release = async {
+            // let every waiter queue up first
+            tokio::task::yield_now().await;
+            drop(holder);
+        };
+
+        tokio::join!(waiter(1), waiter(3), waiter(2), waiter(3), release);
+
+        assert_eq!(*started.lock().unwrap(), [3, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_waiter_does_not_leak_its_permit() {
+        let semaphore = RankedSemaphore::new(1);
+        let holder = semaphore.acquire(0).await;
+
+        {
+            let abandoned = semaphore.acquire(1);
+            tokio::pin!(abandoned);
+            assert!(futures::poll!(abandoned.as_mut()).is_pending());
+        }
+
+        drop(holder);
+
+        let _permit = semaphore.acquire(0).await;
+        assert_eq!(semaphore.state.lock().unwrap().available, 0);
+    }
+}
@@ -8,7 +8,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIs};
 
-#[derive(Debug, new, EnumIs, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, new, EnumIs, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "status")]
 #[serde(rename_all = "kebab-case")]
 pub enum TaskExecutionResult {
@@ -106,7 +106,9 @@ impl TaskExecutionResult {
     }
 }
 
-#[derive(Debug, new, EnumIs, Display, Serialize, Deserialize, JsonSchema)]
+#[derive(
+    Debug, Clone, new, EnumIs, Display, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(rename_all = "kebab-case")]
 pub enum SkipReason {
     #[strum(to_string = "task in a previous batch failed")]
@@ -0,0 +1,609 @@
+use std::{cmp::Reverse, collections::BinaryHeap, time::Duration};
+
+use maps::{UnorderedMap, unordered_map};
+use omni_core::{BatchedExecutionPlan, TaskExecutionNode};
+use sets::{UnorderedSet, unordered_set};
+
+/// Dependency-driven scheduler over a [`BatchedExecutionPlan`].
+///
+/// Instead of running the plan level by level, each *unit* is released the
+/// moment the tasks it depends on have completed. A unit is a
+/// sibling-connected component of the plan (tasks that must be co-scheduled)
+/// and is usually a single task.
+///
+/// When several units are ready at once, the ones with the highest priority
+/// are released first, then the ones on the longest remaining critical path,
+/// estimated from the [`SchedulingHints`], so that long chains of tasks don't
+/// start last and stretch the run. The batch levels of the plan break the
+/// remaining ties: units from earlier levels are released first.
+///
+/// Persistent units with no dependents in the plan run "forever", so they are
+/// held back until every other unit has completed, mirroring the plan's rule
+/// of placing them in the final batch.
+///
+/// The scheduler is a pure state machine: it never runs anything itself. The
+/// caller drains [`DagScheduler::take_ready`], runs the returned units and
+/// reports every finished task through [`DagScheduler::complete`].
+#[derive(Debug)]
+pub struct DagScheduler {
+    units: Vec<Unit>,
+    /// Index of the unit every scheduled task belongs to, by full task name.
+    unit_of: UnorderedMap<String, usize>,
+    /// Units waiting on a task, by the full task name they wait on.
+    waiters: UnorderedMap<String, Vec<usize>>,
+    ready: BinaryHeap<Rank>,
+    /// Deferred units whose own dependencies are done, waiting on the rest of
+    /// the plan to drain.
+    parked: Vec<usize>,
+    /// Tasks of non-deferred units that have not completed yet.
+    remaining_non_deferred: usize,
+    completed: UnorderedSet<String>,
+    total_tasks: usize,
+}
+
+#[derive(Debug)]
+struct Unit {
+    tasks: Vec<TaskExecutionNode>,
+    pending_dependencies: usize,
+    deferred: bool,
+    released: bool,
+    priority: i32,
+    critical_path: Duration,
+}
+
+/// Order of the ready units: highest priority, then longest critical path,
+/// then earliest in the plan.
+pub(crate) type Rank = (i32, Duration, Reverse<usize>);
+
+/// What the scheduler knows about the tasks of a plan beyond their
+/// dependencies, by full task name.
+#[derive(Debug, Clone, Default)]
+pub struct SchedulingHints {
+    /// How long the tasks took the last time they ran. Tasks without a
+    /// duration are assumed to take the mean of the known ones.
+    pub durations: UnorderedMap<String, Duration>,
+    /// The configured priorities of the tasks, `0` if not configured.
+    pub priorities: UnorderedMap<String, i32>,
+}
+
+impl DagScheduler {
+    pub fn new(plan: BatchedExecutionPlan, hints: SchedulingHints) -> Self {
+        let nodes = plan.into_iter().flatten().collect::<Vec<_>>();
+        let total_tasks = nodes.len();
+
+        let index_of = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.full_task_name().to_string(), i))
+            .collect::<UnorderedMap<_, _>>();
+
+        // Group sibling-connected tasks into units. Plan order is preserved so
+        // that a unit's index breaks ties between ready units.
+        let mut parent = (0..nodes.len()).collect::<Vec<_>>();
+        for (i, node) in nodes.iter().enumerate() {
+            for sibling in node.siblings() {
+                if let Some(&j) = index_of.get(sibling) {
+                    union(&mut parent, i, j);
+                }
+            }
+        }
+
+        let mut unit_by_root = unordered_map!(cap: nodes.len());
+        let mut unit_of = unordered_map!(cap: nodes.len());
+        let mut units: Vec<Unit> = Vec::new();
+        for (i, node) in nodes.into_iter().enumerate() {
+            let root = find(&mut parent, i);
+            let unit_idx = *unit_by_root.entry(root).or_insert_with(|| {
+                units.push(Unit {
+                    tasks: Vec::new(),
+                    pending_dependencies: 0,
+                    deferred: false,
+                    released: false,
+                    priority: 0,
+                    critical_path: Duration::ZERO,
+                });
+                units.len() - 1
+            });
+
+            unit_of.insert(node.full_task_name().to_string(), unit_idx);
+            units[unit_idx].tasks.push(node);
+        }
+
+        // Register every in-plan dependency that lives outside the unit.
+        // Dependencies that are not part of the plan have nothing to wait on.
+        let mut waiters: UnorderedMap<String, Vec<usize>> = unordered_map!();
+        let mut dependents = vec![Vec::new(); units.len()];
+        for (unit_idx, unit) in units.iter_mut().enumerate() {
+            let mut seen = unordered_set!();
+            for task in &unit.tasks {
+                for dep in task.dependencies() {
+                    let Some(&dep_unit) = unit_of.get(dep) else {
+                        continue;
+                    };
+
+                    if dep_unit == unit_idx || !seen.insert(dep.clone()) {
+                        continue;
+                    }
+
+                    unit.pending_dependencies += 1;
+                    dependents[dep_unit].push(unit_idx);
+                    waiters.entry(dep.clone()).or_default().push(unit_idx);
+                }
+            }
+        }
+
+        for dependents in &mut dependents {
+            dependents.sort_unstable();
+            dependents.dedup();
+        }
+
+        rank_units(&mut units, &dependents, &hints);
+
+        let mut remaining_non_deferred = 0;
+        for (unit_idx, unit) in units.iter_mut().enumerate() {
+            unit.deferred = dependents[unit_idx].is_empty()
+                && unit.tasks.iter().any(|t| t.persistent());
+
+            if !unit.deferred {
+                remaining_non_deferred += unit.tasks.len();
+            }
+        }
+
+        let mut scheduler = Self {
+            units,
+            unit_of,
+            waiters,
+            ready: BinaryHeap::new(),
+            parked: Vec::new(),
+            remaining_non_deferred,
+            completed: unordered_set!(cap: total_tasks),
+            total_tasks,
+        };
+
+        for unit_idx in 0..scheduler.units.len() {
+            if scheduler.units[unit_idx].pending_dependencies == 0 {
+                scheduler.make_ready(unit_idx);
+            }
+        }
+
+        scheduler
+    }
+
+    /// Removes and returns every unit that can start now, in priority order,
+    /// along with its rank so the units keep that order while they wait for
+    /// a free slot.
+    pub fn take_ready(&mut self) -> Vec<(Rank, Vec<TaskExecutionNode>)> {
+        let mut ready = Vec::with_capacity(self.ready.len());
+        while let Some(rank) = self.ready.pop() {
+            let unit = &mut self.units[rank.2.0];
+            unit.released = true;
+            ready.push((rank, std::mem::take(&mut unit.tasks)));
+        }
+        ready
+    }
+
+    /// Records that `full_task_name` finished (in any way) and releases the
+    /// units that were only waiting on it.
+    pub fn complete(&mut self, full_task_name: &str) {
+        let Some(&unit_idx) = self.unit_of.get(full_task_name) else {
+            return;
+        };
+
+        if !self.completed.insert(full_task_name.to_string()) {
+            return;
+        }
+
+        if !self.units[unit_idx].deferred {
+            self.remaining_non_deferred -= 1;
+        }
+
+        if let Some(waiters) = self.waiters.remove(full_task_name) {
+            for waiter in waiters {
+                let unit = &mut self.units[waiter];
+                unit.pending_dependencies -= 1;
+                if unit.pending_dependencies == 0 {
+                    self.make_ready(waiter);
+                }
+            }
+        }
+
+        if self.remaining_non_deferred == 0 {
+            for unit_idx in std::mem::take(&mut self.parked) {
+                self.ready.push(self.rank(unit_idx));
+            }
+        }
+    }
+
+    /// `true` once every task in the plan has been reported complete.
+    pub fn is_done(&self) -> bool {
+        self.completed.len() == self.total_tasks
+    }
+
+    /// Full names of tasks that have not been released yet.
+    pub fn pending_tasks(&self) -> Vec<String> {
+        let mut tasks = self
+            .units
+            .iter()
+            .filter(|u| !u.released)
+            .flat_map(|u| {
+                u.tasks.iter().map(|t| t.full_task_name().to_string())
+            })
+            .collect::<Vec<_>>();
+        tasks.sort();
+        tasks
+    }
+
+    fn make_ready(&mut self, unit_idx: usize) {
+        if self.units[unit_idx].deferred && self.remaining_non_deferred > 0 {
+            self.parked.push(unit_idx);
+        } else {
+            self.ready.push(self.rank(unit_idx));
+        }
+    }
+
+    fn rank(&self, unit_idx: usize) -> Rank {
+        let unit = &self.units[unit_idx];
+        (unit.priority, unit.critical_path, Reverse(unit_idx))
+    }
+}
+
+/// Computes the priority and critical path of every unit.
+///
+/// A unit's critical path is its own estimated duration plus the longest
+/// critical path of the units depending on it, i.e. how long the run takes at
+/// least once the unit starts. Priorities carry over to dependencies so that a
+/// prioritised task isn't held back by the tasks it waits on.
+fn rank_units(
+    units: &mut [Unit],
+    dependents: &[Vec<usize>],
+    hints: &SchedulingHints,
+) {
+    let known = units
+        .iter()
+        .flat_map(|u| &u.tasks)
+        .filter_map(|t| hints.durations.get(t.full_task_name()))
+        .collect::<Vec<_>>();
+    let fallback = if known.is_empty() {
+        Duration::ZERO
+    } else {
+        known.iter().copied().sum::<Duration>() / known.len() as u32
+    };
+
+    let mut dependencies = vec![Vec::new(); units.len()];
+    for (unit_idx, dependents) in dependents.iter().enumerate() {
+        for &dependent in dependents {
+            dependencies[dependent].push(unit_idx);
+        }
+    }
+
+    // Visit the units from the leaves of the plan up, so that the dependents
+    // of a unit are always ranked before it.
+    let mut remaining_dependents =
+        dependents.iter().map(|d| d.len()).collect::<Vec<_>>();
+    let mut queue = (0..units.len())
+        .filter(|&i| remaining_dependents[i] == 0)
+        .collect::<Vec<_>>();
+
+    while let Some(unit_idx) = queue.pop() {
+        let unit = &units[unit_idx];
+        // siblings run side by side, so the slowest one sets the duration
+        let duration = unit
+            .tasks
+            .iter()
+            .map(|t| {
+                hints
+                    .durations
+                    .get(t.full_task_name())
+                    .copied()
+                    .unwrap_or(fallback)
+            })
+            .max()
+            .unwrap_or_default();
+        let priority = unit
+            .tasks
+            .iter()
+            .filter_map(|t| hints.priorities.get(t.full_task_name()))
+            .copied()
+            .max()
+            .unwrap_or(0);
+
+        let (dependents_priority, dependents_path) = dependents[unit_idx]
+            .iter()
+            .map(|&d| (units[d].priority, units[d].critical_path))
+            .fold((i32::MIN, Duration::ZERO), |(p, c), (dp, dc)| {
+                (p.max(dp), c.max(dc))
+            });
+
+        let unit = &mut units[unit_idx];
+        unit.priority = priority.max(dependents_priority);
+        unit.critical_path = duration + dependents_path;
+
+        for &dependency in &dependencies[unit_idx] {
+            remaining_dependents[dependency] -= 1;
+            if remaining_dependents[dependency] == 0 {
+                queue.push(dependency);
+            }
+        }
+    }
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+
+    let mut current = i;
+    while parent[current] != root {
+        let next = parent[current];
+        parent[current] = root;
+        current = next;
+    }
+
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let ra = find(parent, a);
+    let rb = find(parent, b);
+    if ra != rb {
+        // keep the earliest task as the root so unit order follows plan order
+        let (lo, hi) = if ra < rb { (ra, rb) } else { (rb, ra) };
+        parent[hi] = lo;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use omni_config_types::TeraExprBoolean;
+
+    use super::*;
+
+    fn node(name: &str, deps: &[&str]) -> TaskExecutionNode {
+        TaskExecutionNode::new(
+            name,
+            None,
+            None,
+            "p",
+            "",
+            deps.iter().map(|d| format!("p#{d}")).collect(),
+            TeraExprBoolean::Boolean(true),
+            false,
+            false,
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn persistent(name: &str, deps: &[&str]) -> TaskExecutionNode {
+        TaskExecutionNode::new(
+            name,
+            None,
+            None,
+            "p",
+            "",
+            deps.iter().map(|d| format!("p#{d}")).collect(),
+            TeraExprBoolean::Boolean(true),
+            false,
+            true,
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn schedule(plan: BatchedExecutionPlan) -> DagScheduler {
+        DagScheduler::new(plan, SchedulingHints::default())
+    }
+
+    fn hints(
+        durations: &[(&str, u64)],
+        priorities: &[(&str, i32)],
+    ) -> SchedulingHints {
+        SchedulingHints {
+            durations: durations
+                .iter()
+                .map(|(t, secs)| (format!("p#{t}"), Duration::from_secs(*secs)))
+                .collect(),
+            priorities: priorities
+                .iter()
+                .map(|(t, priority)| (format!("p#{t}"), *priority))
+                .collect(),
+        }
+    }
+
+    fn names(units: Vec<(Rank, Vec<TaskExecutionNode>)>) -> Vec<Vec<String>> {
+        units
+            .into_iter()
+            .map(|(_, u)| {
+                let mut names = u
+                    .iter()
+                    .map(|t| t.task_name().to_string())
+                    .collect::<Vec<_>>();
+                names.sort();
+                names
+            })
+            .collect()
+    }
+
+    fn s(v: &[&[&str]]) -> Vec<Vec<String>> {
+        v.iter()
+            .map(|u| u.iter().map(|t| t.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn releases_roots_first() {
+        let mut scheduler = schedule(vec![
+            vec![node("a", &[]), node("b", &[])],
+            vec![node("c", &["a", "b"])],
+        ]);
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["a"], &["b"]]));
+        assert!(scheduler.take_ready().is_empty());
+    }
+
+    #[test]
+    fn dependent_starts_without_waiting_for_unrelated_tasks_in_its_level() {
+        // a and slow share a level, c only depends on a
+        let mut scheduler = schedule(vec![
+            vec![node("a", &[]), node("slow", &[])],
+            vec![node("c", &["a"]), node("d", &["slow"])],
+        ]);
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["a"], &["slow"]]));
+
+        scheduler.complete("p#a");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["c"]]));
+
+        scheduler.complete("p#c");
+        assert!(scheduler.take_ready().is_empty());
+        assert!(!scheduler.is_done());
+
+        scheduler.complete("p#slow");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["d"]]));
+
+        scheduler.complete("p#d");
+        assert!(scheduler.is_done());
+        assert!(scheduler.pending_tasks().is_empty());
+    }
+
+    #[test]
+    fn waits_for_every_dependency() {
+        let mut scheduler = schedule(vec![
+            vec![node("a", &[]), node("b", &[])],
+            vec![node("c", &["a", "b"])],
+        ]);
+        scheduler.take_ready();
+
+        scheduler.complete("p#a");
+        assert!(scheduler.take_ready().is_empty());
+
+        scheduler.complete("p#b");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["c"]]));
+    }
+
+    #[test]
+    fn dependencies_outside_the_plan_are_ignored() {
+        let mut scheduler = schedule(vec![vec![node("a", &["not-planned"])]]);
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["a"]]));
+    }
+
+    #[test]
+    fn completing_a_task_twice_is_a_no_op() {
+        let mut scheduler =
+            schedule(vec![vec![node("a", &[])], vec![node("b", &["a"])]]);
+        scheduler.take_ready();
+
+        scheduler.complete("p#a");
+        scheduler.complete("p#a");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["b"]]));
+        assert!(!scheduler.is_done());
+    }
+
+    #[test]
+    fn siblings_are_released_together() {
+        let plan: BatchedExecutionPlan =
+            serde_json::from_value(serde_json::json!([[
+                sibling_json("x", &["p#y"]),
+                sibling_json("y", &[]),
+                sibling_json("z", &[]),
+            ]]))
+            .unwrap();
+        let mut scheduler = schedule(plan);
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["x", "y"], &["z"]]));
+    }
+
+    #[test]
+    fn persistent_roots_wait_for_the_rest_of_the_plan() {
+        let mut scheduler = schedule(vec![
+            vec![node("build", &[]), persistent("serve", &[])],
+            vec![node("test", &["build"])],
+        ]);
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["build"]]));
+
+        scheduler.complete("p#build");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["test"]]));
+
+        scheduler.complete("p#test");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["serve"]]));
+    }
+
+    #[test]
+    fn plan_of_only_persistent_tasks_starts_immediately() {
+        let mut scheduler = schedule(vec![vec![persistent("serve", &[])]]);
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["serve"]]));
+    }
+
+    #[test]
+    fn ready_units_follow_plan_order() {
+        let mut scheduler = schedule(vec![
+            vec![node("a", &[]), node("b", &[])],
+            vec![node("c", &["a"])],
+            vec![node("d", &["c"])],
+        ]);
+        scheduler.take_ready();
+        scheduler.complete("p#a");
+        scheduler.complete("p#b");
+        assert_eq!(names(scheduler.take_ready()), s(&[&["c"]]));
+        assert_eq!(scheduler.pending_tasks(), vec!["p#d".to_string()]);
+    }
+
+    #[test]
+    fn longest_critical_path_is_released_first() {
+        // b is as fast as a but the slow d waits on it
+        let mut scheduler = DagScheduler::new(
+            vec![
+                vec![node("a", &[]), node("b", &[])],
+                vec![node("c", &["a"]), node("d", &["b"])],
+            ],
+            hints(&[("a", 1), ("b", 1), ("c", 1), ("d", 10)], &[]),
+        );
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["b"], &["a"]]));
+    }
+
+    #[test]
+    fn unknown_durations_are_estimated_from_the_known_ones() {
+        let mut scheduler = DagScheduler::new(
+            vec![vec![node("a", &[]), node("b", &[]), node("c", &[])]],
+            hints(&[("a", 1), ("c", 5)], &[]),
+        );
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["c"], &["b"], &["a"]]));
+    }
+
+    #[test]
+    fn priority_overrides_the_critical_path() {
+        let mut scheduler = DagScheduler::new(
+            vec![vec![node("a", &[]), node("b", &[])]],
+            hints(&[("a", 10), ("b", 1)], &[("b", 1)]),
+        );
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["b"], &["a"]]));
+    }
+
+    #[test]
+    fn priority_carries_over_to_dependencies() {
+        let mut scheduler = DagScheduler::new(
+            vec![
+                vec![node("a", &[]), node("b", &[])],
+                vec![node("c", &["b"])],
+            ],
+            hints(&[("a", 10), ("b", 1), ("c", 1)], &[("c", 5)]),
+        );
+
+        assert_eq!(names(scheduler.take_ready()), s(&[&["b"], &["a"]]));
+    }
+
+    fn sibling_json(name: &str, siblings: &[&str]) -> serde_json::Value {
+        let mut value = serde_json::to_value(node(name, &[])).unwrap();
+        value["siblings"] = serde_json::json!(siblings);
+        value
+    }
+}
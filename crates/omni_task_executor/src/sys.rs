@@ -1,5 +1,8 @@
 use omni_context::ContextSys;
-use system_traits::FsCreateDirAllAsync;
+use system_traits::{FsCreateDirAllAsync, FsRemoveFileAsync};
 
 #[system_traits::auto_impl]
-pub trait TaskExecutorSys: ContextSys + FsCreateDirAllAsync {}
+pub trait TaskExecutorSys:
+    ContextSys + FsCreateDirAllAsync + FsRemoveFileAsync
+{
+}
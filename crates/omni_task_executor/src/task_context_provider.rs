@@ -25,11 +25,15 @@ impl<'a, TSys: ContextSys> DefaultTaskContextProvider<'a, TSys> {
     pub fn new(
         context: &'a LoadedContext<TSys>,
         overall_results: &'a UnorderedMap<String, TaskExecutionResult>,
+        known_hashes: Option<&'a UnorderedMap<String, DefaultHash>>,
         override_args: Option<&'a UnorderedMap<String, serde_json::Value>>,
     ) -> Self {
         Self {
             inner: Implementation::new(
-                OverallResultsTashHashProvider { overall_results },
+                OverallResultsTashHashProvider {
+                    overall_results,
+                    known_hashes,
+                },
                 ContextWrapper {
                     context,
                     override_args,
@@ -110,12 +114,86 @@ impl<'a, TSys: ContextSys> ContextTrait for ContextWrapper<'a, TSys> {
 
 struct OverallResultsTashHashProvider<'a> {
     overall_results: &'a UnorderedMap<String, TaskExecutionResult>,
+    /// Hashes of tasks that are not part of the run, see
+    /// [`crate::ExecutionConfig::dependency_hashes`].
+    known_hashes: Option<&'a UnorderedMap<String, DefaultHash>>,
 }
 
 impl<'a> TaskHashProvider for OverallResultsTashHashProvider<'a> {
     fn get_task_hash(&self, task_full_name: &str) -> Option<DefaultHash> {
-        self.overall_results
-                .get(task_full_name)
-                .map(|r| r.hash())?
+        match self.overall_results.get(task_full_name) {
+            Some(result) => result.hash(),
+            None => self.known_hashes?.get(task_full_name).copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use maps::unordered_map;
+    use omni_config_types::TeraExprBoolean;
+
+    use super::*;
+
+    fn node(name: &str) -> TaskExecutionNode {
+        TaskExecutionNode::new(
+            name,
+            None,
+            None,
+            "p",
+            "",
+            vec![],
+            TeraExprBoolean::Boolean(true),
+            false,
+            false,
+            None,
+            None,
+            None,
+        )
+    }
+
+    fn dependency_hashes(provider: &impl TaskHashProvider) -> Vec<DefaultHash> {
+        ["p#a", "p#b"]
+            .iter()
+            .filter_map(|d| provider.get_task_hash(d))
+            .collect()
+    }
+
+    #[test]
+    fn test_partial_run_keeps_dependency_digests() {
+        let completed = |name: &str, hash: u8| {
+            TaskExecutionResult::new_completed(
+                [hash; 32],
+                node(name),
+                0,
+                Duration::ZERO,
+                false,
+                1,
+            )
+        };
+
+        let full_run = unordered_map!(
+            "p#a".to_string() => completed("a", 1),
+            "p#b".to_string() => completed("b", 2),
+        );
+        let full = OverallResultsTashHashProvider {
+            overall_results: &full_run,
+            known_hashes: None,
+        };
+
+        // only `p#b` runs again, `p#a` is known from the earlier run
+        let partial_run = unordered_map!(
+            "p#b".to_string() => completed("b", 2),
+        );
+        let known = unordered_map!("p#a".to_string() => [1; 32]);
+        let partial = OverallResultsTashHashProvider {
+            overall_results: &partial_run,
+            known_hashes: Some(&known),
+        };
+
+        assert_eq!(dependency_hashes(&full), [[1; 32], [2; 32]]);
+        assert_eq!(dependency_hashes(&partial), dependency_hashes(&full));
     }
 }
@@ -0,0 +1,317 @@
+//! Capability planning for `omni run` task processes.
+//!
+//! A task that declares `capabilities` (or inherits them from its project or
+//! from workspace rules tagged `tasks`) is spawned under the OS sandbox. Unlike
+//! bridge scripts there is no in-process broker or shim in the stack: a task is
+//! an arbitrary native program, so every governed domain must be confined by
+//! the kernel on its own.
+//!
+//! * `fs.read` / `fs.write` lower onto [`NativeOsSandbox`] (Landlock on Linux,
+//!   Seatbelt on macOS).
+//! * `net` lowers onto [`ConnectFloor`], the Landlock TCP-connect floor, which
+//!   needs a Landlock ABI of at least 4.
+//!
+//! A domain with no backend on the host fails the task closed rather than
+//! running it with ambient authority, so a task policy is refused outright on
+//! Windows (AppContainer needs a dedicated spawn path) and, because nothing
+//! confines `net` there, on macOS.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use merge::Merge as _;
+use omni_capabilities::{
+    CapabilitiesStrictness, CapabilityFloors as _, CapabilityRules, PathRoots,
+    RequiredCapabilities, Root, project, validate,
+};
+use omni_capability_enforcement::{
+    ConnectFloor, EnforcementBackend, FloorStrictness, NativeOsSandbox,
+    OsSandboxSpec, UnenforceablePolicy, build_plan_layered,
+};
+use omni_configurations::TaskProcess;
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+
+/// The confinement resolved for a single task process.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskSandboxPlan {
+    /// The OS sandbox to install, or `None` when the policy lowers to no
+    /// confinement at all (or the sandbox is disabled).
+    pub spec: Option<OsSandboxSpec>,
+    /// Diagnostics to surface for the task: rules that opted into
+    /// `on_unenforceable: warn` and the `OMNI_DISABLE_OS_SANDBOX` downgrade.
+    pub warnings: Vec<String>,
+}
+
+/// Plan the OS sandbox for a task from its capability `levels`, outermost →
+/// innermost (workspace floor, project, task).
+///
+/// Returns `Ok(None)` when no level declares anything: such a task runs
+/// unconfined, exactly as before capabilities existed. `disabled` is the single
+/// `OMNI_DISABLE_OS_SANDBOX` read, threaded from the caller so the plan and
+/// the spawn cannot disagree.
+pub fn plan_task_sandbox(
+    levels: &[CapabilityRules<TaskProcess>],
+    strictness: CapabilitiesStrictness,
+    roots: &PathRoots<Root>,
+    disabled: bool,
+) -> Result<Option<TaskSandboxPlan>, TaskSandboxError> {
+    let levels: Vec<&CapabilityRules<TaskProcess>> =
+        levels.iter().filter(|l| !l.is_empty()).collect();
+    if levels.is_empty() {
+        return Ok(None);
+    }
+
+    for level in &levels {
+        validate(*level).map_err(|e| TaskSandboxErrorInner::Invalid {
+            message: e.to_string(),
+        })?;
+    }
+
+    // A task has no in-process mechanism to fall back on, so dropping the OS
+    // sandbox leaves it fully unconfined; say so loudly instead of planning
+    // against backends that would all report no coverage.
+    if disabled {
+        return Ok(Some(TaskSandboxPlan {
+            spec: None,
+            warnings: vec![
+                "OS-level sandbox is DISABLED (OMNI_DISABLE_OS_SANDBOX is \
+                 set): the task runs unconfined despite its capability policy"
+                    .to_string(),
+            ],
+        }));
+    }
+
+    // The merged chain is the conservative superset the OS sandbox consumes;
+    // the per-level projections let the planner attenuate across levels.
+    let mut chain = TaskProcess::baseline_read_chain();
+    for level in &levels {
+        chain.merge((*level).clone());
+    }
+    let required = project(&chain, &());
+    let level_reqs: Vec<RequiredCapabilities> =
+        levels.iter().map(|level| project(level, &())).collect();
+
+    let os = NativeOsSandbox::resolved(false, !cfg!(target_os = "windows"));
+    let connect = ConnectFloor::resolved(false);
+    let backends: [&dyn EnforcementBackend; 2] = [&os, &connect];
+
+    let strictness = match strictness {
+        CapabilitiesStrictness::Warn => FloorStrictness::Warn,
+        CapabilitiesStrictness::RequireFloor => FloorStrictness::RequireFloor,
+    };
+
+    let plan = build_plan_layered(
+        &required,
+        &level_reqs,
+        roots,
+        &backends,
+        UnenforceablePolicy::default(),
+        strictness,
+    )
+    .map_err(|e| TaskSandboxErrorInner::Unenforceable {
+        message: e.to_string(),
+    })?;
+
+    let mut warnings = Vec::new();
+    for warning in plan.warnings {
+        warnings
+            .push(format!("capability policy not fully enforced: {warning}"));
+    }
+    for gap in plan.floor_gaps {
+        warnings.push(format!(
+            "capability enforced without an un-bypassable floor: {}",
+            gap.reason
+        ));
+    }
+
+    Ok(Some(TaskSandboxPlan {
+        spec: plan.spawn.os_sandbox,
+        warnings,
+    }))
+}
+
+/// Grant a confined task what it needs to start: read/execute on the
+/// directory of `program` (and of its symlink target, for version-manager
+/// shims) plus a writable temp directory. System prefixes (`/usr`, `/lib`,
+/// `/etc`, …) are already in the sandbox baseline.
+pub fn with_program_essentials(
+    mut spec: OsSandboxSpec,
+    program: &str,
+    path_env: Option<&OsStr>,
+    cwd: &Path,
+) -> OsSandboxSpec {
+    if let Ok(path) = which::which_in(program, path_env, cwd) {
+        push_parent(&mut spec.read_paths, &path);
+        if let Ok(canonical) = std::fs::canonicalize(&path) {
+            push_parent(&mut spec.read_paths, &canonical);
+        }
+    }
+
+    let temp = std::env::temp_dir();
+    let temp = std::fs::canonicalize(&temp).unwrap_or(temp);
+    if !spec.write_paths.contains(&temp) {
+        spec.write_paths.push(temp);
+    }
+
+    spec
+}
+
+/// Grant what the programs of a built-in shell `script` need to start, as the
+/// confined interpreter spawns them itself. A script that doesn't parse
+/// grants nothing more.
+pub fn with_script_programs(
+    mut spec: OsSandboxSpec,
+    script: &str,
+    path_env: Option<&OsStr>,
+    cwd: &Path,
+) -> OsSandboxSpec {
+    let Ok(script) = omni_shell::parse(script) else {
+        return spec;
+    };
+
+    for program in script.programs() {
+        spec = with_program_essentials(spec, &program, path_env, cwd);
+    }
+
+    spec
+}
+
+fn push_parent(paths: &mut Vec<PathBuf>, file: &Path) {
+    if let Some(dir) = file.parent()
+        && !paths.iter().any(|p| p == dir)
+    {
+        paths.push(dir.to_path_buf());
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct TaskSandboxError(pub(crate) TaskSandboxErrorInner);
+
+impl TaskSandboxError {
+    #[allow(unused)]
+    pub fn kind(&self) -> TaskSandboxErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<TaskSandboxErrorInner>> From<T> for TaskSandboxError {
+    fn from(value: T) -> Self {
+        let inner = value.into();
+        Self(inner)
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants)]
+#[strum_discriminants(name(TaskSandboxErrorKind), vis(pub))]
+pub(crate) enum TaskSandboxErrorInner {
+    #[error("invalid task capability policy: {message}")]
+    Invalid { message: String },
+
+    #[error("task capability policy cannot be enforced: {message}")]
+    Unenforceable { message: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(json: &str) -> CapabilityRules<TaskProcess> {
+        serde_json::from_str(json).expect("valid task capabilities")
+    }
+
+    fn roots() -> PathRoots<Root> {
+        PathRoots::new().with(Root::Workspace, PathBuf::from("/repo"))
+    }
+
+    #[test]
+    fn no_declared_capabilities_leaves_the_task_unconfined() {
+        let plan = plan_task_sandbox(
+            &[CapabilityRules::default(), CapabilityRules::default()],
+            CapabilitiesStrictness::Warn,
+            &roots(),
+            false,
+        )
+        .expect("plans");
+
+        assert!(plan.is_none());
+    }
+
+    #[test]
+    fn unsupported_domain_is_rejected() {
+        let err = plan_task_sandbox(
+            &[rules(
+                r#"[{ "access": "allow", "domain": "process", "patterns": ["git"] }]"#,
+            )],
+            CapabilitiesStrictness::Warn,
+            &roots(),
+            false,
+        )
+        .expect_err("process is not a task domain");
+
+        assert_eq!(err.kind(), TaskSandboxErrorKind::Invalid);
+    }
+
+    #[test]
+    fn disabled_sandbox_runs_unconfined_with_a_warning() {
+        let plan = plan_task_sandbox(
+            &[rules(
+                r#"[{ "access": "allow", "domain": "fs.write", "patterns": ["@workspace/out/**"] }]"#,
+            )],
+            CapabilitiesStrictness::Warn,
+            &roots(),
+            true,
+        )
+        .expect("plans")
+        .expect("a policy is declared");
+
+        assert!(plan.spec.is_none());
+        assert_eq!(plan.warnings.len(), 1);
+    }
+
+    #[test]
+    fn essentials_grant_the_temp_dir_once() {
+        let spec = with_program_essentials(
+            OsSandboxSpec::default(),
+            "definitely-not-a-real-program",
+            None,
+            Path::new("/"),
+        );
+        let spec = with_program_essentials(
+            spec,
+            "definitely-not-a-real-program",
+            None,
+            Path::new("/"),
+        );
+
+        assert_eq!(spec.write_paths.len(), 1);
+        assert!(spec.read_paths.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn script_programs_grant_their_own_dirs() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let bin = tempfile::tempdir().expect("temp dir");
+        let bin_dir = std::fs::canonicalize(bin.path()).expect("canonical");
+        let program = bin_dir.join("fake-cargo");
+        std::fs::write(&program, "#!/bin/sh\n").expect("write program");
+        std::fs::set_permissions(
+            &program,
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .expect("make executable");
+
+        let spec = with_script_programs(
+            OsSandboxSpec::default(),
+            "fake-cargo build && echo done",
+            Some(bin_dir.as_os_str()),
+            Path::new("/"),
+        );
+
+        assert_eq!(spec.read_paths, vec![bin_dir]);
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::Level;
+use crate::{Level, OtlpConfig};
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct TracingConfig {
@@ -10,6 +10,8 @@ pub struct TracingConfig {
     pub file_path: Option<PathBuf>,
     pub stderr_level: Level,
     pub stderr_show_traces: bool,
+    /// Export spans to an OpenTelemetry collector.
+    pub otlp: Option<OtlpConfig>,
 }
 
 impl TracingConfig {
@@ -24,6 +26,7 @@ impl TracingConfig {
             file_path: None,
             stderr_level: Level::Off,
             stderr_show_traces: false,
+            otlp: None,
         }
     }
 }
@@ -4,9 +4,11 @@ mod config;
 pub mod custom_output;
 mod level;
 mod log_handling;
+mod otlp;
 
 pub use config::*;
 pub use level::*;
+pub use otlp::*;
 use tracing_subscriber::Layer;
 use tracing_subscriber::filter::FilterExt;
 use tracing_subscriber::fmt::FormatEvent;
@@ -42,6 +44,7 @@ pub fn noop_subscriber() -> TracingSubscriber {
             stdout_level: Level::Off,
             stderr_show_traces: false,
             stdout_show_traces: false,
+            otlp: None,
         },
         vec![],
     )
@@ -50,6 +53,7 @@ pub fn noop_subscriber() -> TracingSubscriber {
 
 pub struct TracingSubscriber {
     inner: Box<dyn Subscriber + Send + Sync>,
+    otlp_exporter: Option<OtlpExporter>,
 }
 
 impl TracingSubscriber {
@@ -178,10 +182,31 @@ impl TracingSubscriber {
             }
         }
 
+        let otlp_exporter =
+            config.otlp.as_ref().map(OtlpExporter::new).transpose()?;
+
+        if let Some(exporter) = &otlp_exporter {
+            let otlp_layer = tracing_opentelemetry::layer()
+                .with_tracer(exporter.tracer())
+                .with_filter(
+                    main_filters.clone().with_default(LevelFilter::INFO),
+                )
+                .boxed();
+
+            layers.push(otlp_layer);
+        }
+
         Ok(Self {
             inner: Box::new(Registry::default().with(layers)),
+            otlp_exporter,
         })
     }
+
+    /// The exporter of the spans, if exporting to a collector is configured.
+    /// It must be shut down before the process exits to not lose spans.
+    pub fn otlp_exporter(&self) -> Option<OtlpExporter> {
+        self.otlp_exporter.clone()
+    }
 }
 
 fn apply_settings<N, L, T, W>(
@@ -0,0 +1,315 @@
+use opentelemetry::trace::{TraceContextExt as _, TracerProvider as _};
+use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig as _};
+use opentelemetry_sdk::{
+    Resource,
+    trace::{SdkTracer, SdkTracerProvider},
+};
+use strum::{Display, EnumIs, VariantArray};
+use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+/// The environment variable carrying the trace context into child processes,
+/// as specified by the W3C Trace Context recommendation.
+pub const TRACEPARENT: &str = "TRACEPARENT";
+
+const SERVICE_NAME: &str = "omni";
+const HTTP_TRACES_PATH: &str = "/v1/traces";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpConfig {
+    /// The base URL of the collector, e.g. `http://localhost:4317` for gRPC
+    /// or `http://localhost:4318` for HTTP.
+    pub endpoint: String,
+    pub protocol: OtlpProtocol,
+}
+
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, EnumIs, Display, VariantArray,
+)]
+pub enum OtlpProtocol {
+    #[strum(serialize = "grpc")]
+    #[default]
+    Grpc,
+    #[strum(serialize = "http")]
+    Http,
+}
+
+/// Handle to the span exporter of a [`TracingSubscriber`], spans are exported
+/// in batches and the last batch must be flushed before the process exits.
+///
+/// [`TracingSubscriber`]: crate::TracingSubscriber
+#[derive(Debug, Clone)]
+pub struct OtlpExporter {
+    provider: SdkTracerProvider,
+}
+
+impl OtlpExporter {
+    pub(crate) fn new(config: &OtlpConfig) -> eyre::Result<Self> {
+        let exporter = match config.protocol {
+            OtlpProtocol::Grpc => SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(&config.endpoint)
+                .build()?,
+            OtlpProtocol::Http => SpanExporter::builder()
+                .with_http()
+                .with_protocol(Protocol::HttpBinary)
+                .with_endpoint(http_traces_endpoint(&config.endpoint))
+                .build()?,
+        };
+
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                Resource::builder().with_service_name(SERVICE_NAME).build(),
+            )
+            .build();
+
+        Ok(Self { provider })
+    }
+
+    pub(crate) fn tracer(&self) -> SdkTracer {
+        self.provider.tracer(SERVICE_NAME)
+    }
+
+    /// Export the spans that are still buffered and stop exporting.
+    ///
+    /// The gRPC exporter sends through the tokio runtime it was created in,
+    /// which must still be running, though this may be called outside of it.
+    pub fn shutdown(&self) -> eyre::Result<()> {
+        self.provider.shutdown()?;
+
+        Ok(())
+    }
+}
+
+/// The URL spans are posted to, collectors serve OTLP over HTTP below the
+/// `/v1/traces` path of their base URL.
+fn http_traces_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+
+    if endpoint.ends_with(HTTP_TRACES_PATH) {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}{HTTP_TRACES_PATH}")
+    }
+}
+
+/// The `traceparent` of `span` to pass to child processes, or `None` if the
+/// span isn't exported.
+pub fn traceparent(span: &tracing::Span) -> Option<String> {
+    let context = span.context();
+    let span_ref = context.span();
+    let span_context = span_ref.span_context();
+
+    if !span_context.is_valid() {
+        return None;
+    }
+
+    Some(format!(
+        "00-{}-{}-{:02x}",
+        span_context.trace_id(),
+        span_context.span_id(),
+        span_context.trace_flags().to_u8()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead as _, BufReader, Read as _, Write as _},
+        net::TcpListener,
+        sync::mpsc,
+        time::Duration,
+    };
+
+    use opentelemetry_proto::tonic::collector::trace::v1::{
+        ExportTraceServiceRequest, ExportTraceServiceResponse,
+        trace_service_server::{TraceService, TraceServiceServer},
+    };
+
+    use crate::{TracingConfig, TracingSubscriber};
+
+    use super::*;
+
+    /// Accepts OTLP requests like a collector would and reports the path
+    /// each one was posted to.
+    fn collector() -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\ncontent-type: application/x-protobuf\r\ncontent-length: 0\r\n\r\n",
+                    )
+                    .unwrap();
+
+                let path = request_line
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string();
+                if tx.send(path).is_err() {
+                    break;
+                }
+            }
+        });
+
+        (endpoint, rx)
+    }
+
+    /// Accepts OTLP exports over gRPC like a collector would and reports the
+    /// names of the spans of each one.
+    struct GrpcCollector(mpsc::Sender<Vec<String>>);
+
+    #[tonic::async_trait]
+    impl TraceService for GrpcCollector {
+        async fn export(
+            &self,
+            request: tonic::Request<ExportTraceServiceRequest>,
+        ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status>
+        {
+            let names = request
+                .into_inner()
+                .resource_spans
+                .into_iter()
+                .flat_map(|r| r.scope_spans)
+                .flat_map(|s| s.spans)
+                .map(|s| s.name)
+                .collect();
+            let _ = self.0.send(names);
+
+            Ok(tonic::Response::new(ExportTraceServiceResponse::default()))
+        }
+    }
+
+    #[test]
+    fn test_http_traces_endpoint() {
+        assert_eq!(
+            http_traces_endpoint("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            http_traces_endpoint("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            http_traces_endpoint("http://localhost:4318/v1/traces"),
+            "http://localhost:4318/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_traceparent_is_none_without_exporter() {
+        let span = tracing::info_span!("task");
+
+        assert_eq!(traceparent(&span), None);
+    }
+
+    #[test]
+    fn test_spans_are_exported_to_the_collector() {
+        let (endpoint, requests) = collector();
+        let config = TracingConfig {
+            otlp: Some(OtlpConfig {
+                endpoint,
+                protocol: OtlpProtocol::Http,
+            }),
+            ..TracingConfig::disabled()
+        };
+        let subscriber = TracingSubscriber::new(&config, vec![]).unwrap();
+        let exporter = subscriber.otlp_exporter().unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("run");
+            let traceparent = traceparent(&span).unwrap();
+            let parts = traceparent.split('-').collect::<Vec<_>>();
+
+            assert_eq!(parts.len(), 4);
+            assert_eq!(parts[0], "00");
+            assert_eq!(parts[1].len(), 32);
+            assert_eq!(parts[2].len(), 16);
+        });
+
+        exporter.shutdown().unwrap();
+
+        assert_eq!(
+            requests.recv_timeout(Duration::from_secs(10)).unwrap(),
+            "/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_spans_are_exported_over_grpc_and_flushed_after_the_run() {
+        // the CLI sets up tracing inside its runtime and flushes the traces
+        // once the command returned, outside of the runtime
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let (tx, spans) = mpsc::channel();
+
+        let exporter = rt.block_on(async {
+            let listener =
+                tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let endpoint = format!("http://{}", listener.local_addr().unwrap());
+            tokio::spawn(
+                tonic::transport::Server::builder()
+                    .add_service(TraceServiceServer::new(GrpcCollector(tx)))
+                    .serve_with_incoming(
+                        tokio_stream::wrappers::TcpListenerStream::new(
+                            listener,
+                        ),
+                    ),
+            );
+
+            let config = TracingConfig {
+                otlp: Some(OtlpConfig {
+                    endpoint,
+                    protocol: OtlpProtocol::Grpc,
+                }),
+                ..TracingConfig::disabled()
+            };
+            let subscriber = TracingSubscriber::new(&config, vec![]).unwrap();
+            let exporter = subscriber.otlp_exporter().unwrap();
+
+            tracing::subscriber::with_default(subscriber, || {
+                tracing::info_span!("run").in_scope(|| {});
+            });
+
+            exporter
+        });
+
+        exporter.shutdown().unwrap();
+
+        assert_eq!(
+            spans.recv_timeout(Duration::from_secs(10)).unwrap(),
+            ["run"]
+        );
+
+        rt.shutdown_background();
+    }
+}
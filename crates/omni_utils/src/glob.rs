@@ -11,9 +11,13 @@
 //! is immutable once built, which makes it trivially memoizable with no
 //! invalidation. [`build_glob_set`] returns a shared, cached [`GlobSet`] keyed
 //! by the exact ordered patterns.
+//!
+//! [`NegatableGlobSet`] builds on it for user-facing filters, where a pattern
+//! prefixed with `!` excludes what it matches.
 
 use std::{
     collections::HashMap,
+    path::Path,
     sync::{Arc, LazyLock, RwLock},
 };
 
@@ -64,6 +68,70 @@ pub fn build_glob_set<S: AsRef<str>>(
     Ok(Arc::clone(entry))
 }
 
+/// A filter made of glob patterns where a leading `!` negates a pattern.
+///
+/// A candidate matches when it matches at least one positive pattern and no
+/// negated one. When only negated patterns are given, every candidate not
+/// excluded by them matches, so `["!legacy-*"]` selects everything except the
+/// `legacy-*` entries.
+#[derive(Debug, Clone)]
+pub struct NegatableGlobSet {
+    include: Option<Arc<GlobSet>>,
+    exclude: Option<Arc<GlobSet>>,
+}
+
+impl NegatableGlobSet {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self, globset::Error> {
+        Self::new_with(patterns, |p| p.to_string())
+    }
+
+    /// Like [`NegatableGlobSet::new`], but maps every pattern (with the `!`
+    /// already stripped) through `map` before compiling it, e.g. to root
+    /// relative patterns at a directory.
+    pub fn new_with<S: AsRef<str>>(
+        patterns: &[S],
+        map: impl Fn(&str) -> String,
+    ) -> Result<Self, globset::Error> {
+        let mut include = vec![];
+        let mut exclude = vec![];
+
+        for pattern in patterns {
+            match pattern.as_ref().strip_prefix('!') {
+                Some(negated) => exclude.push(map(negated)),
+                None => include.push(map(pattern.as_ref())),
+            }
+        }
+
+        let build = |patterns: &[String]| {
+            if patterns.is_empty() {
+                Ok(None)
+            } else {
+                build_glob_set(patterns).map(Some)
+            }
+        };
+
+        Ok(Self {
+            include: build(&include)?,
+            exclude: build(&exclude)?,
+        })
+    }
+
+    pub fn is_match<P: AsRef<Path>>(&self, candidate: P) -> bool {
+        let candidate = candidate.as_ref();
+
+        if let Some(include) = &self.include
+            && !include.is_match(candidate)
+        {
+            return false;
+        }
+
+        !self
+            .exclude
+            .as_ref()
+            .is_some_and(|exclude| exclude.is_match(candidate))
+    }
+}
+
 fn read_cache() -> std::sync::RwLockReadGuard<'static, GlobSetCache> {
     // The critical sections only touch the map (no user code runs while the
     // lock is held), so the lock cannot be poisoned in practice; recover the
@@ -143,4 +211,34 @@ mod tests {
         // An unclosed character class is rejected by globset.
         assert!(build_glob_set(&["a[b"]).is_err());
     }
+
+    #[test]
+    fn negated_patterns_exclude_from_positive_ones() {
+        let set = NegatableGlobSet::new(&["*", "!legacy-*"]).unwrap();
+
+        assert!(set.is_match("app"));
+        assert!(!set.is_match("legacy-app"));
+    }
+
+    #[test]
+    fn only_negated_patterns_match_everything_else() {
+        let set = NegatableGlobSet::new(&["!legacy-*", "!old"]).unwrap();
+
+        assert!(set.is_match("app"));
+        assert!(!set.is_match("legacy-app"));
+        assert!(!set.is_match("old"));
+    }
+
+    #[test]
+    fn negatable_set_maps_stripped_patterns() {
+        let set =
+            NegatableGlobSet::new_with(&["packages/*", "!packages/old"], |p| {
+                format!("/ws/{p}")
+            })
+            .unwrap();
+
+        assert!(set.is_match("/ws/packages/new"));
+        assert!(!set.is_match("/ws/packages/old"));
+        assert!(!set.is_match("/ws/apps/new"));
+    }
 }
@@ -12,5 +12,8 @@ pub fn main() -> eyre::Result<()> {
         })
         .build()?;
 
+    // Migrations are embedded by `sqlx::migrate!`.
+    println!("cargo:rerun-if-changed=migrations");
+
     Ok(())
 }
@@ -0,0 +1,51 @@
+CREATE TABLE tenants (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    display_name TEXT NOT NULL,
+    description TEXT
+);
+
+CREATE TABLE organizations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id INTEGER NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+    code TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    description TEXT,
+    UNIQUE (tenant_id, code)
+);
+
+CREATE TABLE workspaces (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    organization_id INTEGER NOT NULL REFERENCES organizations (id) ON DELETE CASCADE,
+    code TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    description TEXT,
+    UNIQUE (organization_id, code)
+);
+
+CREATE TABLE environments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    workspace_id INTEGER NOT NULL REFERENCES workspaces (id) ON DELETE CASCADE,
+    code TEXT NOT NULL,
+    display_name TEXT NOT NULL,
+    description TEXT,
+    UNIQUE (workspace_id, code)
+);
+
+CREATE INDEX organizations_code ON organizations (code);
+CREATE INDEX workspaces_code ON workspaces (code);
+CREATE INDEX environments_code ON environments (code);
+
+-- The access lists (`scopes`, `tenants`, ...) hold the JSON form of
+-- `AllOrSpecificConfiguration`: either "all" or an array of codes.
+CREATE TABLE api_keys (
+    key TEXT PRIMARY KEY,
+    description TEXT,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    scopes TEXT NOT NULL DEFAULT '"all"',
+    tenants TEXT NOT NULL DEFAULT '"all"',
+    organizations TEXT NOT NULL DEFAULT '"all"',
+    workspaces TEXT NOT NULL DEFAULT '"all"',
+    environments TEXT NOT NULL DEFAULT '"all"',
+    expires_at TEXT
+);
@@ -0,0 +1,6 @@
+-- Lets API keys be listed and revoked without exposing the key itself.
+ALTER TABLE api_keys ADD COLUMN id TEXT;
+
+UPDATE api_keys SET id = lower(hex(randomblob(16))) WHERE id IS NULL;
+
+CREATE UNIQUE INDEX api_keys_id ON api_keys (id);
@@ -0,0 +1,10 @@
+-- When each stored artifact was last uploaded, read or checked, for the
+-- retention rules. Keyed like the storage backend: container is
+-- `tenant/org/ws/env`, key is the artifact digest.
+CREATE TABLE artifact_accesses (
+    container TEXT NOT NULL,
+    key TEXT NOT NULL,
+    task TEXT,
+    last_accessed_at TEXT NOT NULL,
+    PRIMARY KEY (container, key)
+);
@@ -0,0 +1,39 @@
+-- API keys are stored as the hex encoded sha256 digest of the key rather than
+-- the key itself. SQLite can't hash, so the keys stored so far are moved to
+-- `api_keys_to_hash`, which the service hashes and empties right after
+-- migrating.
+CREATE TABLE api_keys_to_hash (
+    id TEXT PRIMARY KEY,
+    key TEXT NOT NULL
+);
+
+INSERT INTO api_keys_to_hash (id, key) SELECT id, key FROM api_keys;
+
+CREATE TABLE api_keys_hashed (
+    id TEXT NOT NULL,
+    key_digest TEXT,
+    description TEXT,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    scopes TEXT NOT NULL DEFAULT '"all"',
+    tenants TEXT NOT NULL DEFAULT '"all"',
+    organizations TEXT NOT NULL DEFAULT '"all"',
+    workspaces TEXT NOT NULL DEFAULT '"all"',
+    environments TEXT NOT NULL DEFAULT '"all"',
+    expires_at TEXT
+);
+
+INSERT INTO api_keys_hashed (
+    id, description, enabled, scopes, tenants, organizations, workspaces,
+    environments, expires_at
+)
+SELECT
+    id, description, enabled, scopes, tenants, organizations, workspaces,
+    environments, expires_at
+FROM api_keys;
+
+DROP TABLE api_keys;
+
+ALTER TABLE api_keys_hashed RENAME TO api_keys;
+
+CREATE UNIQUE INDEX api_keys_id ON api_keys (id);
+CREATE UNIQUE INDEX api_keys_key_digest ON api_keys (key_digest);
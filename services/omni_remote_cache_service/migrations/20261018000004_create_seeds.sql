@@ -0,0 +1,12 @@
+-- Holds a single row once the configuration was imported. The configuration
+-- only seeds a new database; afterwards the database is the source of truth,
+-- even when every tenant and API key was removed from it.
+CREATE TABLE seeds (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    seeded_at TEXT NOT NULL
+);
+
+-- Databases in use already were seeded when they were created.
+INSERT INTO seeds (id, seeded_at)
+SELECT 1, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+WHERE EXISTS (SELECT 1 FROM tenants) OR EXISTS (SELECT 1 FROM api_keys);
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use derive_new::new;
 use strum::Display;
 
@@ -53,6 +55,30 @@ pub struct ServeArgs {
     )]
     pub config_type: Option<ConfigType>,
 
+    #[clap(
+        long,
+        help = "SQLite database to keep tenants, organizations, workspaces, environments and API keys in, e.g. sqlite://orcs.db. A new database is seeded from the configuration once",
+        env = "OMNI_REMOTE_CACHE_SERVICE_DATABASE_URL"
+    )]
+    pub database_url: Option<String>,
+
+    #[clap(
+        long,
+        help = "API key for the admin API, which manages tenants, organizations, workspaces, environments and API keys. The admin API is disabled without it, and requires --database-url",
+        env = "OMNI_REMOTE_CACHE_SERVICE_ADMIN_API_KEY",
+        hide_env_values = true
+    )]
+    pub admin_api_key: Option<String>,
+
+    #[clap(
+        long,
+        default_value = "1h",
+        help = "How often to evict artifacts the retention rules of the configuration no longer allow to be kept, and the chunks of abandoned uploads",
+        env = "OMNI_REMOTE_CACHE_SERVICE_GC_INTERVAL",
+        value_parser = humantime::parse_duration
+    )]
+    pub gc_interval: Option<Duration>,
+
     #[clap(
         long,
         short,
@@ -1,5 +1,6 @@
-use std::{hash::Hash, path::Path};
+use std::{hash::Hash, path::Path, time::Duration};
 
+use bytesize::ByteSize;
 use derive_new::new;
 use maps::UnorderedMap;
 use schemars::JsonSchema;
@@ -32,6 +33,12 @@ use crate::{
 pub struct Configuration {
     pub tenants: UnorderedMap<String, TenantConfiguration>,
     pub security: SecurityConfiguration,
+
+    /// Retention rules for stored artifacts. Unlike the tenancy and security
+    /// sections, these are read on every start, also when running on a
+    /// database.
+    #[serde(default)]
+    pub retention: Vec<RetentionRuleConfiguration>,
 }
 
 impl Configuration {
@@ -68,10 +75,10 @@ impl Configuration {
                 tenant_config.description.clone(),
             ));
 
-            for (org_id, (org_code, org_config)) in
-                tenant_config.organizations.iter().enumerate()
-            {
-                let org_id = OrganizationId::new(org_id as u64);
+            // ids are unique across the database, codes are only unique
+            // within their parent
+            for (org_code, org_config) in tenant_config.organizations.iter() {
+                let org_id = OrganizationId::new(db.organizations.len() as u64);
 
                 db.organizations.push(Organization::new(
                     org_id,
@@ -84,10 +91,8 @@ impl Configuration {
                     org_config.description.clone(),
                 ));
 
-                for (ws_id, (ws_code, ws_config)) in
-                    org_config.workspaces.iter().enumerate()
-                {
-                    let ws_id = WorkspaceId::new(ws_id as u64);
+                for (ws_code, ws_config) in org_config.workspaces.iter() {
+                    let ws_id = WorkspaceId::new(db.workspaces.len() as u64);
 
                     db.workspaces.push(Workspace::new(
                         ws_id,
@@ -100,10 +105,10 @@ impl Configuration {
                         ws_config.description.clone(),
                     ));
 
-                    for (env_id, (env_code, env_config)) in
-                        ws_config.environments.iter().enumerate()
+                    for (env_code, env_config) in ws_config.environments.iter()
                     {
-                        let env_id = EnvironmentId::new(env_id as u64);
+                        let env_id =
+                            EnvironmentId::new(db.environments.len() as u64);
 
                         db.environments.push(Environment::new(
                             env_id,
@@ -193,6 +198,58 @@ pub struct EnvironmentConfiguration {
     pub description: Option<String>,
 }
 
+/// How long and how many artifacts of the environments of a workspace are
+/// kept. Organization and workspace codes are those of the `tenant`. The
+/// first rule matching an environment applies to it; every limit is enforced
+/// per environment.
+#[derive(
+    Clone, Debug, Deserialize, Serialize, PartialEq, Eq, JsonSchema, new,
+)]
+pub struct RetentionRuleConfiguration {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+
+    #[serde(default)]
+    pub environments: AllOrSpecificConfiguration,
+
+    /// Evict artifacts that were not uploaded, read or checked for this long
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub max_age: Option<Duration>,
+
+    /// Evict the least recently accessed artifacts while the environment
+    /// holds more than this
+    #[serde(default)]
+    #[schemars(with = "Option<String>")]
+    pub max_size: Option<ByteSize>,
+
+    /// Keep at most this many artifacts per task, evicting the least recently
+    /// accessed ones. Only applies to artifacts uploaded with a task name
+    #[serde(default)]
+    pub max_entries_per_task: Option<usize>,
+}
+
+impl RetentionRuleConfiguration {
+    pub fn matches(
+        &self,
+        tenant: &str,
+        org: &str,
+        ws: &str,
+        env: &str,
+    ) -> bool {
+        self.tenant == tenant
+            && self.org == org
+            && self.ws == ws
+            && match &self.environments {
+                AllOrSpecificConfiguration::All(_) => true,
+                AllOrSpecificConfiguration::Specific(envs) => {
+                    envs.contains(env)
+                }
+            }
+    }
+}
+
 #[derive(
     Clone,
     Debug,
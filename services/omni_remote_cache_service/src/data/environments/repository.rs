@@ -9,8 +9,12 @@ pub trait EnvironmentRepository: Send + Sync + 'static {
         code: &str,
     ) -> Result<bool, EnvironmentRepositoryError>;
 
+    /// Whether the environment belongs to the workspace, checked through the
+    /// whole tenant, organization and workspace chain.
     async fn belongs_to_workspace(
         &self,
+        tenant_code: &str,
+        organization_code: &str,
         workspace_code: &str,
         environment_code: &str,
     ) -> Result<bool, EnvironmentRepositoryError>;
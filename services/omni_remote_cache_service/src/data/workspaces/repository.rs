@@ -9,8 +9,11 @@ pub trait WorkspaceRepository: Send + Sync + 'static {
         workspace_code: &str,
     ) -> Result<bool, WorkspaceRepositoryError>;
 
+    /// Whether the workspace belongs to the organization of the tenant. The
+    /// same codes can be used by other tenants, so the whole chain is checked.
     async fn belongs_to_organization(
         &self,
+        tenant_code: &str,
         organization_code: &str,
         workspace_code: &str,
     ) -> Result<bool, WorkspaceRepositoryError>;
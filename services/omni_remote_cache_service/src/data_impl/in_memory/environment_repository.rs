@@ -27,23 +27,43 @@ impl EnvironmentRepository for InMemoryEnvironmentRepository {
 
     async fn belongs_to_workspace(
         &self,
+        tenant_code: &str,
+        organization_code: &str,
         workspace_code: &str,
         environment_code: &str,
     ) -> Result<bool, EnvironmentRepositoryError> {
-        let workspace = self
+        if !self
             .db
             .workspaces
             .iter()
-            .find(|workspace| workspace.code == workspace_code)
-            .ok_or_else(|| {
+            .any(|workspace| workspace.code == workspace_code)
+        {
+            return Err(
                 EnvironmentRepositoryErrorInner::WorkspaceDoesNotExistByCode(
                     workspace_code.to_string(),
                 )
-            })?;
+                .into(),
+            );
+        }
 
-        Ok(self.db.environments.iter().any(|environment| {
-            environment.code == environment_code
-                && environment.workspace_id == workspace.id
+        let db = &self.db;
+        let workspace = db.workspaces.iter().find(|workspace| {
+            workspace.code == workspace_code
+                && db.organizations.iter().any(|organization| {
+                    organization.code == organization_code
+                        && organization.id == workspace.organization_id
+                        && db.tenants.iter().any(|tenant| {
+                            tenant.code == tenant_code
+                                && tenant.id == organization.tenant_id
+                        })
+                })
+        });
+
+        Ok(workspace.is_some_and(|workspace| {
+            db.environments.iter().any(|environment| {
+                environment.code == environment_code
+                    && environment.workspace_id == workspace.id
+            })
         }))
     }
 }
@@ -31,23 +31,38 @@ impl WorkspaceRepository for InMemoryWorkspaceRepository {
 
     async fn belongs_to_organization(
         &self,
+        tenant_code: &str,
         organization_code: &str,
         workspace_code: &str,
     ) -> Result<bool, WorkspaceRepositoryError> {
-        let org = self
+        if !self
             .db
             .organizations
             .iter()
-            .find(|organization| organization.code == organization_code)
-            .ok_or_else(|| {
+            .any(|organization| organization.code == organization_code)
+        {
+            return Err(
                 WorkspaceRepositoryErrorInner::OrganizationDoesNotExistByCode(
                     organization_code.to_string(),
                 )
-            })?;
+                .into(),
+            );
+        }
 
-        Ok(self.db.workspaces.iter().any(|workspace| {
-            workspace.code == workspace_code
-                && workspace.organization_id == org.id
+        let db = &self.db;
+        let organization = db.organizations.iter().find(|organization| {
+            organization.code == organization_code
+                && db.tenants.iter().any(|tenant| {
+                    tenant.code == tenant_code
+                        && tenant.id == organization.tenant_id
+                })
+        });
+
+        Ok(organization.is_some_and(|organization| {
+            db.workspaces.iter().any(|workspace| {
+                workspace.code == workspace_code
+                    && workspace.organization_id == organization.id
+            })
         }))
     }
 }
@@ -1 +1,2 @@
 pub mod in_memory;
+pub mod sqlite;
@@ -0,0 +1,447 @@
+use derive_new::new;
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+use time::OffsetDateTime;
+
+use crate::{
+    config::ApiKeyConfiguration,
+    data::{
+        environments::{Environment, EnvironmentId},
+        organizations::{Organization, OrganizationId},
+        tenants::{Tenant, TenantId},
+        workspaces::{Workspace, WorkspaceId},
+    },
+    data_impl::sqlite::{ApiKeyRow, SqliteDatabase, insert_api_key},
+};
+
+/// Fields shared by everything created through the admin API.
+#[derive(Debug, Clone, PartialEq, Eq, new)]
+pub struct NewEntity<'a> {
+    pub code: &'a str,
+    pub display_name: Option<&'a str>,
+    pub description: Option<&'a str>,
+}
+
+/// An API key as listed by the admin API, without the key itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredApiKey {
+    pub id: String,
+    pub config: ApiKeyConfiguration,
+}
+
+type EntityRow = (i64, i64, String, String, Option<String>);
+
+#[derive(sqlx::FromRow)]
+struct StoredApiKeyRow {
+    id: String,
+    #[sqlx(flatten)]
+    config: ApiKeyRow,
+}
+
+/// Writes and listings behind the admin API.
+impl SqliteDatabase {
+    pub async fn list_tenants(
+        &self,
+    ) -> Result<Vec<Tenant>, SqliteDatabaseError> {
+        let rows = sqlx::query_as::<_, (i64, String, String, Option<String>)>(
+            "SELECT id, code, display_name, description
+             FROM tenants
+             ORDER BY code",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, code, display_name, description)| {
+                Tenant::new(
+                    TenantId(id as u64),
+                    code,
+                    display_name,
+                    description,
+                )
+            })
+            .collect())
+    }
+
+    pub async fn create_tenant(
+        &self,
+        tenant: NewEntity<'_>,
+    ) -> Result<Tenant, SqliteDatabaseError> {
+        let display_name = tenant.display_name.unwrap_or(tenant.code);
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO tenants (code, display_name, description)
+             VALUES (?, ?, ?)
+             RETURNING id",
+        )
+        .bind(tenant.code)
+        .bind(display_name)
+        .bind(tenant.description)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| already_exists(e, "tenant"))?;
+
+        Ok(Tenant::new(
+            TenantId(id as u64),
+            tenant.code.to_string(),
+            display_name.to_string(),
+            tenant.description.map(str::to_string),
+        ))
+    }
+
+    pub async fn list_organizations(
+        &self,
+        tenant_code: &str,
+    ) -> Result<Vec<Organization>, SqliteDatabaseError> {
+        let tenant_id = self.tenant_id(tenant_code).await?;
+        let rows = sqlx::query_as::<_, EntityRow>(
+            "SELECT id, tenant_id, code, display_name, description
+             FROM organizations
+             WHERE tenant_id = ?
+             ORDER BY code",
+        )
+        .bind(tenant_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, tenant_id, code, display_name, description)| {
+                Organization::new(
+                    OrganizationId(id as u64),
+                    TenantId(tenant_id as u64),
+                    code,
+                    display_name,
+                    description,
+                )
+            })
+            .collect())
+    }
+
+    pub async fn create_organization(
+        &self,
+        tenant_code: &str,
+        organization: NewEntity<'_>,
+    ) -> Result<Organization, SqliteDatabaseError> {
+        let tenant_id = self.tenant_id(tenant_code).await?;
+        let display_name =
+            organization.display_name.unwrap_or(organization.code);
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO organizations
+                (tenant_id, code, display_name, description)
+             VALUES (?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(tenant_id)
+        .bind(organization.code)
+        .bind(display_name)
+        .bind(organization.description)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| already_exists(e, "organization"))?;
+
+        Ok(Organization::new(
+            OrganizationId(id as u64),
+            TenantId(tenant_id as u64),
+            organization.code.to_string(),
+            display_name.to_string(),
+            organization.description.map(str::to_string),
+        ))
+    }
+
+    pub async fn list_workspaces(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+    ) -> Result<Vec<Workspace>, SqliteDatabaseError> {
+        let organization_id =
+            self.organization_id(tenant_code, organization_code).await?;
+        let rows = sqlx::query_as::<_, EntityRow>(
+            "SELECT id, organization_id, code, display_name, description
+             FROM workspaces
+             WHERE organization_id = ?
+             ORDER BY code",
+        )
+        .bind(organization_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, organization_id, code, display_name, description)| {
+                Workspace::new(
+                    WorkspaceId(id as u64),
+                    OrganizationId(organization_id as u64),
+                    code,
+                    display_name,
+                    description,
+                )
+            })
+            .collect())
+    }
+
+    pub async fn create_workspace(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace: NewEntity<'_>,
+    ) -> Result<Workspace, SqliteDatabaseError> {
+        let organization_id =
+            self.organization_id(tenant_code, organization_code).await?;
+        let display_name = workspace.display_name.unwrap_or(workspace.code);
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO workspaces
+                (organization_id, code, display_name, description)
+             VALUES (?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(organization_id)
+        .bind(workspace.code)
+        .bind(display_name)
+        .bind(workspace.description)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| already_exists(e, "workspace"))?;
+
+        Ok(Workspace::new(
+            WorkspaceId(id as u64),
+            OrganizationId(organization_id as u64),
+            workspace.code.to_string(),
+            display_name.to_string(),
+            workspace.description.map(str::to_string),
+        ))
+    }
+
+    pub async fn list_environments(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+    ) -> Result<Vec<Environment>, SqliteDatabaseError> {
+        let workspace_id = self
+            .workspace_id(tenant_code, organization_code, workspace_code)
+            .await?;
+        let rows = sqlx::query_as::<_, EntityRow>(
+            "SELECT id, workspace_id, code, display_name, description
+             FROM environments
+             WHERE workspace_id = ?
+             ORDER BY code",
+        )
+        .bind(workspace_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, workspace_id, code, display_name, description)| {
+                Environment::new(
+                    EnvironmentId(id as u64),
+                    WorkspaceId(workspace_id as u64),
+                    code,
+                    display_name,
+                    description,
+                )
+            })
+            .collect())
+    }
+
+    pub async fn create_environment(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+        environment: NewEntity<'_>,
+    ) -> Result<Environment, SqliteDatabaseError> {
+        let workspace_id = self
+            .workspace_id(tenant_code, organization_code, workspace_code)
+            .await?;
+        let display_name = environment.display_name.unwrap_or(environment.code);
+        let id = sqlx::query_scalar::<_, i64>(
+            "INSERT INTO environments
+                (workspace_id, code, display_name, description)
+             VALUES (?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(workspace_id)
+        .bind(environment.code)
+        .bind(display_name)
+        .bind(environment.description)
+        .fetch_one(self.pool())
+        .await
+        .map_err(|e| already_exists(e, "environment"))?;
+
+        Ok(Environment::new(
+            EnvironmentId(id as u64),
+            WorkspaceId(workspace_id as u64),
+            environment.code.to_string(),
+            display_name.to_string(),
+            environment.description.map(str::to_string),
+        ))
+    }
+
+    pub async fn list_api_keys(
+        &self,
+    ) -> Result<Vec<StoredApiKey>, SqliteDatabaseError> {
+        let rows = sqlx::query_as::<_, StoredApiKeyRow>(
+            "SELECT id, description, enabled, scopes, tenants, organizations,
+                    workspaces, environments, expires_at
+             FROM api_keys
+             ORDER BY id",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(StoredApiKey {
+                    id: row.id,
+                    config: row.config.into_configuration()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Mint a new random API key with the access described by `config`,
+    /// returning its id and the key. The key is not retrievable afterwards.
+    pub async fn create_api_key(
+        &self,
+        config: &ApiKeyConfiguration,
+    ) -> Result<(String, String), SqliteDatabaseError> {
+        let key = format!("omni_{}", uuid::Uuid::new_v4().simple());
+        let mut conn = self.pool().acquire().await?;
+        let id = insert_api_key(&mut conn, &key, config).await?;
+
+        Ok((id, key))
+    }
+
+    /// Disable the API key with `id`; requests using it are rejected from
+    /// then on.
+    pub async fn revoke_api_key(
+        &self,
+        id: &str,
+    ) -> Result<(), SqliteDatabaseError> {
+        let result = sqlx::query(
+            "UPDATE api_keys SET enabled = 0, expires_at = ? WHERE id = ?",
+        )
+        .bind(OffsetDateTime::now_utc())
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(SqliteDatabaseErrorInner::NotFound(format!(
+                "api key '{id}'"
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    async fn tenant_id(
+        &self,
+        tenant_code: &str,
+    ) -> Result<i64, SqliteDatabaseError> {
+        sqlx::query_scalar::<_, i64>("SELECT id FROM tenants WHERE code = ?")
+            .bind(tenant_code)
+            .fetch_optional(self.pool())
+            .await?
+            .ok_or_else(|| {
+                SqliteDatabaseErrorInner::NotFound(format!(
+                    "tenant '{tenant_code}'"
+                ))
+                .into()
+            })
+    }
+
+    async fn organization_id(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+    ) -> Result<i64, SqliteDatabaseError> {
+        let tenant_id = self.tenant_id(tenant_code).await?;
+
+        sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM organizations WHERE tenant_id = ? AND code = ?",
+        )
+        .bind(tenant_id)
+        .bind(organization_code)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| {
+            SqliteDatabaseErrorInner::NotFound(format!(
+                "organization '{organization_code}'"
+            ))
+            .into()
+        })
+    }
+
+    async fn workspace_id(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+    ) -> Result<i64, SqliteDatabaseError> {
+        let organization_id =
+            self.organization_id(tenant_code, organization_code).await?;
+
+        sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM workspaces WHERE organization_id = ? AND code = ?",
+        )
+        .bind(organization_id)
+        .bind(workspace_code)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| {
+            SqliteDatabaseErrorInner::NotFound(format!(
+                "workspace '{workspace_code}'"
+            ))
+            .into()
+        })
+    }
+}
+
+/// Turn a unique constraint violation into [`SqliteDatabaseErrorKind::AlreadyExists`].
+pub(super) fn already_exists(
+    error: sqlx::Error,
+    what: &str,
+) -> SqliteDatabaseError {
+    match &error {
+        sqlx::Error::Database(e) if e.is_unique_violation() => {
+            SqliteDatabaseErrorInner::AlreadyExists(what.to_string()).into()
+        }
+        _ => error.into(),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct SqliteDatabaseError(pub(crate) SqliteDatabaseErrorInner);
+
+impl SqliteDatabaseError {
+    pub fn kind(&self) -> SqliteDatabaseErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<SqliteDatabaseErrorInner>> From<T> for SqliteDatabaseError {
+    fn from(inner: T) -> Self {
+        let inner = inner.into();
+        Self(inner)
+    }
+}
+
+#[derive(Debug, EnumDiscriminants, thiserror::Error, new)]
+#[strum_discriminants(vis(pub), name(SqliteDatabaseErrorKind))]
+pub(crate) enum SqliteDatabaseErrorInner {
+    #[error("{0} does not exist")]
+    NotFound(String),
+
+    #[error("{0} already exists")]
+    AlreadyExists(String),
+
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
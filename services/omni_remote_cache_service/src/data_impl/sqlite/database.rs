@@ -0,0 +1,243 @@
+use std::str::FromStr as _;
+
+use ring::digest::{SHA256, digest};
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+};
+use time::OffsetDateTime;
+
+use crate::{
+    config::{ApiKeyConfiguration, Configuration},
+    data_impl::sqlite::{SqliteDatabaseError, already_exists},
+};
+
+/// SQLite-backed store for tenants, organizations, workspaces, environments
+/// and API keys. Unlike the in-memory database it can be changed while the
+/// service runs, and keeps its state across restarts.
+#[derive(Debug, Clone)]
+pub struct SqliteDatabase {
+    pool: SqlitePool,
+}
+
+impl SqliteDatabase {
+    /// Open (creating it if needed) the database at `url`, e.g.
+    /// `sqlite://orcs.db`, and apply pending migrations.
+    pub async fn connect(url: &str) -> eyre::Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .foreign_keys(true);
+
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+
+        sqlx::migrate!("./migrations").run(&pool).await?;
+        hash_migrated_api_keys(&pool).await?;
+
+        Ok(Self { pool })
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// Import everything `config` declares in a single transaction, unless a
+    /// configuration was imported before. Returns whether it was imported.
+    pub async fn seed(&self, config: &Configuration) -> eyre::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let first_seed = sqlx::query(
+            "INSERT OR IGNORE INTO seeds (id, seeded_at) VALUES (1, ?)",
+        )
+        .bind(OffsetDateTime::now_utc())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        if !first_seed {
+            return Ok(false);
+        }
+
+        for (tenant_code, tenant) in &config.tenants {
+            let tenant_id = sqlx::query_scalar::<_, i64>(
+                "INSERT INTO tenants (code, display_name, description)
+                 VALUES (?, ?, ?)
+                 RETURNING id",
+            )
+            .bind(tenant_code)
+            .bind(tenant.display_name.as_deref().unwrap_or(tenant_code))
+            .bind(tenant.description.as_deref())
+            .fetch_one(&mut *tx)
+            .await?;
+
+            for (org_code, org) in &tenant.organizations {
+                let org_id = sqlx::query_scalar::<_, i64>(
+                    "INSERT INTO organizations
+                        (tenant_id, code, display_name, description)
+                     VALUES (?, ?, ?, ?)
+                     RETURNING id",
+                )
+                .bind(tenant_id)
+                .bind(org_code)
+                .bind(org.display_name.as_deref().unwrap_or(org_code))
+                .bind(org.description.as_deref())
+                .fetch_one(&mut *tx)
+                .await?;
+
+                for (ws_code, ws) in &org.workspaces {
+                    let ws_id = sqlx::query_scalar::<_, i64>(
+                        "INSERT INTO workspaces
+                            (organization_id, code, display_name, description)
+                         VALUES (?, ?, ?, ?)
+                         RETURNING id",
+                    )
+                    .bind(org_id)
+                    .bind(ws_code)
+                    .bind(ws.display_name.as_deref().unwrap_or(ws_code))
+                    .bind(ws.description.as_deref())
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+                    for (env_code, env) in &ws.environments {
+                        sqlx::query(
+                            "INSERT INTO environments
+                                (workspace_id, code, display_name, description)
+                             VALUES (?, ?, ?, ?)",
+                        )
+                        .bind(ws_id)
+                        .bind(env_code)
+                        .bind(env.display_name.as_deref().unwrap_or(env_code))
+                        .bind(env.description.as_deref())
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                }
+            }
+        }
+
+        for (key, api_key) in &config.security.api_keys {
+            insert_api_key(&mut *tx, key, api_key).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// The API key configuration stored for `key`, if any.
+    pub async fn find_api_key(
+        &self,
+        key: &str,
+    ) -> eyre::Result<Option<ApiKeyConfiguration>> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(
+            "SELECT description, enabled, scopes, tenants, organizations,
+                    workspaces, environments, expires_at
+             FROM api_keys
+             WHERE key_digest = ?",
+        )
+        .bind(api_key_digest(key))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(ApiKeyRow::into_configuration).transpose()?)
+    }
+}
+
+/// The hex encoded sha256 digest an API key is stored and looked up by, the
+/// key itself is never stored.
+pub fn api_key_digest(key: &str) -> String {
+    digest(&SHA256, key.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Hash the keys the migration to [`api_key_digest`]s moved aside.
+async fn hash_migrated_api_keys(pool: &SqlitePool) -> eyre::Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let keys = sqlx::query_as::<_, (String, String)>(
+        "SELECT id, key FROM api_keys_to_hash",
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for (id, key) in &keys {
+        sqlx::query("UPDATE api_keys SET key_digest = ? WHERE id = ?")
+            .bind(api_key_digest(key))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query("DELETE FROM api_keys_to_hash")
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+pub(super) struct ApiKeyRow {
+    description: Option<String>,
+    enabled: bool,
+    scopes: String,
+    tenants: String,
+    organizations: String,
+    workspaces: String,
+    environments: String,
+    expires_at: Option<OffsetDateTime>,
+}
+
+impl ApiKeyRow {
+    pub(super) fn into_configuration(
+        self,
+    ) -> Result<ApiKeyConfiguration, serde_json::Error> {
+        Ok(ApiKeyConfiguration {
+            description: self.description,
+            enabled: self.enabled,
+            scopes: serde_json::from_str(&self.scopes)?,
+            tenants: serde_json::from_str(&self.tenants)?,
+            organizations: serde_json::from_str(&self.organizations)?,
+            workspaces: serde_json::from_str(&self.workspaces)?,
+            environments: serde_json::from_str(&self.environments)?,
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+/// Store the digest of `key` and return the id it can be referred to by.
+pub(super) async fn insert_api_key(
+    conn: &mut sqlx::SqliteConnection,
+    key: &str,
+    api_key: &ApiKeyConfiguration,
+) -> Result<String, SqliteDatabaseError> {
+    let id = uuid::Uuid::new_v4().simple().to_string();
+
+    sqlx::query(
+        "INSERT INTO api_keys (
+            id, key_digest, description, enabled, scopes, tenants,
+            organizations, workspaces, environments, expires_at
+         )
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(api_key_digest(key))
+    .bind(api_key.description.as_deref())
+    .bind(api_key.enabled)
+    .bind(serde_json::to_string(&api_key.scopes)?)
+    .bind(serde_json::to_string(&api_key.tenants)?)
+    .bind(serde_json::to_string(&api_key.organizations)?)
+    .bind(serde_json::to_string(&api_key.workspaces)?)
+    .bind(serde_json::to_string(&api_key.environments)?)
+    .bind(api_key.expires_at)
+    .execute(conn)
+    .await
+    .map_err(|e| already_exists(e, "api key"))?;
+
+    Ok(id)
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use derive_new::new;
+
+use crate::{
+    data::{
+        environments::{
+            EnvironmentRepository, EnvironmentRepositoryError,
+            EnvironmentRepositoryErrorInner,
+        },
+        workspaces::WorkspaceRepository as _,
+    },
+    data_impl::sqlite::{SqliteDatabase, SqliteWorkspaceRepository},
+};
+
+#[derive(Debug, Clone, new)]
+pub struct SqliteEnvironmentRepository {
+    db: SqliteDatabase,
+}
+
+#[async_trait]
+impl EnvironmentRepository for SqliteEnvironmentRepository {
+    async fn exists_by_code(
+        &self,
+        code: &str,
+    ) -> Result<bool, EnvironmentRepositoryError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM environments WHERE code = ?)",
+        )
+        .bind(code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(exists)
+    }
+
+    async fn belongs_to_workspace(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+        environment_code: &str,
+    ) -> Result<bool, EnvironmentRepositoryError> {
+        let workspace_exists = SqliteWorkspaceRepository::new(self.db.clone())
+            .exists_by_code(workspace_code)
+            .await
+            .map_err(eyre::Report::new)?;
+
+        if !workspace_exists {
+            return Err(
+                EnvironmentRepositoryErrorInner::WorkspaceDoesNotExistByCode(
+                    workspace_code.to_string(),
+                )
+                .into(),
+            );
+        }
+
+        let belongs = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                SELECT 1
+                FROM environments e
+                JOIN workspaces w ON w.id = e.workspace_id
+                JOIN organizations o ON o.id = w.organization_id
+                JOIN tenants t ON t.id = o.tenant_id
+                WHERE t.code = ? AND o.code = ? AND w.code = ? AND e.code = ?
+            )",
+        )
+        .bind(tenant_code)
+        .bind(organization_code)
+        .bind(workspace_code)
+        .bind(environment_code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(belongs)
+    }
+}
@@ -0,0 +1,13 @@
+mod admin;
+mod database;
+mod environment_repository;
+mod organization_repository;
+mod tenant_repository;
+mod workspace_repository;
+
+pub use admin::*;
+pub use database::*;
+pub use environment_repository::*;
+pub use organization_repository::*;
+pub use tenant_repository::*;
+pub use workspace_repository::*;
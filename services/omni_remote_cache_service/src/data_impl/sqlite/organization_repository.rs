@@ -0,0 +1,72 @@
+use async_trait::async_trait;
+use derive_new::new;
+
+use crate::{
+    data::{
+        organizations::{
+            OrganizationRepository, OrganizationRepositoryError,
+            OrganizationRepositoryErrorInner,
+        },
+        tenants::TenantRepository as _,
+    },
+    data_impl::sqlite::{SqliteDatabase, SqliteTenantRepository},
+};
+
+#[derive(Debug, Clone, new)]
+pub struct SqliteOrganizationRepository {
+    db: SqliteDatabase,
+}
+
+#[async_trait]
+impl OrganizationRepository for SqliteOrganizationRepository {
+    async fn exists_by_code(
+        &self,
+        code: &str,
+    ) -> Result<bool, OrganizationRepositoryError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM organizations WHERE code = ?)",
+        )
+        .bind(code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(exists)
+    }
+
+    async fn belongs_to_tenant(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+    ) -> Result<bool, OrganizationRepositoryError> {
+        let tenant_exists = SqliteTenantRepository::new(self.db.clone())
+            .exists_by_code(tenant_code)
+            .await
+            .map_err(eyre::Report::new)?;
+
+        if !tenant_exists {
+            return Err(
+                OrganizationRepositoryErrorInner::TenantDoesNotExistByCode(
+                    tenant_code.to_string(),
+                )
+                .into(),
+            );
+        }
+
+        let belongs = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                SELECT 1
+                FROM organizations o
+                JOIN tenants t ON t.id = o.tenant_id
+                WHERE t.code = ? AND o.code = ?
+            )",
+        )
+        .bind(tenant_code)
+        .bind(organization_code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(belongs)
+    }
+}
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+use derive_new::new;
+
+use crate::{
+    data::tenants::{TenantRepository, TenantRepositoryError},
+    data_impl::sqlite::SqliteDatabase,
+};
+
+#[derive(Debug, Clone, new)]
+pub struct SqliteTenantRepository {
+    db: SqliteDatabase,
+}
+
+#[async_trait]
+impl TenantRepository for SqliteTenantRepository {
+    async fn exists_by_code(
+        &self,
+        code: &str,
+    ) -> Result<bool, TenantRepositoryError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM tenants WHERE code = ?)",
+        )
+        .bind(code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(exists)
+    }
+}
@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use derive_new::new;
+
+use crate::{
+    data::{
+        organizations::OrganizationRepository as _,
+        workspaces::{
+            WorkspaceRepository, WorkspaceRepositoryError,
+            WorkspaceRepositoryErrorInner,
+        },
+    },
+    data_impl::sqlite::{SqliteDatabase, SqliteOrganizationRepository},
+};
+
+#[derive(Debug, Clone, new)]
+pub struct SqliteWorkspaceRepository {
+    db: SqliteDatabase,
+}
+
+#[async_trait]
+impl WorkspaceRepository for SqliteWorkspaceRepository {
+    async fn exists_by_code(
+        &self,
+        workspace_code: &str,
+    ) -> Result<bool, WorkspaceRepositoryError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM workspaces WHERE code = ?)",
+        )
+        .bind(workspace_code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(exists)
+    }
+
+    async fn belongs_to_organization(
+        &self,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+    ) -> Result<bool, WorkspaceRepositoryError> {
+        let organization_exists =
+            SqliteOrganizationRepository::new(self.db.clone())
+                .exists_by_code(organization_code)
+                .await
+                .map_err(eyre::Report::new)?;
+
+        if !organization_exists {
+            return Err(
+                WorkspaceRepositoryErrorInner::OrganizationDoesNotExistByCode(
+                    organization_code.to_string(),
+                )
+                .into(),
+            );
+        }
+
+        let belongs = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                SELECT 1
+                FROM workspaces w
+                JOIN organizations o ON o.id = w.organization_id
+                JOIN tenants t ON t.id = o.tenant_id
+                WHERE t.code = ? AND o.code = ? AND w.code = ?
+            )",
+        )
+        .bind(tenant_code)
+        .bind(organization_code)
+        .bind(workspace_code)
+        .fetch_one(self.db.pool())
+        .await
+        .map_err(eyre::Report::new)?;
+
+        Ok(belongs)
+    }
+}
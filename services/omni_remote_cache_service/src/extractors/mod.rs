@@ -1,5 +1,9 @@
 mod api_key;
+mod compat_api_key;
+mod task_name;
 mod tenant_code;
 
 pub use api_key::*;
+pub use compat_api_key::*;
+pub use task_name::*;
 pub use tenant_code::*;
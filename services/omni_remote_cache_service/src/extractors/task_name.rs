@@ -0,0 +1,26 @@
+use std::convert::Infallible;
+
+use axum::extract::FromRequestParts;
+use http::request::Parts;
+
+/// The task an uploaded artifact belongs to, in the form of `project#task`,
+/// used by the per-task retention caps. Optional, as artifacts are keyed by
+/// digest alone.
+pub struct TaskName(pub Option<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for TaskName {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(TaskName(
+            parts
+                .headers
+                .get("X-OMNI-TASK")
+                .and_then(|task| task.to_str().ok())
+                .map(str::to_string),
+        ))
+    }
+}
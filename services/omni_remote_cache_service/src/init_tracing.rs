@@ -12,6 +12,7 @@ pub fn init_tracing() -> eyre::Result<()> {
             "./.omni/trace/omni-remote-cache-service.log",
         )),
         file_level: Level::Off,
+        otlp: None,
     };
 
     let sub = TracingSubscriber::new(&tracing_config, vec![])?;
@@ -12,6 +12,7 @@ pub mod local_disk_backend;
 pub mod providers;
 pub mod request;
 pub mod response;
+pub mod retention;
 pub mod routes;
 pub mod s3_backend;
 mod scalar;
@@ -42,6 +43,10 @@ pub async fn main() -> eyre::Result<()> {
         CliSubcommands::Serve(serve) => {
             let state = ServiceState::from_args(&serve.args).await?;
 
+            if let Some(interval) = serve.args.gc_interval {
+                state.garbage_collector.clone().spawn(interval);
+            }
+
             let routing_config = serve.args.routes.unwrap_or_default();
             let router = routes::root::build_router(&routing_config)
                 .with_state(state)
@@ -1,5 +1,7 @@
 mod config;
+mod sqlite;
 mod traits;
 
 pub use config::*;
+pub use sqlite::*;
 pub use traits::*;
@@ -0,0 +1,53 @@
+use derive_new::new;
+
+use crate::{
+    data::{
+        environments::DynEnvironmentRepository,
+        organizations::DynOrganizationRepository, tenants::DynTenantRepository,
+        workspaces::DynWorkspaceRepository,
+    },
+    data_impl::sqlite::{
+        SqliteDatabase, SqliteEnvironmentRepository,
+        SqliteOrganizationRepository, SqliteTenantRepository,
+        SqliteWorkspaceRepository,
+    },
+    providers::DependencyProvider,
+    security::{DynSecurityService, SqliteSecurityService},
+    services::{DefaultValidationService, DynValidationService},
+};
+
+#[derive(Clone, new)]
+pub struct SqliteDependencyProvider {
+    db: SqliteDatabase,
+}
+
+impl DependencyProvider for SqliteDependencyProvider {
+    fn workspace_repository(&self) -> DynWorkspaceRepository {
+        Box::new(SqliteWorkspaceRepository::new(self.db.clone()))
+    }
+
+    fn organization_repository(&self) -> DynOrganizationRepository {
+        Box::new(SqliteOrganizationRepository::new(self.db.clone()))
+    }
+
+    fn environment_repository(&self) -> DynEnvironmentRepository {
+        Box::new(SqliteEnvironmentRepository::new(self.db.clone()))
+    }
+
+    fn tenant_repository(&self) -> DynTenantRepository {
+        Box::new(SqliteTenantRepository::new(self.db.clone()))
+    }
+
+    fn validation_service(&self) -> DynValidationService {
+        Box::new(DefaultValidationService::new(
+            self.tenant_repository(),
+            self.organization_repository(),
+            self.workspace_repository(),
+            self.environment_repository(),
+        ))
+    }
+
+    fn security_service(&self) -> DynSecurityService {
+        Box::new(SqliteSecurityService::new(self.db.clone()))
+    }
+}
@@ -0,0 +1,410 @@
+use std::{cmp::Reverse, sync::Arc, time::Duration};
+
+use derive_new::new;
+use maps::UnorderedMap;
+use omni_remote_cache_storage::{RemoteCacheStorageBackend, error::Error};
+use serde::{Deserialize, Serialize};
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use crate::{
+    config::{AllOrSpecificConfiguration, RetentionRuleConfiguration},
+    retention::{AccessTrackerError, DynAccessTracker},
+    routes::v1::artifacts::common::CHUNK_CONTAINER_PREFIX,
+    storage_backend::StorageBackend,
+    utils::path::escape_path_component,
+};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum EvictionReason {
+    /// Not accessed within the rule's `max_age`
+    MaxAge,
+    /// More recently accessed artifacts of the same task fill its
+    /// `max_entries_per_task`
+    MaxEntriesPerTask,
+    /// More recently accessed artifacts fill its `max_size`
+    MaxSize,
+    /// A chunk of an upload that wasn't completed within
+    /// [`ABANDONED_UPLOAD_AGE`] of its last chunk
+    AbandonedUpload,
+}
+
+/// Chunked uploads without a new chunk for this long are considered
+/// abandoned, their chunks are removed regardless of the retention rules.
+pub const ABANDONED_UPLOAD_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Eviction {
+    pub key: String,
+    pub size: u64,
+    pub task: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    #[schema(value_type = String, format = DateTime)]
+    pub last_accessed_at: OffsetDateTime,
+    pub reason: EvictionReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ContainerReport {
+    /// `tenant/org/ws/env`
+    pub container: String,
+    pub kept_count: usize,
+    pub kept_size: u64,
+    pub evicted_size: u64,
+    pub evictions: Vec<Eviction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GcReport {
+    /// Whether the evictions were only planned, not carried out
+    pub dry_run: bool,
+    pub containers: Vec<ContainerReport>,
+}
+
+struct Entry {
+    key: String,
+    size: u64,
+    task: Option<String>,
+    last_accessed_at: OffsetDateTime,
+    /// Not tracked yet, e.g. uploaded before access tracking started.
+    untracked: bool,
+}
+
+/// Applies the retention rules to the stored artifacts.
+#[derive(Clone, new)]
+pub struct GarbageCollector {
+    storage_backend: Arc<StorageBackend>,
+    access_tracker: DynAccessTracker,
+    rules: Arc<Vec<RetentionRuleConfiguration>>,
+}
+
+impl GarbageCollector {
+    /// Report what a collection would evict now, without changing anything.
+    pub async fn plan(&self) -> Result<GcReport, GarbageCollectorError> {
+        self.run(true).await
+    }
+
+    /// Evict what the retention rules no longer allow to be kept.
+    pub async fn collect(&self) -> Result<GcReport, GarbageCollectorError> {
+        self.run(false).await
+    }
+
+    /// Collect every `interval` until the service stops.
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+
+            loop {
+                interval.tick().await;
+
+                match self.collect().await {
+                    Ok(report) => {
+                        let (count, size) = report.containers.iter().fold(
+                            (0, 0),
+                            |(count, size), c| {
+                                (
+                                    count + c.evictions.len(),
+                                    size + c.evicted_size,
+                                )
+                            },
+                        );
+                        log::info!(
+                            "Garbage collection evicted {count} artifacts ({})",
+                            bytesize::ByteSize::b(size)
+                        );
+                    }
+                    Err(e) => {
+                        log::error!("Garbage collection failed: {e}");
+                    }
+                }
+            }
+        })
+    }
+
+    async fn run(
+        &self,
+        dry_run: bool,
+    ) -> Result<GcReport, GarbageCollectorError> {
+        let now = OffsetDateTime::now_utc();
+        let mut containers = Vec::new();
+        let mut seen = Vec::<String>::new();
+
+        // A container is governed by the first rule matching it. Containers
+        // are looked up in the storage backend, the access tracker doesn't
+        // know of artifacts stored before tracking started.
+        for rule in self.rules.iter() {
+            let prefix = container_prefix(&rule.tenant, &rule.org, &rule.ws);
+            let candidates = match &rule.environments {
+                AllOrSpecificConfiguration::All(_) => self
+                    .storage_backend
+                    .list_containers(&prefix)
+                    .await?
+                    .into_iter()
+                    .filter(|c| {
+                        c.strip_prefix(&prefix)
+                            .is_some_and(|env| !env.contains('/'))
+                    })
+                    .collect::<Vec<_>>(),
+                AllOrSpecificConfiguration::Specific(envs) => envs
+                    .iter()
+                    .map(|env| {
+                        format!("{prefix}{}", escape_path_component(env))
+                    })
+                    .collect(),
+            };
+
+            for container in candidates {
+                if seen.contains(&container) {
+                    continue;
+                }
+                seen.push(container.clone());
+
+                containers.push(
+                    self.run_container(&container, rule, now, dry_run).await?,
+                );
+            }
+        }
+
+        for container in self
+            .storage_backend
+            .list_containers(CHUNK_CONTAINER_PREFIX)
+            .await?
+        {
+            if let Some(report) =
+                self.run_upload_container(&container, now, dry_run).await?
+            {
+                containers.push(report);
+            }
+        }
+
+        containers.sort_by(|a, b| a.container.cmp(&b.container));
+
+        Ok(GcReport {
+            dry_run,
+            containers,
+        })
+    }
+
+    async fn run_container(
+        &self,
+        container: &str,
+        rule: &RetentionRuleConfiguration,
+        now: OffsetDateTime,
+        dry_run: bool,
+    ) -> Result<ContainerReport, GarbageCollectorError> {
+        let items = self.storage_backend.list(Some(container)).await?;
+        let mut records = self
+            .access_tracker
+            .records(container)
+            .await?
+            .into_iter()
+            .map(|r| (r.key.clone(), r))
+            .collect::<UnorderedMap<_, _>>();
+
+        let mut entries = items
+            .into_iter()
+            .map(|item| match records.remove(item.key()) {
+                Some(record) => Entry {
+                    key: item.key,
+                    size: item.size.as_u64(),
+                    task: record.task,
+                    last_accessed_at: record.last_accessed_at,
+                    untracked: false,
+                },
+                // Unknown artifacts get a full `max_age` from now on, rather
+                // than being evicted right away.
+                None => Entry {
+                    key: item.key,
+                    size: item.size.as_u64(),
+                    task: None,
+                    last_accessed_at: now,
+                    untracked: true,
+                },
+            })
+            .collect::<Vec<_>>();
+
+        // Most recently accessed first.
+        entries.sort_by_key(|e| Reverse(e.last_accessed_at));
+
+        let mut evictions = Vec::new();
+        let mut kept = Vec::with_capacity(entries.len());
+        let mut per_task = UnorderedMap::<String, usize>::default();
+        let mut kept_size = 0u64;
+
+        for entry in entries {
+            let expired = rule
+                .max_age
+                .is_some_and(|max_age| entry.last_accessed_at + max_age < now);
+
+            let over_task_cap = !expired
+                && match (rule.max_entries_per_task, &entry.task) {
+                    (Some(max), Some(task)) => {
+                        let count = per_task.entry(task.clone()).or_default();
+                        *count += 1;
+                        *count > max
+                    }
+                    _ => false,
+                };
+
+            let reason = if expired {
+                Some(EvictionReason::MaxAge)
+            } else if over_task_cap {
+                Some(EvictionReason::MaxEntriesPerTask)
+            } else if rule
+                .max_size
+                .is_some_and(|max| kept_size + entry.size > max.as_u64())
+            {
+                Some(EvictionReason::MaxSize)
+            } else {
+                None
+            };
+
+            match reason {
+                Some(reason) => evictions.push(Eviction {
+                    key: entry.key,
+                    size: entry.size,
+                    task: entry.task,
+                    last_accessed_at: entry.last_accessed_at,
+                    reason,
+                }),
+                None => {
+                    kept_size += entry.size;
+                    kept.push(entry);
+                }
+            }
+        }
+
+        if !dry_run {
+            for eviction in &evictions {
+                self.storage_backend
+                    .delete(Some(container), &eviction.key)
+                    .await?;
+            }
+
+            for entry in kept.iter().filter(|e| e.untracked) {
+                self.access_tracker
+                    .record_access(container, &entry.key, None, now)
+                    .await?;
+            }
+
+            // Records left over belong to artifacts that are gone already.
+            let forgotten = evictions
+                .iter()
+                .map(|e| e.key.clone())
+                .chain(records.into_keys())
+                .collect::<Vec<_>>();
+            self.access_tracker.forget(container, &forgotten).await?;
+        }
+
+        Ok(ContainerReport {
+            container: container.to_string(),
+            kept_count: kept.len(),
+            kept_size,
+            evicted_size: evictions.iter().map(|e| e.size).sum(),
+            evictions,
+        })
+    }
+
+    /// Remove the chunks of an upload in `container` once it is abandoned.
+    async fn run_upload_container(
+        &self,
+        container: &str,
+        now: OffsetDateTime,
+        dry_run: bool,
+    ) -> Result<Option<ContainerReport>, GarbageCollectorError> {
+        let records = self.access_tracker.records(container).await?;
+        let Some(last_accessed_at) =
+            records.iter().map(|r| r.last_accessed_at).max()
+        else {
+            // Untracked chunks get a full `ABANDONED_UPLOAD_AGE` from now on.
+            if !dry_run {
+                for item in self.storage_backend.list(Some(container)).await? {
+                    self.access_tracker
+                        .record_access(container, &item.key, None, now)
+                        .await?;
+                }
+            }
+
+            return Ok(None);
+        };
+
+        if last_accessed_at + ABANDONED_UPLOAD_AGE >= now {
+            return Ok(None);
+        }
+
+        let evictions = self
+            .storage_backend
+            .list(Some(container))
+            .await?
+            .into_iter()
+            .map(|item| Eviction {
+                size: item.size.as_u64(),
+                key: item.key,
+                task: None,
+                last_accessed_at,
+                reason: EvictionReason::AbandonedUpload,
+            })
+            .collect::<Vec<_>>();
+
+        if !dry_run {
+            for eviction in &evictions {
+                self.storage_backend
+                    .delete(Some(container), &eviction.key)
+                    .await?;
+            }
+
+            let forgotten =
+                records.into_iter().map(|r| r.key).collect::<Vec<_>>();
+            self.access_tracker.forget(container, &forgotten).await?;
+        }
+
+        Ok(Some(ContainerReport {
+            container: container.to_string(),
+            kept_count: 0,
+            kept_size: 0,
+            evicted_size: evictions.iter().map(|e| e.size).sum(),
+            evictions,
+        }))
+    }
+}
+
+fn container_prefix(tenant: &str, org: &str, ws: &str) -> String {
+    format!(
+        "{}/{}/{}/",
+        escape_path_component(tenant),
+        escape_path_component(org),
+        escape_path_component(ws)
+    )
+}
+
+#[derive(Debug, thiserror::Error, new)]
+#[error(transparent)]
+pub struct GarbageCollectorError(pub(crate) GarbageCollectorErrorInner);
+
+impl GarbageCollectorError {
+    #[allow(unused)]
+    pub fn kind(&self) -> GarbageCollectorErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<GarbageCollectorErrorInner>> From<T> for GarbageCollectorError {
+    fn from(inner: T) -> Self {
+        let inner = inner.into();
+        Self(inner)
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants, new)]
+#[strum_discriminants(vis(pub), name(GarbageCollectorErrorKind))]
+pub(crate) enum GarbageCollectorErrorInner {
+    #[error(transparent)]
+    Storage(#[from] Error),
+
+    #[error(transparent)]
+    AccessTracker(#[from] AccessTrackerError),
+}
@@ -0,0 +1,82 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use maps::UnorderedMap;
+use time::OffsetDateTime;
+
+use crate::retention::{AccessRecord, AccessTracker, AccessTrackerError};
+
+/// Tracks accesses in memory only, used when the service runs without a
+/// database. Artifacts seen for the first time after a restart are treated
+/// as accessed then.
+#[derive(Debug, Default)]
+pub struct InMemoryAccessTracker {
+    containers: Mutex<UnorderedMap<String, UnorderedMap<String, AccessRecord>>>,
+}
+
+#[async_trait]
+impl AccessTracker for InMemoryAccessTracker {
+    async fn record_access(
+        &self,
+        container: &str,
+        key: &str,
+        task: Option<&str>,
+        at: OffsetDateTime,
+    ) -> Result<(), AccessTrackerError> {
+        let mut containers = self.containers.lock().expect("poisoned");
+        let records = containers.entry(container.to_string()).or_default();
+
+        match records.get_mut(key) {
+            Some(record) => {
+                record.last_accessed_at = at;
+                if let Some(task) = task {
+                    record.task = Some(task.to_string());
+                }
+            }
+            None => {
+                records.insert(
+                    key.to_string(),
+                    AccessRecord::new(
+                        key.to_string(),
+                        task.map(str::to_string),
+                        at,
+                    ),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn records(
+        &self,
+        container: &str,
+    ) -> Result<Vec<AccessRecord>, AccessTrackerError> {
+        let containers = self.containers.lock().expect("poisoned");
+
+        Ok(containers
+            .get(container)
+            .map(|records| records.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn forget(
+        &self,
+        container: &str,
+        keys: &[String],
+    ) -> Result<(), AccessTrackerError> {
+        let mut containers = self.containers.lock().expect("poisoned");
+
+        if let Some(records) = containers.get_mut(container) {
+            for key in keys {
+                records.remove(key);
+            }
+
+            if records.is_empty() {
+                containers.remove(container);
+            }
+        }
+
+        Ok(())
+    }
+}
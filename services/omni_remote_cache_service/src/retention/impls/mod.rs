@@ -0,0 +1,5 @@
+mod in_memory;
+mod sqlite;
+
+pub use in_memory::*;
+pub use sqlite::*;
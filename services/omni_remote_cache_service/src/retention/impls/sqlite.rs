@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use derive_new::new;
+use time::OffsetDateTime;
+
+use crate::{
+    data_impl::sqlite::SqliteDatabase,
+    retention::{AccessRecord, AccessTracker, AccessTrackerError},
+};
+
+/// Tracks accesses in the database, so they survive restarts.
+#[derive(Debug, Clone, new)]
+pub struct SqliteAccessTracker {
+    db: SqliteDatabase,
+}
+
+#[derive(sqlx::FromRow)]
+struct AccessRecordRow {
+    key: String,
+    task: Option<String>,
+    last_accessed_at: OffsetDateTime,
+}
+
+#[async_trait]
+impl AccessTracker for SqliteAccessTracker {
+    async fn record_access(
+        &self,
+        container: &str,
+        key: &str,
+        task: Option<&str>,
+        at: OffsetDateTime,
+    ) -> Result<(), AccessTrackerError> {
+        sqlx::query(
+            "INSERT INTO artifact_accesses (container, key, task, last_accessed_at)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT (container, key) DO UPDATE SET
+                task = COALESCE(excluded.task, task),
+                last_accessed_at = excluded.last_accessed_at",
+        )
+        .bind(container)
+        .bind(key)
+        .bind(task)
+        .bind(at)
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn records(
+        &self,
+        container: &str,
+    ) -> Result<Vec<AccessRecord>, AccessTrackerError> {
+        let rows = sqlx::query_as::<_, AccessRecordRow>(
+            "SELECT key, task, last_accessed_at
+             FROM artifact_accesses
+             WHERE container = ?",
+        )
+        .bind(container)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                AccessRecord::new(row.key, row.task, row.last_accessed_at)
+            })
+            .collect())
+    }
+
+    async fn forget(
+        &self,
+        container: &str,
+        keys: &[String],
+    ) -> Result<(), AccessTrackerError> {
+        let mut tx = self.db.pool().begin().await?;
+
+        for key in keys {
+            sqlx::query(
+                "DELETE FROM artifact_accesses WHERE container = ? AND key = ?",
+            )
+            .bind(container)
+            .bind(key)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+mod gc;
+mod impls;
+mod traits;
+
+pub use gc::*;
+pub use impls::*;
+pub use traits::*;
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use derive_new::new;
+use strum::{EnumDiscriminants, IntoDiscriminant as _};
+use time::OffsetDateTime;
+
+/// When an artifact was last accessed, and by which task it was uploaded.
+#[derive(Debug, Clone, PartialEq, Eq, new)]
+pub struct AccessRecord {
+    pub key: String,
+    pub task: Option<String>,
+    pub last_accessed_at: OffsetDateTime,
+}
+
+/// Keeps track of when the artifacts of each container were last uploaded,
+/// read or checked, which the retention rules are evaluated against.
+#[async_trait]
+pub trait AccessTracker: Send + Sync + 'static {
+    /// Record an access of `key` at `at`. `task` is only set on upload; an
+    /// access without it keeps the task recorded before.
+    async fn record_access(
+        &self,
+        container: &str,
+        key: &str,
+        task: Option<&str>,
+        at: OffsetDateTime,
+    ) -> Result<(), AccessTrackerError>;
+
+    async fn records(
+        &self,
+        container: &str,
+    ) -> Result<Vec<AccessRecord>, AccessTrackerError>;
+
+    async fn forget(
+        &self,
+        container: &str,
+        keys: &[String],
+    ) -> Result<(), AccessTrackerError>;
+}
+
+pub type DynAccessTracker = Arc<dyn AccessTracker>;
+
+#[derive(Debug, thiserror::Error, new)]
+#[error(transparent)]
+pub struct AccessTrackerError(pub(crate) AccessTrackerErrorInner);
+
+impl AccessTrackerError {
+    #[allow(unused)]
+    pub fn kind(&self) -> AccessTrackerErrorKind {
+        self.0.discriminant()
+    }
+}
+
+impl<T: Into<AccessTrackerErrorInner>> From<T> for AccessTrackerError {
+    fn from(inner: T) -> Self {
+        let inner = inner.into();
+        Self(inner)
+    }
+}
+
+#[derive(Debug, thiserror::Error, EnumDiscriminants, new)]
+#[strum_discriminants(vis(pub), name(AccessTrackerErrorKind))]
+pub(crate) enum AccessTrackerErrorInner {
+    #[error(transparent)]
+    Sqlx(#[from] sqlx::Error),
+}
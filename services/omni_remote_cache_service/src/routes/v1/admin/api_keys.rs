@@ -0,0 +1,213 @@
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::routing::TypedPath;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+
+use crate::{
+    config::{
+        AllOrSpecificConfiguration, ApiKeyConfiguration, ScopesConfiguration,
+    },
+    data_impl::sqlite::StoredApiKey,
+    extractors::ApiKey,
+    response::data::Data,
+    routes::v1::admin::common::{admin_guard, database_error_response},
+    state::ServiceState,
+};
+
+/// What a new API key grants access to. Every list is either `"all"` or an
+/// array of codes (scopes for `scopes`), and defaults to `"all"`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, ToSchema)]
+pub struct CreateApiKeyRequest {
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub scopes: AllOrSpecificConfiguration<ScopesConfiguration>,
+
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub tenants: AllOrSpecificConfiguration,
+
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub organizations: AllOrSpecificConfiguration,
+
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub workspaces: AllOrSpecificConfiguration,
+
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub environments: AllOrSpecificConfiguration,
+
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl From<CreateApiKeyRequest> for ApiKeyConfiguration {
+    fn from(request: CreateApiKeyRequest) -> Self {
+        Self {
+            description: request.description,
+            enabled: true,
+            scopes: request.scopes,
+            organizations: request.organizations,
+            workspaces: request.workspaces,
+            tenants: request.tenants,
+            environments: request.environments,
+            expires_at: request.expires_at,
+        }
+    }
+}
+
+/// A newly minted API key. The key is only ever returned here.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct CreatedApiKey {
+    pub id: String,
+    pub key: String,
+}
+
+/// A stored API key, without the key itself.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct ApiKeyItem {
+    pub id: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+
+    #[schema(value_type = Object)]
+    pub scopes: AllOrSpecificConfiguration<ScopesConfiguration>,
+
+    #[schema(value_type = Object)]
+    pub tenants: AllOrSpecificConfiguration,
+
+    #[schema(value_type = Object)]
+    pub organizations: AllOrSpecificConfiguration,
+
+    #[schema(value_type = Object)]
+    pub workspaces: AllOrSpecificConfiguration,
+
+    #[schema(value_type = Object)]
+    pub environments: AllOrSpecificConfiguration,
+
+    #[serde(with = "time::serde::rfc3339::option")]
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl From<StoredApiKey> for ApiKeyItem {
+    fn from(StoredApiKey { id, config }: StoredApiKey) -> Self {
+        Self {
+            id,
+            description: config.description,
+            enabled: config.enabled,
+            scopes: config.scopes,
+            tenants: config.tenants,
+            organizations: config.organizations,
+            workspaces: config.workspaces,
+            environments: config.environments,
+            expires_at: config.expires_at,
+        }
+    }
+}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/api-keys")]
+pub struct ApiKeysPath {}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/api-keys/{id}/revoke")]
+pub struct RevokeApiKeyPath {
+    pub id: String,
+}
+
+#[utoipa::path(
+    get,
+    description = "List API keys",
+    path = "/api-keys",
+    responses(
+        (status = OK, description = "Success", body = Data<Vec<ApiKeyItem>>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn get_api_keys(
+    _: ApiKeysPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.list_api_keys().await {
+        Ok(keys) => Json(Data::new(
+            keys.into_iter().map(ApiKeyItem::from).collect::<Vec<_>>(),
+        ))
+        .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    description = "Mint a new API key",
+    path = "/api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = CREATED, description = "Created", body = Data<CreatedApiKey>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn post_api_key(
+    _: ApiKeysPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    Json(body): Json<CreateApiKeyRequest>,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.create_api_key(&body.into()).await {
+        Ok((id, key)) => (
+            StatusCode::CREATED,
+            Json(Data::new(CreatedApiKey { id, key })),
+        )
+            .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    description = "Revoke an API key; requests using it are rejected from then on",
+    path = "/api-keys/{id}/revoke",
+    params(
+        ("id" = String, Path, description = "API key id"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "Success"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The API key does not exist"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn revoke_api_key(
+    RevokeApiKeyPath { id }: RevokeApiKeyPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.revoke_api_key(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
@@ -0,0 +1,151 @@
+use axum::{
+    Json,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::response::InternalServerError;
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::{
+    data::{
+        environments::Environment, organizations::Organization,
+        tenants::Tenant, workspaces::Workspace,
+    },
+    data_impl::sqlite::{
+        NewEntity, SqliteDatabaseError, SqliteDatabaseErrorKind,
+    },
+};
+
+/// Body of the requests creating a tenant, organization, workspace or
+/// environment.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct CreateEntityRequest {
+    /// The code used to refer to it, unique among its siblings
+    pub code: String,
+    /// Defaults to the code
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl CreateEntityRequest {
+    pub fn as_new_entity(&self) -> NewEntity<'_> {
+        NewEntity::new(
+            &self.code,
+            self.display_name.as_deref(),
+            self.description.as_deref(),
+        )
+    }
+}
+
+/// A tenant, organization, workspace or environment.
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema, PartialEq, Eq)]
+pub struct EntityItem {
+    pub code: String,
+    pub display_name: String,
+    pub description: Option<String>,
+}
+
+macro_rules! impl_from_entity {
+    ($($entity:ty),+) => {
+        $(
+            impl From<$entity> for EntityItem {
+                fn from(entity: $entity) -> Self {
+                    Self {
+                        code: entity.code,
+                        display_name: entity.display_name,
+                        description: entity.description,
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_from_entity!(Tenant, Organization, Workspace, Environment);
+
+fn problem_response(status: StatusCode, title: &str, detail: &str) -> Response {
+    (
+        status,
+        Json(json!({
+            "type": format!("https://httpstatuses.com/{}", status.as_u16()),
+            "title": title,
+            "detail": detail,
+            "instance": "",
+            "status": status.as_u16(),
+        })),
+    )
+        .into_response()
+}
+
+pub fn database_error_response(error: SqliteDatabaseError) -> Response {
+    match error.kind() {
+        SqliteDatabaseErrorKind::NotFound => problem_response(
+            StatusCode::NOT_FOUND,
+            "Not Found",
+            &error.to_string(),
+        ),
+        SqliteDatabaseErrorKind::AlreadyExists => problem_response(
+            StatusCode::CONFLICT,
+            "Conflict",
+            &error.to_string(),
+        ),
+        _ => InternalServerError(error).into_response(),
+    }
+}
+
+pub fn admin_disabled_response() -> Response {
+    problem_response(
+        StatusCode::NOT_FOUND,
+        "Not Found",
+        "The admin API is not enabled, it requires --admin-api-key and --database-url",
+    )
+}
+
+/// Compare in constant time, so the admin key can't be guessed byte by byte
+/// from response times.
+pub fn keys_match(expected: &str, actual: &str) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .bytes()
+            .zip(actual.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Reject the request unless `$api_key` is the admin API key.
+pub macro admin_key_guard($state:expr, $api_key:expr $(,)?) {{
+    use crate::routes::v1::{
+        admin::common::{admin_disabled_response, keys_match},
+        artifacts::common::forbidden_response,
+    };
+
+    let Some(admin_api_key) = $state.args.admin_api_key.as_deref() else {
+        return admin_disabled_response();
+    };
+
+    if !keys_match(admin_api_key, $api_key) {
+        return forbidden_response(Some(
+            "You are not authorized to process this request",
+        ));
+    }
+}}
+
+/// Reject the request unless the admin API is enabled and `$api_key` is the
+/// admin API key; evaluates to the database the admin API manages.
+pub macro admin_guard($state:expr, $api_key:expr $(,)?) {{
+    use crate::routes::v1::admin::common::{
+        admin_disabled_response, admin_key_guard,
+    };
+
+    admin_key_guard!($state, $api_key);
+
+    let Some(database) = $state.database.as_ref() else {
+        return admin_disabled_response();
+    };
+
+    database
+}}
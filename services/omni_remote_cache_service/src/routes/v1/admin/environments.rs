@@ -0,0 +1,103 @@
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::routing::TypedPath;
+use http::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    extractors::ApiKey,
+    response::data::Data,
+    routes::v1::admin::common::{
+        CreateEntityRequest, EntityItem, admin_guard, database_error_response,
+    },
+    state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path(
+    "/tenants/{tenant}/organizations/{org}/workspaces/{ws}/environments"
+)]
+pub struct EnvironmentsPath {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+}
+
+#[utoipa::path(
+    get,
+    description = "List the environments of a workspace",
+    path = "/tenants/{tenant}/organizations/{org}/workspaces/{ws}/environments",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+    ),
+    responses(
+        (status = OK, description = "Success", body = Data<Vec<EntityItem>>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The tenant, organization or workspace does not exist"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn get_environments(
+    EnvironmentsPath { tenant, org, ws }: EnvironmentsPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.list_environments(&tenant, &org, &ws).await {
+        Ok(environments) => Json(Data::new(
+            environments
+                .into_iter()
+                .map(EntityItem::from)
+                .collect::<Vec<_>>(),
+        ))
+        .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    description = "Create an environment in a workspace",
+    path = "/tenants/{tenant}/organizations/{org}/workspaces/{ws}/environments",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+    ),
+    request_body = CreateEntityRequest,
+    responses(
+        (status = CREATED, description = "Created", body = Data<EntityItem>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The tenant, organization or workspace does not exist"),
+        (status = CONFLICT, description = "An environment with the same code exists in the workspace"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn post_environment(
+    EnvironmentsPath { tenant, org, ws }: EnvironmentsPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    Json(body): Json<CreateEntityRequest>,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db
+        .create_environment(&tenant, &org, &ws, body.as_new_entity())
+        .await
+    {
+        Ok(environment) => (
+            StatusCode::CREATED,
+            Json(Data::new(EntityItem::from(environment))),
+        )
+            .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
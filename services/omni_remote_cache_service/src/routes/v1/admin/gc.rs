@@ -0,0 +1,40 @@
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::{response::InternalServerError, routing::TypedPath};
+use serde::Deserialize;
+
+use crate::{
+    extractors::ApiKey, response::data::Data, retention::GcReport,
+    routes::v1::admin::common::admin_key_guard, state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/gc/report")]
+pub struct GcReportPath {}
+
+#[utoipa::path(
+    get,
+    description = "Report which artifacts the retention rules would evict now, without evicting them",
+    path = "/gc/report",
+    responses(
+        (status = OK, description = "Success", body = Data<GcReport>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn get_gc_report(
+    _: GcReportPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    admin_key_guard!(state, &api_key);
+
+    match state.garbage_collector.plan().await {
+        Ok(report) => Json(Data::new(report)).into_response(),
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
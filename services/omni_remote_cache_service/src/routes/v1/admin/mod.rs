@@ -0,0 +1,94 @@
+mod api_keys;
+pub(crate) mod common;
+mod environments;
+mod gc;
+mod organizations;
+mod tenants;
+mod workspaces;
+
+pub use api_keys::*;
+pub use common::{CreateEntityRequest, EntityItem};
+pub use environments::*;
+pub use gc::*;
+pub use organizations::*;
+pub use tenants::*;
+pub use workspaces::*;
+
+use axum::Router;
+use axum_extra::routing::RouterExt;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self, ComponentsBuilder,
+        security::{ApiKey, ApiKeyValue, SecurityScheme},
+    },
+};
+
+use crate::{response::data::Data, retention::GcReport, state::ServiceState};
+
+pub fn build_router() -> Router<ServiceState> {
+    Router::new()
+        .typed_get(get_tenants)
+        .typed_post(post_tenant)
+        .typed_get(get_organizations)
+        .typed_post(post_organization)
+        .typed_get(get_workspaces)
+        .typed_post(post_workspace)
+        .typed_get(get_environments)
+        .typed_post(post_environment)
+        .typed_get(get_api_keys)
+        .typed_post(post_api_key)
+        .typed_post(revoke_api_key)
+        .typed_get(get_gc_report)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_tenants,
+        post_tenant,
+        get_organizations,
+        post_organization,
+        get_workspaces,
+        post_workspace,
+        get_environments,
+        post_environment,
+        get_api_keys,
+        post_api_key,
+        revoke_api_key,
+        get_gc_report,
+    ),
+    components(
+        schemas(
+            CreateEntityRequest,
+            CreateApiKeyRequest,
+            Data<EntityItem>,
+            Data<Vec<EntityItem>>,
+            Data<CreatedApiKey>,
+            Data<Vec<ApiKeyItem>>,
+            Data<GcReport>,
+        )
+    ),
+    security(
+        ("admin_api_key" = []),
+    ),
+    modifiers(&SecurityAddon),
+)]
+pub struct AdminApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        openapi.components = Some(
+            ComponentsBuilder::new()
+                .security_scheme(
+                    "admin_api_key",
+                    SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(
+                        "X-API-KEY",
+                    ))),
+                )
+                .build(),
+        )
+    }
+}
@@ -0,0 +1,92 @@
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::routing::TypedPath;
+use http::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    extractors::ApiKey,
+    response::data::Data,
+    routes::v1::admin::common::{
+        CreateEntityRequest, EntityItem, admin_guard, database_error_response,
+    },
+    state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/tenants/{tenant}/organizations")]
+pub struct OrganizationsPath {
+    pub tenant: String,
+}
+
+#[utoipa::path(
+    get,
+    description = "List the organizations of a tenant",
+    path = "/tenants/{tenant}/organizations",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+    ),
+    responses(
+        (status = OK, description = "Success", body = Data<Vec<EntityItem>>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The tenant does not exist"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn get_organizations(
+    OrganizationsPath { tenant }: OrganizationsPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.list_organizations(&tenant).await {
+        Ok(organizations) => Json(Data::new(
+            organizations
+                .into_iter()
+                .map(EntityItem::from)
+                .collect::<Vec<_>>(),
+        ))
+        .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    description = "Create an organization in a tenant",
+    path = "/tenants/{tenant}/organizations",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+    ),
+    request_body = CreateEntityRequest,
+    responses(
+        (status = CREATED, description = "Created", body = Data<EntityItem>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The tenant does not exist"),
+        (status = CONFLICT, description = "An organization with the same code exists in the tenant"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn post_organization(
+    OrganizationsPath { tenant }: OrganizationsPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    Json(body): Json<CreateEntityRequest>,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.create_organization(&tenant, body.as_new_entity()).await {
+        Ok(organization) => (
+            StatusCode::CREATED,
+            Json(Data::new(EntityItem::from(organization))),
+        )
+            .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
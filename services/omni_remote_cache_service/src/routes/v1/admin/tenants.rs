@@ -0,0 +1,82 @@
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::routing::TypedPath;
+use http::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    extractors::ApiKey,
+    response::data::Data,
+    routes::v1::admin::common::{
+        CreateEntityRequest, EntityItem, admin_guard, database_error_response,
+    },
+    state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/tenants")]
+pub struct TenantsPath {}
+
+#[utoipa::path(
+    get,
+    description = "List tenants",
+    path = "/tenants",
+    responses(
+        (status = OK, description = "Success", body = Data<Vec<EntityItem>>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn get_tenants(
+    _: TenantsPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.list_tenants().await {
+        Ok(tenants) => Json(Data::new(
+            tenants
+                .into_iter()
+                .map(EntityItem::from)
+                .collect::<Vec<_>>(),
+        ))
+        .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    description = "Create a tenant",
+    path = "/tenants",
+    request_body = CreateEntityRequest,
+    responses(
+        (status = CREATED, description = "Created", body = Data<EntityItem>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = CONFLICT, description = "A tenant with the same code exists"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn post_tenant(
+    _: TenantsPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    Json(body): Json<CreateEntityRequest>,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.create_tenant(body.as_new_entity()).await {
+        Ok(tenant) => (
+            StatusCode::CREATED,
+            Json(Data::new(EntityItem::from(tenant))),
+        )
+            .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
@@ -0,0 +1,98 @@
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::routing::TypedPath;
+use http::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    extractors::ApiKey,
+    response::data::Data,
+    routes::v1::admin::common::{
+        CreateEntityRequest, EntityItem, admin_guard, database_error_response,
+    },
+    state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/tenants/{tenant}/organizations/{org}/workspaces")]
+pub struct WorkspacesPath {
+    pub tenant: String,
+    pub org: String,
+}
+
+#[utoipa::path(
+    get,
+    description = "List the workspaces of an organization",
+    path = "/tenants/{tenant}/organizations/{org}/workspaces",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+    ),
+    responses(
+        (status = OK, description = "Success", body = Data<Vec<EntityItem>>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The tenant or organization does not exist"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn get_workspaces(
+    WorkspacesPath { tenant, org }: WorkspacesPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db.list_workspaces(&tenant, &org).await {
+        Ok(workspaces) => Json(Data::new(
+            workspaces
+                .into_iter()
+                .map(EntityItem::from)
+                .collect::<Vec<_>>(),
+        ))
+        .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    description = "Create a workspace in an organization",
+    path = "/tenants/{tenant}/organizations/{org}/workspaces",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+    ),
+    request_body = CreateEntityRequest,
+    responses(
+        (status = CREATED, description = "Created", body = Data<EntityItem>),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = NOT_FOUND, description = "The tenant or organization does not exist"),
+        (status = CONFLICT, description = "A workspace with the same code exists in the organization"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn post_workspace(
+    WorkspacesPath { tenant, org }: WorkspacesPath,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    Json(body): Json<CreateEntityRequest>,
+) -> Response {
+    let db = admin_guard!(state, &api_key);
+
+    match db
+        .create_workspace(&tenant, &org, body.as_new_entity())
+        .await
+    {
+        Ok(workspace) => (
+            StatusCode::CREATED,
+            Json(Data::new(EntityItem::from(workspace))),
+        )
+            .into_response(),
+        Err(e) => database_error_response(e),
+    }
+}
@@ -1,13 +1,25 @@
+use std::sync::{Arc, Mutex};
+
 use axum::{
     Json,
+    body::Body,
     response::{IntoResponse, Response},
 };
+use bytes::Bytes;
 use http::StatusCode;
+use omni_remote_cache_storage::BoxStream;
+use ring::digest::{Context, SHA256};
 use serde::Deserialize;
 use serde_json::json;
 use utoipa::{IntoParams, ToSchema};
 
-use crate::{services::Violation, utils::path::escape_path_component};
+use time::OffsetDateTime;
+use tokio_stream::StreamExt as _;
+
+use crate::{
+    services::Violation, state::ServiceState,
+    utils::path::escape_path_component,
+};
 
 #[allow(unused)]
 #[derive(Deserialize, IntoParams, Debug, ToSchema)]
@@ -20,16 +32,125 @@ pub struct CommonArtifactQuery {
 
 #[allow(unused)]
 #[inline(always)]
-pub fn container_common(query: &CommonArtifactQuery) -> String {
-    container(&query.org, &query.ws, &query.env)
+pub fn container_common(tenant: &str, query: &CommonArtifactQuery) -> String {
+    container(tenant, &query.org, &query.ws, &query.env)
 }
 
+/// Where the artifacts of an environment are kept. Organization, workspace
+/// and environment codes are only unique within their tenant, so the tenant
+/// leads the container.
 #[inline(always)]
-pub fn container(org: &str, ws: &str, env: &str) -> String {
+pub fn container(tenant: &str, org: &str, ws: &str, env: &str) -> String {
+    let tenant = escape_path_component(tenant);
     let org = escape_path_component(org);
     let ws = escape_path_component(ws);
     let env = escape_path_component(env);
-    format!("{}/{}/{}", org, ws, env)
+    format!("{}/{}/{}/{}", tenant, org, ws, env)
+}
+
+/// Prefix of the containers of in-progress chunked uploads.
+pub const CHUNK_CONTAINER_PREFIX: &str = ".chunks/";
+
+/// Where the chunks of an in-progress chunked upload of `digest` are kept until
+/// they are assembled. Kept outside of the artifact container so pending
+/// chunks never show up as artifacts.
+#[inline(always)]
+pub fn chunk_container(
+    tenant: &str,
+    org: &str,
+    ws: &str,
+    env: &str,
+    digest: &str,
+) -> String {
+    format!(
+        "{CHUNK_CONTAINER_PREFIX}{}/{}",
+        container(tenant, org, ws, env),
+        escape_path_component(digest)
+    )
+}
+
+#[inline(always)]
+pub fn chunk_key(index: u32) -> String {
+    format!("{index:08}")
+}
+
+/// Where the sha256 digest of the chunk at `index` is kept, it is only
+/// present while the chunk itself is complete.
+#[inline(always)]
+pub fn chunk_digest_key(index: u32) -> String {
+    format!("{index:08}.sha256")
+}
+
+struct BodyState {
+    digest: Context,
+    error: Option<axum::Error>,
+}
+
+/// Tracks a request body streamed into the storage backend with
+/// [`body_stream`].
+pub struct StreamedBody(Arc<Mutex<BodyState>>);
+
+impl StreamedBody {
+    /// The hex encoded sha256 digest of the streamed body, or the error that
+    /// ended the stream early. What was saved from the stream must not be
+    /// kept in that case.
+    pub fn finish(self) -> Result<String, axum::Error> {
+        let mut state = self.0.lock().expect("poisoned");
+        if let Some(error) = state.error.take() {
+            return Err(error);
+        }
+
+        let digest = state.digest.clone().finish();
+
+        Ok(digest.as_ref().iter().map(|b| format!("{b:02x}")).collect())
+    }
+}
+
+/// The request body as a stream for the storage backend. Storage streams
+/// can't fail, so a body error ends the stream and is returned by
+/// [`StreamedBody::finish`] instead.
+pub fn body_stream(body: Body) -> (BoxStream<Bytes>, StreamedBody) {
+    let state = Arc::new(Mutex::new(BodyState {
+        digest: Context::new(&SHA256),
+        error: None,
+    }));
+
+    let stream = {
+        let state = state.clone();
+        body.into_data_stream().map_while(move |r| {
+            let mut state = state.lock().expect("poisoned");
+            match r {
+                Ok(bytes) => {
+                    state.digest.update(&bytes);
+                    Some(bytes)
+                }
+                Err(e) => {
+                    state.error = Some(e);
+                    None
+                }
+            }
+        })
+    };
+
+    (Box::pin(stream), StreamedBody(state))
+}
+
+/// Record an access of `digest` for the retention rules. Failing to do so
+/// doesn't fail the request, the artifact is just treated as accessed when it
+/// is next seen by the garbage collector.
+pub async fn record_access(
+    state: &ServiceState,
+    container: &str,
+    digest: &str,
+    task: Option<&str>,
+) {
+    if let Err(e) = state
+        .access_tracker
+        .record_access(container, digest, task, OffsetDateTime::now_utc())
+        .await
+    {
+        log::warn!("Failed to record access of {container}/{digest}: {e}");
+    }
 }
 
 #[inline(always)]
@@ -80,7 +201,7 @@ fn translate_violation(
         Violation::TenantDoesNotHaveOrganization => {
             format!(
                 "tenant for code '{}' does not have an organization with code '{}'",
-                org, org
+                tenant, org
             )
         }
         Violation::OrganizationDoesNotExist => {
@@ -0,0 +1,174 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::{response::InternalServerError, routing::TypedPath};
+use http::StatusCode;
+use omni_remote_cache_storage::{RemoteCacheStorageBackend, error::Error};
+use serde::Deserialize;
+use tokio_stream::StreamExt;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{ApiKey, TaskName, TenantCode},
+    routes::v1::artifacts::common::{
+        chunk_container, chunk_digest_key, chunk_key, container, guard,
+        record_access, validate_ownership,
+    },
+    state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug, IntoParams)]
+#[typed_path("/{digest}/chunks")]
+pub struct CompleteArtifactChunksPath {
+    #[param()]
+    /// The digest of the artifact
+    pub digest: String,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct CompleteArtifactChunksQuery {
+    #[param()]
+    /// The organization code
+    pub org: String,
+    #[param()]
+    /// The workspace code
+    pub ws: String,
+    #[param()]
+    /// The environment code
+    pub env: String,
+    #[param()]
+    /// The number of chunks the artifact was split into
+    pub count: u32,
+}
+
+#[utoipa::path(
+    post,
+    description = "Complete a chunked upload: assemble the uploaded chunks, in order, into the artifact",
+    path = "/{digest}/chunks",
+    params(
+        CompleteArtifactChunksPath,
+        CompleteArtifactChunksQuery,
+        ("X-OMNI-TENANT" = String, Header, description = "Tenant code"),
+        ("X-OMNI-TASK" = Option<String>, Header, description = "The task the artifact belongs to, in the form of project#task"),
+    ),
+    responses(
+        (status = NO_CONTENT, description = "Success"),
+        (status = BAD_REQUEST, description = "Bad request, e.g. a chunk is missing"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn complete_artifact_chunks(
+    CompleteArtifactChunksPath { digest }: CompleteArtifactChunksPath,
+    Query(query): Query<CompleteArtifactChunksQuery>,
+    TenantCode(tenant_code): TenantCode,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    TaskName(task): TaskName,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &tenant_code,
+        &query,
+        &["write:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &tenant_code, &query);
+
+    let chunks = chunk_container(
+        &tenant_code,
+        &query.org,
+        &query.ws,
+        &query.env,
+        &digest,
+    );
+    let container = container(&tenant_code, &query.org, &query.ws, &query.env);
+    let backend = state.storage_backend.clone();
+
+    let mut expected_size = 0;
+    for index in 0..query.count {
+        match backend.size(Some(chunks.as_ref()), &chunk_key(index)).await {
+            Ok(Some(size)) => expected_size += size.as_u64(),
+            Ok(None) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    format!("chunk {index} of artifact {digest} is missing"),
+                )
+                    .into_response();
+            }
+            Err(e) => return InternalServerError(e).into_response(),
+        }
+    }
+
+    // Stream the chunks back to back; one is only opened once the previous
+    // one is drained.
+    let stream = {
+        let backend = backend.clone();
+        let chunks = chunks.clone();
+        let count = query.count;
+        async_stream::stream! {
+            for index in 0..count {
+                match backend.get_stream(Some(chunks.as_ref()), &chunk_key(index)).await {
+                    Ok(Some(mut chunk)) => {
+                        while let Some(bytes) = chunk.next().await {
+                            yield bytes;
+                        }
+                    }
+                    Ok(None) => {
+                        log::error!("chunk {index} vanished during assembly");
+                        break;
+                    }
+                    Err(e) => {
+                        log::error!("error reading chunk {index}: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+    };
+
+    let result = async {
+        backend
+            .save_stream(Some(container.as_ref()), &digest, Box::pin(stream))
+            .await?;
+
+        // A chunk that failed to read mid-assembly truncates the stream;
+        // never leave a truncated artifact behind.
+        let size = backend.size(Some(container.as_ref()), &digest).await?;
+        if size.map(|s| s.as_u64()) != Some(expected_size) {
+            backend.delete(Some(container.as_ref()), &digest).await?;
+            return Err(Error::custom(eyre::eyre!(
+                "assembled artifact {digest} is incomplete"
+            )));
+        }
+
+        for index in 0..query.count {
+            backend
+                .delete(Some(chunks.as_ref()), &chunk_digest_key(index))
+                .await?;
+            backend
+                .delete(Some(chunks.as_ref()), &chunk_key(index))
+                .await?;
+        }
+
+        Ok::<_, Error>(())
+    }
+    .await
+    .map_err(InternalServerError);
+
+    match result {
+        Ok(()) => {
+            let keys = (0..query.count).map(chunk_key).collect::<Vec<_>>();
+            if let Err(e) = state.access_tracker.forget(&chunks, &keys).await {
+                log::warn!("Failed to forget the chunks of {chunks}: {e}");
+            }
+
+            record_access(&state, &container, &digest, task.as_deref()).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
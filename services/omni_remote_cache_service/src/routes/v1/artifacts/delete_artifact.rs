@@ -67,7 +67,7 @@ pub async fn delete_artifact(
 
     validate_ownership!(state.provider, &tenant_code, &query);
 
-    let container = container(&query.org, &query.ws, &query.env);
+    let container = container(&tenant_code, &query.org, &query.ws, &query.env);
     let x = state
         .storage_backend
         .delete(Some(container.as_ref()), &digest)
@@ -75,7 +75,18 @@ pub async fn delete_artifact(
         .map_err(InternalServerError);
 
     match x {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Ok(_) => {
+            if let Err(e) = state
+                .access_tracker
+                .forget(&container, std::slice::from_ref(&digest))
+                .await
+            {
+                log::warn!(
+                    "Failed to forget access of {container}/{digest}: {e}"
+                );
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
         Err(e) => e.into_response(),
     }
 }
@@ -15,7 +15,7 @@ use utoipa::IntoParams;
 use super::common::container;
 use crate::{
     extractors::{ApiKey, TenantCode},
-    routes::v1::artifacts::common::{guard, validate_ownership},
+    routes::v1::artifacts::common::{guard, record_access, validate_ownership},
     state::ServiceState,
 };
 
@@ -74,7 +74,7 @@ pub async fn get_artifact(
 
     validate_ownership!(state.provider, &tenant_code, &query);
 
-    let container = container(&query.org, &query.ws, &query.env);
+    let container = container(&tenant_code, &query.org, &query.ws, &query.env);
     let x = state
         .storage_backend
         .get_stream(Some(container.as_ref()), &digest)
@@ -84,6 +84,8 @@ pub async fn get_artifact(
     match x {
         Ok(o) => match o {
             Some(stream) => {
+                record_access(&state, &container, &digest, None).await;
+
                 let body =
                     Body::from_stream(stream.map(Ok::<_, Infallible>));
 
@@ -11,7 +11,7 @@ use utoipa::IntoParams;
 use super::common::container;
 use crate::{
     extractors::{ApiKey, TenantCode},
-    routes::v1::artifacts::common::{guard, validate_ownership},
+    routes::v1::artifacts::common::{guard, record_access, validate_ownership},
     state::ServiceState,
 };
 
@@ -69,7 +69,7 @@ pub async fn head_artifact(
 
     validate_ownership!(state.provider, &tenant_code, &query);
 
-    let container = container(&query.org, &query.ws, &query.env);
+    let container = container(&tenant_code, &query.org, &query.ws, &query.env);
     let exists = state
         .storage_backend
         .exists(Some(container.as_ref()), &digest)
@@ -79,6 +79,7 @@ pub async fn head_artifact(
     match exists {
         Ok(o) => {
             if o {
+                record_access(&state, &container, &digest, None).await;
                 StatusCode::NO_CONTENT.into_response()
             } else {
                 StatusCode::NOT_FOUND.into_response()
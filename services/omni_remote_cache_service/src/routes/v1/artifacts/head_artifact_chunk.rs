@@ -0,0 +1,126 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+};
+use axum_extra::{response::InternalServerError, routing::TypedPath};
+use http::StatusCode;
+use omni_remote_cache_storage::{RemoteCacheStorageBackend, error::Error};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{ApiKey, TenantCode},
+    routes::v1::artifacts::common::{
+        chunk_container, chunk_digest_key, chunk_key, guard, validate_ownership,
+    },
+    state::ServiceState,
+};
+
+/// Size in bytes of the stored chunk.
+pub const CHUNK_SIZE_HEADER: &str = "X-OMNI-CHUNK-SIZE";
+
+/// Hex encoded sha256 digest of the stored chunk.
+pub const CHUNK_DIGEST_HEADER: &str = "X-OMNI-CHUNK-DIGEST";
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/{digest}/chunks/{index}")]
+pub struct HeadArtifactChunkPath {
+    pub digest: String,
+    pub index: u32,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct HeadArtifactChunkQuery {
+    /// The organization code
+    pub org: String,
+    /// The workspace code
+    pub ws: String,
+    /// The environment code
+    pub env: String,
+}
+
+#[utoipa::path(
+    head,
+    path = "/{digest}/chunks/{index}",
+    description = "Check whether a chunk of an in-progress upload was already received, to resume the upload",
+    params(
+        ("digest" = String, Path, description = "Artifact digest"),
+        ("index" = u32, Path, description = "Zero-based chunk index"),
+        ("X-OMNI-TENANT" = String, Header, description = "Tenant code"),
+        HeadArtifactChunkQuery
+    ),
+    responses(
+        (
+            status = NO_CONTENT,
+            description = "Success",
+            headers(
+                ("X-OMNI-CHUNK-SIZE" = u64, description = "Size of the stored chunk in bytes"),
+                ("X-OMNI-CHUNK-DIGEST" = String, description = "Hex encoded sha256 digest of the stored chunk")
+            )
+        ),
+        (status = NOT_FOUND, description = "Not found"),
+        (status = BAD_REQUEST, description = "Bad request"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state))]
+pub async fn head_artifact_chunk(
+    HeadArtifactChunkPath { digest, index }: HeadArtifactChunkPath,
+    Query(query): Query<HeadArtifactChunkQuery>,
+    TenantCode(tenant_code): TenantCode,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &tenant_code,
+        &query,
+        &["write:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &tenant_code, &query);
+
+    let container = chunk_container(
+        &tenant_code,
+        &query.org,
+        &query.ws,
+        &query.env,
+        &digest,
+    );
+    let backend = &state.storage_backend;
+    let chunk = async {
+        // A chunk without a digest is still being written or was interrupted.
+        let Some(digest) = backend
+            .get(Some(container.as_ref()), &chunk_digest_key(index))
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let size = backend
+            .size(Some(container.as_ref()), &chunk_key(index))
+            .await?;
+
+        Ok::<_, Error>(size.map(|size| (size, digest)))
+    }
+    .await
+    .map_err(InternalServerError);
+
+    match chunk {
+        Ok(Some((size, digest))) => (
+            StatusCode::NO_CONTENT,
+            [
+                (CHUNK_SIZE_HEADER, size.as_u64().to_string()),
+                (
+                    CHUNK_DIGEST_HEADER,
+                    String::from_utf8_lossy(&digest).into_owned(),
+                ),
+            ],
+        )
+            .into_response(),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
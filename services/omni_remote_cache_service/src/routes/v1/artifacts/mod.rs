@@ -1,17 +1,23 @@
-mod common;
+pub(crate) mod common;
+mod complete_artifact_chunks;
 mod delete_artifact;
 mod get_artifact;
 mod get_artifacts;
 mod head_artifact;
+mod head_artifact_chunk;
 mod head_artifacts;
 mod put_artifact;
+mod put_artifact_chunk;
 
+pub use complete_artifact_chunks::*;
 pub use delete_artifact::*;
 pub use get_artifact::*;
 pub use get_artifacts::*;
 pub use head_artifact::*;
+pub use head_artifact_chunk::*;
 pub use head_artifacts::*;
 pub use put_artifact::*;
+pub use put_artifact_chunk::*;
 
 use axum::Router;
 use axum_extra::routing::RouterExt;
@@ -33,6 +39,9 @@ pub fn build_router() -> Router<ServiceState> {
         .typed_put(put_artifact)
         .typed_delete(delete_artifact)
         .typed_head(head_artifact)
+        .typed_put(put_artifact_chunk)
+        .typed_head(head_artifact_chunk)
+        .typed_post(complete_artifact_chunks)
 }
 
 #[derive(OpenApi)]
@@ -44,6 +53,9 @@ pub fn build_router() -> Router<ServiceState> {
         delete_artifact,
         head_artifact,
         head_artifacts,
+        put_artifact_chunk,
+        head_artifact_chunk,
+        complete_artifact_chunks,
     ),
     components(
         schemas(
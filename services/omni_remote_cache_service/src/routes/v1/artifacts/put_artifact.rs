@@ -11,8 +11,10 @@ use tokio_stream::StreamExt;
 use utoipa::IntoParams;
 
 use crate::{
-    extractors::{ApiKey, TenantCode},
-    routes::v1::artifacts::common::{container, guard, validate_ownership},
+    extractors::{ApiKey, TaskName, TenantCode},
+    routes::v1::artifacts::common::{
+        container, guard, record_access, validate_ownership,
+    },
     state::ServiceState,
 };
 
@@ -46,6 +48,7 @@ pub struct PutArtifactQuery {
         PutArtifactPath,
         PutArtifactQuery,
         ("X-OMNI-TENANT" = String, Header, description = "Tenant code"),
+        ("X-OMNI-TASK" = Option<String>, Header, description = "The task the artifact belongs to, in the form of project#task"),
     ),
     request_body(content_type = "application/octet-stream", description = "Raw file streaming content", content = Vec<u8>),
     responses(
@@ -61,6 +64,7 @@ pub async fn put_artifact(
     TenantCode(tenant_code): TenantCode,
     State(state): State<ServiceState>,
     ApiKey(api_key): ApiKey,
+    TaskName(task): TaskName,
     body: Body,
 ) -> Response {
     guard!(
@@ -73,7 +77,7 @@ pub async fn put_artifact(
 
     validate_ownership!(state.provider, &tenant_code, &query);
 
-    let container = container(&query.org, &query.ws, &query.env);
+    let container = container(&tenant_code, &query.org, &query.ws, &query.env);
     let stream = body.into_data_stream().filter_map(|r| match r {
         Ok(b) => Some(b),
         Err(e) => {
@@ -89,7 +93,10 @@ pub async fn put_artifact(
         .map_err(InternalServerError);
 
     match x {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Ok(_) => {
+            record_access(&state, &container, &digest, task.as_deref()).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
         Err(e) => e.into_response(),
     }
 }
@@ -0,0 +1,133 @@
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::{response::InternalServerError, routing::TypedPath};
+use bytes::Bytes;
+use http::StatusCode;
+use omni_remote_cache_storage::{RemoteCacheStorageBackend, error::Error};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    extractors::{ApiKey, TenantCode},
+    routes::v1::artifacts::common::{
+        body_stream, chunk_container, chunk_digest_key, chunk_key, guard,
+        record_access, validate_ownership,
+    },
+    state::ServiceState,
+};
+
+#[derive(TypedPath, Deserialize, Debug, IntoParams)]
+#[typed_path("/{digest}/chunks/{index}")]
+pub struct PutArtifactChunkPath {
+    #[param()]
+    /// The digest of the artifact
+    pub digest: String,
+    #[param()]
+    /// The zero-based index of the chunk
+    pub index: u32,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+#[into_params(parameter_in = Query)]
+pub struct PutArtifactChunkQuery {
+    #[param()]
+    /// The organization code
+    pub org: String,
+    #[param()]
+    /// The workspace code
+    pub ws: String,
+    #[param()]
+    /// The environment code
+    pub env: String,
+}
+
+#[utoipa::path(
+    put,
+    description = "Upload one chunk of an artifact. Chunks are assembled into the artifact once the upload is completed",
+    path = "/{digest}/chunks/{index}",
+    params(
+        PutArtifactChunkPath,
+        PutArtifactChunkQuery,
+        ("X-OMNI-TENANT" = String, Header, description = "Tenant code"),
+    ),
+    request_body(content_type = "application/octet-stream", description = "Raw chunk content", content = Vec<u8>),
+    responses(
+        (status = NO_CONTENT, description = "Success"),
+        (status = BAD_REQUEST, description = "Bad request"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, body))]
+pub async fn put_artifact_chunk(
+    PutArtifactChunkPath { digest, index }: PutArtifactChunkPath,
+    Query(query): Query<PutArtifactChunkQuery>,
+    TenantCode(tenant_code): TenantCode,
+    State(state): State<ServiceState>,
+    ApiKey(api_key): ApiKey,
+    body: Body,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &tenant_code,
+        &query,
+        &["write:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &tenant_code, &query);
+
+    let container = chunk_container(
+        &tenant_code,
+        &query.org,
+        &query.ws,
+        &query.env,
+        &digest,
+    );
+    let backend = &state.storage_backend;
+    let key = chunk_key(index);
+    let digest_key = chunk_digest_key(index);
+    let (stream, body) = body_stream(body);
+
+    let result = async {
+        // The digest is only present while the chunk is complete, so a resumed
+        // upload never trusts a chunk that is being replaced.
+        backend
+            .delete(Some(container.as_ref()), &digest_key)
+            .await?;
+        backend
+            .save_stream(Some(container.as_ref()), &key, stream)
+            .await?;
+
+        let chunk_digest = match body.finish() {
+            Ok(chunk_digest) => chunk_digest,
+            Err(e) => {
+                backend.delete(Some(container.as_ref()), &key).await?;
+                return Err(Error::custom(eyre::eyre!(
+                    "reading chunk {index} of artifact {digest} failed: {e}"
+                )));
+            }
+        };
+
+        backend
+            .save(
+                Some(container.as_ref()),
+                &digest_key,
+                Bytes::from(chunk_digest),
+            )
+            .await
+    }
+    .await
+    .map_err(InternalServerError);
+
+    match result {
+        Ok(()) => {
+            // Lets the garbage collector find abandoned uploads.
+            record_access(&state, &container, &key, None).await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,283 @@
+//! The Bazel HTTP remote cache protocol. Point Bazel at it with
+//! `--remote_cache={base_url}/v1/compat/bazel/{tenant}/{org}/{ws}/{env}` and
+//! either `--remote_header=X-API-KEY={api_key}` or the API key as the
+//! password of the URL.
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::{response::InternalServerError, routing::TypedPath};
+use http::StatusCode;
+use omni_remote_cache_storage::{RemoteCacheStorageBackend as _, error::Error};
+use serde::Deserialize;
+use serde_json::json;
+use strum::Display;
+
+use crate::{
+    extractors::CompatApiKey,
+    routes::v1::{
+        artifacts::common::{
+            container, guard, record_access, validate_ownership,
+        },
+        compat::common::{
+            artifact_response, invalid_hash_response, is_valid_hash, save_body,
+        },
+    },
+    state::ServiceState,
+};
+
+/// The action cache maps action digests to results, the content addressable
+/// store maps content digests to content. The same digest may be in both.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum BazelStore {
+    Ac,
+    Cas,
+}
+
+fn bazel_key(store: BazelStore, hash: &str) -> String {
+    format!("bazel-{store}-{hash}")
+}
+
+/// Save a content addressable store entry only if its content matches `hash`,
+/// so a corrupt or malicious upload can't poison the entry. The content is
+/// staged under a temporary key until its digest is known. Returns whether the
+/// content matched.
+async fn save_cas_body(
+    state: &ServiceState,
+    container: &str,
+    key: &str,
+    hash: &str,
+    body: Body,
+) -> Result<bool, Error> {
+    let backend = &state.storage_backend;
+    let staging = format!("{key}.upload-{}", uuid::Uuid::new_v4().simple());
+
+    let digest = save_body(state, container, &staging, body).await?;
+    if !digest.eq_ignore_ascii_case(hash) {
+        backend.delete(Some(container), &staging).await?;
+        return Ok(false);
+    }
+
+    let result = async {
+        let stream = backend
+            .get_stream(Some(container), &staging)
+            .await?
+            .ok_or_else(|| {
+                Error::custom(eyre::eyre!("staged entry {staging} is gone"))
+            })?;
+
+        backend.save_stream(Some(container), key, stream).await
+    }
+    .await;
+
+    backend.delete(Some(container), &staging).await?;
+
+    result.map(|()| true)
+}
+
+fn digest_mismatch_response(hash: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "type": "https://httpstatuses.com/400",
+            "title": "Digest Mismatch",
+            "detail": format!("the content doesn't have the sha256 digest '{hash}'"),
+            "instance": "",
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+        })),
+    )
+        .into_response()
+}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/bazel/{tenant}/{org}/{ws}/{env}/{store}/{hash}")]
+pub struct BazelEntryPath {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+    pub env: String,
+    pub store: BazelStore,
+    pub hash: String,
+}
+
+#[utoipa::path(
+    get,
+    description = "Bazel: download an action cache (`ac`) or content addressable store (`cas`) entry",
+    path = "/bazel/{tenant}/{org}/{ws}/{env}/{store}/{hash}",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+        ("store" = String, Path, description = "`ac` or `cas`"),
+        ("hash" = String, Path, description = "Entry hash"),
+    ),
+    responses(
+        (status = OK, description = "Success", content_type = "application/octet-stream"),
+        (status = NOT_FOUND, description = "Not found"),
+        (status = BAD_REQUEST, description = "Invalid hash"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn bazel_get_entry(
+    path: BazelEntryPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    if !is_valid_hash(&path.hash) {
+        return invalid_hash_response(&path.hash);
+    }
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let key = bazel_key(path.store, &path.hash);
+
+    match state
+        .storage_backend
+        .get_stream(Some(container.as_ref()), &key)
+        .await
+    {
+        Ok(Some(stream)) => {
+            record_access(&state, &container, &key, None).await;
+            artifact_response(stream)
+        }
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    head,
+    description = "Bazel: check whether an action cache (`ac`) or content addressable store (`cas`) entry exists",
+    path = "/bazel/{tenant}/{org}/{ws}/{env}/{store}/{hash}",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+        ("store" = String, Path, description = "`ac` or `cas`"),
+        ("hash" = String, Path, description = "Entry hash"),
+    ),
+    responses(
+        (status = OK, description = "Exists"),
+        (status = NOT_FOUND, description = "Not found"),
+        (status = BAD_REQUEST, description = "Invalid hash"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn bazel_head_entry(
+    path: BazelEntryPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    if !is_valid_hash(&path.hash) {
+        return invalid_hash_response(&path.hash);
+    }
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let key = bazel_key(path.store, &path.hash);
+
+    match state
+        .storage_backend
+        .exists(Some(container.as_ref()), &key)
+        .await
+    {
+        Ok(true) => {
+            record_access(&state, &container, &key, None).await;
+            StatusCode::OK.into_response()
+        }
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    description = "Bazel: upload an action cache (`ac`) or content addressable store (`cas`) entry",
+    path = "/bazel/{tenant}/{org}/{ws}/{env}/{store}/{hash}",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+        ("store" = String, Path, description = "`ac` or `cas`"),
+        ("hash" = String, Path, description = "Entry hash"),
+    ),
+    request_body(content_type = "application/octet-stream", content = Vec<u8>),
+    responses(
+        (status = OK, description = "Success"),
+        (status = BAD_REQUEST, description = "Invalid hash, or a `cas` entry whose content doesn't have the sha256 digest `hash`"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key, body))]
+pub async fn bazel_put_entry(
+    path: BazelEntryPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+    body: Body,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["write:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    if !is_valid_hash(&path.hash) {
+        return invalid_hash_response(&path.hash);
+    }
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let key = bazel_key(path.store, &path.hash);
+
+    let saved = match path.store {
+        BazelStore::Ac => save_body(&state, &container, &key, body)
+            .await
+            .map(|_| true),
+        BazelStore::Cas => {
+            save_cas_body(&state, &container, &key, &path.hash, body).await
+        }
+    };
+
+    match saved {
+        Ok(true) => {
+            record_access(&state, &container, &key, None).await;
+            StatusCode::OK.into_response()
+        }
+        Ok(false) => digest_mismatch_response(&path.hash),
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
@@ -0,0 +1,71 @@
+use std::convert::Infallible;
+
+use axum::{
+    Json,
+    body::Body,
+    response::{IntoResponse as _, Response},
+};
+use http::{StatusCode, header};
+use omni_remote_cache_storage::{
+    BoxStream, RemoteCacheStorageBackend as _, error::Error,
+};
+use serde_json::json;
+use tokio_stream::StreamExt as _;
+
+use crate::{routes::v1::artifacts::common::body_stream, state::ServiceState};
+
+/// Hashes become storage keys, so only allow what the tools actually send.
+pub fn is_valid_hash(hash: &str) -> bool {
+    !hash.is_empty()
+        && hash.len() <= 128
+        && hash
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn invalid_hash_response(hash: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "type": "https://httpstatuses.com/400",
+            "title": "Invalid Hash",
+            "detail": format!("'{hash}' is not a valid hash"),
+            "instance": "",
+            "status": StatusCode::BAD_REQUEST.as_u16(),
+        })),
+    )
+        .into_response()
+}
+
+pub fn artifact_response(stream: BoxStream<bytes::Bytes>) -> Response {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from_stream(stream.map(Ok::<_, Infallible>)))
+        .expect("should be able to build response from stream")
+}
+
+/// Save the request body under `key` and return its hex encoded sha256
+/// digest. What was saved is removed again if the body couldn't be read
+/// completely.
+pub async fn save_body(
+    state: &ServiceState,
+    container: &str,
+    key: &str,
+    body: Body,
+) -> Result<String, Error> {
+    let (stream, body) = body_stream(body);
+
+    state
+        .storage_backend
+        .save_stream(Some(container), key, stream)
+        .await?;
+
+    match body.finish() {
+        Ok(digest) => Ok(digest),
+        Err(e) => {
+            state.storage_backend.delete(Some(container), key).await?;
+            Err(Error::custom(eyre::eyre!("reading {key} failed: {e}")))
+        }
+    }
+}
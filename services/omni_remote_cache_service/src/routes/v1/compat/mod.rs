@@ -0,0 +1,93 @@
+mod bazel;
+pub(crate) mod common;
+mod turbo;
+
+pub use bazel::*;
+pub use turbo::*;
+
+use axum::Router;
+use axum_extra::routing::RouterExt;
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self, ComponentsBuilder,
+        security::{
+            ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme,
+        },
+    },
+};
+
+use crate::state::ServiceState;
+
+pub fn build_router() -> Router<ServiceState> {
+    Router::new()
+        .typed_get(turbo_status)
+        .typed_post(turbo_events)
+        .typed_post(turbo_query_artifacts)
+        .typed_get(turbo_get_artifact)
+        .typed_head(turbo_head_artifact)
+        .typed_put(turbo_put_artifact)
+        .typed_get(bazel_get_entry)
+        .typed_head(bazel_head_entry)
+        .typed_put(bazel_put_entry)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        turbo_status,
+        turbo_events,
+        turbo_query_artifacts,
+        turbo_get_artifact,
+        turbo_head_artifact,
+        turbo_put_artifact,
+        bazel_get_entry,
+        bazel_head_entry,
+        bazel_put_entry,
+    ),
+    components(
+        schemas(
+            TurboArtifactsQueryRequest,
+        )
+    ),
+    security(
+        ("api_key" = ["read:artifacts", "write:artifacts"]),
+        ("bearer" = ["read:artifacts", "write:artifacts"]),
+        ("basic" = ["read:artifacts", "write:artifacts"]),
+    ),
+    modifiers(&SecurityAddon),
+)]
+pub struct CompatApiDoc;
+
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        openapi.components = Some(
+            ComponentsBuilder::new()
+                .security_scheme(
+                    "api_key",
+                    SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(
+                        "X-API-KEY",
+                    ))),
+                )
+                .security_scheme(
+                    "bearer",
+                    SecurityScheme::Http(
+                        HttpBuilder::new()
+                            .scheme(HttpAuthScheme::Bearer)
+                            .build(),
+                    ),
+                )
+                .security_scheme(
+                    "basic",
+                    SecurityScheme::Http(
+                        HttpBuilder::new()
+                            .scheme(HttpAuthScheme::Basic)
+                            .build(),
+                    ),
+                )
+                .build(),
+        )
+    }
+}
@@ -0,0 +1,365 @@
+//! The Turborepo remote cache API. Point Turborepo at it with
+//! `TURBO_API={base_url}/v1/compat/turbo/{tenant}/{org}/{ws}/{env}` and
+//! `TURBO_TOKEN={api_key}`; the team it sends is ignored.
+
+use axum::{
+    Json,
+    body::Body,
+    extract::State,
+    response::{IntoResponse as _, Response},
+};
+use axum_extra::{response::InternalServerError, routing::TypedPath};
+use http::StatusCode;
+use omni_remote_cache_storage::RemoteCacheStorageBackend as _;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::{
+    extractors::CompatApiKey,
+    routes::v1::{
+        artifacts::common::{
+            container, guard, record_access, validate_ownership,
+        },
+        compat::common::{
+            artifact_response, invalid_hash_response, is_valid_hash, save_body,
+        },
+    },
+    state::ServiceState,
+};
+
+fn turbo_key(hash: &str) -> String {
+    format!("turbo-{hash}")
+}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/status")]
+pub struct TurboStatusPath {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+    pub env: String,
+}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/events")]
+pub struct TurboEventsPath {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+    pub env: String,
+}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts")]
+pub struct TurboArtifactsPath {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+    pub env: String,
+}
+
+#[derive(TypedPath, Deserialize, Debug)]
+#[typed_path("/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/{hash}")]
+pub struct TurboArtifactPath {
+    pub tenant: String,
+    pub org: String,
+    pub ws: String,
+    pub env: String,
+    pub hash: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, ToSchema)]
+pub struct TurboArtifactsQueryRequest {
+    pub hashes: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    description = "Turborepo: whether remote caching is enabled",
+    path = "/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/status",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+    ),
+    responses(
+        (status = OK, description = "Success"),
+        (status = FORBIDDEN, description = "Forbidden"),
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn turbo_status(
+    path: TurboStatusPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    Json(json!({ "status": "enabled" })).into_response()
+}
+
+#[utoipa::path(
+    post,
+    description = "Turborepo: record cache usage events; they are accepted and ignored",
+    path = "/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/events",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+    ),
+    responses(
+        (status = OK, description = "Success"),
+        (status = FORBIDDEN, description = "Forbidden"),
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn turbo_events(
+    path: TurboEventsPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    StatusCode::OK.into_response()
+}
+
+#[utoipa::path(
+    post,
+    description = "Turborepo: query which of the given artifacts exist and their sizes",
+    path = "/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+    ),
+    request_body = TurboArtifactsQueryRequest,
+    responses(
+        (status = OK, description = "Success"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn turbo_query_artifacts(
+    path: TurboArtifactsPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+    Json(body): Json<TurboArtifactsQueryRequest>,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let mut result = serde_json::Map::new();
+
+    for hash in body.hashes {
+        let size = if is_valid_hash(&hash) {
+            match state
+                .storage_backend
+                .size(Some(container.as_ref()), &turbo_key(&hash))
+                .await
+            {
+                Ok(size) => size,
+                Err(e) => return InternalServerError(e).into_response(),
+            }
+        } else {
+            None
+        };
+
+        let info = match size {
+            Some(size) => json!({ "size": size.as_u64(), "taskDurationMs": 0 }),
+            None => json!({ "error": { "message": "Artifact not found" } }),
+        };
+        result.insert(hash, info);
+    }
+
+    Json(result).into_response()
+}
+
+#[utoipa::path(
+    get,
+    description = "Turborepo: download an artifact",
+    path = "/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/{hash}",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+        ("hash" = String, Path, description = "Artifact hash"),
+    ),
+    responses(
+        (status = OK, description = "Success", content_type = "application/octet-stream"),
+        (status = NOT_FOUND, description = "Not found"),
+        (status = BAD_REQUEST, description = "Invalid hash"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn turbo_get_artifact(
+    path: TurboArtifactPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    if !is_valid_hash(&path.hash) {
+        return invalid_hash_response(&path.hash);
+    }
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let key = turbo_key(&path.hash);
+
+    match state
+        .storage_backend
+        .get_stream(Some(container.as_ref()), &key)
+        .await
+    {
+        Ok(Some(stream)) => {
+            record_access(&state, &container, &key, None).await;
+            artifact_response(stream)
+        }
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    head,
+    description = "Turborepo: check whether an artifact exists",
+    path = "/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/{hash}",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+        ("hash" = String, Path, description = "Artifact hash"),
+    ),
+    responses(
+        (status = OK, description = "Exists"),
+        (status = NOT_FOUND, description = "Not found"),
+        (status = BAD_REQUEST, description = "Invalid hash"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key))]
+pub async fn turbo_head_artifact(
+    path: TurboArtifactPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["read:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    if !is_valid_hash(&path.hash) {
+        return invalid_hash_response(&path.hash);
+    }
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let key = turbo_key(&path.hash);
+
+    match state
+        .storage_backend
+        .exists(Some(container.as_ref()), &key)
+        .await
+    {
+        Ok(true) => {
+            record_access(&state, &container, &key, None).await;
+            StatusCode::OK.into_response()
+        }
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    description = "Turborepo: upload an artifact",
+    path = "/turbo/{tenant}/{org}/{ws}/{env}/v8/artifacts/{hash}",
+    params(
+        ("tenant" = String, Path, description = "Tenant code"),
+        ("org" = String, Path, description = "Organization code"),
+        ("ws" = String, Path, description = "Workspace code"),
+        ("env" = String, Path, description = "Environment code"),
+        ("hash" = String, Path, description = "Artifact hash"),
+    ),
+    request_body(content_type = "application/octet-stream", content = Vec<u8>),
+    responses(
+        (status = ACCEPTED, description = "Success"),
+        (status = BAD_REQUEST, description = "Invalid hash"),
+        (status = FORBIDDEN, description = "Forbidden"),
+        (status = INTERNAL_SERVER_ERROR, description = "Internal server error")
+    )
+)]
+#[tracing::instrument(skip(state, api_key, body))]
+pub async fn turbo_put_artifact(
+    path: TurboArtifactPath,
+    State(state): State<ServiceState>,
+    CompatApiKey(api_key): CompatApiKey,
+    body: Body,
+) -> Response {
+    guard!(
+        state.provider,
+        &api_key,
+        &path.tenant,
+        &path,
+        &["write:artifacts"],
+    );
+
+    validate_ownership!(state.provider, &path.tenant, &path);
+
+    if !is_valid_hash(&path.hash) {
+        return invalid_hash_response(&path.hash);
+    }
+
+    let container = container(&path.tenant, &path.org, &path.ws, &path.env);
+    let key = turbo_key(&path.hash);
+
+    match save_body(&state, &container, &key, body).await {
+        Ok(_) => {
+            record_access(&state, &container, &key, None).await;
+            (StatusCode::ACCEPTED, Json(json!({ "urls": [path.hash] })))
+                .into_response()
+        }
+        Err(e) => InternalServerError(e).into_response(),
+    }
+}
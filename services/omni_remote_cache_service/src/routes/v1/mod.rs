@@ -1,2 +1,4 @@
+pub mod admin;
 pub mod artifacts;
+pub mod compat;
 pub mod root;
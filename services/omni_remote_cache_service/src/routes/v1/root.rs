@@ -3,10 +3,17 @@ use utoipa::OpenApi;
 
 use crate::{build, state::ServiceState};
 
-use super::artifacts::{self, ArtifactsApiDoc};
+use super::{
+    admin::{self, AdminApiDoc},
+    artifacts::{self, ArtifactsApiDoc},
+    compat::{self, CompatApiDoc},
+};
 
 pub fn build_router() -> Router<ServiceState> {
-    Router::new().nest("/artifacts", artifacts::build_router())
+    Router::new()
+        .nest("/artifacts", artifacts::build_router())
+        .nest("/admin", admin::build_router())
+        .nest("/compat", compat::build_router())
 }
 
 #[derive(OpenApi)]
@@ -18,6 +25,8 @@ pub fn build_router() -> Router<ServiceState> {
     ),
     nest(
         (path = "/artifacts", api = ArtifactsApiDoc),
+        (path = "/admin", api = AdminApiDoc),
+        (path = "/compat", api = CompatApiDoc),
     ),
 )]
 pub struct V1RootApiDoc;
@@ -0,0 +1,100 @@
+//! Access checks against the configuration of a single API key, shared by
+//! the security service implementations.
+
+use std::str::FromStr as _;
+
+use time::OffsetDateTime;
+
+use crate::{
+    config::{
+        AllOrSpecificConfiguration, ApiKeyConfiguration, ScopesConfiguration,
+    },
+    security::SecurityServiceError,
+};
+
+pub(crate) fn is_valid(config: &ApiKeyConfiguration) -> bool {
+    config.enabled
+        && config
+            .expires_at
+            .is_none_or(|expires_at| expires_at > OffsetDateTime::now_utc())
+}
+
+fn allows(items: &AllOrSpecificConfiguration, code: &str) -> bool {
+    match items {
+        AllOrSpecificConfiguration::All(_) => true,
+        AllOrSpecificConfiguration::Specific(items) => {
+            items.contains(&code.to_string())
+        }
+    }
+}
+
+pub(crate) fn can_access_tenant(
+    config: &ApiKeyConfiguration,
+    tenant_code: &str,
+) -> bool {
+    is_valid(config) && allows(&config.tenants, tenant_code)
+}
+
+pub(crate) fn can_access_organization(
+    config: &ApiKeyConfiguration,
+    tenant_code: &str,
+    organization_code: &str,
+) -> bool {
+    can_access_tenant(config, tenant_code)
+        && allows(&config.organizations, organization_code)
+}
+
+pub(crate) fn can_access_workspace(
+    config: &ApiKeyConfiguration,
+    tenant_code: &str,
+    organization_code: &str,
+    workspace_code: &str,
+) -> bool {
+    can_access_organization(config, tenant_code, organization_code)
+        && allows(&config.workspaces, workspace_code)
+}
+
+pub(crate) fn can_access_environment(
+    config: &ApiKeyConfiguration,
+    tenant_code: &str,
+    organization_code: &str,
+    workspace_code: &str,
+    environment_code: &str,
+) -> bool {
+    can_access_workspace(config, tenant_code, organization_code, workspace_code)
+        && allows(&config.environments, environment_code)
+}
+
+pub(crate) fn can_access(
+    config: &ApiKeyConfiguration,
+    tenant_code: &str,
+    organization_code: &str,
+    workspace_code: &str,
+    environment_code: &str,
+    required_scopes: &[&str],
+) -> Result<bool, SecurityServiceError> {
+    if !can_access_environment(
+        config,
+        tenant_code,
+        organization_code,
+        workspace_code,
+        environment_code,
+    ) {
+        return Ok(false);
+    }
+
+    Ok(match &config.scopes {
+        AllOrSpecificConfiguration::All(_) => true,
+        AllOrSpecificConfiguration::Specific(items) => {
+            for scope in required_scopes {
+                let scope = ScopesConfiguration::from_str(scope)
+                    .map_err(SecurityServiceError::custom)?;
+                if !items.contains(&scope) {
+                    return Ok(false);
+                }
+            }
+
+            true
+        }
+    })
+}
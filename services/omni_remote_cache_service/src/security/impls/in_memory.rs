@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use derive_new::new;
+use maps::UnorderedMap;
+
+use crate::{
+    config::ApiKeyConfiguration,
+    security::{SecurityService, SecurityServiceError, impls::access},
+};
+
+#[derive(Clone, new, PartialEq, Eq)]
+pub struct InMemorySecurityService {
+    api_keys: Arc<UnorderedMap<String, ApiKeyConfiguration>>,
+}
+
+#[async_trait::async_trait]
+impl SecurityService for InMemorySecurityService {
+    async fn is_valid(
+        &self,
+        api_key: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_keys.get(api_key).is_some_and(access::is_valid))
+    }
+
+    async fn can_access_tenant(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_keys.get(api_key).is_some_and(|config| {
+            access::can_access_tenant(config, tenant_code)
+        }))
+    }
+
+    async fn can_access_organization(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_keys.get(api_key).is_some_and(|config| {
+            access::can_access_organization(
+                config,
+                tenant_code,
+                organization_code,
+            )
+        }))
+    }
+
+    async fn can_access_workspace(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_keys.get(api_key).is_some_and(|config| {
+            access::can_access_workspace(
+                config,
+                tenant_code,
+                organization_code,
+                workspace_code,
+            )
+        }))
+    }
+
+    async fn can_access_environment(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+        environment_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_keys.get(api_key).is_some_and(|config| {
+            access::can_access_environment(
+                config,
+                tenant_code,
+                organization_code,
+                workspace_code,
+                environment_code,
+            )
+        }))
+    }
+
+    async fn can_access(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+        environment_code: &str,
+        required_scopes: &[&str],
+    ) -> Result<bool, SecurityServiceError> {
+        match self.api_keys.get(api_key) {
+            Some(config) => access::can_access(
+                config,
+                tenant_code,
+                organization_code,
+                workspace_code,
+                environment_code,
+                required_scopes,
+            ),
+            None => Ok(false),
+        }
+    }
+}
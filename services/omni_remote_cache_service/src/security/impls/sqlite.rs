@@ -0,0 +1,122 @@
+use derive_new::new;
+
+use crate::{
+    config::ApiKeyConfiguration,
+    data_impl::sqlite::SqliteDatabase,
+    security::{SecurityService, SecurityServiceError, impls::access},
+};
+
+/// Reads API keys from the database on every check, so keys that are added,
+/// disabled or removed take effect without a restart.
+#[derive(Clone, new)]
+pub struct SqliteSecurityService {
+    db: SqliteDatabase,
+}
+
+impl SqliteSecurityService {
+    async fn api_key(
+        &self,
+        api_key: &str,
+    ) -> Result<Option<ApiKeyConfiguration>, SecurityServiceError> {
+        self.db
+            .find_api_key(api_key)
+            .await
+            .map_err(SecurityServiceError::custom)
+    }
+}
+
+#[async_trait::async_trait]
+impl SecurityService for SqliteSecurityService {
+    async fn is_valid(
+        &self,
+        api_key: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self
+            .api_key(api_key)
+            .await?
+            .is_some_and(|config| access::is_valid(&config)))
+    }
+
+    async fn can_access_tenant(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_key(api_key).await?.is_some_and(|config| {
+            access::can_access_tenant(&config, tenant_code)
+        }))
+    }
+
+    async fn can_access_organization(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_key(api_key).await?.is_some_and(|config| {
+            access::can_access_organization(
+                &config,
+                tenant_code,
+                organization_code,
+            )
+        }))
+    }
+
+    async fn can_access_workspace(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_key(api_key).await?.is_some_and(|config| {
+            access::can_access_workspace(
+                &config,
+                tenant_code,
+                organization_code,
+                workspace_code,
+            )
+        }))
+    }
+
+    async fn can_access_environment(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+        environment_code: &str,
+    ) -> Result<bool, SecurityServiceError> {
+        Ok(self.api_key(api_key).await?.is_some_and(|config| {
+            access::can_access_environment(
+                &config,
+                tenant_code,
+                organization_code,
+                workspace_code,
+                environment_code,
+            )
+        }))
+    }
+
+    async fn can_access(
+        &self,
+        api_key: &str,
+        tenant_code: &str,
+        organization_code: &str,
+        workspace_code: &str,
+        environment_code: &str,
+        required_scopes: &[&str],
+    ) -> Result<bool, SecurityServiceError> {
+        match self.api_key(api_key).await? {
+            Some(config) => access::can_access(
+                &config,
+                tenant_code,
+                organization_code,
+                workspace_code,
+                environment_code,
+                required_scopes,
+            ),
+            None => Ok(false),
+        }
+    }
+}
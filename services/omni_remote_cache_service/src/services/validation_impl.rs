@@ -73,7 +73,11 @@ impl ValidationService for DefaultValidationService {
 
         if !self
             .workspace_repository
-            .belongs_to_organization(organization_code, workspace_code)
+            .belongs_to_organization(
+                tenant_code,
+                organization_code,
+                workspace_code,
+            )
             .await?
         {
             violations.push(Violation::OrganizationDoesNotHaveWorkspace);
@@ -81,7 +85,12 @@ impl ValidationService for DefaultValidationService {
 
         if !self
             .environment_repository
-            .belongs_to_workspace(workspace_code, environment_code)
+            .belongs_to_workspace(
+                tenant_code,
+                organization_code,
+                workspace_code,
+                environment_code,
+            )
             .await?
         {
             violations.push(Violation::WorkspaceDoesNotHaveEnvironment);
@@ -1,11 +1,19 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use derive_new::new;
 
 use crate::{
     args::{ConfigType, ServeArgs},
     config::Configuration,
-    providers::{ConfigBasedDependencyProvider, DependencyProvider},
+    data_impl::sqlite::SqliteDatabase,
+    providers::{
+        ConfigBasedDependencyProvider, DependencyProvider,
+        SqliteDependencyProvider,
+    },
+    retention::{
+        DynAccessTracker, GarbageCollector, InMemoryAccessTracker,
+        SqliteAccessTracker,
+    },
     storage_backend::StorageBackend,
 };
 
@@ -14,6 +22,10 @@ pub struct ServiceState {
     pub storage_backend: Arc<StorageBackend>,
     pub args: Arc<ServeArgs>,
     pub provider: Arc<dyn DependencyProvider>,
+    /// Only set when the service runs on a SQLite database.
+    pub database: Option<SqliteDatabase>,
+    pub access_tracker: DynAccessTracker,
+    pub garbage_collector: GarbageCollector,
 }
 
 impl ServiceState {
@@ -21,20 +33,66 @@ impl ServiceState {
         let config = args.config.as_deref().unwrap_or("orcs.config.json");
         let cfg_type = args.config_type.unwrap_or(ConfigType::File);
 
-        let config = match cfg_type {
-            ConfigType::Inline => Configuration::from_inline(config)?,
-            ConfigType::File => Configuration::from_file(config)?,
+        let mut database = None;
+        let provider: Arc<dyn DependencyProvider>;
+        let access_tracker: DynAccessTracker;
+        let config = match &args.database_url {
+            Some(url) => {
+                let db = SqliteDatabase::connect(url).await?;
+
+                // The configuration only seeds a fresh database; afterwards
+                // the database is the source of truth. A missing
+                // configuration file is fine in that case.
+                let config = match cfg_type {
+                    ConfigType::Inline => {
+                        Some(Configuration::from_inline(config)?)
+                    }
+                    ConfigType::File if Path::new(config).exists() => {
+                        Some(Configuration::from_file(config)?)
+                    }
+                    ConfigType::File => None,
+                };
+
+                if let Some(config) = &config {
+                    db.seed(config).await?;
+                }
+
+                database = Some(db.clone());
+                provider = Arc::new(SqliteDependencyProvider::new(db.clone()));
+                access_tracker = Arc::new(SqliteAccessTracker::new(db));
+
+                config.unwrap_or_default()
+            }
+            None => {
+                let config = match cfg_type {
+                    ConfigType::Inline => Configuration::from_inline(config)?,
+                    ConfigType::File => Configuration::from_file(config)?,
+                };
+
+                provider = Arc::new(ConfigBasedDependencyProvider::new(
+                    Arc::new(config.to_in_memory_database()),
+                    Arc::new(config.security.api_keys.clone()),
+                ));
+                access_tracker = Arc::new(InMemoryAccessTracker::default());
+
+                config
+            }
         };
 
+        let storage_backend =
+            Arc::new(StorageBackend::from_cli_args(args).await);
+
         Ok(Self {
-            storage_backend: Arc::new(
-                StorageBackend::from_cli_args(args).await,
+            garbage_collector: GarbageCollector::new(
+                storage_backend.clone(),
+                access_tracker.clone(),
+                Arc::new(config.retention),
             ),
+            storage_backend,
             args: Arc::new(args.clone()),
-            provider: Arc::new(ConfigBasedDependencyProvider::new(
-                Arc::new(config.to_in_memory_database()),
-                Arc::new(config.security.api_keys.clone()),
-            )),
+            provider,
+            database,
+            access_tracker,
         })
     }
 }
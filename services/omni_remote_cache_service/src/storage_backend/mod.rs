@@ -178,6 +178,27 @@ impl RemoteCacheStorageBackend for StorageBackend {
         }
     }
 
+    async fn list_containers(
+        &self,
+        prefix: &str,
+    ) -> Result<Vec<String>, error::Error> {
+        match self {
+            StorageBackend::LruCachedLocalDisk(inner) => {
+                inner.list_containers(prefix).await
+            }
+            StorageBackend::LocalDisk(inner) => {
+                inner.list_containers(prefix).await
+            }
+            StorageBackend::LruCachedS3(inner) => {
+                inner.list_containers(prefix).await
+            }
+            StorageBackend::S3(inner) => inner.list_containers(prefix).await,
+            StorageBackend::InMemory(inner) => {
+                inner.list_containers(prefix).await
+            }
+        }
+    }
+
     async fn paged_list(
         &self,
         container: Option<&str>,
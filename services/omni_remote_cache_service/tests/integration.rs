@@ -7,14 +7,24 @@ use omni_remote_cache_service::{
     config::{
         All, AllOrSpecificConfiguration, ApiKeyConfiguration, Configuration,
         EnvironmentConfiguration, OrganizationConfiguration,
-        ScopesConfiguration, SecurityConfiguration, TenantConfiguration,
-        WorkspaceConfiguration,
+        RetentionRuleConfiguration, ScopesConfiguration, SecurityConfiguration,
+        TenantConfiguration, WorkspaceConfiguration,
     },
+    data_impl::sqlite::{SqliteDatabase, api_key_digest},
     response::data::Data,
-    routes::{root::RouterConfig, v1::artifacts::CacheItem},
+    retention::{EvictionReason, GcReport},
+    routes::{
+        root::RouterConfig,
+        v1::{
+            admin::{CreatedApiKey, EntityItem},
+            artifacts::CacheItem,
+        },
+    },
     state::ServiceState,
 };
+use omni_remote_cache_storage::RemoteCacheStorageBackend as _;
 use sets::{UnorderedSet, unordered_set};
+use tempfile::TempDir;
 
 fn default_config() -> Configuration {
     Configuration {
@@ -56,6 +66,7 @@ fn default_config() -> Configuration {
                 }
             ),
         },
+        retention: Vec::new(),
     }
 }
 
@@ -82,6 +93,21 @@ fn default_body() -> Bytes {
 }
 
 async fn create_server(cfg: &Configuration) -> TestServer {
+    create_server_with_database(cfg, None).await
+}
+
+async fn create_server_with_database(
+    cfg: &Configuration,
+    database_url: Option<String>,
+) -> TestServer {
+    create_server_with_admin(cfg, database_url, None).await
+}
+
+async fn create_server_with_admin(
+    cfg: &Configuration,
+    database_url: Option<String>,
+    admin_api_key: Option<String>,
+) -> TestServer {
     let json_config = serde_json::to_string(cfg)
         .expect("should be able to serialize to json");
 
@@ -91,6 +117,9 @@ async fn create_server(cfg: &Configuration) -> TestServer {
         "".to_string(), // since test server doesn't actually listen, just use an empty string
         Some(json_config),
         Some(ConfigType::Inline),
+        database_url,
+        admin_api_key,
+        None,
         false,
         None,
         None,
@@ -675,3 +704,1117 @@ async fn test_request_with_api_key_no_access_to_env() {
 
     get_resp.assert_status_forbidden();
 }
+
+const OTHER_TENANT: &str = "other-tenant";
+const OTHER_WORKSPACE: &str = "other-workspace";
+const OTHER_ENV: &str = "other-env";
+
+/// Two tenants that both have the default organization, workspace and
+/// environment codes. Only the other tenant has the other workspace and the
+/// other environment.
+fn two_tenant_config() -> Configuration {
+    let mut cfg = default_config();
+
+    let mut other = cfg.tenants[DEFAULT_TENANT].clone();
+    let workspaces = &mut other
+        .organizations
+        .get_mut(DEFAULT_ORG)
+        .expect("should have default org")
+        .workspaces;
+    let environments = workspaces[DEFAULT_WORKSPACE].environments.clone();
+    workspaces
+        .get_mut(DEFAULT_WORKSPACE)
+        .expect("should have default workspace")
+        .environments
+        .insert(
+            OTHER_ENV.to_string(),
+            EnvironmentConfiguration {
+                description: None,
+                display_name: None,
+            },
+        );
+    workspaces.insert(
+        OTHER_WORKSPACE.to_string(),
+        WorkspaceConfiguration {
+            description: None,
+            display_name: None,
+            environments,
+        },
+    );
+
+    cfg.tenants.insert(OTHER_TENANT.to_string(), other);
+
+    cfg
+}
+
+async fn assert_tenants_do_not_share_artifacts(server: &TestServer) {
+    let other_body = Bytes::from_static(b"other tenant artifact");
+
+    put_artifact(
+        server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await
+    .assert_status(StatusCode::NO_CONTENT);
+
+    get_artifact(
+        server,
+        OTHER_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await
+    .assert_status_not_found();
+
+    put_artifact(
+        server,
+        OTHER_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        other_body.clone(),
+    )
+    .await
+    .assert_status(StatusCode::NO_CONTENT);
+
+    for (tenant, body) in
+        [(DEFAULT_TENANT, default_body()), (OTHER_TENANT, other_body)]
+    {
+        let get_resp = get_artifact(
+            server,
+            tenant,
+            DEFAULT_API_KEY,
+            DEFAULT_ORG,
+            DEFAULT_WORKSPACE,
+            DEFAULT_ENV,
+            DEFAULT_DIGEST,
+        )
+        .await;
+
+        get_resp.assert_status_ok();
+        assert_eq!(get_resp.as_bytes(), &body);
+    }
+}
+
+async fn assert_ownership_is_checked_within_tenant(server: &TestServer) {
+    // the workspace only exists in the other tenant's organization
+    let other_ws_resp = get_artifact(
+        server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        OTHER_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await;
+
+    // the environment only exists in the other tenant's workspace
+    let other_env_resp = get_artifact(
+        server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        OTHER_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await;
+
+    other_ws_resp.assert_status_bad_request();
+    other_env_resp.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_tenants_do_not_share_artifacts() {
+    let server = create_server(&two_tenant_config()).await;
+
+    assert_tenants_do_not_share_artifacts(&server).await;
+    assert_ownership_is_checked_within_tenant(&server).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_database_tenants_do_not_share_artifacts() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server = create_server_with_database(
+        &two_tenant_config(),
+        Some(sqlite_url(&dir)),
+    )
+    .await;
+
+    assert_tenants_do_not_share_artifacts(&server).await;
+    assert_ownership_is_checked_within_tenant(&server).await;
+}
+
+fn get_chunk_path(index: Option<u32>, query: &str) -> String {
+    let base = format!(
+        "/api/v1/artifacts/{DEFAULT_DIGEST}/chunks{}",
+        index.map(|i| format!("/{i}")).unwrap_or_default()
+    );
+    format!(
+        "{base}?org={DEFAULT_ORG}&ws={DEFAULT_WORKSPACE}&env={DEFAULT_ENV}{query}"
+    )
+}
+
+async fn put_artifact_chunk(
+    server: &TestServer,
+    index: u32,
+    body: Bytes,
+) -> TestResponse {
+    server
+        .put(&get_chunk_path(Some(index), ""))
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .add_header("Content-Type", "application/octet-stream")
+        .add_header("X-OMNI-TENANT", DEFAULT_TENANT)
+        .bytes(body)
+        .await
+}
+
+async fn complete_artifact_chunks(
+    server: &TestServer,
+    count: u32,
+) -> TestResponse {
+    server
+        .post(&get_chunk_path(None, &format!("&count={count}")))
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .add_header("X-OMNI-TENANT", DEFAULT_TENANT)
+        .await
+}
+
+#[tokio::test]
+async fn test_chunked_upload() {
+    let cfg = default_config();
+    let server = create_server(&cfg).await;
+    let body = default_body();
+    let (first, second) = body.split_at(body.len() / 2);
+
+    put_artifact_chunk(&server, 1, Bytes::copy_from_slice(second))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+    put_artifact_chunk(&server, 0, Bytes::copy_from_slice(first))
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let head_resp = server
+        .method(http::Method::HEAD, &get_chunk_path(Some(0), ""))
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .add_header("X-OMNI-TENANT", DEFAULT_TENANT)
+        .await;
+    head_resp.assert_status(StatusCode::NO_CONTENT);
+    head_resp.assert_header("X-OMNI-CHUNK-SIZE", first.len().to_string());
+
+    complete_artifact_chunks(&server, 2)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let get_resp = get_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await;
+
+    get_resp.assert_status_ok();
+    assert_eq!(
+        *get_resp.as_bytes(),
+        body,
+        "chunks should be assembled in order"
+    );
+}
+
+#[tokio::test]
+async fn test_chunked_upload_with_missing_chunk() {
+    let cfg = default_config();
+    let server = create_server(&cfg).await;
+
+    put_artifact_chunk(&server, 0, default_body()).await;
+
+    complete_artifact_chunks(&server, 2)
+        .await
+        .assert_status_bad_request();
+
+    let get_resp = get_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await;
+
+    get_resp.assert_status_not_found();
+}
+
+fn sqlite_url(dir: &TempDir) -> String {
+    format!("sqlite://{}", dir.path().join("orcs.db").display())
+}
+
+#[tokio::test]
+async fn test_sqlite_database_is_seeded_from_configuration() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server =
+        create_server_with_database(&default_config(), Some(sqlite_url(&dir)))
+            .await;
+
+    let put_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    let invalid_env_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        "invalid-env",
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    put_resp.assert_status(StatusCode::NO_CONTENT);
+    invalid_env_resp.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_sqlite_database_keeps_state_across_restarts() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+
+    drop(
+        create_server_with_database(&default_config(), Some(sqlite_url(&dir)))
+            .await,
+    );
+
+    // The database is no longer empty, so the (empty) configuration is not
+    // imported again and the seeded state is kept.
+    let server = create_server_with_database(
+        &Configuration::default(),
+        Some(sqlite_url(&dir)),
+    )
+    .await;
+
+    let put_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    put_resp.assert_status(StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_sqlite_database_is_only_seeded_once() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+
+    drop(
+        create_server_with_database(
+            &Configuration::default(),
+            Some(sqlite_url(&dir)),
+        )
+        .await,
+    );
+
+    // The database is still empty, but it was seeded already, so the
+    // configuration is not imported anymore.
+    let server =
+        create_server_with_database(&default_config(), Some(sqlite_url(&dir)))
+            .await;
+
+    let put_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    put_resp.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn test_sqlite_database_only_stores_api_key_digests() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    drop(
+        create_server_with_database(&default_config(), Some(sqlite_url(&dir)))
+            .await,
+    );
+
+    let db = SqliteDatabase::connect(&sqlite_url(&dir))
+        .await
+        .expect("should be able to connect to the database");
+    let stored =
+        sqlx::query_scalar::<_, String>("SELECT key_digest FROM api_keys")
+            .fetch_all(db.pool())
+            .await
+            .expect("should be able to list api keys");
+
+    assert_eq!(stored, [api_key_digest(DEFAULT_API_KEY)]);
+    assert!(
+        db.find_api_key(DEFAULT_API_KEY)
+            .await
+            .expect("should be able to find the api key")
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_sqlite_database_revoked_api_key_without_restart() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server =
+        create_server_with_database(&default_config(), Some(sqlite_url(&dir)))
+            .await;
+
+    let before_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    let db = SqliteDatabase::connect(&sqlite_url(&dir))
+        .await
+        .expect("should be able to connect to the database");
+    sqlx::query("UPDATE api_keys SET enabled = 0 WHERE key_digest = ?")
+        .bind(api_key_digest(DEFAULT_API_KEY))
+        .execute(db.pool())
+        .await
+        .expect("should be able to revoke the api key");
+
+    let after_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    before_resp.assert_status(StatusCode::NO_CONTENT);
+    after_resp.assert_status_forbidden();
+}
+
+const ADMIN_API_KEY: &str = "test-admin-api-key";
+
+async fn create_admin_server(dir: &TempDir) -> TestServer {
+    create_server_with_admin(
+        &default_config(),
+        Some(sqlite_url(dir)),
+        Some(ADMIN_API_KEY.to_string()),
+    )
+    .await
+}
+
+async fn admin_post(
+    server: &TestServer,
+    api_key: &str,
+    path: &str,
+    body: serde_json::Value,
+) -> TestResponse {
+    server
+        .post(&format!("/api/v1/admin{path}"))
+        .add_header("X-API-KEY", api_key)
+        .json(&body)
+        .await
+}
+
+#[tokio::test]
+async fn test_admin_api_disabled_without_admin_api_key() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server =
+        create_server_with_database(&default_config(), Some(sqlite_url(&dir)))
+            .await;
+
+    let resp = server
+        .get("/api/v1/admin/tenants")
+        .add_header("X-API-KEY", ADMIN_API_KEY)
+        .await;
+
+    resp.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_admin_api_rejects_non_admin_api_key() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server = create_admin_server(&dir).await;
+
+    let resp = server
+        .get("/api/v1/admin/tenants")
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .await;
+
+    resp.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn test_admin_api_lists_seeded_tenants() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server = create_admin_server(&dir).await;
+
+    let resp = server
+        .get("/api/v1/admin/tenants")
+        .add_header("X-API-KEY", ADMIN_API_KEY)
+        .await;
+
+    resp.assert_status_ok();
+    let tenants = resp.json::<Data<Vec<EntityItem>>>().data;
+    assert_eq!(tenants.len(), 1);
+    assert_eq!(tenants[0].code, DEFAULT_TENANT);
+}
+
+#[tokio::test]
+async fn test_admin_api_create_duplicate_tenant() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server = create_admin_server(&dir).await;
+
+    let resp = admin_post(
+        &server,
+        ADMIN_API_KEY,
+        "/tenants",
+        serde_json::json!({ "code": DEFAULT_TENANT }),
+    )
+    .await;
+
+    resp.assert_status(StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_admin_api_create_organization_in_missing_tenant() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server = create_admin_server(&dir).await;
+
+    let resp = admin_post(
+        &server,
+        ADMIN_API_KEY,
+        "/tenants/missing-tenant/organizations",
+        serde_json::json!({ "code": "org" }),
+    )
+    .await;
+
+    resp.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_admin_api_mint_and_revoke_api_key() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let server = create_admin_server(&dir).await;
+
+    for (path, code) in [
+        ("/tenants", "tenant"),
+        ("/tenants/tenant/organizations", "org"),
+        ("/tenants/tenant/organizations/org/workspaces", "ws"),
+        (
+            "/tenants/tenant/organizations/org/workspaces/ws/environments",
+            "env",
+        ),
+    ] {
+        admin_post(
+            &server,
+            ADMIN_API_KEY,
+            path,
+            serde_json::json!({ "code": code }),
+        )
+        .await
+        .assert_status(StatusCode::CREATED);
+    }
+
+    let mint_resp = admin_post(
+        &server,
+        ADMIN_API_KEY,
+        "/api-keys",
+        serde_json::json!({
+            "scopes": ["write:artifacts"],
+            "tenants": ["tenant"],
+        }),
+    )
+    .await;
+    mint_resp.assert_status(StatusCode::CREATED);
+    let CreatedApiKey { id, key } =
+        mint_resp.json::<Data<CreatedApiKey>>().data;
+
+    let put_resp = put_artifact(
+        &server,
+        "tenant",
+        &key,
+        "org",
+        "ws",
+        "env",
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+    let other_tenant_resp = put_artifact(
+        &server,
+        DEFAULT_TENANT,
+        &key,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    admin_post(
+        &server,
+        ADMIN_API_KEY,
+        &format!("/api-keys/{id}/revoke"),
+        serde_json::json!({}),
+    )
+    .await
+    .assert_status(StatusCode::NO_CONTENT);
+
+    let revoked_resp = put_artifact(
+        &server,
+        "tenant",
+        &key,
+        "org",
+        "ws",
+        "env",
+        DEFAULT_DIGEST,
+        default_body(),
+    )
+    .await;
+
+    put_resp.assert_status(StatusCode::NO_CONTENT);
+    other_tenant_resp.assert_status_forbidden();
+    revoked_resp.assert_status_forbidden();
+}
+
+fn retention_config(rule: RetentionRuleConfiguration) -> Configuration {
+    let mut cfg = default_config();
+    cfg.retention.push(rule);
+    cfg
+}
+
+fn retention_rule() -> RetentionRuleConfiguration {
+    RetentionRuleConfiguration::new(
+        DEFAULT_TENANT.to_string(),
+        DEFAULT_ORG.to_string(),
+        DEFAULT_WORKSPACE.to_string(),
+        AllOrSpecificConfiguration::default(),
+        None,
+        None,
+        None,
+    )
+}
+
+const OTHER_DIGEST: &str =
+    "0c3f2a9b7d8e4c1a5b6f7e8d9c0b1a2f3e4d5c6b7a8f9e0d1c2b3a4f";
+
+async fn put_task_artifact(
+    server: &TestServer,
+    digest: &str,
+    task: &str,
+) -> TestResponse {
+    let resp = server
+        .put(&get_path(
+            DEFAULT_ORG,
+            DEFAULT_WORKSPACE,
+            DEFAULT_ENV,
+            Some(digest),
+        ))
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .add_header("Content-Type", "application/octet-stream")
+        .add_header("X-OMNI-TENANT", DEFAULT_TENANT)
+        .add_header("X-OMNI-TASK", task)
+        .bytes(default_body())
+        .await;
+
+    // Keep the access times of consecutive uploads apart.
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    resp
+}
+
+async fn get_gc_report(server: &TestServer) -> GcReport {
+    let resp = server
+        .get("/api/v1/admin/gc/report")
+        .add_header("X-API-KEY", ADMIN_API_KEY)
+        .await;
+
+    resp.assert_status_ok();
+    resp.json::<Data<GcReport>>().data
+}
+
+#[tokio::test]
+async fn test_gc_report_requires_admin_api_key() {
+    let server = create_server_with_admin(
+        &retention_config(retention_rule()),
+        None,
+        Some(ADMIN_API_KEY.to_string()),
+    )
+    .await;
+
+    let resp = server
+        .get("/api/v1/admin/gc/report")
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .await;
+
+    resp.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn test_gc_report_evicts_least_recently_accessed_over_max_size() {
+    let mut rule = retention_rule();
+    rule.max_size = Some(bytesize::ByteSize::b(default_body().len() as u64));
+    let server = create_server_with_admin(
+        &retention_config(rule),
+        None,
+        Some(ADMIN_API_KEY.to_string()),
+    )
+    .await;
+
+    put_task_artifact(&server, DEFAULT_DIGEST, "project#a").await;
+    put_task_artifact(&server, OTHER_DIGEST, "project#b").await;
+
+    // Reading the older artifact makes it the most recently accessed one.
+    let get_resp = get_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await;
+    get_resp.assert_status_ok();
+
+    let report = get_gc_report(&server).await;
+
+    assert!(report.dry_run);
+    assert_eq!(report.containers.len(), 1);
+    let container = &report.containers[0];
+    assert_eq!(container.kept_count, 1);
+    assert_eq!(container.evictions.len(), 1);
+    assert_eq!(container.evictions[0].key, OTHER_DIGEST);
+    assert_eq!(container.evictions[0].reason, EvictionReason::MaxSize);
+
+    // A dry run evicts nothing.
+    let other_resp = get_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        OTHER_DIGEST,
+    )
+    .await;
+    other_resp.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_gc_report_evicts_over_max_entries_per_task() {
+    let mut rule = retention_rule();
+    rule.max_entries_per_task = Some(1);
+    let server = create_server_with_admin(
+        &retention_config(rule),
+        None,
+        Some(ADMIN_API_KEY.to_string()),
+    )
+    .await;
+
+    put_task_artifact(&server, DEFAULT_DIGEST, "project#build").await;
+    put_task_artifact(&server, OTHER_DIGEST, "project#build").await;
+
+    let report = get_gc_report(&server).await;
+
+    let container = &report.containers[0];
+    assert_eq!(container.kept_count, 1);
+    assert_eq!(container.evictions.len(), 1);
+    assert_eq!(container.evictions[0].key, DEFAULT_DIGEST);
+    assert_eq!(
+        container.evictions[0].task.as_deref(),
+        Some("project#build")
+    );
+    assert_eq!(
+        container.evictions[0].reason,
+        EvictionReason::MaxEntriesPerTask
+    );
+}
+
+#[tokio::test]
+async fn test_gc_collect_evicts_expired_artifacts() {
+    let dir = tempfile::tempdir().expect("should be able to create tempdir");
+    let mut rule = retention_rule();
+    rule.max_age = Some(std::time::Duration::from_millis(200));
+    let serve_args = ServeArgs::new(
+        "".to_string(),
+        Some(
+            serde_json::to_string(&retention_config(rule))
+                .expect("should be able to serialize to json"),
+        ),
+        Some(ConfigType::Inline),
+        Some(sqlite_url(&dir)),
+        None,
+        None,
+        false,
+        None,
+        None,
+        Some(100),
+        BackendType::InMemory,
+        Some(RouterConfig::new(Some("/api".to_string()), true)),
+    );
+    let state = ServiceState::from_args(&serve_args)
+        .await
+        .expect("must be able to construct state");
+    let server = TestServer::new(
+        omni_remote_cache_service::routes::root::build_router(
+            serve_args.routes.as_ref().unwrap(),
+        )
+        .with_state(state.clone()),
+    );
+
+    put_task_artifact(&server, DEFAULT_DIGEST, "project#build").await;
+    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    put_task_artifact(&server, OTHER_DIGEST, "project#build").await;
+
+    let report = state
+        .garbage_collector
+        .collect()
+        .await
+        .expect("should be able to collect");
+
+    let expired_resp = get_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        DEFAULT_DIGEST,
+    )
+    .await;
+    let fresh_resp = get_artifact(
+        &server,
+        DEFAULT_TENANT,
+        DEFAULT_API_KEY,
+        DEFAULT_ORG,
+        DEFAULT_WORKSPACE,
+        DEFAULT_ENV,
+        OTHER_DIGEST,
+    )
+    .await;
+
+    assert!(!report.dry_run);
+    assert_eq!(report.containers[0].evictions.len(), 1);
+    assert_eq!(
+        report.containers[0].evictions[0].reason,
+        EvictionReason::MaxAge
+    );
+    expired_resp.assert_status_not_found();
+    fresh_resp.assert_status_ok();
+}
+
+fn compat_path(tool: &str, rest: &str) -> String {
+    tenant_compat_path(tool, DEFAULT_TENANT, rest)
+}
+
+fn tenant_compat_path(tool: &str, tenant: &str, rest: &str) -> String {
+    format!(
+        "/api/v1/compat/{tool}/{tenant}/{DEFAULT_ORG}/{DEFAULT_WORKSPACE}/{DEFAULT_ENV}/{rest}"
+    )
+}
+
+fn basic_auth(user: &str, password: &str) -> String {
+    use base64::Engine as _;
+
+    let credentials = base64::engine::general_purpose::STANDARD
+        .encode(format!("{user}:{password}"));
+    format!("Basic {credentials}")
+}
+
+#[tokio::test]
+async fn test_turbo_put_and_get_artifact() {
+    let cfg = default_config();
+    let server = create_server(&cfg).await;
+    let body = default_body();
+    let bearer = format!("Bearer {DEFAULT_API_KEY}");
+
+    let status_resp = server
+        .get(&compat_path("turbo", "v8/artifacts/status"))
+        .add_header("Authorization", &bearer)
+        .await;
+    status_resp.assert_status_ok();
+    status_resp.assert_json(&serde_json::json!({ "status": "enabled" }));
+
+    let put_resp = server
+        .put(&compat_path("turbo", "v8/artifacts/abc123?teamId=team"))
+        .add_header("Authorization", &bearer)
+        .add_header("Content-Type", "application/octet-stream")
+        .bytes(body.clone())
+        .await;
+    put_resp.assert_status(StatusCode::ACCEPTED);
+
+    let get_resp = server
+        .get(&compat_path("turbo", "v8/artifacts/abc123?teamId=team"))
+        .add_header("Authorization", &bearer)
+        .await;
+    get_resp.assert_status_ok();
+    assert_eq!(*get_resp.as_bytes(), body);
+
+    let query_resp = server
+        .post(&compat_path("turbo", "v8/artifacts"))
+        .add_header("Authorization", &bearer)
+        .json(&serde_json::json!({ "hashes": ["abc123", "missing"] }))
+        .await;
+    query_resp.assert_status_ok();
+    let query = query_resp.json::<serde_json::Value>();
+    assert_eq!(query["abc123"]["size"], body.len() as u64);
+    assert!(query["missing"]["error"].is_object());
+
+    let missing_resp = server
+        .get(&compat_path("turbo", "v8/artifacts/missing"))
+        .add_header("Authorization", &bearer)
+        .await;
+    missing_resp.assert_status_not_found();
+}
+
+#[tokio::test]
+async fn test_turbo_requires_api_key() {
+    let cfg = default_config();
+    let server = create_server(&cfg).await;
+
+    let resp = server
+        .get(&compat_path("turbo", "v8/artifacts/status"))
+        .await;
+    resp.assert_status_unauthorized();
+
+    let resp = server
+        .get(&compat_path("turbo", "v8/artifacts/status"))
+        .add_header("Authorization", "Bearer invalid-api-key")
+        .await;
+    resp.assert_status_forbidden();
+}
+
+#[tokio::test]
+async fn test_turbo_tenants_do_not_share_artifacts() {
+    let server = create_server(&two_tenant_config()).await;
+    let bearer = format!("Bearer {DEFAULT_API_KEY}");
+    let artifact = "v8/artifacts/abc123?teamId=team";
+
+    let put_resp = server
+        .put(&tenant_compat_path("turbo", DEFAULT_TENANT, artifact))
+        .add_header("Authorization", &bearer)
+        .add_header("Content-Type", "application/octet-stream")
+        .bytes(default_body())
+        .await;
+    put_resp.assert_status(StatusCode::ACCEPTED);
+
+    let other_get_resp = server
+        .get(&tenant_compat_path("turbo", OTHER_TENANT, artifact))
+        .add_header("Authorization", &bearer)
+        .await;
+    other_get_resp.assert_status_not_found();
+
+    let other_head_resp = server
+        .method(
+            http::Method::HEAD,
+            &tenant_compat_path("turbo", OTHER_TENANT, artifact),
+        )
+        .add_header("Authorization", &bearer)
+        .await;
+    other_head_resp.assert_status_not_found();
+
+    let other_query_resp = server
+        .post(&tenant_compat_path("turbo", OTHER_TENANT, "v8/artifacts"))
+        .add_header("Authorization", &bearer)
+        .json(&serde_json::json!({ "hashes": ["abc123"] }))
+        .await;
+    other_query_resp.assert_status_ok();
+    let query = other_query_resp.json::<serde_json::Value>();
+    assert!(query["abc123"]["error"].is_object());
+
+    let get_resp = server
+        .get(&tenant_compat_path("turbo", DEFAULT_TENANT, artifact))
+        .add_header("Authorization", &bearer)
+        .await;
+    get_resp.assert_status_ok();
+    assert_eq!(*get_resp.as_bytes(), default_body());
+}
+
+#[tokio::test]
+async fn test_bazel_tenants_do_not_share_entries() {
+    let server = create_server(&two_tenant_config()).await;
+    let basic = basic_auth("omni", DEFAULT_API_KEY);
+    let entry = format!("cas/{DEFAULT_DIGEST}");
+
+    for (tenant, content) in
+        [(DEFAULT_TENANT, "content"), (OTHER_TENANT, "other content")]
+    {
+        let path = tenant_compat_path("bazel", tenant, &entry);
+
+        let missing_resp =
+            server.get(&path).add_header("Authorization", &basic).await;
+        missing_resp.assert_status_not_found();
+
+        let put_resp = server
+            .put(&path)
+            .add_header("Authorization", &basic)
+            .bytes(Bytes::from(content))
+            .await;
+        put_resp.assert_status_ok();
+    }
+
+    for (tenant, content) in
+        [(DEFAULT_TENANT, "content"), (OTHER_TENANT, "other content")]
+    {
+        let get_resp = server
+            .get(&tenant_compat_path("bazel", tenant, &entry))
+            .add_header("Authorization", &basic)
+            .await;
+        get_resp.assert_status_ok();
+        assert_eq!(get_resp.as_bytes(), content.as_bytes());
+    }
+}
+
+#[tokio::test]
+async fn test_bazel_put_and_get_entries() {
+    let cfg = default_config();
+    let server = create_server(&cfg).await;
+    let basic = basic_auth("omni", DEFAULT_API_KEY);
+
+    for (store, content) in [("ac", "action result"), ("cas", "content")] {
+        let path = compat_path("bazel", &format!("{store}/{DEFAULT_DIGEST}"));
+
+        let missing_resp =
+            server.get(&path).add_header("Authorization", &basic).await;
+        missing_resp.assert_status_not_found();
+
+        let put_resp = server
+            .put(&path)
+            .add_header("Authorization", &basic)
+            .bytes(Bytes::from(content))
+            .await;
+        put_resp.assert_status_ok();
+
+        let head_resp = server
+            .method(http::Method::HEAD, &path)
+            .add_header("Authorization", &basic)
+            .await;
+        head_resp.assert_status_ok();
+
+        let get_resp =
+            server.get(&path).add_header("Authorization", &basic).await;
+        get_resp.assert_status_ok();
+        assert_eq!(
+            get_resp.as_bytes(),
+            content.as_bytes(),
+            "ac and cas entries of the same digest should be kept apart"
+        );
+    }
+
+    let invalid_store_resp = server
+        .get(&compat_path("bazel", &format!("other/{DEFAULT_DIGEST}")))
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .await;
+    invalid_store_resp.assert_status_bad_request();
+
+    let invalid_hash_resp = server
+        .get(&compat_path("bazel", "cas/not.a.hash"))
+        .add_header("X-API-KEY", DEFAULT_API_KEY)
+        .await;
+    invalid_hash_resp.assert_status_bad_request();
+}
+
+#[tokio::test]
+async fn test_gc_finds_untracked_artifacts_of_the_rule_tenant() {
+    let mut cfg = two_tenant_config();
+    let mut rule = retention_rule();
+    rule.max_size = Some(bytesize::ByteSize::b(0));
+    cfg.retention.push(rule);
+
+    let serve_args = ServeArgs::new(
+        "".to_string(),
+        Some(
+            serde_json::to_string(&cfg)
+                .expect("should be able to serialize to json"),
+        ),
+        Some(ConfigType::Inline),
+        None,
+        None,
+        None,
+        false,
+        None,
+        None,
+        Some(100),
+        BackendType::InMemory,
+        Some(RouterConfig::new(Some("/api".to_string()), true)),
+    );
+    let state = ServiceState::from_args(&serve_args)
+        .await
+        .expect("must be able to construct state");
+
+    // Stored without going through the routes, so no access is tracked.
+    for tenant in [DEFAULT_TENANT, OTHER_TENANT] {
+        let container =
+            format!("{tenant}/{DEFAULT_ORG}/{DEFAULT_WORKSPACE}/{DEFAULT_ENV}");
+        state
+            .storage_backend
+            .save(Some(&container), DEFAULT_DIGEST, default_body())
+            .await
+            .expect("should be able to save");
+    }
+
+    let report = state
+        .garbage_collector
+        .plan()
+        .await
+        .expect("should be able to plan");
+
+    assert_eq!(report.containers.len(), 1);
+    let container = &report.containers[0];
+    assert_eq!(
+        container.container,
+        format!(
+            "{DEFAULT_TENANT}/{DEFAULT_ORG}/{DEFAULT_WORKSPACE}/{DEFAULT_ENV}"
+        )
+    );
+    assert_eq!(container.evictions.len(), 1);
+    assert_eq!(container.evictions[0].key, DEFAULT_DIGEST);
+    assert_eq!(container.evictions[0].reason, EvictionReason::MaxSize);
+}
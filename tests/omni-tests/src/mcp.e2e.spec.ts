@@ -288,7 +288,7 @@ function conditionalRequiredGeneratorSpec(): WorkspaceSpec {
 describe("+mcp @mcp @cli (protocol)", {
     tags: ["mcp"],
 }, () => {
-    it("tools/list returns all 16 expected tools", async () => {
+    it("tools/list returns all 18 expected tools", async () => {
         const ws = makeWorkspace(singleProjectSpec());
         const { client } = await connectMcp({ cwd: ws.cwd });
 
@@ -304,10 +304,12 @@ describe("+mcp @mcp @cli (protocol)", {
                 "generator_list",
                 "generator_run",
                 "generator_validate_input",
+                "graph",
                 "hash_project",
                 "hash_workspace",
                 "project_config",
                 "project_list",
+                "run_history",
                 "task_run",
                 "tool_inspect",
                 "tool_list",
@@ -328,12 +330,14 @@ describe("+mcp @mcp @cli (protocol)", {
             "workspace_info",
             "project_list",
             "project_config",
+            "graph",
             "generator_list",
             "generator_inspect",
             "generator_validate_input",
             "hash_workspace",
             "hash_project",
             "cache_stats",
+            "run_history",
             "tool_list",
             "tool_inspect",
         ];